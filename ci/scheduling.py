@@ -15,6 +15,7 @@ instances = [
                 ("jobshop", "examples/scheduling/instances/jobshop/ft06.jsp", 55),
                 ("jobshop", "examples/scheduling/instances/jobshop/la01.jsp", 666),
                 ("openshop", "examples/scheduling/instances/openshop/taillard/tai04_04_01.osp", 193),
+                ("rcpsp", "examples/scheduling/instances/rcpsp/small01.sm", 15),
             ] + [
                 ("jobshop", "examples/scheduling/instances/jobshop/orb05.jsp", 887),
             ] * 30
@@ -0,0 +1,774 @@
+//! Encoding of a FlatZinc model into an aries [Model].
+//!
+//! Each FlatZinc constraint is mapped onto the corresponding expression of the modeling language.
+//! The reified (`_reif`) and half-reified (`_imp`) variants are derived automatically from the base constraint.
+//! The global constraints natively supported by aries are exposed by the MiniZinc library (`share/minizinc/aries`)
+//! as `aries_*` constraints.
+
+use crate::parser::*;
+use anyhow::*;
+use aries::core::{IntCst, Lit, LongCst, VarRef, INT_CST_MAX, INT_CST_MIN};
+use aries::model::extensions::AssignmentExt;
+use aries::model::lang::expr::*;
+use aries::model::lang::linear::{LinearLeq, LinearSum, LinearTerm};
+use aries::model::lang::scheduling::Task;
+use aries::model::lang::{IAtom, IVar};
+use aries::reif::{ReifExpr, Reifiable};
+use std::collections::HashMap;
+
+pub type Model = aries::model::Model<String>;
+
+/// A set of integers, represented as a sorted list of disjoint intervals.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntSet(Vec<(IntCst, IntCst)>);
+
+impl IntSet {
+    pub fn range(lb: IntCst, ub: IntCst) -> IntSet {
+        if lb <= ub {
+            IntSet(vec![(lb, ub)])
+        } else {
+            IntSet(Vec::new())
+        }
+    }
+
+    pub fn from_values(mut values: Vec<IntCst>) -> IntSet {
+        values.sort_unstable();
+        values.dedup();
+        let mut intervals: Vec<(IntCst, IntCst)> = Vec::new();
+        for v in values {
+            match intervals.last_mut() {
+                Some((_, ub)) if *ub + 1 == v => *ub = v,
+                _ => intervals.push((v, v)),
+            }
+        }
+        IntSet(intervals)
+    }
+
+    /// Smallest and largest values of the set, or `None` if the set is empty.
+    pub fn bounds(&self) -> Option<(IntCst, IntCst)> {
+        Some((self.0.first()?.0, self.0.last()?.1))
+    }
+
+    pub fn intervals(&self) -> &[(IntCst, IntCst)] {
+        &self.0
+    }
+}
+
+/// Value associated to an identifier of the FlatZinc model.
+#[derive(Clone, Debug)]
+pub enum Value {
+    Bool(Lit),
+    Int(IAtom),
+    Set(IntSet),
+    Array(Vec<Value>),
+}
+
+/// An element of the model whose value must be printed for each solution.
+#[derive(Clone, Debug)]
+pub enum Output {
+    Single(String, Value),
+    /// An array, with the index set of each of its dimensions.
+    Array {
+        name: String,
+        dims: Vec<(IntCst, IntCst)>,
+        values: Vec<Value>,
+    },
+}
+
+impl Output {
+    /// All variables whose value is printed.
+    pub fn variables(&self) -> Vec<Value> {
+        match self {
+            Output::Single(_, v) => vec![v.clone()],
+            Output::Array { values, .. } => values.clone(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Objective {
+    Satisfy,
+    /// Minimization of the given expression. For maximization problems, the expression is the negation of
+    /// the FlatZinc objective.
+    Minimize(IAtom),
+}
+
+/// Result of the encoding of a FlatZinc model.
+pub struct Encoding {
+    pub model: Model,
+    pub outputs: Vec<Output>,
+    pub objective: Objective,
+}
+
+pub fn encode(fzn: &FlatZinc) -> Result<Encoding> {
+    let mut encoder = Encoder {
+        model: Model::new(),
+        env: HashMap::new(),
+        bool_views: HashMap::new(),
+    };
+    let mut outputs = Vec::new();
+    for decl in &fzn.decls {
+        if let Some(output) = encoder
+            .declare(decl)
+            .with_context(|| format!("In declaration of {}", decl.name))?
+        {
+            outputs.push(output);
+        }
+    }
+    for constraint in &fzn.constraints {
+        encoder
+            .post(&constraint.name, &constraint.args)
+            .with_context(|| format!("In constraint {}", constraint.name))?;
+    }
+    let objective = match &fzn.goal {
+        Goal::Satisfy => Objective::Satisfy,
+        Goal::Minimize(e) => Objective::Minimize(encoder.int(e)?),
+        Goal::Maximize(e) => {
+            let obj = encoder.int(e)?;
+            let (lb, ub) = encoder.model.domain_of(obj);
+            let negated = encoder.model.new_ivar(-ub, -lb, "negated_objective".to_string());
+            let sum = LinearSum::from(obj) + negated;
+            encoder.model.enforce(sum.clone().leq(0), []);
+            encoder.model.enforce(sum.geq(0), []);
+            Objective::Minimize(negated.into())
+        }
+    };
+    Ok(Encoding {
+        model: encoder.model,
+        outputs,
+        objective,
+    })
+}
+
+/// Constraints whose last argument is a boolean that is equivalent to the constraint on the other arguments.
+const FUNCTIONAL_REIFICATIONS: [(&str, usize); 5] = [
+    ("bool_and", 3),
+    ("bool_or", 3),
+    ("bool_xor", 3),
+    ("array_bool_and", 2),
+    ("array_bool_or", 2),
+];
+
+struct Encoder {
+    model: Model,
+    env: HashMap<String, Value>,
+    /// Integer variables in `[0,1]` that are equal to a literal.
+    bool_views: HashMap<Lit, IVar>,
+}
+
+impl Encoder {
+    /* ============================ Declarations ============================ */
+
+    fn declare(&mut self, decl: &Decl) -> Result<Option<Output>> {
+        let value = if decl.array_len.is_some() {
+            // arrays of variables are always defined by the list of their elements
+            let value = decl.value.as_ref().context("Array without value")?;
+            self.eval(value)?
+        } else if let Some(value) = &decl.value {
+            let value = self.eval(value)?;
+            if decl.is_var {
+                self.restrict_domain(&value, &decl.domain)?;
+            }
+            value
+        } else {
+            self.new_var(&decl.name, &decl.domain)?
+        };
+        self.env.insert(decl.name.clone(), value.clone());
+
+        let mut output = None;
+        for annotation in &decl.annotations {
+            match annotation {
+                Expr::Ident(a) if a == "output_var" => output = Some(Output::Single(decl.name.clone(), value.clone())),
+                Expr::Call(a, args) if a == "output_array" => {
+                    let dims = match args.as_slice() {
+                        [Expr::Array(dims)] => dims
+                            .iter()
+                            .map(|d| match d {
+                                Expr::Range(lb, ub) => Ok((*lb, *ub)),
+                                _ => bail!("Invalid dimension in output annotation: {d:?}"),
+                            })
+                            .collect::<Result<Vec<_>>>()?,
+                        _ => bail!("Invalid output annotation"),
+                    };
+                    let values = self.array(&value)?;
+                    output = Some(Output::Array {
+                        name: decl.name.clone(),
+                        dims,
+                        values,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(output)
+    }
+
+    fn new_var(&mut self, name: &str, domain: &Domain) -> Result<Value> {
+        let label = name.to_string();
+        match domain {
+            Domain::Bool => Ok(Value::Bool(self.model.new_bvar(label).true_lit())),
+            Domain::Int => Ok(Value::Int(self.model.new_ivar(INT_CST_MIN, INT_CST_MAX, label).into())),
+            Domain::IntRange(lb, ub) => Ok(Value::Int(self.model.new_ivar(*lb, *ub, label).into())),
+            Domain::IntSet(values) => {
+                let set = IntSet::from_values(values.clone());
+                let (lb, ub) = set.bounds().context("Empty domain")?;
+                let var = Value::Int(self.model.new_ivar(lb, ub, label).into());
+                self.restrict_domain(&var, domain)?;
+                Ok(var)
+            }
+            Domain::Float => bail!("Float variables are not supported"),
+            Domain::SetOfInt(_) => bail!("Set variables are not supported"),
+        }
+    }
+
+    /// Enforces the value of a variable to be in the given domain.
+    fn restrict_domain(&mut self, value: &Value, domain: &Domain) -> Result<()> {
+        match (value, domain) {
+            (Value::Bool(_), Domain::Bool) | (Value::Int(_), Domain::Int) => Ok(()),
+            (Value::Int(i), Domain::IntRange(lb, ub)) => {
+                self.model.enforce(geq(*i, *lb), []);
+                self.model.enforce(leq(*i, *ub), []);
+                Ok(())
+            }
+            (Value::Int(i), Domain::IntSet(values)) => {
+                let set = IntSet::from_values(values.clone());
+                let in_set = self.in_set(*i, &set);
+                self.model.enforce(in_set, []);
+                Ok(())
+            }
+            _ => bail!("Value {value:?} is not compatible with the domain {domain:?}"),
+        }
+    }
+
+    /* ============================= Evaluation ============================= */
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value> {
+        match expr {
+            Expr::Bool(b) => Ok(Value::Bool(if *b { Lit::TRUE } else { Lit::FALSE })),
+            Expr::Int(i) => Ok(Value::Int(IAtom::from(*i))),
+            Expr::Range(lb, ub) => Ok(Value::Set(IntSet::range(*lb, *ub))),
+            Expr::Set(values) => Ok(Value::Set(IntSet::from_values(values.clone()))),
+            Expr::Array(elems) => Ok(Value::Array(elems.iter().map(|e| self.eval(e)).collect::<Result<_>>()?)),
+            Expr::Ident(name) => self
+                .env
+                .get(name)
+                .cloned()
+                .with_context(|| format!("Unknown or unsupported identifier: {name}")),
+            Expr::Access(name, index) => {
+                let array = self.eval(&Expr::Ident(name.clone()))?;
+                let array = self.array(&array)?;
+                let value = usize::try_from(*index - 1).ok().and_then(|i| array.get(i));
+                value
+                    .cloned()
+                    .with_context(|| format!("Index out of bounds: {name}[{index}]"))
+            }
+            Expr::Float(_) => bail!("Floats are not supported"),
+            Expr::Str(_) | Expr::Call(_, _) => bail!("Unexpected expression: {expr:?}"),
+        }
+    }
+
+    fn array(&self, value: &Value) -> Result<Vec<Value>> {
+        match value {
+            Value::Array(values) => Ok(values.clone()),
+            _ => bail!("Expected an array but got {value:?}"),
+        }
+    }
+
+    fn int(&mut self, expr: &Expr) -> Result<IAtom> {
+        match self.eval(expr)? {
+            Value::Int(i) => Ok(i),
+            value => bail!("Expected an integer but got {value:?}"),
+        }
+    }
+
+    fn bool(&mut self, expr: &Expr) -> Result<Lit> {
+        match self.eval(expr)? {
+            Value::Bool(b) => Ok(b),
+            value => bail!("Expected a boolean but got {value:?}"),
+        }
+    }
+
+    /// Evaluates an expression that must have a fixed integer value.
+    fn par_int(&mut self, expr: &Expr) -> Result<IntCst> {
+        let i = self.int(expr)?;
+        match self.model.domain_of(i) {
+            (lb, ub) if lb == ub => Ok(lb),
+            _ => bail!("Expected a fixed integer but got {expr:?}"),
+        }
+    }
+
+    fn set(&mut self, expr: &Expr) -> Result<IntSet> {
+        match self.eval(expr)? {
+            Value::Set(s) => Ok(s),
+            value => bail!("Expected a set but got {value:?}"),
+        }
+    }
+
+    fn ints(&mut self, expr: &Expr) -> Result<Vec<IAtom>> {
+        let values = self.eval(expr)?;
+        self.array(&values)?
+            .into_iter()
+            .map(|v| match v {
+                Value::Int(i) => Ok(i),
+                _ => bail!("Expected an integer but got {v:?}"),
+            })
+            .collect()
+    }
+
+    fn par_ints(&mut self, expr: &Expr) -> Result<Vec<IntCst>> {
+        let values = self.ints(expr)?;
+        values
+            .into_iter()
+            .map(|i| match self.model.domain_of(i) {
+                (lb, ub) if lb == ub => Ok(lb),
+                _ => bail!("Expected an array of fixed integers: {expr:?}"),
+            })
+            .collect()
+    }
+
+    fn bools(&mut self, expr: &Expr) -> Result<Vec<Lit>> {
+        let values = self.eval(expr)?;
+        self.array(&values)?
+            .into_iter()
+            .map(|v| match v {
+                Value::Bool(b) => Ok(b),
+                _ => bail!("Expected a boolean but got {v:?}"),
+            })
+            .collect()
+    }
+
+    /// Returns a variable that is equal to the given integer atom, creating it if necessary.
+    fn ivar(&mut self, atom: IAtom) -> IVar {
+        if atom.shift == 0 && atom.var != IVar::ZERO {
+            atom.var
+        } else {
+            let (lb, ub) = self.model.domain_of(atom);
+            let var = self.model.new_ivar(lb, ub, format!("{atom:?}"));
+            self.model.enforce(eq(var, atom), []);
+            var
+        }
+    }
+
+    /// Returns an integer variable in `[0, 1]` that is equal to 1 iff the literal is true.
+    fn bool_view(&mut self, lit: Lit) -> IVar {
+        if let Some(view) = self.bool_views.get(&lit) {
+            return *view;
+        }
+        let view = self.model.new_ivar(0, 1, format!("bool2int({lit:?})"));
+        self.model.bind(geq(view, 1), lit);
+        self.bool_views.insert(lit, view);
+        view
+    }
+
+    /// Builds the linear sum `Σ coefs[i] * vars[i]`.
+    fn linear_sum(&mut self, coefs: &Expr, vars: &[IAtom]) -> Result<LinearSum> {
+        let coefs = self.par_ints(coefs)?;
+        ensure!(
+            coefs.len() == vars.len(),
+            "Coefficients and variables of different sizes"
+        );
+        let mut sum = LinearSum::zero();
+        for (c, v) in coefs.into_iter().zip(vars) {
+            sum += LinearTerm::int(c, v.var, Lit::TRUE);
+            sum += c * v.shift;
+        }
+        Ok(sum)
+    }
+
+    fn linear_eq(&mut self, sum: LinearSum, value: IntCst) -> [LinearLeq; 2] {
+        [sum.clone().leq(value), sum.geq(value)]
+    }
+
+    /// Returns a sum equivalent to the given one, whose comparison to a constant can be reified.
+    /// As the solver only supports the reification of linear constraints on a single variable or on the difference
+    /// of two variables, other sums are replaced by an auxiliary variable.
+    fn reifiable_sum(&mut self, sum: LinearSum) -> LinearSum {
+        let unit = |t: &LinearTerm| t.factor().abs() == 1 && t.lit() == Lit::TRUE;
+        match sum.terms() {
+            [] => return sum,
+            [t] if unit(t) => return sum,
+            [a, b] if unit(a) && unit(b) && a.factor() == -b.factor() && a.var() != b.var() => return sum,
+            _ => {}
+        }
+        let (mut lb, mut ub) = (0 as LongCst, 0 as LongCst);
+        for t in sum.terms() {
+            let (vlb, vub) = self.model.domain_of(t.var());
+            let (a, b) = (
+                t.factor() as LongCst * vlb as LongCst,
+                t.factor() as LongCst * vub as LongCst,
+            );
+            lb += a.min(b);
+            ub += a.max(b);
+        }
+        let clamp = |v: LongCst| v.clamp(INT_CST_MIN as LongCst, INT_CST_MAX as LongCst) as IntCst;
+        let var = self.model.new_ivar(clamp(lb), clamp(ub), format!("{sum}"));
+        let constant = sum.constant();
+        let [le, ge] = self.linear_eq(sum - constant - var, 0);
+        self.model.enforce(le, []);
+        self.model.enforce(ge, []);
+        LinearSum::from(var) + constant
+    }
+
+    /// Expression that is true iff the value of the atom is in the set.
+    fn in_set(&mut self, atom: IAtom, set: &IntSet) -> ReifExpr {
+        let intervals: Vec<Lit> = set
+            .intervals()
+            .iter()
+            .map(|&(lb, ub)| {
+                let above = self.model.reify(geq(atom, lb));
+                let below = self.model.reify(leq(atom, ub));
+                self.model.reify(and([above, below]))
+            })
+            .collect();
+        or(intervals).into()
+    }
+
+    /* ============================ Constraints ============================= */
+
+    /// Posts the given constraint in the model.
+    fn post(&mut self, name: &str, args: &[Expr]) -> Result<()> {
+        let functional = FUNCTIONAL_REIFICATIONS.contains(&(name, args.len()));
+        if let Some(base) = name.strip_suffix("_reif").or(functional.then_some(name)) {
+            // r <=> constraint
+            let (r, args) = args.split_last().context("Missing arguments")?;
+            let r = self.bool(r)?;
+            let expr = self.reifiable(base, args, true)?;
+            self.model.bind(expr, r);
+        } else if let Some(base) = name.strip_suffix("_imp") {
+            // r => constraint
+            let (r, args) = args.split_last().context("Missing arguments")?;
+            let r = self.bool(r)?;
+            let expr = self.reifiable(base, args, true)?;
+            let expr = self.model.reify(expr);
+            self.model.enforce(implies(r, expr), []);
+        } else if !self.post_global(name, args)? {
+            let expr = self.reifiable(name, args, false)?;
+            self.model.enforce(expr, []);
+        }
+        Ok(())
+    }
+
+    /// Returns an expression that is equivalent to the constraint, which can be either enforced or reified
+    /// (`reified = true`).
+    fn reifiable(&mut self, name: &str, args: &[Expr], reified: bool) -> Result<ReifExpr> {
+        let expr: ReifExpr = match (name, args) {
+            ("int_eq", [a, b]) => eq(self.int(a)?, self.int(b)?).decompose(&mut self.model),
+            ("int_ne", [a, b]) => neq(self.int(a)?, self.int(b)?).decompose(&mut self.model),
+            ("int_le", [a, b]) => leq(self.int(a)?, self.int(b)?).into(),
+            ("int_lt", [a, b]) => lt(self.int(a)?, self.int(b)?).into(),
+            ("int_lin_le", [coefs, vars, c]) => {
+                let vars = self.ints(vars)?;
+                let mut sum = self.linear_sum(coefs, &vars)?;
+                if reified {
+                    sum = self.reifiable_sum(sum);
+                }
+                sum.leq(self.par_int(c)?).into()
+            }
+            ("int_lin_eq" | "int_lin_ne", [coefs, vars, c]) => {
+                let vars = self.ints(vars)?;
+                let sum = self.linear_sum(coefs, &vars)?;
+                let sum = self.reifiable_sum(sum);
+                let c = self.par_int(c)?;
+                let [le, ge] = self.linear_eq(sum, c);
+                let eq: ReifExpr = and([self.model.reify(le), self.model.reify(ge)]).into();
+                if name == "int_lin_eq" {
+                    eq
+                } else {
+                    !eq
+                }
+            }
+            ("bool_lin_le" | "bool_lin_eq", [coefs, vars, c]) => {
+                let vars: Vec<IAtom> = self
+                    .bools(vars)?
+                    .into_iter()
+                    .map(|b| self.bool_view(b).into())
+                    .collect();
+                let mut sum = self.linear_sum(coefs, &vars)?;
+                if reified || name == "bool_lin_eq" {
+                    sum = self.reifiable_sum(sum);
+                }
+                let c = self.par_int(c)?;
+                if name == "bool_lin_le" {
+                    sum.leq(c).into()
+                } else {
+                    let [le, ge] = self.linear_eq(sum, c);
+                    and([self.model.reify(le), self.model.reify(ge)]).into()
+                }
+            }
+            ("bool_eq", [a, b]) => eq(self.bool(a)?, self.bool(b)?).decompose(&mut self.model),
+            ("bool_xor" | "bool_ne", [a, b]) => neq(self.bool(a)?, self.bool(b)?).decompose(&mut self.model),
+            ("bool_not", [a, b]) => neq(self.bool(a)?, self.bool(b)?).decompose(&mut self.model),
+            ("bool_le", [a, b]) => implies(self.bool(a)?, self.bool(b)?).into(),
+            ("bool_lt", [a, b]) => and([!self.bool(a)?, self.bool(b)?]).into(),
+            ("bool_and", [a, b]) => and([self.bool(a)?, self.bool(b)?]).into(),
+            ("bool_or", [a, b]) => or([self.bool(a)?, self.bool(b)?]).into(),
+            ("array_bool_and", [a]) => and(self.bools(a)?).into(),
+            ("array_bool_or", [a]) => or(self.bools(a)?).into(),
+            ("bool_clause", [pos, neg]) => {
+                let mut lits = self.bools(pos)?;
+                lits.extend(self.bools(neg)?.into_iter().map(|l| !l));
+                or(lits).into()
+            }
+            ("set_in", [x, s]) => {
+                let x = self.int(x)?;
+                let s = self.set(s)?;
+                self.in_set(x, &s)
+            }
+            _ => bail!("Unsupported constraint: {name} with {} arguments", args.len()),
+        };
+        Ok(expr)
+    }
+
+    /// Posts a constraint that can only be enforced (and not reified).
+    /// Returns false if the constraint is not one of them.
+    fn post_global(&mut self, name: &str, args: &[Expr]) -> Result<bool> {
+        match (name, args) {
+            ("int_lin_eq" | "bool_lin_eq", [coefs, vars, c]) => {
+                // more efficient than the enforcement of the reified equality
+                let vars = if name == "int_lin_eq" {
+                    self.ints(vars)?
+                } else {
+                    let lits = self.bools(vars)?;
+                    lits.into_iter().map(|b| self.bool_view(b).into()).collect()
+                };
+                let sum = self.linear_sum(coefs, &vars)?;
+                let c = self.par_int(c)?;
+                let [le, ge] = self.linear_eq(sum, c);
+                self.model.enforce(le, []);
+                self.model.enforce(ge, []);
+            }
+            ("int_plus", [a, b, c]) => {
+                let sum = LinearSum::from(self.int(a)?) + self.int(b)? - self.int(c)?;
+                let [le, ge] = self.linear_eq(sum, 0);
+                self.model.enforce(le, []);
+                self.model.enforce(ge, []);
+            }
+            ("bool2int", [b, i]) => {
+                let b = self.bool(b)?;
+                let i = self.int(i)?;
+                let view = self.bool_view(b);
+                self.model.enforce(eq(view, i), []);
+            }
+            ("array_bool_xor", [a]) => {
+                // chain of exclusive or, where `parity` is true if an odd number of literals are true
+                let mut parity = Lit::FALSE;
+                for l in self.bools(a)? {
+                    parity = self.model.reify(neq(parity, l));
+                }
+                self.model.enforce(parity, []);
+            }
+            ("int_times", [a, b, c]) => {
+                let (a, b, c) = (self.int(a)?, self.int(b)?, self.int(c)?);
+                let (a, b, c) = (self.ivar(a), self.ivar(b), self.ivar(c));
+                self.model.enforce(eq_mul(c, a, b), []);
+            }
+            ("int_div" | "int_mod", [a, b, c]) => {
+                let a = self.int(a)?;
+                let a = self.ivar(a);
+                let b = self.par_int(b).context("Only divisions by a constant are supported")?;
+                ensure!(b != 0, "Division by zero");
+                let c = self.int(c)?;
+                let c = self.ivar(c);
+                if name == "int_div" {
+                    self.model.enforce(eq_div(c, a, b), []);
+                } else {
+                    self.model.enforce(eq_mod(c, a, b), []);
+                }
+            }
+            ("int_abs", [a, b]) => {
+                let (a, b) = (self.int(a)?, self.int(b)?);
+                let (a, b) = (self.ivar(a), self.ivar(b));
+                self.model.enforce(eq_abs(b, a), []);
+            }
+            ("int_min" | "int_max", [a, b, c]) => {
+                let (a, b, c) = (self.int(a)?, self.int(b)?, self.int(c)?);
+                let (a, b, c) = (self.ivar(a), self.ivar(b), self.ivar(c));
+                if name == "int_min" {
+                    self.model.enforce(eq_min(c, [a, b]), []);
+                } else {
+                    self.model.enforce(eq_max(c, [a, b]), []);
+                }
+            }
+            ("array_int_minimum" | "array_int_maximum", [m, xs]) => {
+                let m = self.int(m)?;
+                let m = self.ivar(m);
+                let xs: Vec<IVar> = self.ints(xs)?.into_iter().map(|x| self.ivar(x)).collect();
+                ensure!(!xs.is_empty(), "Minimum or maximum of an empty array");
+                if name == "array_int_minimum" {
+                    self.model.enforce(eq_min(m, xs), []);
+                } else {
+                    self.model.enforce(eq_max(m, xs), []);
+                }
+            }
+            ("array_int_element", [index, array, value]) => {
+                let index = self.int(index)?;
+                let index = self.ivar(index);
+                let mut array = self.par_ints(array)?;
+                ensure!(!array.is_empty(), "Element of an empty array");
+                self.model.enforce(geq(index, 1), []);
+                self.model.enforce(leq(index, array.len() as IntCst), []);
+                // the element constraint is indexed from 0, add a dummy value that can never be selected
+                array.insert(0, array[0]);
+                let value = self.int(value)?;
+                let value = self.ivar(value);
+                self.model.enforce(element(array, index, value), []);
+            }
+            ("array_var_int_element" | "array_bool_element" | "array_var_bool_element", [index, array, value]) => {
+                let index = self.int(index)?;
+                let array = self.eval(array)?;
+                let array = self.array(&array)?;
+                let value = self.eval(value)?;
+                self.model.enforce(geq(index, 1), []);
+                self.model.enforce(leq(index, array.len() as IntCst), []);
+                for (i, elem) in array.into_iter().enumerate() {
+                    let selected = self.model.reify(eq(index, i as IntCst + 1));
+                    let equal = match (&elem, &value) {
+                        (Value::Int(e), Value::Int(v)) => self.model.reify(eq(*e, *v)),
+                        (Value::Bool(e), Value::Bool(v)) => self.model.reify(eq(*e, *v)),
+                        _ => bail!("Incompatible element {elem:?} and value {value:?}"),
+                    };
+                    self.model.enforce(implies(selected, equal), []);
+                }
+            }
+            ("aries_all_different_int", [xs]) => {
+                let xs: Vec<IVar> = self.ints(xs)?.into_iter().map(|x| self.ivar(x)).collect();
+                self.model.enforce(all_different(xs), []);
+            }
+            ("aries_table_int", [xs, table]) => {
+                let xs: Vec<IVar> = self.ints(xs)?.into_iter().map(|x| self.ivar(x)).collect();
+                let table = self.par_ints(table)?;
+                ensure!(
+                    !xs.is_empty() && table.len() % xs.len() == 0,
+                    "Table size is not a multiple of the number of variables"
+                );
+                let tuples = table.chunks(xs.len()).map(|t| t.to_vec()).collect();
+                self.model.enforce(aries::model::lang::expr::table(xs, tuples), []);
+            }
+            ("aries_cumulative", [starts, durations, demands, capacity]) => {
+                let tasks = self.tasks(starts, durations, Some(demands))?;
+                let capacity = self.par_int(capacity)?;
+                self.model.enforce(cumulative(tasks, capacity), []);
+            }
+            ("aries_no_overlap", [starts, durations]) => {
+                let tasks = self.tasks(starts, durations, None)?;
+                self.model.enforce(no_overlap(tasks), []);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn tasks(&mut self, starts: &Expr, durations: &Expr, demands: Option<&Expr>) -> Result<Vec<Task>> {
+        let starts: Vec<IVar> = self.ints(starts)?.into_iter().map(|s| self.ivar(s)).collect();
+        let durations = self.par_ints(durations)?;
+        let demands = match demands {
+            Some(demands) => self.par_ints(demands)?,
+            None => vec![1; starts.len()],
+        };
+        ensure!(
+            starts.len() == durations.len() && starts.len() == demands.len(),
+            "Tasks parameters of different sizes"
+        );
+        Ok((0..starts.len())
+            .map(|i| Task::new(starts[i], durations[i], demands[i]))
+            .collect())
+    }
+}
+
+/// Returns the variables whose values appear in the given outputs.
+pub fn output_variables(outputs: &[Output]) -> Vec<VarRef> {
+    let mut vars = Vec::new();
+    for value in outputs.iter().flat_map(|o| o.variables()) {
+        match value {
+            Value::Bool(l) => vars.push(l.variable()),
+            Value::Int(i) => vars.push(i.var.into()),
+            _ => {}
+        }
+    }
+    vars.retain(|v| *v != VarRef::ZERO);
+    vars.sort();
+    vars.dedup();
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aries::solver::Solver;
+
+    /// Returns all solutions of the problem, as the value of its output variables.
+    fn solutions(input: &str) -> Vec<Vec<IntCst>> {
+        let fzn = FlatZinc::parse(input).unwrap();
+        let encoding = encode(&fzn).unwrap();
+        let vars = output_variables(&encoding.outputs);
+        let mut solver = Solver::new(encoding.model);
+        let mut sols = solver.enumerate(&vars).unwrap();
+        sols.sort();
+        sols
+    }
+
+    #[test]
+    fn test_linear() {
+        let input = "var 0..3: x :: output_var;\nvar 0..3: y :: output_var;\n\
+            constraint int_lin_le([1, 2], [x, y], 2);\nconstraint int_lin_ne([1, -1], [x, y], 0);\nsolve satisfy;";
+        assert_eq!(solutions(input), vec![vec![0, 1], vec![1, 0], vec![2, 0]]);
+    }
+
+    #[test]
+    fn test_reification() {
+        let input = "var 0..2: x :: output_var;\nvar bool: b :: output_var;\nvar bool: c :: output_var;\n\
+            constraint int_le_reif(x, 1, b);\nconstraint int_eq_imp(x, 2, c);\nconstraint bool_clause([c], [b]);\n\
+            solve satisfy;";
+        // x <= 1 <=> b,  c => x = 2,  c \/ !b
+        assert_eq!(solutions(input), vec![vec![2, 0, 0], vec![2, 0, 1]]);
+    }
+
+    #[test]
+    fn test_reified_linear() {
+        let input = "var 0..2: x :: output_var;\nvar 0..2: y :: output_var;\nvar 0..1: z :: output_var;\n\
+            var bool: b :: output_var;\nconstraint int_lin_ne([1, 1, 1], [x, y, z], 3);\n\
+            constraint int_lin_le_reif([2, 1], [x, y], 1, b);\nconstraint bool_clause([b], []);\nsolve satisfy;";
+        assert_eq!(
+            solutions(input),
+            vec![vec![0, 0, 0, 1], vec![0, 0, 1, 1], vec![0, 1, 0, 1], vec![0, 1, 1, 1]]
+        );
+    }
+
+    #[test]
+    fn test_domains_and_sets() {
+        let input = "var {1, 3, 5}: x :: output_var;\nvar bool: b :: output_var;\n\
+            constraint set_in_reif(x, 2..4, b);\nsolve satisfy;";
+        assert_eq!(solutions(input), vec![vec![1, 0], vec![3, 1], vec![5, 0]]);
+    }
+
+    #[test]
+    fn test_element_and_globals() {
+        let input = "array [1..3] of int: a = [10, 20, 30];\n\
+            var 1..3: i :: output_var;\nvar 1..3: j :: output_var;\nvar 0..100: v :: output_var;\n\
+            constraint array_int_element(i, a, v);\nconstraint aries_all_different_int([i, j]);\n\
+            constraint int_le(25, v);\nconstraint int_lt(j, 3);\nsolve satisfy;";
+        assert_eq!(solutions(input), vec![vec![3, 1, 30], vec![3, 2, 30]]);
+    }
+
+    #[test]
+    fn test_maximize() {
+        let input = "var 0..10: x :: output_var;\nvar 0..10: y :: output_var;\n\
+            constraint int_lin_le([3, 2], [x, y], 12);\nsolve maximize x;";
+        let fzn = FlatZinc::parse(input).unwrap();
+        let encoding = encode(&fzn).unwrap();
+        let Objective::Minimize(objective) = encoding.objective else {
+            panic!()
+        };
+        let mut solver = Solver::new(encoding.model);
+        let (cost, _) = solver.minimize(objective).unwrap().unwrap();
+        assert_eq!(cost, -4);
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let fzn = FlatZinc::parse("var 0..3: x;\nconstraint int_pow(x, 2, x);\nsolve satisfy;").unwrap();
+        assert!(encode(&fzn).is_err());
+        let fzn = FlatZinc::parse("var float: x;\nsolve satisfy;").unwrap();
+        assert!(encode(&fzn).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! FlatZinc front-end of aries, to be used as a MiniZinc solver.
+//!
+//! Solutions are printed in the standard FlatZinc output format: the value of each output variable,
+//! followed by a `----------` line. A `==========` line indicates that the search is complete.
+
+mod encode;
+mod parser;
+
+use crate::encode::{encode, output_variables, Encoding, Objective, Output, Value};
+use crate::parser::FlatZinc;
+use anyhow::*;
+use aries::backtrack::Backtrack;
+use aries::core::{IntCst, Lit};
+use aries::model::extensions::{AssignmentExt, SavedAssignment};
+use aries::model::lang::expr::or;
+use aries::model::lang::IAtom;
+use aries::solver::parallel::signals::InputSignal;
+use aries::solver::parallel::{ParSolver, Solution, SolverResult};
+use aries::solver::search::activity::{ActivityBrancher, BranchingParams};
+use aries::solver::Solver;
+use itertools::Itertools;
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "fzn-aries")]
+struct Opt {
+    /// FlatZinc file to solve.
+    file: PathBuf,
+    /// For satisfaction problems, print all solutions.
+    /// For optimization problems, print all intermediate solutions.
+    #[structopt(short = "a")]
+    all_solutions: bool,
+    /// Free search: ignore the search annotations.
+    /// This is always the case as search annotations are not supported.
+    #[structopt(short = "f")]
+    free_search: bool,
+    /// Time limit in milliseconds.
+    #[structopt(short = "t")]
+    time_limit: Option<u64>,
+    /// Number of workers to be run in parallel.
+    #[structopt(short = "p", default_value = "1")]
+    threads: usize,
+}
+
+const SOLUTION_SEPARATOR: &str = "----------";
+const SEARCH_COMPLETE: &str = "==========";
+const UNSATISFIABLE: &str = "=====UNSATISFIABLE=====";
+const UNKNOWN: &str = "=====UNKNOWN=====";
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let deadline = opt.time_limit.map(|ms| Instant::now() + Duration::from_millis(ms));
+    ensure!(opt.threads >= 1, "At least one worker is needed");
+
+    let input = std::fs::read_to_string(&opt.file).context("Could not read file")?;
+    let fzn = FlatZinc::parse(&input)?;
+    if !opt.free_search && !fzn.search_annotations.is_empty() {
+        eprintln!("Warning: search annotations are not supported and will be ignored");
+    }
+    let encoding = encode(&fzn)?;
+
+    match encoding.objective {
+        Objective::Satisfy if opt.all_solutions => enumerate(encoding, deadline),
+        Objective::Satisfy => solve(encoding, &opt, deadline),
+        Objective::Minimize(objective) => minimize(&encoding.outputs, encoding.model, objective, &opt, deadline),
+    }
+    Ok(())
+}
+
+fn par_solver(solver: Box<Solver<String>>, opt: &Opt) -> ParSolver<String> {
+    ParSolver::new(solver, opt.threads, |id, solver| {
+        if id > 0 {
+            // diversify the search of the additional workers
+            solver.set_brancher(ActivityBrancher::new_with_params(BranchingParams {
+                prefer_min_value: id % 2 == 0,
+                ..Default::default()
+            }))
+        }
+    })
+}
+
+/// Finds a single solution to a satisfaction problem.
+fn solve(encoding: Encoding, opt: &Opt, deadline: Option<Instant>) {
+    let mut solver = par_solver(Box::new(Solver::new(encoding.model)), opt);
+    match solver.solve(deadline) {
+        SolverResult::Sol(sol) => print_solution(&encoding.outputs, &sol),
+        SolverResult::Unsat => println!("{UNSATISFIABLE}"),
+        SolverResult::Timeout(_) => println!("{UNKNOWN}"),
+    }
+}
+
+/// Finds all solutions of a satisfaction problem, where two solutions are distinct if they differ on the value
+/// of at least one output variable.
+fn enumerate(encoding: Encoding, deadline: Option<Instant>) {
+    let vars = output_variables(&encoding.outputs);
+    let mut solver = Solver::new(encoding.model);
+    if let Some(deadline) = deadline {
+        let input = solver.input_stream();
+        std::thread::spawn(move || {
+            std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            let _ = input.sender.send(InputSignal::Interrupt);
+        });
+    }
+    let mut num_solutions = 0;
+    loop {
+        match solver.solve() {
+            Result::Ok(Some(sol)) => {
+                print_solution(&encoding.outputs, &sol);
+                num_solutions += 1;
+                // forbid the values of the output variables in subsequent solutions
+                let clause = vars
+                    .iter()
+                    .flat_map(|&v| {
+                        let value = sol.var_domain(v).lb;
+                        [Lit::lt(v, value), Lit::gt(v, value)]
+                    })
+                    .collect_vec();
+                if clause.is_empty() {
+                    println!("{SEARCH_COMPLETE}");
+                    return;
+                }
+                solver.reset();
+                solver.enforce(or(clause), []);
+            }
+            Result::Ok(None) if num_solutions == 0 => return println!("{UNSATISFIABLE}"),
+            Result::Ok(None) => return println!("{SEARCH_COMPLETE}"),
+            Err(_) if num_solutions == 0 => return println!("{UNKNOWN}"),
+            Err(_) => return,
+        }
+    }
+}
+
+/// Minimizes the objective, printing intermediate solutions if requested.
+fn minimize(outputs: &[Output], model: encode::Model, objective: IAtom, opt: &Opt, deadline: Option<Instant>) {
+    let mut solver = par_solver(Box::new(Solver::new(model)), opt);
+
+    // last objective value printed, the final solution may be returned before the intermediate ones are reported
+    let last_printed: Cell<Option<IntCst>> = Cell::new(None);
+    let print = |sol: &Solution| {
+        let value = sol.var_domain(objective).lb;
+        if last_printed.replace(Some(value)) != Some(value) {
+            print_solution(outputs, sol);
+        }
+    };
+    let on_improved_solution = |sol: Solution| {
+        if opt.all_solutions {
+            print(&sol)
+        }
+    };
+    match solver.minimize_with(objective, on_improved_solution, deadline) {
+        SolverResult::Sol(sol) => {
+            print(&sol);
+            println!("{SEARCH_COMPLETE}");
+        }
+        SolverResult::Timeout(Some(sol)) => print(&sol),
+        SolverResult::Timeout(None) => println!("{UNKNOWN}"),
+        SolverResult::Unsat => println!("{UNSATISFIABLE}"),
+    }
+}
+
+fn print_solution(outputs: &[Output], sol: &SavedAssignment) {
+    for output in outputs {
+        match output {
+            Output::Single(name, value) => println!("{name} = {};", format_value(value, sol)),
+            Output::Array { name, dims, values } => {
+                let num_dims = dims.len();
+                let dims = dims.iter().map(|(lb, ub)| format!("{lb}..{ub}")).join(", ");
+                let values = values.iter().map(|v| format_value(v, sol)).join(", ");
+                println!("{name} = array{num_dims}d({dims}, [{values}]);")
+            }
+        }
+    }
+    println!("{SOLUTION_SEPARATOR}");
+}
+
+fn format_value(value: &Value, sol: &SavedAssignment) -> String {
+    match value {
+        Value::Bool(l) => sol.entails(*l).to_string(),
+        Value::Int(i) => sol.var_domain(*i).lb.to_string(),
+        Value::Set(s) => {
+            let elems = s
+                .intervals()
+                .iter()
+                .map(|(lb, ub)| format!("{lb}..{ub}"))
+                .join(" union ");
+            if elems.is_empty() {
+                "{}".to_string()
+            } else {
+                elems
+            }
+        }
+        Value::Array(values) => format!("[{}]", values.iter().map(|v| format_value(v, sol)).join(", ")),
+    }
+}
@@ -0,0 +1,483 @@
+//! Parser for the FlatZinc language, as produced by the MiniZinc compiler.
+//!
+//! The parser only builds a syntax tree of the input: declarations, constraints and the solve item.
+//! Predicate declarations are ignored as the semantic of each constraint is given by its name.
+
+use anyhow::*;
+use aries::core::IntCst;
+
+/// An expression, appearing as the argument of a constraint, the value of a declaration or an annotation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Bool(bool),
+    Int(IntCst),
+    Float(f64),
+    /// An integer range `lb..ub`
+    Range(IntCst, IntCst),
+    /// A set literal `{v1, v2, ...}`
+    Set(Vec<IntCst>),
+    Array(Vec<Expr>),
+    Str(String),
+    Ident(String),
+    /// Access to an element of an array `name[index]`, where `index` starts at 1.
+    Access(String, IntCst),
+    /// A call `name(args)`, only appearing in annotations
+    Call(String, Vec<Expr>),
+}
+
+/// Domain of a parameter or variable.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Domain {
+    Bool,
+    Int,
+    IntRange(IntCst, IntCst),
+    IntSet(Vec<IntCst>),
+    Float,
+    /// A set of integers, optionally restricted to be a subset of the given expression
+    SetOfInt(Option<Box<Domain>>),
+}
+
+/// Declaration of a parameter (`is_var = false`) or of a variable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decl {
+    pub name: String,
+    pub is_var: bool,
+    /// Length of the array if this is an array declaration.
+    pub array_len: Option<usize>,
+    pub domain: Domain,
+    pub annotations: Vec<Expr>,
+    /// Value assigned to the declared element, mandatory for parameters.
+    pub value: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constraint {
+    pub name: String,
+    pub args: Vec<Expr>,
+    pub annotations: Vec<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Goal {
+    Satisfy,
+    Minimize(Expr),
+    Maximize(Expr),
+}
+
+/// A FlatZinc model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlatZinc {
+    pub decls: Vec<Decl>,
+    pub constraints: Vec<Constraint>,
+    pub goal: Goal,
+    pub search_annotations: Vec<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(IntCst),
+    Float(f64),
+    Str(String),
+    /// Punctuation, one of `..`, `::`, `:`, `;`, `,`, `=`, `(`, `)`, `[`, `]`, `{`, `}`
+    Sym(&'static str),
+}
+
+const SYMBOLS: [&str; 12] = ["..", "::", ":", ";", ",", "=", "(", ")", "[", "]", "{", "}"];
+
+/// Splits the input into a sequence of tokens, each associated to the line on which it appears.
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    for (line_num, line) in input.lines().enumerate() {
+        let line_num = line_num + 1;
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let rest: String = chars[i..].iter().take(2).collect();
+            if c == '%' {
+                break; // comment until the end of the line
+            } else if c.is_whitespace() {
+                i += 1;
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push((Token::Ident(chars[start..i].iter().collect()), line_num));
+            } else if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                // a dot starts the decimal part of a float, unless it is part of a range `..`
+                let is_float = (chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()))
+                    || matches!(chars.get(i), Some('e') | Some('E'));
+                if is_float {
+                    let digits = |i: &mut usize| {
+                        while *i < chars.len() && chars[*i].is_ascii_digit() {
+                            *i += 1;
+                        }
+                    };
+                    if chars[i] == '.' {
+                        i += 1;
+                        digits(&mut i);
+                    }
+                    if matches!(chars.get(i), Some('e') | Some('E')) {
+                        i += 1;
+                        if matches!(chars.get(i), Some('-') | Some('+')) {
+                            i += 1;
+                        }
+                        digits(&mut i);
+                    }
+                    let s: String = chars[start..i].iter().collect();
+                    let f = s
+                        .parse()
+                        .with_context(|| format!("Line {line_num}: invalid float: {s}"))?;
+                    tokens.push((Token::Float(f), line_num));
+                } else {
+                    let s: String = chars[start..i].iter().collect();
+                    let n = s
+                        .parse()
+                        .with_context(|| format!("Line {line_num}: unsupported integer: {s}"))?;
+                    tokens.push((Token::Int(n), line_num));
+                }
+            } else if c == '"' {
+                let start = i + 1;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    i += 1;
+                }
+                ensure!(i < chars.len(), "Line {line_num}: unterminated string");
+                tokens.push((Token::Str(chars[start..i].iter().collect()), line_num));
+                i += 1;
+            } else if let Some(sym) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+                tokens.push((Token::Sym(sym), line_num));
+                i += sym.len();
+            } else {
+                bail!("Line {line_num}: unexpected character '{c}'")
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map(|(_, l)| *l)
+            .unwrap_or(0)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let line = self.line();
+        let (tok, _) = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .with_context(|| format!("Line {line}: unexpected end of input"))?;
+        self.pos += 1;
+        Ok(tok)
+    }
+
+    fn is_sym(&self, sym: &str) -> bool {
+        matches!(self.peek(), Some(Token::Sym(s)) if *s == sym)
+    }
+
+    fn is_ident(&self, ident: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s == ident)
+    }
+
+    fn expect_sym(&mut self, sym: &str) -> Result<()> {
+        let line = self.line();
+        match self.next()? {
+            Token::Sym(s) if s == sym => Ok(()),
+            tok => bail!("Line {line}: expected '{sym}' but got {tok:?}"),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String> {
+        let line = self.line();
+        match self.next()? {
+            Token::Ident(s) => Ok(s),
+            tok => bail!("Line {line}: expected an identifier but got {tok:?}"),
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        let line = self.line();
+        match self.expect_ident()? {
+            s if s == keyword => Ok(()),
+            s => bail!("Line {line}: expected '{keyword}' but got '{s}'"),
+        }
+    }
+
+    fn expect_int(&mut self) -> Result<IntCst> {
+        let line = self.line();
+        match self.next()? {
+            Token::Int(i) => Ok(i),
+            tok => bail!("Line {line}: expected an integer but got {tok:?}"),
+        }
+    }
+
+    /// Parses a comma separated list of elements, until the closing symbol (which is consumed).
+    fn list<T>(&mut self, close: &str, mut elem: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut elems = Vec::new();
+        while !self.is_sym(close) {
+            elems.push(elem(self)?);
+            if !self.is_sym(close) {
+                self.expect_sym(",")?;
+            }
+        }
+        self.expect_sym(close)?;
+        Ok(elems)
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let line = self.line();
+        match self.next()? {
+            Token::Ident(b) if b == "true" => Ok(Expr::Bool(true)),
+            Token::Ident(b) if b == "false" => Ok(Expr::Bool(false)),
+            Token::Ident(name) => {
+                if self.is_sym("[") {
+                    self.expect_sym("[")?;
+                    let index = self.expect_int()?;
+                    self.expect_sym("]")?;
+                    Ok(Expr::Access(name, index))
+                } else if self.is_sym("(") {
+                    self.expect_sym("(")?;
+                    let args = self.list(")", Self::expr)?;
+                    Ok(Expr::Call(name, args))
+                } else {
+                    Ok(Expr::Ident(name))
+                }
+            }
+            Token::Int(lb) => {
+                if self.is_sym("..") {
+                    self.expect_sym("..")?;
+                    let ub = self.expect_int()?;
+                    Ok(Expr::Range(lb, ub))
+                } else {
+                    Ok(Expr::Int(lb))
+                }
+            }
+            Token::Float(f) => {
+                ensure!(!self.is_sym(".."), "Line {line}: float ranges are not supported");
+                Ok(Expr::Float(f))
+            }
+            Token::Str(s) => Ok(Expr::Str(s)),
+            Token::Sym("{") => Ok(Expr::Set(self.list("}", Self::expect_int)?)),
+            Token::Sym("[") => Ok(Expr::Array(self.list("]", Self::expr)?)),
+            tok => bail!("Line {line}: unexpected token {tok:?}"),
+        }
+    }
+
+    fn annotations(&mut self) -> Result<Vec<Expr>> {
+        let mut annotations = Vec::new();
+        while self.is_sym("::") {
+            self.expect_sym("::")?;
+            annotations.push(self.expr()?);
+        }
+        Ok(annotations)
+    }
+
+    /// Parses the domain of a declaration, after the optional `var` keyword
+    fn domain(&mut self) -> Result<Domain> {
+        let line = self.line();
+        match self.next()? {
+            Token::Ident(s) if s == "bool" => Ok(Domain::Bool),
+            Token::Ident(s) if s == "int" => Ok(Domain::Int),
+            Token::Ident(s) if s == "float" => Ok(Domain::Float),
+            Token::Ident(s) if s == "set" => {
+                self.expect_keyword("of")?;
+                let elems = self.domain()?;
+                match elems {
+                    Domain::Int => Ok(Domain::SetOfInt(None)),
+                    Domain::IntRange(_, _) | Domain::IntSet(_) => Ok(Domain::SetOfInt(Some(Box::new(elems)))),
+                    _ => bail!("Line {line}: unsupported set type"),
+                }
+            }
+            Token::Int(lb) => {
+                self.expect_sym("..")?;
+                let ub = self.expect_int()?;
+                Ok(Domain::IntRange(lb, ub))
+            }
+            Token::Float(_) => {
+                self.expect_sym("..")?;
+                self.next()?;
+                Ok(Domain::Float)
+            }
+            Token::Sym("{") => Ok(Domain::IntSet(self.list("}", Self::expect_int)?)),
+            tok => bail!("Line {line}: expected a type but got {tok:?}"),
+        }
+    }
+
+    fn decl(&mut self) -> Result<Decl> {
+        let line = self.line();
+        let array_len = if self.is_ident("array") {
+            self.expect_keyword("array")?;
+            self.expect_sym("[")?;
+            let first = self.expect_int()?;
+            ensure!(first == 1, "Line {line}: arrays must be indexed from 1");
+            self.expect_sym("..")?;
+            let last = self.expect_int()?;
+            self.expect_sym("]")?;
+            self.expect_keyword("of")?;
+            Some(last.max(0) as usize)
+        } else {
+            None
+        };
+        let is_var = self.is_ident("var");
+        if is_var {
+            self.expect_keyword("var")?;
+        }
+        let domain = self.domain()?;
+        self.expect_sym(":")?;
+        let name = self.expect_ident()?;
+        let annotations = self.annotations()?;
+        let value = if self.is_sym("=") {
+            self.expect_sym("=")?;
+            Some(self.expr()?)
+        } else {
+            None
+        };
+        ensure!(
+            is_var || value.is_some(),
+            "Line {line}: parameter '{name}' has no value"
+        );
+        Ok(Decl {
+            name,
+            is_var,
+            array_len,
+            domain,
+            annotations,
+            value,
+        })
+    }
+}
+
+impl FlatZinc {
+    pub fn parse(input: &str) -> Result<FlatZinc> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            pos: 0,
+        };
+        let mut decls = Vec::new();
+        let mut constraints = Vec::new();
+        let mut solve = None;
+        while parser.peek().is_some() {
+            if parser.is_ident("predicate") {
+                // predicate declarations have no effect on the problem, skip them
+                while !parser.is_sym(";") {
+                    parser.next()?;
+                }
+            } else if parser.is_ident("constraint") {
+                parser.expect_keyword("constraint")?;
+                let name = parser.expect_ident()?;
+                parser.expect_sym("(")?;
+                let args = parser.list(")", Parser::expr)?;
+                let annotations = parser.annotations()?;
+                constraints.push(Constraint {
+                    name,
+                    args,
+                    annotations,
+                });
+            } else if parser.is_ident("solve") {
+                let line = parser.line();
+                ensure!(solve.is_none(), "Line {line}: duplicated solve item");
+                parser.expect_keyword("solve")?;
+                let annotations = parser.annotations()?;
+                let goal = match parser.expect_ident()?.as_str() {
+                    "satisfy" => Goal::Satisfy,
+                    "minimize" => Goal::Minimize(parser.expr()?),
+                    "maximize" => Goal::Maximize(parser.expr()?),
+                    other => bail!("Line {line}: unexpected solve goal '{other}'"),
+                };
+                solve = Some((goal, annotations));
+            } else {
+                decls.push(parser.decl()?);
+            }
+            parser.expect_sym(";")?;
+        }
+        let (goal, search_annotations) = solve.context("Missing solve item")?;
+        Ok(FlatZinc {
+            decls,
+            constraints,
+            goal,
+            search_annotations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"
+            % a comment
+            predicate my_pred(array [int] of var int: xs);
+            array [1..3] of int: coefs = [1, -2, 3];
+            set of int: s = 1..5;
+            var 1..10: x :: output_var;
+            var {1, 3, 5}: y;
+            var bool: b :: output_var :: is_defined_var;
+            array [1..2] of var int: xs :: output_array([1..2]) = [x, y];
+            constraint int_lin_le(coefs, [x, y, xs[1]], 10) :: domain;
+            solve :: int_search(xs, input_order, indomain_min, complete) minimize x;
+        "#;
+        let fzn = FlatZinc::parse(input).unwrap();
+        assert_eq!(fzn.decls.len(), 6);
+        assert_eq!(fzn.decls[0].array_len, Some(3));
+        assert_eq!(
+            fzn.decls[0].value,
+            Some(Expr::Array(vec![Expr::Int(1), Expr::Int(-2), Expr::Int(3)]))
+        );
+        assert_eq!(fzn.decls[1].value, Some(Expr::Range(1, 5)));
+        assert_eq!(fzn.decls[2].domain, Domain::IntRange(1, 10));
+        assert_eq!(fzn.decls[2].annotations, vec![Expr::Ident("output_var".to_string())]);
+        assert_eq!(fzn.decls[3].domain, Domain::IntSet(vec![1, 3, 5]));
+        assert_eq!(fzn.decls[4].annotations.len(), 2);
+        assert_eq!(fzn.decls[5].array_len, Some(2));
+        assert_eq!(
+            fzn.decls[5].annotations[0],
+            Expr::Call("output_array".to_string(), vec![Expr::Array(vec![Expr::Range(1, 2)])])
+        );
+        assert_eq!(fzn.constraints.len(), 1);
+        assert_eq!(fzn.constraints[0].name, "int_lin_le");
+        assert_eq!(fzn.constraints[0].args[1], {
+            let ident = |s: &str| Expr::Ident(s.to_string());
+            Expr::Array(vec![ident("x"), ident("y"), Expr::Access("xs".to_string(), 1)])
+        });
+        assert_eq!(fzn.goal, Goal::Minimize(Expr::Ident("x".to_string())));
+        assert_eq!(fzn.search_annotations.len(), 1);
+    }
+
+    #[test]
+    fn test_floats() {
+        let fzn = FlatZinc::parse("float: f = 1.5e3;\nvar 0.0..1.0: g;\nsolve satisfy;").unwrap();
+        assert_eq!(fzn.decls[0].value, Some(Expr::Float(1500.0)));
+        assert_eq!(fzn.decls[1].domain, Domain::Float);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(FlatZinc::parse("var 1..3: x;").is_err()); // no solve item
+        assert!(FlatZinc::parse("int: n;\nsolve satisfy;").is_err()); // parameter without value
+        assert!(FlatZinc::parse("constraint int_le(x, 3)\nsolve satisfy;").is_err()); // missing semicolon
+        assert!(FlatZinc::parse("var 1..3: x;\nsolve satisfy;\nsolve satisfy;").is_err());
+    }
+}
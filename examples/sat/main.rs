@@ -1,11 +1,20 @@
 #![allow(clippy::map_entry)]
 
+mod wcnf;
+
+use crate::wcnf::Wcnf;
 use anyhow::*;
-use aries::core::Lit;
+use aries::core::{IntCst, Lit, VarRef, INT_CST_MAX};
+use aries::model::extensions::AssignmentExt;
 use aries::model::lang::expr::or;
-use aries::solver::parallel::{ParSolver, SolverResult};
+use aries::model::lang::{BVar, IVar};
+use aries::reasoners::sat::proof::{ProofFormat, ProofLog};
+use aries::solver::optimization::{OptimizationMode, OptimizationOptions};
+use aries::solver::parallel::{ParSolver, Solution, SolverResult};
 use aries::solver::search::activity::{ActivityBrancher, BranchingParams};
+use aries::solver::search::restarts::Restarts;
 use aries::solver::Solver;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
@@ -25,6 +34,22 @@ struct Opt {
     /// Number of workers to be run in parallel (default to 4).
     #[structopt(long, default_value = "4")]
     threads: usize,
+    /// If set, a DRAT proof of unsatisfiability will be written to this file.
+    /// When set, the problem is solved by a single worker and the `--threads` option is ignored.
+    #[structopt(long)]
+    proof: Option<PathBuf>,
+    /// Restart policy of the workers, replacing the default geometric restarts.
+    /// Possible values: "none", "geom[:<conflicts>[:<ratio>]]", "luby[:<unit>]", "glucose", "glucose-blocking".
+    /// When repeated, the i-th worker uses the `i % n`-th policy.
+    #[structopt(long = "restarts", number_of_values = 1)]
+    restarts: Vec<Restarts>,
+    /// If set, the input is a weighted MaxSAT problem in the WCNF format, whose cost is minimized.
+    #[structopt(long)]
+    maxsat: bool,
+    /// Algorithm used to minimize the cost of a MaxSAT problem.
+    /// Possible values: "linear", "binary", "lb-first", "core".
+    #[structopt(long = "optimization", default_value = "core")]
+    optimization: OptimizationMode,
 }
 
 enum Source {
@@ -87,14 +112,25 @@ fn main() -> Result<()> {
 
     let input = source.read(&opt.file)?;
 
-    let cnf = varisat_dimacs::DimacsParser::parse(input.as_bytes())?;
-    let model = load(cnf)?;
-
     ensure!(
         1 <= opt.threads && opt.threads <= 4,
         "Unsupported number of threads: {}",
         opt.threads
     );
+
+    if opt.maxsat {
+        ensure!(opt.proof.is_none(), "Proofs are not supported for MaxSAT problems");
+        let wcnf = Wcnf::parse(&input)?;
+        return solve_maxsat(&wcnf, &opt);
+    }
+
+    let cnf = varisat_dimacs::DimacsParser::parse(input.as_bytes())?;
+    let model = load(cnf)?;
+
+    if let Some(proof_file) = &opt.proof {
+        return solve_with_proof(model, &opt, proof_file);
+    }
+
     solve_multi_threads(model, &opt, opt.threads)
 }
 
@@ -104,25 +140,118 @@ fn solve_multi_threads(model: Model, opt: &Opt, num_threads: usize) -> Result<()
     let search_params = search_params();
 
     let mut par_solver = ParSolver::new(solver, num_threads, |id, solver| {
-        solver.set_brancher(ActivityBrancher::new_with_params(search_params[id].clone()))
+        solver.set_brancher(ActivityBrancher::new_with_params(search_params[id].clone()));
+        if !opt.restarts.is_empty() {
+            solver.set_restart_policy(opt.restarts[id % opt.restarts.len()].policy());
+        }
     });
 
     match par_solver.solve(None) {
-        SolverResult::Sol(_sol) => {
+        SolverResult::Sol(_sol) => report(opt, Some(true)),
+        SolverResult::Unsat => report(opt, Some(false)),
+        SolverResult::Timeout(_) => report(opt, None),
+    }
+    par_solver.print_stats();
+
+    Ok(())
+}
+
+/// Minimizes the cost of a MaxSAT problem, printing the result in the output format of the MaxSAT evaluations:
+/// a `o <cost>` line for each improved solution, followed by the status (`s ...`) and the best assignment (`v ...`).
+fn solve_maxsat(wcnf: &Wcnf, opt: &Opt) -> Result<()> {
+    let (model, vars, cost) = load_wcnf(wcnf)?;
+    let solver = Box::new(Solver::new(model));
+
+    let search_params = search_params();
+
+    let mut par_solver = ParSolver::new(solver, opt.threads, |id, solver| {
+        solver.set_brancher(ActivityBrancher::new_with_params(search_params[id].clone()));
+        if !opt.restarts.is_empty() {
+            solver.set_restart_policy(opt.restarts[id % opt.restarts.len()].policy());
+        }
+        solver.set_optimization_options(OptimizationOptions { mode: opt.optimization });
+    });
+
+    // last cost printed, the final solution may be returned before the intermediate ones are reported
+    let last_printed = Cell::new(None);
+    let print_cost = |sol: &Solution| {
+        let value = sol.var_domain(cost).lb;
+        if last_printed.replace(Some(value)) != Some(value) {
+            println!("o {value}");
+        }
+    };
+    let result = par_solver.minimize_with(cost, |sol| print_cost(&sol), None);
+    let (status, best) = match result {
+        SolverResult::Sol(sol) => ("OPTIMUM FOUND", Some(sol)),
+        SolverResult::Unsat => ("UNSATISFIABLE", None),
+        SolverResult::Timeout(Some(sol)) => ("SATISFIABLE", Some(sol)),
+        SolverResult::Timeout(None) => ("UNKNOWN", None),
+    };
+    if let Some(sol) = &best {
+        print_cost(sol);
+    }
+    println!("s {status}");
+    if let Some(sol) = &best {
+        // values of the variables, as a string of 0s and 1s
+        let values: String = vars[1..]
+            .iter()
+            .map(|v| match v {
+                Some(v) if sol.entails(v.true_lit()) => '1',
+                _ => '0',
+            })
+            .collect();
+        println!("v {values}");
+    }
+    par_solver.print_stats();
+    match status {
+        "UNKNOWN" => report(opt, None),
+        _ => report(opt, Some(best.is_some())),
+    }
+    Ok(())
+}
+
+/// Solves the problem with a single solver, writing a DRAT proof of unsatisfiability to the given file.
+fn solve_with_proof(model: Model, opt: &Opt, proof_file: &Path) -> Result<()> {
+    // variables of the proof must be the ones of the DIMACS file, which are used as labels in the model
+    let var_ids: HashMap<VarRef, usize> = model
+        .state
+        .variables()
+        .filter_map(|v| Some((v, model.shape.labels.get(v)?.parse().ok()?)))
+        .collect();
+    let output = File::create(proof_file).context("Could not create proof file")?;
+    let proof = ProofLog::new(ProofFormat::Drat, output).with_variable_ids(move |v| var_ids[&v]);
+
+    let mut solver = Solver::new(model);
+    solver.set_proof_log(proof.clone())?;
+    if let Some(restarts) = opt.restarts.first() {
+        solver.set_restart_policy(restarts.policy());
+    }
+    let result = solver.solve().map_err(|_| anyhow!("Solver interrupted"))?;
+    proof.flush().context("Could not write proof")?;
+    report(opt, Some(result.is_some()));
+    solver.print_stats();
+    Ok(())
+}
+
+/// Prints the satisfiability of the problem (`None` if unknown) and exits with an error
+/// if it does not match the expected one.
+fn report(opt: &Opt, satisfiable: Option<bool>) {
+    match satisfiable {
+        Some(true) => {
             println!("SAT");
             if opt.expected_satisfiability == Some(false) {
                 eprintln!("Error: expected UNSAT but got SAT");
                 std::process::exit(1);
             }
         }
-        SolverResult::Unsat => {
+        Some(false) => {
             println!("UNSAT");
             if opt.expected_satisfiability == Some(true) {
                 eprintln!("Error: expected SAT but got UNSAT");
                 std::process::exit(1);
             }
         }
-        SolverResult::Timeout(_) => {
+        None => {
             println!("TIMEOUT");
             if opt.expected_satisfiability.is_some() {
                 eprintln!("Error: could not conclude on SAT or UNSAT within the allocated time");
@@ -130,9 +259,6 @@ fn solve_multi_threads(model: Model, opt: &Opt, num_threads: usize) -> Result<()
             }
         }
     }
-    par_solver.print_stats();
-
-    Ok(())
 }
 
 /// Load a CNF formula into a model and a set of constraints
@@ -161,6 +287,39 @@ pub fn load(cnf: varisat_formula::CnfFormula) -> Result<Model> {
     Ok(model)
 }
 
+/// Loads a weighted MaxSAT problem into a model, returning it together with the variable associated to each
+/// DIMACS index (none for index 0 and unused variables) and the cost to minimize.
+pub fn load_wcnf(wcnf: &Wcnf) -> Result<(Model, Vec<Option<BVar>>, IVar)> {
+    let mut model = Model::new();
+    let mut vars: Vec<Option<BVar>> = vec![None; wcnf.num_vars + 1];
+    let mut lit = |model: &mut Model, l: i64| {
+        let index = l.unsigned_abs() as usize;
+        let var = *vars[index].get_or_insert_with(|| model.new_bvar(index.to_string()));
+        if l > 0 {
+            var.true_lit()
+        } else {
+            var.false_lit()
+        }
+    };
+
+    let total_weight = wcnf.soft.iter().try_fold(0u64, |acc, (_, w)| acc.checked_add(*w));
+    ensure!(
+        total_weight.is_some_and(|w| w <= INT_CST_MAX as u64),
+        "Total weight of the soft clauses exceeds the maximal integer value ({INT_CST_MAX})"
+    );
+
+    for clause in &wcnf.hard {
+        let lits: Vec<Lit> = clause.iter().map(|&l| lit(&mut model, l)).collect();
+        model.enforce(or(lits.as_slice()), []);
+    }
+    for (clause, weight) in &wcnf.soft {
+        let lits: Vec<Lit> = clause.iter().map(|&l| lit(&mut model, l)).collect();
+        model.add_soft(or(lits.as_slice()), *weight as IntCst);
+    }
+    let cost = model.violation_cost()?;
+    Ok((model, vars, cost))
+}
+
 /// Default search parameters for the first threads of the search.
 fn search_params() -> [BranchingParams; 4] {
     [
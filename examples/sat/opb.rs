@@ -0,0 +1,160 @@
+//! Reader for pseudo-Boolean problems in the OPB format of the pseudo-Boolean competitions.
+//!
+//! Each statement is terminated by a `;` and is either the objective to minimize (`min: <terms> ;`) or a constraint
+//! `<terms> <relation> <constant> ;` where the relation is one of `>=`, `<=` or `=`.
+//! A term is an integer coefficient followed by one or more literals (`x3` or its negation `~x3`),
+//! several literals denoting their product. Lines starting with `*` are comments.
+
+use anyhow::*;
+
+/// A product of literals, weighted by a coefficient. Literals are given as non-zero DIMACS integers.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Term {
+    pub coef: i64,
+    pub lits: Vec<i64>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Relation {
+    Geq,
+    Leq,
+    Eq,
+}
+
+/// Constraint `sum(terms) <relation> rhs`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Constraint {
+    pub terms: Vec<Term>,
+    pub relation: Relation,
+    pub rhs: i64,
+}
+
+/// A pseudo-Boolean problem.
+#[derive(Debug, Default)]
+pub struct Opb {
+    /// Largest variable index appearing in the problem.
+    pub num_vars: usize,
+    /// Terms of the sum to minimize, if any.
+    pub objective: Option<Vec<Term>>,
+    pub constraints: Vec<Constraint>,
+}
+
+impl Opb {
+    pub fn parse(input: &str) -> Result<Opb> {
+        let mut opb = Opb::default();
+        let body: String = input
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('*'))
+            .flat_map(|line| [line, "\n"])
+            .collect();
+        let mut statements: Vec<&str> = body.split(';').collect();
+        let trailing = statements.pop().unwrap_or_default();
+        ensure!(
+            trailing.trim().is_empty(),
+            "Unterminated statement: {}",
+            trailing.trim()
+        );
+
+        for statement in statements {
+            let mut tokens: Vec<&str> = statement.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+            if tokens[0] == "min:" {
+                ensure!(opb.objective.is_none(), "Several objectives");
+                opb.objective = Some(opb.parse_terms(&tokens[1..])?);
+                continue;
+            }
+            ensure!(tokens.len() >= 2, "Invalid constraint: {}", statement.trim());
+            let rhs = tokens.pop().unwrap();
+            let rhs: i64 = rhs.parse().with_context(|| format!("Invalid right-hand side: {rhs}"))?;
+            let relation = match tokens.pop().unwrap() {
+                ">=" => Relation::Geq,
+                "<=" => Relation::Leq,
+                "=" => Relation::Eq,
+                rel => bail!("Unsupported relation: {rel}"),
+            };
+            let terms = opb.parse_terms(&tokens)?;
+            opb.constraints.push(Constraint { terms, relation, rhs });
+        }
+        Ok(opb)
+    }
+
+    fn parse_terms(&mut self, tokens: &[&str]) -> Result<Vec<Term>> {
+        let mut terms: Vec<Term> = Vec::new();
+        for &token in tokens {
+            if let Some(var) = token.strip_prefix('~').unwrap_or(token).strip_prefix('x') {
+                let var: usize = var.parse().with_context(|| format!("Invalid literal: {token}"))?;
+                ensure!(var > 0, "Invalid literal: {token}");
+                self.num_vars = self.num_vars.max(var);
+                let lit = if token.starts_with('~') {
+                    -(var as i64)
+                } else {
+                    var as i64
+                };
+                if terms.is_empty() {
+                    // coefficient omitted
+                    terms.push(Term { coef: 1, lits: vec![] });
+                }
+                // literals following another one denote a product
+                terms.last_mut().unwrap().lits.push(lit);
+            } else {
+                ensure!(terms.last().is_none_or(|t| !t.lits.is_empty()), "Term without literal");
+                let coef: i64 = token.parse().with_context(|| format!("Invalid coefficient: {token}"))?;
+                terms.push(Term { coef, lits: vec![] });
+            }
+        }
+        ensure!(terms.last().is_none_or(|t| !t.lits.is_empty()), "Term without literal");
+        Ok(terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(coef: i64, lits: &[i64]) -> Term {
+        Term {
+            coef,
+            lits: lits.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = "* #variable= 4 #constraint= 3\nmin: +2 x1 -3 x2 ;\n+1 x1 +1 ~x3 >= 1 ;\n\
+                     +1 x1\n -2 x2 x4 = -1;\nx2 +3 ~x4 <= 2 ;\n";
+        let opb = Opb::parse(input).unwrap();
+        assert_eq!(opb.num_vars, 4);
+        assert_eq!(opb.objective, Some(vec![term(2, &[1]), term(-3, &[2])]));
+        assert_eq!(
+            opb.constraints,
+            vec![
+                Constraint {
+                    terms: vec![term(1, &[1]), term(1, &[-3])],
+                    relation: Relation::Geq,
+                    rhs: 1
+                },
+                Constraint {
+                    terms: vec![term(1, &[1]), term(-2, &[2, 4])],
+                    relation: Relation::Eq,
+                    rhs: -1
+                },
+                Constraint {
+                    terms: vec![term(1, &[2]), term(3, &[-4])],
+                    relation: Relation::Leq,
+                    rhs: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Opb::parse("+1 x1 >= 1").is_err());
+        assert!(Opb::parse("+1 x1 > 1 ;").is_err());
+        assert!(Opb::parse("+1 +2 x1 >= 1 ;").is_err());
+        assert!(Opb::parse("+1 y1 >= 1 ;").is_err());
+        assert!(Opb::parse("min: x1 ; min: x2 ;").is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! Pseudo-Boolean front-end of aries, for problems in the OPB format.
+//!
+//! The output follows the format of the pseudo-Boolean competitions: a `o <cost>` line for each improved solution,
+//! the final status (`s OPTIMUM FOUND`, `s SATISFIABLE`, `s UNSATISFIABLE` or `s UNKNOWN`)
+//! and the best assignment on a `v` line.
+
+mod opb;
+
+use crate::opb::{Opb, Relation, Term};
+use anyhow::*;
+use aries::core::{IntCst, Lit, LongCst};
+use aries::model::extensions::AssignmentExt;
+use aries::model::lang::expr::{and, pb_geq, pb_leq};
+use aries::model::lang::{BVar, IVar};
+use aries::solver::optimization::{OptimizationMode, OptimizationOptions};
+use aries::solver::parallel::{ParSolver, Solution, SolverResult};
+use aries::solver::search::activity::{ActivityBrancher, BranchingParams};
+use aries::solver::Solver;
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+type Model = aries::model::Model<String>;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "aries-pb")]
+struct Opt {
+    /// OPB file to solve.
+    file: PathBuf,
+    /// Time limit in seconds.
+    #[structopt(short = "t", long = "timeout")]
+    timeout: Option<u64>,
+    /// Number of workers to be run in parallel.
+    #[structopt(long, default_value = "1")]
+    threads: usize,
+    /// Algorithm used to minimize the objective.
+    /// Possible values: "linear", "binary", "lb-first", "core".
+    #[structopt(long = "optimization", default_value = "core")]
+    optimization: OptimizationMode,
+}
+
+/// A pseudo-Boolean problem encoded into a model.
+struct Encoding {
+    model: Model,
+    /// Variable associated to each OPB index (none for index 0 and unused variables).
+    vars: Vec<Option<BVar>>,
+    /// Objective to minimize, as a variable and a constant offset to add to its value.
+    objective: Option<(IVar, LongCst)>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let start = Instant::now();
+    let deadline = opt.timeout.map(|s| start + Duration::from_secs(s));
+    ensure!(opt.threads >= 1, "At least one worker is needed");
+
+    let input = std::fs::read_to_string(&opt.file).context("Could not read file")?;
+    let opb = Opb::parse(&input)?;
+    let encoding = encode(&opb)?;
+
+    let mut solver = ParSolver::new(Box::new(Solver::new(encoding.model.clone())), opt.threads, |id, s| {
+        if id > 0 {
+            // diversify the search of the additional workers
+            s.set_brancher(ActivityBrancher::new_with_params(BranchingParams {
+                prefer_min_value: id % 2 == 0,
+                ..Default::default()
+            }))
+        }
+        s.set_optimization_options(OptimizationOptions { mode: opt.optimization });
+    });
+
+    let (status, best) = match encoding.objective {
+        None => match solver.solve(deadline) {
+            SolverResult::Sol(sol) => ("SATISFIABLE", Some(sol)),
+            SolverResult::Unsat => ("UNSATISFIABLE", None),
+            SolverResult::Timeout(_) => ("UNKNOWN", None),
+        },
+        Some((cost, offset)) => {
+            // last cost printed, the final solution may be returned before the intermediate ones are reported
+            let last_printed = Cell::new(None);
+            let print_cost = |sol: &Solution| {
+                let value = sol.var_domain(cost).lb as LongCst + offset;
+                if last_printed.replace(Some(value)) != Some(value) {
+                    println!("o {value}");
+                }
+            };
+            let result = match solver.minimize_with(cost, |sol| print_cost(&sol), deadline) {
+                SolverResult::Sol(sol) => ("OPTIMUM FOUND", Some(sol)),
+                SolverResult::Unsat => ("UNSATISFIABLE", None),
+                SolverResult::Timeout(Some(sol)) => ("SATISFIABLE", Some(sol)),
+                SolverResult::Timeout(None) => ("UNKNOWN", None),
+            };
+            if let Some(sol) = &result.1 {
+                print_cost(sol);
+            }
+            result
+        }
+    };
+    println!("s {status}");
+    if let Some(sol) = &best {
+        let values: Vec<String> = encoding
+            .vars
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| match v {
+                Some(v) if sol.entails(v.true_lit()) => Some(format!("x{i}")),
+                Some(_) => Some(format!("-x{i}")),
+                None => None,
+            })
+            .collect();
+        println!("v {}", values.join(" "));
+    }
+    println!("c solved in {:.3}s", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Encodes the problem, each constraint being enforced as a pseudo-Boolean constraint.
+///
+/// The objective is encoded with soft constraints (so that it can be minimized by core-guided optimization):
+/// a term `c * l` is a soft constraint `!l` of weight `c` if `c` is positive and a soft constraint `l` of weight `-c`
+/// (with an offset of `c`) otherwise.
+fn encode(opb: &Opb) -> Result<Encoding> {
+    let mut model = Model::new();
+    let mut vars: Vec<Option<BVar>> = vec![None; opb.num_vars + 1];
+
+    // weighted literals of the terms, products of literals being replaced by their conjunction
+    let mut weighted_lits = |model: &mut Model, terms: &[Term]| -> Result<Vec<(IntCst, Lit)>> {
+        let mut weighted = Vec::with_capacity(terms.len());
+        for term in terms {
+            let weight = int(term.coef)?;
+            let lits: Vec<Lit> = term
+                .lits
+                .iter()
+                .map(|&l| {
+                    let index = l.unsigned_abs() as usize;
+                    let var = *vars[index].get_or_insert_with(|| model.new_bvar(format!("x{index}")));
+                    if l > 0 {
+                        var.true_lit()
+                    } else {
+                        var.false_lit()
+                    }
+                })
+                .collect();
+            let lit = match lits.as_slice() {
+                [lit] => *lit,
+                _ => model.reify(and(lits)),
+            };
+            weighted.push((weight, lit));
+        }
+        Ok(weighted)
+    };
+
+    for constraint in &opb.constraints {
+        let terms = weighted_lits(&mut model, &constraint.terms)?;
+        let rhs = int(constraint.rhs)?;
+        let overflow = || format!("Integer overflow in the normalization of the constraint {constraint:?}");
+        match constraint.relation {
+            Relation::Geq => model.enforce(pb_geq(terms, rhs).with_context(overflow)?, []),
+            Relation::Leq => model.enforce(pb_leq(terms, rhs).with_context(overflow)?, []),
+            Relation::Eq => {
+                model.enforce(pb_geq(terms.clone(), rhs).with_context(overflow)?, []);
+                model.enforce(pb_leq(terms, rhs).with_context(overflow)?, []);
+            }
+        }
+    }
+
+    let objective = match &opb.objective {
+        Some(terms) => {
+            let mut offset: LongCst = 0;
+            for (weight, lit) in weighted_lits(&mut model, terms)? {
+                if weight >= 0 {
+                    model.add_soft(!lit, weight);
+                } else {
+                    model.add_soft(lit, int(-(weight as i64))?);
+                    offset += weight as LongCst;
+                }
+            }
+            let cost = model
+                .violation_cost()
+                .context("Total weight of the objective exceeds the maximal integer value")?;
+            Some((cost, offset))
+        }
+        None => None,
+    };
+    Ok(Encoding { model, vars, objective })
+}
+
+fn int(value: i64) -> Result<IntCst> {
+    IntCst::try_from(value).with_context(|| format!("Integer {value} exceeds the maximal integer value"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn optimum(input: &str) -> Option<LongCst> {
+        let encoding = encode(&Opb::parse(input).unwrap()).unwrap();
+        let (cost, offset) = encoding.objective.unwrap();
+        let mut solver = Solver::new(encoding.model);
+        solver
+            .minimize(cost)
+            .unwrap()
+            .map(|(value, _)| value as LongCst + offset)
+    }
+
+    #[test]
+    fn test_knapsack() {
+        // maximize the value of the selected items, with a capacity of 10
+        let input = "min: -6 x1 -5 x2 -8 x3 -9 x4 ;\n+5 x1 +3 x2 +4 x3 +6 x4 <= 10 ;\n";
+        assert_eq!(optimum(input), Some(-17));
+    }
+
+    #[test]
+    fn test_products_and_equalities() {
+        // x1 = x2 = x3 = 1 is forced by the equality, the product x1.x2 costs 3
+        let input = "min: +3 x1 x2 +1 ~x3 ;\n+1 x1 +1 x2 +1 x3 = 3 ;\n";
+        assert_eq!(optimum(input), Some(3));
+
+        let input = "min: +1 x1 ;\n+2 x1 +2 x2 >= 3 ;\n-1 x2 >= 0 ;\n";
+        assert_eq!(optimum(input), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        // normalized upper bound of 5e9
+        let input = "-2000000000 x1 -2000000000 x2 <= 1000000000 ;\n";
+        assert!(encode(&Opb::parse(input).unwrap()).is_err());
+
+        // total weight of the soft constraints of 4e9
+        let input = "min: -2000000000 x1 -2000000000 x2 ;\n";
+        assert!(encode(&Opb::parse(input).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Reader for weighted MaxSAT problems in the WCNF format of the MaxSAT evaluations.
+//!
+//! Both variants of the format are supported:
+//!  - the pre-2022 format, with a `p wcnf <vars> <clauses> [<top>]` header and where each clause is prefixed with
+//!    its weight. Clauses whose weight is at least `top` are hard.
+//!  - the current format, without header, where hard clauses are prefixed with `h` and soft ones with their weight.
+
+use anyhow::*;
+
+/// A weighted MaxSAT problem, where literals are given as non-zero DIMACS integers.
+#[derive(Debug, Default)]
+pub struct Wcnf {
+    /// Largest variable index appearing in the problem (or declared in the header).
+    pub num_vars: usize,
+    /// Clauses that must be satisfied.
+    pub hard: Vec<Vec<i64>>,
+    /// Clauses that should be satisfied, with the weight paid if they are not.
+    pub soft: Vec<(Vec<i64>, u64)>,
+}
+
+impl Wcnf {
+    pub fn parse(input: &str) -> Result<Wcnf> {
+        let mut wcnf = Wcnf::default();
+        // weight above which a clause is hard, as declared in the header of the pre-2022 format
+        let mut top: Option<u64> = None;
+        let mut tokens = Vec::new();
+        for (line_num, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            } else if line.starts_with('p') {
+                let header: Vec<&str> = line.split_whitespace().collect();
+                ensure!(
+                    header.len() >= 4 && header[1] == "wcnf",
+                    "Invalid header on line {}: {line}",
+                    line_num + 1
+                );
+                wcnf.num_vars = header[2].parse().context("Invalid number of variables in header")?;
+                top = header
+                    .get(4)
+                    .map(|t| t.parse())
+                    .transpose()
+                    .context("Invalid top weight")?;
+            } else {
+                tokens.extend(line.split_whitespace());
+            }
+        }
+
+        // clauses may span several lines: process them as a stream of tokens
+        let mut tokens = tokens.into_iter();
+        while let Some(weight) = tokens.next() {
+            let weight = if weight == "h" {
+                None
+            } else {
+                let w: u64 = weight.parse().with_context(|| format!("Invalid weight: {weight}"))?;
+                top.is_none_or(|top| w < top).then_some(w)
+            };
+            let mut clause = Vec::new();
+            loop {
+                let lit = tokens.next().context("Unterminated clause")?;
+                let lit: i64 = lit.parse().with_context(|| format!("Invalid literal: {lit}"))?;
+                if lit == 0 {
+                    break;
+                }
+                wcnf.num_vars = wcnf.num_vars.max(lit.unsigned_abs() as usize);
+                clause.push(lit);
+            }
+            match weight {
+                Some(w) => wcnf.soft.push((clause, w)),
+                None => wcnf.hard.push(clause),
+            }
+        }
+        Ok(wcnf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_format() {
+        let input = "c comment\nh 1 2 0\nh -1 -2\n 0\n3 1 0\n5 -2 3 0\n";
+        let wcnf = Wcnf::parse(input).unwrap();
+        assert_eq!(wcnf.num_vars, 3);
+        assert_eq!(wcnf.hard, vec![vec![1, 2], vec![-1, -2]]);
+        assert_eq!(wcnf.soft, vec![(vec![1], 3), (vec![-2, 3], 5)]);
+    }
+
+    #[test]
+    fn test_legacy_format() {
+        let input = "p wcnf 4 3 10\n10 1 2 0\n3 -1 0\n2 4 0\n";
+        let wcnf = Wcnf::parse(input).unwrap();
+        assert_eq!(wcnf.num_vars, 4);
+        assert_eq!(wcnf.hard, vec![vec![1, 2]]);
+        assert_eq!(wcnf.soft, vec![(vec![-1], 3), (vec![4], 2)]);
+
+        // without top weight, all clauses are soft
+        let wcnf = Wcnf::parse("p wcnf 2 1\n10 1 2 0\n").unwrap();
+        assert!(wcnf.hard.is_empty());
+        assert_eq!(wcnf.soft.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Wcnf::parse("h 1 2").is_err());
+        assert!(Wcnf::parse("x 1 0").is_err());
+        assert!(Wcnf::parse("p cnf 2 1\n1 2 0\n").is_err());
+    }
+}
@@ -1,12 +1,16 @@
 mod parser;
 mod problem;
+mod rcpsp;
 mod search;
 
-use crate::problem::ProblemKind;
+use crate::problem::{Problem, ProblemKind};
+use crate::rcpsp::Rcpsp;
 use crate::search::{SearchStrategy, Solver, Var};
 use anyhow::*;
+use aries::core::IntCst;
 use aries::model::extensions::{AssignmentExt, Shaped};
 use aries::model::lang::IVar;
+use aries::solver::optimization::{OptimizationMode, OptimizationOptions};
 use aries::solver::parallel::SolverResult;
 use std::fmt::Write;
 use std::fs;
@@ -17,7 +21,7 @@ use walkdir::WalkDir;
 #[derive(Debug, StructOpt)]
 #[structopt(name = "aries-scheduler")]
 pub struct Opt {
-    /// Kind of the problem to be solved in {jobshop, openshop}
+    /// Kind of the problem to be solved in {jobshop, openshop, rcpsp}
     kind: ProblemKind,
     /// File containing the instance to solve.
     file: String,
@@ -31,9 +35,12 @@ pub struct Opt {
     lower_bound: u32,
     #[structopt(long = "upper-bound", default_value = "100000")]
     upper_bound: u32,
-    /// Search strategy to use in {activity, est, parallel}
+    /// Search strategy to use in {activity, learning-rate, lns}
     #[structopt(long = "search", default_value = "learning-rate")]
     search: SearchStrategy,
+    /// Optimization algorithm in {linear, binary, lb-first, core}
+    #[structopt(long = "optimization", default_value = "linear")]
+    optimization: OptimizationMode,
     /// maximum runtime, in seconds.
     #[structopt(long = "timeout", short = "t")]
     timeout: Option<u32>,
@@ -49,7 +56,7 @@ fn main() -> Result<()> {
     } else {
         for entry in WalkDir::new(file).follow_links(true).into_iter().filter_map(|e| e.ok()) {
             let f_name = entry.file_name().to_string_lossy();
-            if f_name.ends_with(".txt") {
+            if f_name.ends_with(".txt") || f_name.ends_with(".sm") {
                 println!("{f_name}");
                 solve(opt.kind, &entry.path().to_string_lossy(), &opt);
             }
@@ -58,25 +65,97 @@ fn main() -> Result<()> {
     }
 }
 
+/// An instance of one of the supported problem kinds.
+enum Instance {
+    /// Jobshop or openshop problem
+    Shop(Problem),
+    Rcpsp(Rcpsp),
+}
+
+impl Instance {
+    fn makespan_lower_bound(&self) -> IntCst {
+        match self {
+            Instance::Shop(pb) => pb.makespan_lower_bound(),
+            Instance::Rcpsp(pb) => pb.makespan_lower_bound(),
+        }
+    }
+
+    fn start_variables(&self) -> Vec<Var> {
+        match self {
+            Instance::Shop(pb) => pb.start_variables(),
+            Instance::Rcpsp(pb) => pb.start_variables(),
+        }
+    }
+
+    fn resources(&self) -> Vec<Vec<Var>> {
+        match self {
+            Instance::Shop(pb) => pb.resources(),
+            Instance::Rcpsp(pb) => pb.resources(),
+        }
+    }
+
+    fn encode(&self, lower_bound: u32, upper_bound: u32) -> search::Model {
+        match self {
+            Instance::Shop(pb) => problem::encode(pb, lower_bound, upper_bound),
+            Instance::Rcpsp(pb) => rcpsp::encode(pb, lower_bound, upper_bound),
+        }
+    }
+
+    /// Formats the solution: in resource order for shop problems (each machine is given an ordered list of tasks to process)
+    /// and as a list of start times for RCPSP.
+    fn format_solution(&self, start_time: impl Fn(&Var) -> IntCst) -> String {
+        let mut formatted_solution = String::new();
+        match self {
+            Instance::Shop(pb) => {
+                for m in pb.machines() {
+                    // all tasks on this machine
+                    let mut tasks = Vec::new();
+                    for j in 0..pb.num_jobs {
+                        let task = Var::Start(j, m);
+                        tasks.push(((j, m), start_time(&task)));
+                    }
+                    // sort task by their start time
+                    tasks.sort_by_key(|(_task, start_time)| *start_time);
+                    write!(formatted_solution, "Machine {m}:\t").unwrap();
+                    for ((job, op), _) in tasks {
+                        write!(formatted_solution, "({job}, {op})\t").unwrap();
+                    }
+                    writeln!(formatted_solution).unwrap();
+                }
+            }
+            Instance::Rcpsp(pb) => {
+                for (i, task) in pb.start_variables().iter().enumerate() {
+                    writeln!(formatted_solution, "Activity {}:\t{}", i + 1, start_time(task)).unwrap();
+                }
+            }
+        }
+        formatted_solution
+    }
+}
+
 fn solve(kind: ProblemKind, instance: &str, opt: &Opt) {
     let deadline = opt.timeout.map(|dur| Instant::now() + Duration::from_secs(dur as u64));
     let start_time = std::time::Instant::now();
     let filecontent = fs::read_to_string(instance).expect("Cannot read file");
     let pb = match kind {
-        ProblemKind::OpenShop => parser::openshop(&filecontent),
-        ProblemKind::JobShop => parser::jobshop(&filecontent),
+        ProblemKind::OpenShop => Instance::Shop(parser::openshop(&filecontent)),
+        ProblemKind::JobShop => Instance::Shop(parser::jobshop(&filecontent)),
+        ProblemKind::Rcpsp => Instance::Rcpsp(parser::rcpsp(&filecontent)),
     };
-    assert_eq!(pb.kind, kind);
+    if let Instance::Shop(pb) = &pb {
+        assert_eq!(pb.kind, kind);
+    }
     // println!("{:?}", pb);
 
     let lower_bound = (opt.lower_bound).max(pb.makespan_lower_bound() as u32);
     println!("Initial lower bound: {lower_bound}");
 
-    let model = problem::encode(&pb, lower_bound, opt.upper_bound);
+    let model = pb.encode(lower_bound, opt.upper_bound);
     let makespan: IVar = IVar::new(model.shape.get_variable(&Var::Makespan).unwrap());
 
-    let solver = Solver::new(model);
-    let mut solver = search::get_solver(solver, opt.search, &pb);
+    let mut solver = Solver::new(model);
+    solver.set_optimization_options(OptimizationOptions { mode: opt.optimization });
+    let mut solver = search::get_solver(solver, opt.search, pb.start_variables(), pb.resources());
 
     let result = solver.minimize(makespan, deadline);
 
@@ -85,25 +164,10 @@ fn solve(kind: ProblemKind, instance: &str, opt: &Opt) {
             let optimum = solution.var_domain(makespan).lb;
             println!("Found optimal solution with makespan: {optimum}");
 
-            // Format the solution in resource order : each machine is given an ordered list of tasks to process.
-            let mut formatted_solution = String::new();
-            for m in pb.machines() {
-                // all tasks on this machine
-                let mut tasks = Vec::new();
-                for j in 0..pb.num_jobs {
-                    let task = Var::Start(j, m);
-                    let start_var = solver.get_int_var(&task).unwrap();
-                    let start_time = solution.var_domain(start_var).lb;
-                    tasks.push(((j, m), start_time));
-                }
-                // sort task by their start time
-                tasks.sort_by_key(|(_task, start_time)| *start_time);
-                write!(formatted_solution, "Machine {m}:\t").unwrap();
-                for ((job, op), _) in tasks {
-                    write!(formatted_solution, "({job}, {op})\t").unwrap();
-                }
-                writeln!(formatted_solution).unwrap();
-            }
+            let formatted_solution = pb.format_solution(|task| {
+                let start_var = solver.get_int_var(task).unwrap();
+                solution.var_domain(start_var).lb
+            });
             // println!("\n=== Solution (resource order) ===");
             // print!("{}", formatted_solution);
             // println!("=================================\n");
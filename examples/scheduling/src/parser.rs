@@ -1,4 +1,6 @@
 use crate::problem::*;
+use crate::rcpsp::Rcpsp;
+use aries::core::IntCst;
 
 fn is_comment(line: &str) -> bool {
     line.chars().any(|c| c == '#')
@@ -24,7 +26,7 @@ pub(crate) fn openshop(input: &str) -> Problem {
     let mut machines = Vec::with_capacity(num_machines * num_jobs);
     for _ in 0..num_jobs {
         for (op_id, duration) in ints(lines.next().unwrap()).enumerate() {
-            times.push(duration as i32);
+            times.push(duration as IntCst);
             machines.push(op_id);
         }
     }
@@ -56,3 +58,65 @@ pub(crate) fn jobshop(input: &str) -> Problem {
 
     Problem::new(ProblemKind::JobShop, num_jobs, num_machines, times, machines)
 }
+
+/// Parses a single-mode RCPSP instance in the PSPLIB format (`.sm` files).
+/// Only renewable resources are considered.
+pub(crate) fn rcpsp(input: &str) -> Rcpsp {
+    let mut lines = input.lines().map(|l| l.trim());
+
+    // skip lines until the one starting with the given prefix
+    fn skip_until<'a>(lines: &mut impl Iterator<Item = &'a str>, prefix: &str) -> &'a str {
+        lines
+            .find(|l| l.starts_with(prefix))
+            .unwrap_or_else(|| panic!("Missing section: {prefix}"))
+    }
+    // value following the last colon of the line
+    fn value(line: &str) -> usize {
+        let value = line.rsplit(':').next().unwrap();
+        value.split_whitespace().next().unwrap().parse().unwrap()
+    }
+
+    let num_activities = value(skip_until(&mut lines, "jobs (incl. supersource/sink )"));
+    let num_resources = value(skip_until(&mut lines, "- renewable"));
+
+    skip_until(&mut lines, "PRECEDENCE RELATIONS:");
+    lines.next(); // drop header
+    let mut successors = Vec::with_capacity(num_activities);
+    for _ in 0..num_activities {
+        let mut x = ints(lines.next().unwrap());
+        let _job = x.next().unwrap();
+        let num_modes = x.next().unwrap();
+        assert_eq!(num_modes, 1, "Multi-mode RCPSP instances are not supported");
+        let num_successors = x.next().unwrap();
+        let succs: Vec<usize> = x.map(|j| j - 1).collect();
+        assert_eq!(succs.len(), num_successors);
+        successors.push(succs);
+    }
+
+    skip_until(&mut lines, "REQUESTS/DURATIONS:");
+    lines.next(); // drop header
+    lines.next(); // drop "-----" line
+    let mut durations = Vec::with_capacity(num_activities);
+    let mut demands = Vec::with_capacity(num_activities);
+    for _ in 0..num_activities {
+        let mut x = ints(lines.next().unwrap());
+        let _job = x.next().unwrap();
+        let _mode = x.next().unwrap();
+        durations.push(x.next().unwrap() as IntCst);
+        demands.push(x.take(num_resources).map(|d| d as IntCst).collect());
+    }
+
+    skip_until(&mut lines, "RESOURCEAVAILABILITIES:");
+    lines.next(); // drop header
+    let capacities: Vec<IntCst> = ints(lines.next().unwrap())
+        .take(num_resources)
+        .map(|c| c as IntCst)
+        .collect();
+
+    Rcpsp {
+        capacities,
+        durations,
+        demands,
+        successors,
+    }
+}
@@ -1,11 +1,15 @@
 use crate::search::{Model, Var};
-use aries::model::lang::expr::leq;
+use aries::core::IntCst;
+use aries::model::lang::expr::{leq, no_overlap};
+use aries::model::lang::scheduling::Task;
 use aries::model::lang::IVar;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ProblemKind {
     JobShop,
     OpenShop,
+    /// Resource-Constrained Project Scheduling Problem
+    Rcpsp,
 }
 
 impl std::str::FromStr for ProblemKind {
@@ -15,6 +19,7 @@ impl std::str::FromStr for ProblemKind {
         match s {
             "jobshop" | "jsp" => Ok(ProblemKind::JobShop),
             "openshop" | "osp" => Ok(ProblemKind::OpenShop),
+            "rcpsp" | "psplib" => Ok(ProblemKind::Rcpsp),
             _ => Err(format!("Unrecognized problem kind: '{s}'")),
         }
     }
@@ -25,7 +30,7 @@ pub struct Op {
     pub job: u32,
     pub op_id: u32,
     pub machine: u32,
-    pub duration: i32,
+    pub duration: IntCst,
 }
 
 #[derive(Clone, Debug)]
@@ -41,7 +46,7 @@ impl Problem {
         kind: ProblemKind,
         num_jobs: usize,
         num_machines: usize,
-        times: Vec<i32>,
+        times: Vec<IntCst>,
         machines: Vec<usize>,
     ) -> Problem {
         let num_ops = num_jobs * num_machines;
@@ -74,7 +79,7 @@ impl Problem {
         self.operations[(job * self.num_machines + op) as usize]
     }
 
-    pub fn duration(&self, job: u32, op: u32) -> i32 {
+    pub fn duration(&self, job: u32, op: u32) -> IntCst {
         self.op(job, op).duration
     }
     pub fn machines(&self) -> impl Iterator<Item = u32> {
@@ -96,19 +101,31 @@ impl Problem {
         panic!("This job is missing a machine")
     }
 
-    pub(crate) fn operations(&self) -> &[Op] {
-        &self.operations
+    /// Labels of the start time variables of all operations.
+    pub fn start_variables(&self) -> Vec<Var> {
+        self.operations.iter().map(|op| Var::Start(op.job, op.op_id)).collect()
+    }
+
+    /// Labels of the start time variables of the operations processed on each machine.
+    pub fn resources(&self) -> Vec<Vec<Var>> {
+        self.machines()
+            .map(|m| self.jobs().map(|j| Var::Start(j, self.op_with_machine(j, m))).collect())
+            .collect()
     }
 
     /// Computes a lower bound on the makespan as the maximum of the operation durations in each
     /// job and on each machine.
-    pub fn makespan_lower_bound(&self) -> i32 {
-        let max_by_jobs: i32 = (0..self.num_jobs)
-            .map(|job| (0..self.num_machines).map(|task| self.duration(job, task)).sum::<i32>())
+    pub fn makespan_lower_bound(&self) -> IntCst {
+        let max_by_jobs: IntCst = (0..self.num_jobs)
+            .map(|job| {
+                (0..self.num_machines)
+                    .map(|task| self.duration(job, task))
+                    .sum::<IntCst>()
+            })
             .max()
             .unwrap();
 
-        let max_by_machine: i32 = self
+        let max_by_machine: IntCst = self
             .machines()
             .map(|m| {
                 (0..self.num_jobs)
@@ -126,8 +143,8 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32) -> Model
     let start = |model: &Model, j: u32, t: u32| IVar::new(model.shape.get_variable(&Var::Start(j, t)).unwrap());
     let end = |model: &Model, j: u32, t: u32| start(model, j, t) + pb.duration(j, t);
 
-    let lower_bound = lower_bound as i32;
-    let upper_bound = upper_bound as i32;
+    let lower_bound = lower_bound as IntCst;
+    let upper_bound = upper_bound as IntCst;
     let mut m = Model::new();
 
     let makespan_variable = m.new_ivar(lower_bound, upper_bound, Var::Makespan);
@@ -138,6 +155,15 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32) -> Model
         }
     }
     for machine in 0..(pb.num_machines) {
+        // global unary resource constraint, redundant with the precedences below, on which the search branches
+        let tasks: Vec<Task> = pb
+            .jobs()
+            .map(|j| {
+                let i = pb.op_with_machine(j, machine);
+                Task::new(start(&m, j, i), pb.duration(j, i), 1)
+            })
+            .collect();
+        m.enforce(no_overlap(tasks), []);
         for j1 in 0..pb.num_jobs {
             for j2 in (j1 + 1)..pb.num_jobs {
                 let i1 = pb.op_with_machine(j1, machine);
@@ -162,6 +188,10 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32) -> Model
         ProblemKind::OpenShop => {
             // enforce non-overlapping between tasks of the same job
             for j in 0..pb.num_jobs {
+                let tasks: Vec<Task> = (0..pb.num_machines)
+                    .map(|i| Task::new(start(&m, j, i), pb.duration(j, i), 1))
+                    .collect();
+                m.enforce(no_overlap(tasks), []);
                 for m1 in 0..pb.num_machines {
                     for m2 in (m1 + 1)..pb.num_machines {
                         let prec = m.new_bvar(Var::Prec(j, m1, j, m2));
@@ -171,6 +201,7 @@ pub(crate) fn encode(pb: &Problem, lower_bound: u32, upper_bound: u32) -> Model
                 }
             }
         }
+        ProblemKind::Rcpsp => unreachable!("RCPSP instances are encoded separately"),
     }
 
     m
@@ -0,0 +1,118 @@
+use crate::search::{Model, Var};
+use aries::core::IntCst;
+use aries::model::lang::expr::{cumulative, leq};
+use aries::model::lang::scheduling::Task;
+use aries::model::lang::IVar;
+
+/// A Resource-Constrained Project Scheduling Problem (RCPSP).
+///
+/// Each activity requires some amount of each renewable resource during its execution and can only start
+/// once all its predecessors are finished.
+/// Activity `i` is represented as a job with a single operation, its start time being `Var::Start(i, 0)`.
+#[derive(Clone, Debug)]
+pub struct Rcpsp {
+    /// Capacity of each renewable resource
+    pub capacities: Vec<IntCst>,
+    /// Duration of each activity
+    pub durations: Vec<IntCst>,
+    /// `demands[i][r]` is the quantity of resource `r` required by activity `i`
+    pub demands: Vec<Vec<IntCst>>,
+    /// `successors[i]` is the list of activities that can only start after the end of activity `i`
+    pub successors: Vec<Vec<usize>>,
+}
+
+impl Rcpsp {
+    pub fn num_activities(&self) -> usize {
+        self.durations.len()
+    }
+
+    pub fn num_resources(&self) -> usize {
+        self.capacities.len()
+    }
+
+    /// Labels of the start time variables of all activities.
+    pub fn start_variables(&self) -> Vec<Var> {
+        (0..self.num_activities()).map(|i| Var::Start(i as u32, 0)).collect()
+    }
+
+    /// Labels of the start time variables of the activities requiring each resource.
+    pub fn resources(&self) -> Vec<Vec<Var>> {
+        (0..self.num_resources())
+            .map(|r| {
+                (0..self.num_activities())
+                    .filter(|&i| self.demands[i][r] > 0)
+                    .map(|i| Var::Start(i as u32, 0))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Computes a lower bound on the makespan as the maximum of the critical path length and of
+    /// the energy required on each resource.
+    pub fn makespan_lower_bound(&self) -> IntCst {
+        // earliest start time of each activity, computed by relaxing precedences until a fixpoint
+        // (the precedence graph is acyclic and thus has at most n levels)
+        let mut est = vec![0; self.num_activities()];
+        for _ in 0..self.num_activities() {
+            let mut updated = false;
+            for i in 0..self.num_activities() {
+                for &j in &self.successors[i] {
+                    if est[j] < est[i] + self.durations[i] {
+                        est[j] = est[i] + self.durations[i];
+                        updated = true;
+                    }
+                }
+            }
+            if !updated {
+                break;
+            }
+        }
+        let critical_path = (0..self.num_activities())
+            .map(|i| est[i] + self.durations[i])
+            .max()
+            .unwrap_or(0);
+
+        let energy_bound = (0..self.num_resources())
+            .filter(|&r| self.capacities[r] > 0)
+            .map(|r| {
+                let energy: IntCst = (0..self.num_activities())
+                    .map(|i| self.durations[i] * self.demands[i][r])
+                    .sum();
+                (energy + self.capacities[r] - 1) / self.capacities[r]
+            })
+            .max()
+            .unwrap_or(0);
+
+        critical_path.max(energy_bound)
+    }
+}
+
+pub(crate) fn encode(pb: &Rcpsp, lower_bound: u32, upper_bound: u32) -> Model {
+    let lower_bound = lower_bound as IntCst;
+    let upper_bound = upper_bound as IntCst;
+    let mut m = Model::new();
+
+    let makespan = m.new_ivar(lower_bound, upper_bound, Var::Makespan);
+    let starts: Vec<IVar> = pb
+        .start_variables()
+        .into_iter()
+        .map(|label| m.new_ivar(0, upper_bound, label))
+        .collect();
+
+    for i in 0..pb.num_activities() {
+        m.enforce(leq(starts[i] + pb.durations[i], makespan), []);
+        for &j in &pb.successors[i] {
+            m.enforce(leq(starts[i] + pb.durations[i], starts[j]), []);
+        }
+    }
+
+    for r in 0..pb.num_resources() {
+        let tasks: Vec<Task> = (0..pb.num_activities())
+            .filter(|&i| pb.demands[i][r] > 0 && pb.durations[i] > 0)
+            .map(|i| Task::new(starts[i], pb.durations[i], pb.demands[i][r]))
+            .collect();
+        m.enforce(cumulative(tasks, pb.capacities[r]), []);
+    }
+
+    m
+}
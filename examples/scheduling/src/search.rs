@@ -1,9 +1,9 @@
 mod greedy;
 
-use crate::problem::Problem;
 use crate::search::greedy::EstBrancher;
 use aries::core::*;
 use aries::model::extensions::Shaped;
+use aries::solver::lns::{Lns, RandomVars, ResourceBased, TemporalWindow};
 use aries::solver::search::activity::Heuristic;
 use aries::solver::search::combinators::{CombinatorExt, UntilFirstConflict};
 use aries::solver::search::conflicts::{ActiveLiterals, ConflictBasedBrancher};
@@ -37,6 +37,8 @@ pub enum SearchStrategy {
     Activity,
     /// greedy earliest-starting-time then LRB with solution guidance
     LearningRate,
+    /// LRB search in parallel with a worker doing Large Neighbourhood Search
+    Lns,
 }
 impl FromStr for SearchStrategy {
     type Err = String;
@@ -45,6 +47,7 @@ impl FromStr for SearchStrategy {
         match s {
             "lrb" | "learning-rate" => Ok(SearchStrategy::LearningRate),
             "vsids" | "activity" => Ok(SearchStrategy::Activity),
+            "lns" => Ok(SearchStrategy::Lns),
             e => Err(format!("Unrecognized option: '{e}'")),
         }
     }
@@ -61,9 +64,54 @@ impl Heuristic<Var> for ResourceOrderingFirst {
     }
 }
 
+/// Builds an LNS optimizer whose neighbourhoods relax tasks at random, in a time window or on a given resource.
+///
+/// The variables fixed by the LNS are the ordering variables or, if there are none, the start times of the tasks.
+fn lns(s: &Solver, tasks: &[Var], resources: &[Vec<Var>]) -> Lns {
+    let var = |label: &Var| s.model.shape.get_variable(label).unwrap();
+    let precedences: Vec<(VarRef, Var, Var)> = s
+        .model
+        .state
+        .variables()
+        .filter_map(|v| match s.model.get_label(v) {
+            Some(&Var::Prec(j1, i1, j2, i2)) => Some((v, Var::Start(j1, i1), Var::Start(j2, i2))),
+            _ => None,
+        })
+        .collect();
+    // variables to relax along with a task
+    let related = |task: &Var| -> Vec<VarRef> {
+        if precedences.is_empty() {
+            vec![var(task)]
+        } else {
+            precedences
+                .iter()
+                .filter(|(_, a, b)| a == task || b == task)
+                .map(|(v, _, _)| *v)
+                .collect()
+        }
+    };
+    let vars = if precedences.is_empty() {
+        tasks.iter().map(var).collect()
+    } else {
+        precedences.iter().map(|(v, _, _)| *v).collect()
+    };
+    let activities = tasks.iter().map(|t| (var(t), related(t))).collect();
+    let resources = resources
+        .iter()
+        .map(|tasks| tasks.iter().flat_map(related).collect())
+        .collect();
+    Lns::new(vars, 500)
+        .with_neighbourhood(RandomVars::new(0.4))
+        .with_neighbourhood(TemporalWindow::new(activities, 0.4))
+        .with_neighbourhood(ResourceBased::new(resources))
+}
+
 /// Builds a solver for the given strategy.
-pub fn get_solver(base: Solver, strategy: SearchStrategy, pb: &Problem) -> ParSolver {
-    let first_est: Brancher<Var> = Box::new(UntilFirstConflict::new(Box::new(EstBrancher::new(pb))));
+/// `tasks` are the labels of the start time variables of all tasks of the problem
+/// and `resources` are the tasks requiring each resource.
+pub fn get_solver(base: Solver, strategy: SearchStrategy, tasks: Vec<Var>, resources: Vec<Vec<Var>>) -> ParSolver {
+    let est = EstBrancher::new(tasks.clone());
+    let first_est: Brancher<Var> = Box::new(UntilFirstConflict::new(Box::new(est.clone())));
 
     let base_solver = Box::new(base);
 
@@ -77,6 +125,13 @@ pub fn get_solver(base: Solver, strategy: SearchStrategy, pb: &Problem) -> ParSo
                 _ => None,
             })
             .collect();
+        if decision_lits.is_empty() {
+            // no ordering variables (e.g. cumulative resources), use the greedy EST strategy for the whole search
+            let est: Brancher<Var> = Box::new(est.clone());
+            let strat = est.with_restarts(100, 1.2).and_then(Box::new(LexicalMinValue::new()));
+            s.set_brancher_boxed(strat);
+            return;
+        }
         let ema: Brancher<Var> = Box::new(ConflictBasedBrancher::with(decision_lits, params));
         let ema = ema.with_restarts(100, 1.2);
         let strat = first_est
@@ -105,5 +160,20 @@ pub fn get_solver(base: Solver, strategy: SearchStrategy, pb: &Problem) -> ParSo
                 },
             )
         }),
+        SearchStrategy::Lns => {
+            let lns = lns(&base_solver, &tasks, &resources);
+            ParSolver::new(base_solver, 2, |id, s| {
+                make_solver(
+                    s,
+                    conflicts::Params {
+                        heuristic: conflicts::Heuristic::LearningRate,
+                        active: ActiveLiterals::Reasoned,
+                    },
+                );
+                if id == 1 {
+                    s.set_lns(lns.clone());
+                }
+            })
+        }
     }
 }
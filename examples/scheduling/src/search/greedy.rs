@@ -1,6 +1,5 @@
 // ============= Forward progression ===========
 
-use crate::problem::{Op, Problem};
 use crate::search::Model;
 use crate::Var;
 use aries::backtrack::{Backtrack, DecLvl, DecisionLevelTracker};
@@ -11,14 +10,15 @@ use aries::solver::stats::Stats;
 
 #[derive(Clone)]
 pub struct EstBrancher {
-    pb: Problem,
+    /// Labels of the start time variables of all tasks
+    tasks: Vec<Var>,
     lvl: DecisionLevelTracker,
 }
 
 impl EstBrancher {
-    pub fn new(pb: &Problem) -> Self {
+    pub fn new(tasks: Vec<Var>) -> Self {
         EstBrancher {
-            pb: pb.clone(),
+            tasks,
             lvl: Default::default(),
         }
     }
@@ -27,7 +27,7 @@ impl EstBrancher {
 impl SearchControl<Var> for EstBrancher {
     fn next_decision(&mut self, _stats: &Stats, model: &Model) -> Option<Decision> {
         // among the task with the smallest "earliest starting time (est)" pick the one that has the least slack
-        let best = active_tasks(&self.pb, model).min_by_key(|(_var, est, lst)| (*est, *lst));
+        let best = active_tasks(&self.tasks, model).min_by_key(|(_var, est, lst)| (*est, *lst));
 
         // decision is to set the start time to the selected task to the smallest possible value.
         // if no task was selected, it means that they are all instantiated and we have a complete schedule
@@ -59,17 +59,14 @@ impl Backtrack for EstBrancher {
 ///  - `est` is its lower bound (the earliest start time of the task)
 ///  - `lst` is its upper bound (the latest start time of the task)
 ///  - `est < lst`: the start time of the task has not been decided yet.
-fn active_tasks<'a>(pb: &'a Problem, model: &'a Model) -> impl Iterator<Item = (VarRef, IntCst, IntCst)> + 'a {
-    pb.operations()
-        .iter()
-        .copied()
-        .filter_map(move |Op { job, op_id, .. }| {
-            let v = model.shape.get_variable(&Var::Start(job, op_id)).unwrap();
-            let (lb, ub) = model.domain_of(v);
-            if lb < ub {
-                Some((v, lb, ub))
-            } else {
-                None
-            }
-        })
+fn active_tasks<'a>(tasks: &'a [Var], model: &'a Model) -> impl Iterator<Item = (VarRef, IntCst, IntCst)> + 'a {
+    tasks.iter().filter_map(move |task| {
+        let v = model.shape.get_variable(task).unwrap();
+        let (lb, ub) = model.domain_of(v);
+        if lb < ub {
+            Some((v, lb, ub))
+        } else {
+            None
+        }
+    })
 }
@@ -0,0 +1,45 @@
+; Job-shop problem with 3 jobs and 3 machines, whose optimal makespan is 11.
+; Task t_j_i is the i-th task of job j. Each task is given as (machine, duration):
+;   job 0: (0, 3) (1, 2) (2, 2)
+;   job 1: (0, 2) (2, 1) (1, 4)
+;   job 2: (1, 4) (2, 3)
+(set-logic QF_IDL)
+(declare-const makespan Int)
+(declare-const t_0_0 Int)
+(declare-const t_0_1 Int)
+(declare-const t_0_2 Int)
+(declare-const t_1_0 Int)
+(declare-const t_1_1 Int)
+(declare-const t_1_2 Int)
+(declare-const t_2_0 Int)
+(declare-const t_2_1 Int)
+; tasks start after the origin and end before the makespan
+(assert (and (>= t_0_0 0) (<= (- t_0_0 makespan) (- 3))))
+(assert (and (>= t_0_1 0) (<= (- t_0_1 makespan) (- 2))))
+(assert (and (>= t_0_2 0) (<= (- t_0_2 makespan) (- 2))))
+(assert (and (>= t_1_0 0) (<= (- t_1_0 makespan) (- 2))))
+(assert (and (>= t_1_1 0) (<= (- t_1_1 makespan) (- 1))))
+(assert (and (>= t_1_2 0) (<= (- t_1_2 makespan) (- 4))))
+(assert (and (>= t_2_0 0) (<= (- t_2_0 makespan) (- 4))))
+(assert (and (>= t_2_1 0) (<= (- t_2_1 makespan) (- 3))))
+; tasks of a job are executed in sequence
+(assert (>= (- t_0_1 t_0_0) 3))
+(assert (>= (- t_0_2 t_0_1) 2))
+(assert (>= (- t_1_1 t_1_0) 2))
+(assert (>= (- t_1_2 t_1_1) 1))
+(assert (>= (- t_2_1 t_2_0) 4))
+; tasks on the same machine do not overlap
+(assert (or (>= (- t_1_0 t_0_0) 3) (>= (- t_0_0 t_1_0) 2)))
+(assert (or (>= (- t_1_2 t_0_1) 2) (>= (- t_0_1 t_1_2) 4)))
+(assert (or (>= (- t_2_0 t_0_1) 2) (>= (- t_0_1 t_2_0) 4)))
+(assert (or (>= (- t_1_1 t_0_2) 2) (>= (- t_0_2 t_1_1) 1)))
+(assert (or (>= (- t_2_1 t_0_2) 2) (>= (- t_0_2 t_2_1) 3)))
+(assert (or (>= (- t_2_1 t_1_1) 1) (>= (- t_1_1 t_2_1) 3)))
+(assert (or (>= (- t_2_0 t_1_2) 4) (>= (- t_1_2 t_2_0) 4)))
+(push 1)
+(assert (<= makespan 10))
+(check-sat)
+(pop 1)
+(assert (<= makespan 11))
+(check-sat)
+(get-model)
@@ -0,0 +1,776 @@
+//! Translation of SMT-LIB terms into an aries model.
+//!
+//! Boolean terms are encoded as literals and integer terms as linear expressions over integer variables.
+//! Comparisons of linear expressions are translated depending on their shape:
+//!  - a bound on a single variable is a literal of the variable,
+//!  - a bound on the difference of two variables (difference logic) is handled by the STN reasoner,
+//!  - other linear constraints are handled by the CP reasoner (through an auxiliary variable when reified).
+//!
+//! Values of finite sorts (datatypes with only nullary constructors) are encoded as the index of their constructor.
+//! Their equalities are handled by the equality reasoner.
+
+use crate::sexpr::{quote, SExpr};
+use anyhow::*;
+use aries::core::{IntCst, Lit, LongCst, INT_CST_MAX, INT_CST_MIN};
+use aries::model::extensions::{AssignmentExt, SavedAssignment};
+use aries::model::lang::expr::{and, or};
+use aries::model::lang::linear::{LinearSum, LinearTerm};
+use aries::model::lang::IVar;
+use aries::reif::ReifExpr;
+use std::collections::HashMap;
+
+pub type Solver = aries::solver::Solver<String>;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Sort {
+    Bool,
+    Int,
+    /// A finite sort, identified by its index.
+    Finite(usize),
+}
+
+/// Encoding of a term.
+#[derive(Clone, Debug)]
+pub enum Value {
+    Bool(Lit),
+    Int(Lin),
+    /// Index of the constructor of a finite sort, either constant or given by a variable.
+    Finite(usize, FiniteValue),
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum FiniteValue {
+    Cst(IntCst),
+    Var(IVar),
+}
+
+/// Value of a term in a solution.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Constant {
+    Bool(bool),
+    Int(LongCst),
+    Finite(usize, IntCst),
+}
+
+/// A linear expression `Σ factor * var + constant`.
+#[derive(Clone, Debug, Default)]
+pub struct Lin {
+    terms: Vec<(IntCst, IVar)>,
+    constant: IntCst,
+}
+
+impl Lin {
+    fn constant(constant: IntCst) -> Lin {
+        Lin {
+            terms: Vec::new(),
+            constant,
+        }
+    }
+
+    fn var(var: IVar) -> Lin {
+        Lin {
+            terms: vec![(1, var)],
+            constant: 0,
+        }
+    }
+
+    fn as_constant(&self) -> Option<IntCst> {
+        self.terms.is_empty().then_some(self.constant)
+    }
+
+    fn plus(mut self, other: Lin) -> Result<Lin> {
+        self.terms.extend(other.terms);
+        self.constant = self.constant.checked_add(other.constant).context("Integer overflow")?;
+        Ok(self)
+    }
+
+    fn times(self, k: IntCst) -> Result<Lin> {
+        let overflow = || anyhow!("Integer overflow");
+        Ok(Lin {
+            terms: self
+                .terms
+                .into_iter()
+                .map(|(f, v)| Ok((f.checked_mul(k).ok_or_else(overflow)?, v)))
+                .collect::<Result<_>>()?,
+            constant: self.constant.checked_mul(k).ok_or_else(overflow)?,
+        })
+    }
+
+    fn minus(self, other: Lin) -> Result<Lin> {
+        self.plus(other.times(-1)?)
+    }
+
+    /// Merges the terms on the same variable and removes the null ones.
+    fn normalized(mut self) -> Result<Lin> {
+        self.terms.sort_by_key(|(_, v)| *v);
+        let mut terms: Vec<(IntCst, IVar)> = Vec::with_capacity(self.terms.len());
+        for (f, v) in self.terms {
+            match terms.last_mut() {
+                Some((g, w)) if *w == v => *g = g.checked_add(f).context("Integer overflow")?,
+                _ => terms.push((f, v)),
+            }
+        }
+        terms.retain(|(f, _)| *f != 0);
+        Ok(Lin {
+            terms,
+            constant: self.constant,
+        })
+    }
+
+    fn sum(&self) -> LinearSum {
+        let mut sum = LinearSum::zero();
+        for &(f, v) in &self.terms {
+            sum += LinearTerm::int(f, v, Lit::TRUE);
+        }
+        sum
+    }
+}
+
+fn gcd(a: IntCst, b: IntCst) -> IntCst {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A constraint `Σ factor * var <= ub` where the factors have no common divisor.
+struct Leq {
+    sum: Lin,
+    ub: IntCst,
+}
+
+impl Leq {
+    /// Normal form of the constraint `lin <= 0`, or its truth value if it has no variable.
+    fn new(lin: Lin) -> Result<std::result::Result<Leq, bool>> {
+        let lin = lin.normalized()?;
+        if lin.terms.is_empty() {
+            return Ok(Err(lin.constant <= 0));
+        }
+        let divisor = lin.terms.iter().fold(0, |acc, (f, _)| gcd(acc, *f));
+        let ub = (-(lin.constant as LongCst)).div_euclid(divisor as LongCst);
+        Ok(std::result::Result::Ok(Leq {
+            sum: Lin {
+                terms: lin.terms.into_iter().map(|(f, v)| (f / divisor, v)).collect(),
+                constant: 0,
+            },
+            ub: IntCst::try_from(ub).context("Integer overflow")?,
+        }))
+    }
+}
+
+/// Symbols in scope: finite sorts, constants and constructors.
+#[derive(Clone, Default)]
+struct Symbols {
+    /// Finite sorts, with the names of their constructors.
+    sorts: Vec<(String, Vec<String>)>,
+    /// Value of each constant symbol (declared or defined) and of each constructor.
+    values: HashMap<String, Value>,
+    /// Declared constants, in the order of their declaration.
+    declared: Vec<(String, Sort)>,
+}
+
+/// Translates SMT-LIB terms into constraints of a solver.
+///
+/// Asserted terms are enforced in the current assertion level of the solver, while the definitions of the
+/// auxiliary variables introduced by the encoding are valid at all levels.
+pub struct Encoder {
+    pub solver: Solver,
+    symbols: Symbols,
+    /// Symbols in scope when each of the open assertion levels was pushed.
+    saved: Vec<Symbols>,
+    /// Auxiliary variables standing for linear sums, to avoid introducing several ones for the same sum.
+    sums: HashMap<Vec<(IntCst, IVar)>, IVar>,
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Encoder {
+            solver: Solver::new(aries::model::Model::new()),
+            symbols: Symbols::default(),
+            saved: Vec::new(),
+            sums: HashMap::new(),
+        }
+    }
+}
+
+type Env<T> = Vec<(String, T)>;
+
+fn lookup<'a, T>(env: &'a Env<T>, name: &str) -> Option<&'a T> {
+    env.iter().rev().find(|(n, _)| n == name).map(|(_, v)| v)
+}
+
+pub fn symbol(e: &SExpr) -> Result<&str> {
+    e.as_atom().with_context(|| format!("Expected a symbol but got {e}"))
+}
+
+impl Encoder {
+    /// Opens a new assertion level.
+    pub fn push(&mut self) {
+        self.solver.push();
+        self.saved.push(self.symbols.clone());
+    }
+
+    /// Closes the innermost assertion level, removing its assertions and the symbols declared in it.
+    pub fn pop(&mut self) {
+        self.solver.pop();
+        self.symbols = self.saved.pop().expect("No assertion level to pop");
+    }
+
+    /// Declared constants, in the order of their declaration.
+    pub fn declared(&self) -> &[(String, Sort)] {
+        &self.symbols.declared
+    }
+
+    pub fn sort(&self, sort: &SExpr) -> Result<Sort> {
+        match symbol(sort)? {
+            "Bool" => Ok(Sort::Bool),
+            "Int" => Ok(Sort::Int),
+            name => match self.symbols.sorts.iter().position(|(n, _)| n == name) {
+                Some(i) => Ok(Sort::Finite(i)),
+                None => bail!("Unsupported sort: {sort}"),
+            },
+        }
+    }
+
+    pub fn sort_name(&self, sort: Sort) -> &str {
+        match sort {
+            Sort::Bool => "Bool",
+            Sort::Int => "Int",
+            Sort::Finite(i) => &self.symbols.sorts[i].0,
+        }
+    }
+
+    fn check_fresh(&self, name: &str) -> Result<()> {
+        let reserved = ["true", "false"].contains(&name) || self.symbols.sorts.iter().any(|(n, _)| n == name);
+        ensure!(
+            !reserved && !self.symbols.values.contains_key(name),
+            "Symbol {name} is already defined"
+        );
+        Ok(())
+    }
+
+    /// Declares a finite sort from the declaration of a datatype whose constructors have no selectors,
+    /// e.g. `((red) (green) (blue))`.
+    pub fn declare_finite_sort(&mut self, name: &str, constructors: &SExpr) -> Result<()> {
+        self.check_fresh(name)?;
+        let constructors = constructors
+            .as_list()
+            .with_context(|| format!("Invalid datatype declaration: {constructors}"))?;
+        ensure!(!constructors.is_empty(), "Datatype {name} has no constructor");
+        let sort = self.symbols.sorts.len();
+        let mut names = Vec::with_capacity(constructors.len());
+        for (i, c) in constructors.iter().enumerate() {
+            let c = match c.as_list() {
+                Some([c]) => symbol(c)?,
+                _ => bail!("Unsupported constructor {c}: only constructors without selectors are supported"),
+            };
+            self.check_fresh(c)?;
+            self.symbols
+                .values
+                .insert(c.to_string(), Value::Finite(sort, FiniteValue::Cst(i as IntCst)));
+            names.push(c.to_string());
+        }
+        self.symbols.sorts.push((name.to_string(), names));
+        Ok(())
+    }
+
+    pub fn declare_const(&mut self, name: &str, sort: Sort) -> Result<()> {
+        self.check_fresh(name)?;
+        let label = name.to_string();
+        let value = match sort {
+            Sort::Bool => Value::Bool(self.solver.model.new_bvar(label).true_lit()),
+            Sort::Int => Value::Int(Lin::var(self.solver.model.new_ivar(INT_CST_MIN, INT_CST_MAX, label))),
+            Sort::Finite(i) => {
+                let size = self.symbols.sorts[i].1.len() as IntCst;
+                Value::Finite(i, FiniteValue::Var(self.solver.model.new_ivar(0, size - 1, label)))
+            }
+        };
+        self.symbols.values.insert(name.to_string(), value);
+        self.symbols.declared.push((name.to_string(), sort));
+        Ok(())
+    }
+
+    pub fn define_const(&mut self, name: &str, sort: Sort, body: &SExpr) -> Result<()> {
+        self.check_fresh(name)?;
+        let value = self.encode(body, &mut Vec::new())?;
+        ensure!(
+            self.sort_of(&value) == sort,
+            "Definition of {name} is not of sort {}",
+            self.sort_name(sort)
+        );
+        self.symbols.values.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Makes the symbol a name of the term.
+    pub fn name(&mut self, name: &str, value: Value) -> Result<()> {
+        self.check_fresh(name)?;
+        self.symbols.values.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Enforces the Boolean term.
+    /// Conjunctions and linear constraints are directly enforced, instead of being reified.
+    pub fn assert(&mut self, term: &SExpr) -> Result<()> {
+        match term.as_application() {
+            Some(("and", args)) => args.iter().try_for_each(|a| self.assert(a)),
+            Some((op @ ("<=" | "<" | ">=" | ">"), args)) if args.len() == 2 => {
+                let env = &mut Vec::new();
+                let (a, b) = (self.int(&args[0], env)?, self.int(&args[1], env)?);
+                match Leq::new(comparison(op, a, b)?)? {
+                    Result::Ok(leq) => self.solver.enforce(leq.sum.sum().leq(leq.ub), []),
+                    Err(value) => self.solver.enforce(Lit::from(value), []),
+                }
+                Ok(())
+            }
+            _ => {
+                let lit = self.bool(term, &mut Vec::new())?;
+                self.solver.enforce(lit, []);
+                Ok(())
+            }
+        }
+    }
+
+    fn sort_of(&self, value: &Value) -> Sort {
+        match value {
+            Value::Bool(_) => Sort::Bool,
+            Value::Int(_) => Sort::Int,
+            Value::Finite(s, _) => Sort::Finite(*s),
+        }
+    }
+
+    pub fn bool(&mut self, term: &SExpr, env: &mut Env<Value>) -> Result<Lit> {
+        match self.encode(term, env)? {
+            Value::Bool(l) => Ok(l),
+            _ => bail!("Expected a Boolean term but got {term}"),
+        }
+    }
+
+    fn int(&mut self, term: &SExpr, env: &mut Env<Value>) -> Result<Lin> {
+        match self.encode(term, env)? {
+            Value::Int(lin) => Ok(lin),
+            _ => bail!("Expected an integer term but got {term}"),
+        }
+    }
+
+    pub fn encode(&mut self, term: &SExpr, env: &mut Env<Value>) -> Result<Value> {
+        let (op, args) = match term {
+            SExpr::Atom(a) => return self.encode_atom(a, env),
+            SExpr::List(_) => term
+                .as_application()
+                .with_context(|| format!("Unsupported term: {term}"))?,
+        };
+        let value = match op {
+            "let" => {
+                let (bindings, body) = let_bindings(args)?;
+                let mut values = Vec::with_capacity(bindings.len());
+                for (name, t) in bindings {
+                    values.push((name.to_string(), self.encode(t, env)?));
+                }
+                let size = env.len();
+                env.extend(values);
+                let result = self.encode(body, env);
+                env.truncate(size);
+                return result;
+            }
+            "!" => {
+                let (body, name) = annotated(args)?;
+                let value = self.encode(body, env)?;
+                if let Some(name) = name {
+                    self.name(name, value.clone())?;
+                }
+                return Ok(value);
+            }
+            "not" => match args {
+                [a] => Value::Bool(!self.bool(a, env)?),
+                _ => bail!("Invalid number of arguments in {term}"),
+            },
+            "and" | "or" | "=>" | "xor" => {
+                let lits = args.iter().map(|a| self.bool(a, env)).collect::<Result<Vec<_>>>()?;
+                Value::Bool(self.bool_op(op, lits).with_context(|| format!("In {term}"))?)
+            }
+            "=" | "distinct" => {
+                ensure!(args.len() >= 2, "Invalid number of arguments in {term}");
+                let values = args.iter().map(|a| self.encode(a, env)).collect::<Result<Vec<_>>>()?;
+                let mut lits = Vec::new();
+                if op == "=" {
+                    for pair in values.windows(2) {
+                        lits.push(self.eq(&pair[0], &pair[1]).with_context(|| format!("In {term}"))?);
+                    }
+                } else {
+                    for (i, a) in values.iter().enumerate() {
+                        for b in &values[i + 1..] {
+                            lits.push(!self.eq(a, b).with_context(|| format!("In {term}"))?);
+                        }
+                    }
+                }
+                Value::Bool(self.solver.model.reify(and(lits)))
+            }
+            "ite" => match args {
+                [c, t, e] => {
+                    let c = self.bool(c, env)?;
+                    let t = self.encode(t, env)?;
+                    let e = self.encode(e, env)?;
+                    self.ite(c, t, e).with_context(|| format!("In {term}"))?
+                }
+                _ => bail!("Invalid number of arguments in {term}"),
+            },
+            "<=" | "<" | ">=" | ">" => {
+                ensure!(args.len() >= 2, "Invalid number of arguments in {term}");
+                let values = args.iter().map(|a| self.int(a, env)).collect::<Result<Vec<_>>>()?;
+                let mut lits = Vec::new();
+                for pair in values.windows(2) {
+                    let leq = comparison(op, pair[0].clone(), pair[1].clone())?;
+                    lits.push(self.leq_zero(leq)?);
+                }
+                Value::Bool(self.solver.model.reify(and(lits)))
+            }
+            "+" => {
+                let mut sum = Lin::constant(0);
+                for a in args {
+                    sum = sum.plus(self.int(a, env)?)?;
+                }
+                Value::Int(sum)
+            }
+            "-" => match args {
+                [] => bail!("Invalid number of arguments in {term}"),
+                [a] => Value::Int(self.int(a, env)?.times(-1)?),
+                [first, rest @ ..] => {
+                    let mut sum = self.int(first, env)?;
+                    for a in rest {
+                        sum = sum.minus(self.int(a, env)?)?;
+                    }
+                    Value::Int(sum)
+                }
+            },
+            "*" => {
+                let mut product = Lin::constant(1);
+                for a in args {
+                    let a = self.int(a, env)?;
+                    product = match (product.as_constant(), a.as_constant()) {
+                        (Some(k), _) => a.times(k)?,
+                        (_, Some(k)) => product.times(k)?,
+                        _ => bail!("Non-linear term: {term}"),
+                    };
+                }
+                Value::Int(product)
+            }
+            _ => bail!("Unsupported function {op} in {term}"),
+        };
+        Ok(value)
+    }
+
+    fn encode_atom(&mut self, atom: &str, env: &mut Env<Value>) -> Result<Value> {
+        if let Some(value) = lookup(env, atom).or_else(|| self.symbols.values.get(atom)) {
+            return Ok(value.clone());
+        }
+        match atom {
+            "true" => Ok(Value::Bool(Lit::TRUE)),
+            "false" => Ok(Value::Bool(Lit::FALSE)),
+            _ if atom.chars().all(|c| c.is_ascii_digit()) => {
+                let value: IntCst = atom.parse().with_context(|| format!("Integer overflow: {atom}"))?;
+                Ok(Value::Int(Lin::constant(value)))
+            }
+            _ => bail!("Unknown symbol: {}", quote(atom)),
+        }
+    }
+
+    fn bool_op(&mut self, op: &str, lits: Vec<Lit>) -> Result<Lit> {
+        let lit = match op {
+            "and" => self.solver.model.reify(and(lits)),
+            "or" => self.solver.model.reify(or(lits)),
+            "=>" => {
+                // right associative: (=> a b c) is (=> a (=> b c))
+                let (last, premises) = lits.split_last().context("Invalid number of arguments")?;
+                let clause: Vec<Lit> = premises.iter().map(|&l| !l).chain([*last]).collect();
+                self.solver.model.reify(or(clause))
+            }
+            _ => {
+                let (first, rest) = lits.split_first().context("Invalid number of arguments")?;
+                let mut acc = *first;
+                for &l in rest {
+                    acc = !self.bool_eq(acc, l);
+                }
+                acc
+            }
+        };
+        Ok(lit)
+    }
+
+    fn bool_eq(&mut self, a: Lit, b: Lit) -> Lit {
+        let ab = self.solver.model.reify(or([!a, b]));
+        let ba = self.solver.model.reify(or([!b, a]));
+        self.solver.model.reify(and([ab, ba]))
+    }
+
+    fn eq(&mut self, a: &Value, b: &Value) -> Result<Lit> {
+        let lit = match (a, b) {
+            (Value::Bool(a), Value::Bool(b)) => self.bool_eq(*a, *b),
+            (Value::Int(a), Value::Int(b)) => {
+                let le = self.leq_zero(a.clone().minus(b.clone())?)?;
+                let ge = self.leq_zero(b.clone().minus(a.clone())?)?;
+                self.solver.model.reify(and([le, ge]))
+            }
+            (Value::Finite(s, a), Value::Finite(t, b)) if s == t => match (*a, *b) {
+                (FiniteValue::Cst(a), FiniteValue::Cst(b)) => Lit::from(a == b),
+                (FiniteValue::Var(x), FiniteValue::Cst(v)) | (FiniteValue::Cst(v), FiniteValue::Var(x)) => {
+                    self.solver.model.reify(ReifExpr::EqVal(x.into(), v))
+                }
+                (FiniteValue::Var(x), FiniteValue::Var(y)) if x == y => Lit::TRUE,
+                (FiniteValue::Var(x), FiniteValue::Var(y)) => {
+                    let (x, y) = if x < y { (x, y) } else { (y, x) };
+                    self.solver.model.reify(ReifExpr::Eq(x.into(), y.into()))
+                }
+            },
+            _ => bail!("Equality between terms of different sorts"),
+        };
+        Ok(lit)
+    }
+
+    fn ite(&mut self, c: Lit, t: Value, e: Value) -> Result<Value> {
+        if c == Lit::TRUE {
+            return Ok(t);
+        } else if c == Lit::FALSE {
+            return Ok(e);
+        }
+        let result = match (&t, &e) {
+            (Value::Bool(t), Value::Bool(e)) => {
+                let then = self.solver.model.reify(and([c, *t]));
+                let otherwise = self.solver.model.reify(and([!c, *e]));
+                Value::Bool(self.solver.model.reify(or([then, otherwise])))
+            }
+            (Value::Int(a), Value::Int(b)) => {
+                let (alb, aub) = self.bounds(a);
+                let (blb, bub) = self.bounds(b);
+                let var = self
+                    .solver
+                    .model
+                    .new_ivar(alb.min(blb), aub.max(bub), format!("ite{c:?}"));
+                Value::Int(Lin::var(var))
+            }
+            (Value::Finite(s, _), Value::Finite(r, _)) if s == r => {
+                let size = self.symbols.sorts[*s].1.len() as IntCst;
+                let var = self.solver.model.new_ivar(0, size - 1, format!("ite{c:?}"));
+                Value::Finite(*s, FiniteValue::Var(var))
+            }
+            _ => bail!("Branches of different sorts"),
+        };
+        if !matches!(result, Value::Bool(_)) {
+            // c => (result = t)  and  !c => (result = e)
+            let then = self.eq(&result, &t)?;
+            let otherwise = self.eq(&result, &e)?;
+            self.solver.model.enforce(or([!c, then]), []);
+            self.solver.model.enforce(or([c, otherwise]), []);
+        }
+        Ok(result)
+    }
+
+    fn bounds(&self, lin: &Lin) -> (IntCst, IntCst) {
+        let (mut lb, mut ub) = (lin.constant as LongCst, lin.constant as LongCst);
+        for &(f, v) in &lin.terms {
+            let (vlb, vub) = self.solver.model.domain_of(v);
+            let (a, b) = (f as LongCst * vlb as LongCst, f as LongCst * vub as LongCst);
+            lb += a.min(b);
+            ub += a.max(b);
+        }
+        let clamp = |v: LongCst| v.clamp(INT_CST_MIN as LongCst, INT_CST_MAX as LongCst) as IntCst;
+        (clamp(lb), clamp(ub))
+    }
+
+    /// Returns a literal that is true iff `lin <= 0`.
+    fn leq_zero(&mut self, lin: Lin) -> Result<Lit> {
+        let Leq { sum, ub } = match Leq::new(lin)? {
+            Result::Ok(leq) => leq,
+            Err(value) => return Ok(Lit::from(value)),
+        };
+        let lit = match sum.terms.as_slice() {
+            [(1, x)] => x.leq(ub),
+            [(-1, x)] => x.geq(-ub),
+            // difference of two variables, handled by the STN reasoner
+            [(1, _), (-1, _)] | [(-1, _), (1, _)] => self.solver.model.reify(sum.sum().leq(ub)),
+            _ => {
+                // the solver cannot reify other linear constraints, compare an auxiliary variable to the bound
+                let var = match self.sums.get(&sum.terms) {
+                    Some(&var) => var,
+                    None => {
+                        let (lb, ub) = self.bounds(&sum);
+                        let var = self.solver.model.new_ivar(lb, ub, format!("{}", sum.sum()));
+                        // the definition is not part of the current assertion level, the variable is kept after a pop
+                        let def = sum.clone().minus(Lin::var(var))?.sum();
+                        self.solver.model.enforce(def.clone().leq(0), []);
+                        self.solver.model.enforce(def.geq(0), []);
+                        self.sums.insert(sum.terms, var);
+                        var
+                    }
+                };
+                var.leq(ub)
+            }
+        };
+        Ok(lit)
+    }
+
+    /// Evaluates the term in the (fully instantiated) assignment.
+    pub fn eval(&self, term: &SExpr, env: &mut Env<Constant>, sol: &SavedAssignment) -> Result<Constant> {
+        let (op, args) = match term {
+            SExpr::Atom(a) => {
+                if let Some(value) = lookup(env, a) {
+                    return Ok(*value);
+                }
+                if let Some(value) = self.symbols.values.get(a.as_str()) {
+                    return Ok(self.value_in(value, sol));
+                }
+                return match a.as_str() {
+                    "true" => Ok(Constant::Bool(true)),
+                    "false" => Ok(Constant::Bool(false)),
+                    _ if a.chars().all(|c| c.is_ascii_digit()) => Ok(Constant::Int(
+                        a.parse().with_context(|| format!("Integer overflow: {a}"))?,
+                    )),
+                    _ => bail!("Unknown symbol: {}", quote(a)),
+                };
+            }
+            SExpr::List(_) => term
+                .as_application()
+                .with_context(|| format!("Unsupported term: {term}"))?,
+        };
+        if op == "let" {
+            let (bindings, body) = let_bindings(args)?;
+            let mut values = Vec::with_capacity(bindings.len());
+            for (name, t) in bindings {
+                values.push((name.to_string(), self.eval(t, env, sol)?));
+            }
+            let size = env.len();
+            env.extend(values);
+            let result = self.eval(body, env, sol);
+            env.truncate(size);
+            return result;
+        } else if op == "!" {
+            return self.eval(annotated(args)?.0, env, sol);
+        }
+        let values = args
+            .iter()
+            .map(|a| self.eval(a, env, sol))
+            .collect::<Result<Vec<_>>>()?;
+        let bools = || -> Result<Vec<bool>> {
+            values
+                .iter()
+                .map(|v| match v {
+                    Constant::Bool(b) => Ok(*b),
+                    _ => bail!("Expected Boolean arguments in {term}"),
+                })
+                .collect()
+        };
+        let ints = || -> Result<Vec<LongCst>> {
+            values
+                .iter()
+                .map(|v| match v {
+                    Constant::Int(i) => Ok(*i),
+                    _ => bail!("Expected integer arguments in {term}"),
+                })
+                .collect()
+        };
+        let value = match (op, values.as_slice()) {
+            ("not", [_]) => Constant::Bool(!bools()?[0]),
+            ("and", _) => Constant::Bool(bools()?.iter().all(|b| *b)),
+            ("or", _) => Constant::Bool(bools()?.iter().any(|b| *b)),
+            ("=>", [_, ..]) => {
+                let bools = bools()?;
+                let (last, premises) = bools.split_last().unwrap();
+                Constant::Bool(*last || premises.iter().any(|b| !b))
+            }
+            ("xor", [_, ..]) => Constant::Bool(bools()?.iter().fold(false, |acc, b| acc ^ b)),
+            ("=", [_, _, ..]) => Constant::Bool(values.windows(2).all(|w| w[0] == w[1])),
+            ("distinct", [_, _, ..]) => {
+                Constant::Bool((0..values.len()).all(|i| !values[i + 1..].contains(&values[i])))
+            }
+            ("ite", [Constant::Bool(c), t, e]) => {
+                if *c {
+                    *t
+                } else {
+                    *e
+                }
+            }
+            ("<=", [_, _, ..]) => Constant::Bool(ints()?.windows(2).all(|w| w[0] <= w[1])),
+            ("<", [_, _, ..]) => Constant::Bool(ints()?.windows(2).all(|w| w[0] < w[1])),
+            (">=", [_, _, ..]) => Constant::Bool(ints()?.windows(2).all(|w| w[0] >= w[1])),
+            (">", [_, _, ..]) => Constant::Bool(ints()?.windows(2).all(|w| w[0] > w[1])),
+            ("+", _) => Constant::Int(ints()?.iter().sum()),
+            ("-", [_]) => Constant::Int(-ints()?[0]),
+            ("-", [_, ..]) => {
+                let ints = ints()?;
+                Constant::Int(ints[1..].iter().fold(ints[0], |acc, i| acc - i))
+            }
+            ("*", _) => Constant::Int(ints()?.iter().product()),
+            _ => bail!("Cannot evaluate {term}"),
+        };
+        Ok(value)
+    }
+
+    fn value_in(&self, value: &Value, sol: &SavedAssignment) -> Constant {
+        match value {
+            Value::Bool(l) => Constant::Bool(sol.entails(*l)),
+            Value::Int(lin) => Constant::Int(
+                lin.terms
+                    .iter()
+                    .map(|&(f, v)| f as LongCst * sol.var_domain(v).lb as LongCst)
+                    .sum::<LongCst>()
+                    + lin.constant as LongCst,
+            ),
+            Value::Finite(s, FiniteValue::Cst(i)) => Constant::Finite(*s, *i),
+            Value::Finite(s, FiniteValue::Var(v)) => Constant::Finite(*s, sol.var_domain(*v).lb),
+        }
+    }
+
+    /// Value of a declared constant in the solution.
+    pub fn value_of(&self, name: &str, sol: &SavedAssignment) -> Constant {
+        self.value_in(&self.symbols.values[name], sol)
+    }
+
+    /// Formats the constant as an SMT-LIB term.
+    pub fn format(&self, value: Constant) -> String {
+        match value {
+            Constant::Bool(b) => b.to_string(),
+            Constant::Int(i) if i < 0 => format!("(- {})", -i),
+            Constant::Int(i) => i.to_string(),
+            Constant::Finite(s, i) => quote(&self.symbols.sorts[s].1[i as usize]),
+        }
+    }
+}
+
+/// The linear expression that must be non-positive for the comparison `a <op> b` to hold.
+fn comparison(op: &str, a: Lin, b: Lin) -> Result<Lin> {
+    match op {
+        "<=" => a.minus(b),
+        "<" => a.minus(b)?.plus(Lin::constant(1)),
+        ">=" => b.minus(a),
+        ">" => b.minus(a)?.plus(Lin::constant(1)),
+        _ => bail!("Unknown comparison: {op}"),
+    }
+}
+
+/// Bindings and body of the arguments of a `let` term.
+fn let_bindings(args: &[SExpr]) -> Result<(Vec<(&str, &SExpr)>, &SExpr)> {
+    let [bindings, body] = args else {
+        bail!("Invalid let term");
+    };
+    let bindings = bindings
+        .as_list()
+        .context("Invalid let bindings")?
+        .iter()
+        .map(|b| match b.as_list() {
+            Some([name, t]) => Ok((symbol(name)?, t)),
+            _ => bail!("Invalid let binding: {b}"),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((bindings, body))
+}
+
+/// Term of the arguments of an annotation `(! term attributes...)`, with its `:named` attribute if any.
+pub fn annotated(args: &[SExpr]) -> Result<(&SExpr, Option<&str>)> {
+    let (body, attributes) = args.split_first().context("Invalid annotation")?;
+    let name = match attributes.iter().position(|a| a.as_atom() == Some(":named")) {
+        Some(i) => Some(symbol(attributes.get(i + 1).context("Missing name")?)?),
+        None => None,
+    };
+    Ok((body, name))
+}
@@ -0,0 +1,467 @@
+//! Interpreter of SMT-LIB commands.
+//!
+//! Levels of the assertion stack are assertion levels of the solver: popping a level removes its assertions,
+//! while keeping everything the solver has learnt independently of them.
+
+use crate::encode::{annotated, symbol, Encoder, Value};
+use crate::sexpr::{quote, SExpr};
+use anyhow::*;
+use aries::backtrack::Backtrack;
+use aries::core::Lit;
+use aries::model::extensions::SavedAssignment;
+use aries::model::lang::expr::implies;
+use itertools::Itertools;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Result of the last satisfiability check.
+enum Status {
+    Sat(Arc<SavedAssignment>),
+    /// Unsatisfiable, with the names of the assertions and the assumptions of the unsatisfiable core.
+    Unsat {
+        core: Vec<String>,
+        assumptions: Vec<SExpr>,
+    },
+    Unknown,
+}
+
+/// State of the assertion stack: the encoded assertions and the named ones.
+#[derive(Default)]
+struct State {
+    encoder: Encoder,
+    /// Named assertions, with the literal activating them.
+    named: Vec<(String, Lit)>,
+    /// True once a declaration, definition or assertion has been made.
+    started: bool,
+}
+
+pub struct Interpreter<W: Write> {
+    out: W,
+    /// Number of named assertions when each of the open levels was pushed.
+    levels: Vec<usize>,
+    state: State,
+    status: Option<Status>,
+    print_success: bool,
+    produce_unsat_cores: bool,
+    exited: bool,
+}
+
+impl<W: Write> Interpreter<W> {
+    pub fn new(out: W) -> Self {
+        Interpreter {
+            out,
+            levels: Vec::new(),
+            state: State::default(),
+            status: None,
+            print_success: false,
+            produce_unsat_cores: false,
+            exited: false,
+        }
+    }
+
+    /// True once an `exit` command has been executed.
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Executes a command, writing its response (or an error) on the output.
+    pub fn execute(&mut self, command: &SExpr) -> std::io::Result<()> {
+        match self.run(command) {
+            Result::Ok(Some(response)) => writeln!(self.out, "{response}")?,
+            Result::Ok(None) if self.print_success => writeln!(self.out, "success")?,
+            Result::Ok(None) => {}
+            Err(e) => {
+                let message = e.chain().join(": ").replace('"', "\"\"");
+                writeln!(self.out, "(error \"{message}\")")?
+            }
+        }
+        self.out.flush()
+    }
+
+    fn run(&mut self, command: &SExpr) -> Result<Option<String>> {
+        let (name, args) = command
+            .as_application()
+            .with_context(|| format!("Invalid command: {command}"))?;
+        match (name, args) {
+            ("set-logic", [_]) | ("set-info", _) => Ok(None),
+            ("set-option", [option, value]) => self.set_option(option, value),
+            ("get-info", [flag]) => match flag.as_atom() {
+                Some(":name") => Ok(Some("(:name \"aries\")".to_string())),
+                Some(":version") => Ok(Some(format!("(:version \"{}\")", env!("CARGO_PKG_VERSION")))),
+                Some(":error-behavior") => Ok(Some("(:error-behavior continued-execution)".to_string())),
+                _ => Ok(Some("unsupported".to_string())),
+            },
+            (
+                "declare-const" | "declare-fun" | "define-fun" | "declare-datatype" | "declare-datatypes" | "assert",
+                _,
+            ) => {
+                self.status = None;
+                apply(&mut self.state, command, self.produce_unsat_cores)?;
+                Ok(None)
+            }
+            ("check-sat", []) => self.check_sat(&[]),
+            ("check-sat-assuming", [assumptions]) => {
+                let assumptions = assumptions.as_list().context("Expected a list of assumptions")?;
+                self.check_sat(assumptions)
+            }
+            ("get-model", []) => {
+                let sol = self.solution()?;
+                let encoder = &self.state.encoder;
+                let mut model = String::from("(");
+                for (name, sort) in encoder.declared() {
+                    let value = encoder.format(encoder.value_of(name, &sol));
+                    let sort = quote(encoder.sort_name(*sort));
+                    model.push_str(&format!("\n  (define-fun {} () {sort} {value})", quote(name)));
+                }
+                model.push_str("\n)");
+                Ok(Some(model))
+            }
+            ("get-value", [terms]) => {
+                let sol = self.solution()?;
+                let encoder = &self.state.encoder;
+                let terms = terms.as_list().context("Expected a list of terms")?;
+                let values = terms
+                    .iter()
+                    .map(|t| {
+                        Ok(format!(
+                            "({t} {})",
+                            encoder.format(encoder.eval(t, &mut Vec::new(), &sol)?)
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Some(format!("({})", values.join(" "))))
+            }
+            ("get-unsat-core", []) => match &self.status {
+                Some(Status::Unsat { core, .. }) => Ok(Some(format!("({})", core.iter().map(|n| quote(n)).join(" ")))),
+                _ => bail!("The last check is not unsatisfiable"),
+            },
+            ("get-unsat-assumptions", []) => match &self.status {
+                Some(Status::Unsat { assumptions, .. }) => Ok(Some(format!("({})", assumptions.iter().join(" ")))),
+                _ => bail!("The last check is not unsatisfiable"),
+            },
+            ("push", _) => {
+                for _ in 0..num_levels(args)? {
+                    self.state.encoder.push();
+                    self.levels.push(self.state.named.len());
+                }
+                self.status = None;
+                Ok(None)
+            }
+            ("pop", _) => {
+                let n = num_levels(args)?;
+                ensure!(n <= self.levels.len(), "Cannot pop {n} levels from the assertion stack");
+                for _ in 0..n {
+                    self.state.encoder.pop();
+                    let num_named = self.levels.pop().unwrap();
+                    self.state.named.truncate(num_named);
+                }
+                self.status = None;
+                Ok(None)
+            }
+            ("reset-assertions", []) => {
+                self.clear();
+                Ok(None)
+            }
+            ("reset", []) => {
+                self.clear();
+                self.print_success = false;
+                self.produce_unsat_cores = false;
+                Ok(None)
+            }
+            ("echo", [message]) => Ok(Some(message.to_string())),
+            ("exit", []) => {
+                self.exited = true;
+                Ok(None)
+            }
+            _ => bail!("Unsupported command: {command}"),
+        }
+    }
+
+    fn set_option(&mut self, option: &SExpr, value: &SExpr) -> Result<Option<String>> {
+        let flag = match value.as_atom() {
+            Some("true") => true,
+            Some("false") => false,
+            _ => return Ok(Some("unsupported".to_string())),
+        };
+        match option.as_atom() {
+            Some(":print-success") => self.print_success = flag,
+            Some(":produce-unsat-cores") => {
+                ensure!(!self.state.started, "The option must be set before any assertion");
+                self.produce_unsat_cores = flag
+            }
+            Some(":produce-models" | ":produce-unsat-assumptions") => {}
+            _ => return Ok(Some("unsupported".to_string())),
+        }
+        Ok(None)
+    }
+
+    /// Empties the assertion stack.
+    fn clear(&mut self) {
+        self.levels.clear();
+        self.state = State::default();
+        self.status = None;
+    }
+
+    fn check_sat(&mut self, assumptions: &[SExpr]) -> Result<Option<String>> {
+        let state = &mut self.state;
+        let mut assumed: Vec<(Lit, Option<&str>, Option<&SExpr>)> = Vec::new();
+        for a in assumptions {
+            // assumptions are Boolean constants or their negation
+            let valid = match a.as_application() {
+                Some(("not", [b])) => b.as_atom().is_some(),
+                _ => a.as_atom().is_some(),
+            };
+            ensure!(valid, "Invalid assumption: {a}");
+            assumed.push((state.encoder.bool(a, &mut Vec::new())?, None, Some(a)));
+        }
+        assumed.extend(state.named.iter().map(|(name, l)| (*l, Some(name.as_str()), None)));
+
+        let lits: Vec<Lit> = assumed.iter().map(|(l, _, _)| *l).collect();
+        let result = state.encoder.solver.solve_with_assumptions(&lits);
+        // go back to the root, where further assertions can be made
+        state.encoder.solver.reset();
+        let status = match result {
+            Result::Ok(Result::Ok(sol)) => Status::Sat(sol),
+            Result::Ok(Err(core)) => {
+                let in_core = assumed.iter().filter(|(l, _, _)| core.contains(l));
+                Status::Unsat {
+                    core: in_core.clone().filter_map(|(_, n, _)| n.map(str::to_string)).collect(),
+                    assumptions: in_core.filter_map(|(_, _, a)| a.cloned()).collect(),
+                }
+            }
+            Err(_) => Status::Unknown,
+        };
+        let response = match status {
+            Status::Sat(_) => "sat",
+            Status::Unsat { .. } => "unsat",
+            Status::Unknown => "unknown",
+        };
+        self.status = Some(status);
+        Ok(Some(response.to_string()))
+    }
+
+    fn solution(&self) -> Result<Arc<SavedAssignment>> {
+        match &self.status {
+            Some(Status::Sat(sol)) => Ok(sol.clone()),
+            _ => bail!("No model available: the last check is not satisfiable"),
+        }
+    }
+}
+
+/// Number of levels of a `push` or `pop` command.
+fn num_levels(args: &[SExpr]) -> Result<usize> {
+    match args {
+        [] => Ok(1),
+        [n] => n
+            .as_atom()
+            .and_then(|n| n.parse().ok())
+            .with_context(|| format!("Invalid number of levels: {n}")),
+        _ => bail!("Invalid arguments"),
+    }
+}
+
+/// Applies a command modifying the state (declaration, definition or assertion).
+fn apply(state: &mut State, command: &SExpr, produce_unsat_cores: bool) -> Result<()> {
+    let (name, args) = command.as_application().unwrap();
+    state.started = true;
+    let encoder = &mut state.encoder;
+    let no_params = |params: &SExpr| match params.as_list() {
+        Some([]) => Ok(()),
+        _ => bail!("Only constants are supported, got parameters {params}"),
+    };
+    match (name, args) {
+        ("declare-const", [name, sort]) => encoder.declare_const(symbol(name)?, encoder.sort(sort)?),
+        ("declare-fun", [name, params, sort]) => {
+            no_params(params)?;
+            encoder.declare_const(symbol(name)?, encoder.sort(sort)?)
+        }
+        ("define-fun", [name, params, sort, body]) => {
+            no_params(params)?;
+            encoder.define_const(symbol(name)?, encoder.sort(sort)?, body)
+        }
+        ("declare-datatype", [name, constructors]) => encoder.declare_finite_sort(symbol(name)?, constructors),
+        ("declare-datatypes", [sorts, datatypes]) => {
+            let sorts = sorts.as_list().context("Invalid sort declarations")?;
+            let datatypes = datatypes.as_list().context("Invalid datatype declarations")?;
+            ensure!(sorts.len() == datatypes.len(), "Invalid datatype declarations");
+            for (sort, constructors) in sorts.iter().zip(datatypes) {
+                let name = match sort.as_list() {
+                    Some([name, arity]) if arity.as_atom() == Some("0") => symbol(name)?,
+                    _ => bail!("Unsupported sort declaration: {sort}"),
+                };
+                encoder.declare_finite_sort(name, constructors)?;
+            }
+            Ok(())
+        }
+        ("assert", [term]) => match term.as_application() {
+            Some(("!", args)) if produce_unsat_cores => {
+                let (body, name) = annotated(args)?;
+                let Some(name) = name else {
+                    return encoder.assert(body);
+                };
+                // the assertion is only enforced when its activation literal is assumed true
+                let lit = encoder.bool(body, &mut Vec::new())?;
+                encoder.name(name, Value::Bool(lit))?;
+                let activation = encoder.solver.model.new_bvar(format!("assert-{name}")).true_lit();
+                encoder.solver.enforce(implies(activation, lit), []);
+                state.named.push((name.to_string(), activation));
+                Ok(())
+            }
+            _ => encoder.assert(term),
+        },
+        _ => bail!("Invalid command: {command}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode::Constant;
+    use crate::sexpr::parse;
+
+    /// Runs the script and returns the responses of the interpreter.
+    fn run(script: &str) -> String {
+        let mut interpreter = Interpreter::new(Vec::new());
+        for command in parse(script).unwrap() {
+            interpreter.execute(&command).unwrap();
+        }
+        String::from_utf8(interpreter.out).unwrap()
+    }
+
+    #[test]
+    fn test_difference_logic() {
+        let script = "(set-logic QF_IDL)
+            (declare-fun a () Int) (declare-fun b () Int) (declare-fun c () Int)
+            (assert (< a b)) (assert (<= (- b c) (- 2))) (assert (<= c (+ a 3)))
+            (assert (or (>= (- a c) 0) (> c 10)))
+            (check-sat)
+            (get-value ((- c a) (> c 10)))
+            (assert (>= a c))
+            (check-sat)";
+        assert_eq!(run(script), "sat\n(((- c a) 3) ((> c 10) true))\nunsat\n");
+
+        let mut interpreter = Interpreter::new(Vec::new());
+        let script = "(declare-const x Int) (declare-const y Int) (assert (<= 3 x (- y 4) 10)) (check-sat)";
+        for command in parse(script).unwrap() {
+            interpreter.execute(&command).unwrap();
+        }
+        let sol = interpreter.solution().unwrap();
+        let value = |name| interpreter.state.encoder.value_of(name, &sol);
+        let (Constant::Int(x), Constant::Int(y)) = (value("x"), value("y")) else {
+            panic!()
+        };
+        assert!(3 <= x && x <= y - 4 && y <= 14);
+    }
+
+    #[test]
+    fn test_linear_arithmetic() {
+        let script = "(set-logic QF_LIA)
+            (declare-const x Int) (declare-const y Int) (declare-const z Int) (declare-const p Bool)
+            (define-fun s () Int (+ x (* 2 y) (* z 3)))
+            (assert (and (>= x 0) (>= y 0) (>= z 0)))
+            (assert (= s 7))
+            (assert (= p (> (+ x y z) 3)))
+            (assert (ite p (distinct x 1) (= y 2)))
+            (check-sat)
+            (get-value (s (+ x y z) p (ite p 1 0)))
+            (assert (not p))
+            (assert (= (* 2 x) (+ 1 z)))
+            (check-sat)";
+        let output = run(script);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "sat");
+        assert!(lines[1].starts_with("((s 7) ((+ x y z) "));
+        assert_eq!(lines[2], "unsat");
+    }
+
+    #[test]
+    fn test_finite_sorts() {
+        let script = "(declare-datatypes ((Color 0)) (((red) (green) (blue))))
+            (declare-datatype Shape ((circle) (square)))
+            (declare-const a Color) (declare-const b Color) (declare-const c Color) (declare-const s Shape)
+            (assert (distinct a b c))
+            (assert (not (= a red)))
+            (assert (=> (= s circle) (= b red)))
+            (assert (= c (ite (= s square) green blue)))
+            (assert (not (= s square)))
+            (check-sat)
+            (get-model)
+            (assert (= a blue))
+            (check-sat)";
+        assert_eq!(
+            run(script),
+            "sat\n(\n  (define-fun a () Color green)\n  (define-fun b () Color red)\n  \
+             (define-fun c () Color blue)\n  (define-fun s () Shape circle)\n)\nunsat\n"
+        );
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let script = "(set-option :print-success true)
+            (declare-const x Int)
+            (assert (>= x 0))
+            (push 1)
+            (declare-const y Int)
+            (assert (< x y))
+            (assert (< (- y x) 0))
+            (check-sat)
+            (pop 1)
+            (check-sat)
+            (assert (< x y 0))
+            (push 2)
+            (declare-const y Bool)
+            (assert (= y (> x 2)))
+            (assert y)
+            (check-sat)
+            (pop 2)
+            (pop 1)";
+        assert_eq!(
+            run(script),
+            "success\nsuccess\nsuccess\nsuccess\nsuccess\nsuccess\nsuccess\nunsat\nsuccess\nsat\n\
+             (error \"Unknown symbol: y\")\n\
+             success\nsuccess\nsuccess\nsuccess\nsat\nsuccess\n\
+             (error \"Cannot pop 1 levels from the assertion stack\")\n"
+        );
+    }
+
+    #[test]
+    fn test_unsat_cores() {
+        let script = "(set-option :produce-unsat-cores true)
+            (declare-const a Bool) (declare-const b Bool) (declare-const x Int)
+            (assert (! (=> a (> x 5)) :named a_big))
+            (assert (! (=> b (< x 3)) :named b_small))
+            (assert (! (>= x 0) :named positive))
+            (check-sat-assuming (a (not b)))
+            (check-sat-assuming (a b))
+            (get-unsat-assumptions)
+            (get-unsat-core)
+            (get-model)";
+        assert_eq!(
+            run(script),
+            "sat\nunsat\n(a b)\n(a_big b_small)\n\
+             (error \"No model available: the last check is not satisfiable\")\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let script = "(declare-fun f (Int) Int)
+            (declare-const x Real)
+            (declare-const x Int)
+            (declare-const x Int)
+            (assert (= (* x x) 4))
+            (get-model)
+            (check-sat)
+            (echo \"done\")";
+        assert_eq!(
+            run(script),
+            "(error \"Only constants are supported, got parameters (Int)\")\n\
+             (error \"Unsupported sort: Real\")\n\
+             (error \"Symbol x is already defined\")\n\
+             (error \"Non-linear term: (* x x)\")\n\
+             (error \"No model available: the last check is not satisfiable\")\n\
+             sat\n\
+             \"done\"\n"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! SMT-LIB 2 front-end of aries.
+//!
+//! Supports the quantifier-free fragments of integer difference logic and linear integer arithmetic,
+//! together with Boolean constants and enumerated datatypes.
+//! Commands are read from the given file or, if none is given, interactively from the standard input.
+
+mod encode;
+mod interpreter;
+mod sexpr;
+
+use crate::interpreter::Interpreter;
+use crate::sexpr::{is_complete, parse};
+use anyhow::*;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "aries-smt")]
+struct Opt {
+    /// SMT-LIB file to execute. Commands are read from the standard input if absent.
+    file: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let stdout = std::io::stdout();
+    let mut interpreter = Interpreter::new(stdout.lock());
+
+    if let Some(file) = &opt.file {
+        let input = std::fs::read_to_string(file).context("Could not read file")?;
+        for command in parse(&input)? {
+            interpreter.execute(&command)?;
+            if interpreter.exited() {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    // read-eval-print loop: a command is executed as soon as all its parentheses are closed
+    let mut buffer = String::new();
+    for line in std::io::stdin().lock().lines() {
+        buffer.push_str(&line?);
+        buffer.push('\n');
+        if !is_complete(&buffer) {
+            continue;
+        }
+        match parse(&buffer) {
+            Result::Ok(commands) => {
+                for command in commands {
+                    interpreter.execute(&command)?;
+                    if interpreter.exited() {
+                        return Ok(());
+                    }
+                }
+            }
+            Err(e) => {
+                println!("(error \"{e}\")");
+                std::io::stdout().flush()?;
+            }
+        }
+        buffer.clear();
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+//! Reader for the S-expressions of the SMT-LIB language.
+//!
+//! Symbols are case-sensitive and may be quoted (`|a symbol|`), in which case they are stored without the bars.
+//! String literals are kept with their double quotes. Comments extend from a `;` to the end of the line.
+
+use anyhow::*;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    pub fn as_atom(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(a) => Some(a),
+            SExpr::List(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[SExpr]> {
+        match self {
+            SExpr::Atom(_) => None,
+            SExpr::List(l) => Some(l),
+        }
+    }
+
+    /// If the expression is an application `(f args...)` of a function symbol, returns `f` and the arguments.
+    pub fn as_application(&self) -> Option<(&str, &[SExpr])> {
+        let (head, args) = self.as_list()?.split_first()?;
+        Some((head.as_atom()?, args))
+    }
+}
+
+impl Display for SExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SExpr::Atom(a) => write!(f, "{}", quote(a)),
+            SExpr::List(l) => {
+                write!(f, "(")?;
+                for (i, e) in l.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{e}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Returns the symbol, surrounded by bars if it cannot be written as a simple symbol.
+pub fn quote(symbol: &str) -> String {
+    let simple = |c: char| c.is_ascii_alphanumeric() || "~!@$%^&*_-+=<>.?/:".contains(c);
+    let is_string = symbol.len() >= 2 && symbol.starts_with('"') && symbol.ends_with('"');
+    if is_string || (!symbol.is_empty() && symbol.chars().all(simple)) {
+        symbol.to_string()
+    } else {
+        format!("|{symbol}|")
+    }
+}
+
+/// Parses all S-expressions of the input.
+pub fn parse(input: &str) -> Result<Vec<SExpr>> {
+    let mut stack: Vec<Vec<SExpr>> = vec![Vec::new()];
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                // comment until the end of the line
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                ensure!(stack.len() > 1, "Unexpected closing parenthesis");
+                let list = stack.pop().unwrap();
+                stack.last_mut().unwrap().push(SExpr::List(list));
+            }
+            '|' => {
+                let mut symbol = String::new();
+                loop {
+                    match chars.next() {
+                        Some('|') => break,
+                        Some(c) => symbol.push(c),
+                        None => bail!("Unterminated quoted symbol"),
+                    }
+                }
+                stack.last_mut().unwrap().push(SExpr::Atom(symbol));
+            }
+            '"' => {
+                let mut string = String::from('"');
+                loop {
+                    match chars.next() {
+                        // a double quote is escaped by another one
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            string.push_str("\"\"");
+                        }
+                        Some('"') => break,
+                        Some(c) => string.push(c),
+                        None => bail!("Unterminated string literal"),
+                    }
+                }
+                string.push('"');
+                stack.last_mut().unwrap().push(SExpr::Atom(string));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut atom = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "();|\"".contains(c) {
+                        break;
+                    }
+                    atom.push(c);
+                    chars.next();
+                }
+                stack.last_mut().unwrap().push(SExpr::Atom(atom));
+            }
+        }
+    }
+    ensure!(stack.len() == 1, "Missing closing parenthesis");
+    Ok(stack.pop().unwrap())
+}
+
+/// Returns true if the input contains no unclosed list, quoted symbol or string, i.e., if it can be parsed
+/// once complete.
+pub fn is_complete(input: &str) -> bool {
+    let mut depth: usize = 0;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' if !chars.by_ref().any(|c| c == '\n') => break,
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '|' | '"' if !chars.by_ref().any(|d| d == c) => return false,
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input =
+            "(declare-fun |x y| () Int) ; comment (\n(echo \"a \"\"quoted\"\" (string\")\n(assert (! x :named a))";
+        let exprs = parse(input).unwrap();
+        assert_eq!(exprs.len(), 3);
+        assert_eq!(exprs[0].as_application().unwrap().1[0], SExpr::Atom("x y".to_string()));
+        assert_eq!(exprs[1].to_string(), "(echo \"a \"\"quoted\"\" (string\")");
+        assert_eq!(exprs[0].to_string(), "(declare-fun |x y| () Int)");
+        assert_eq!(exprs[2].to_string(), "(assert (! x :named a))");
+
+        assert!(parse("(a (b)").is_err());
+        assert!(parse("(a))").is_err());
+        assert!(parse("(a |b)").is_err());
+    }
+
+    #[test]
+    fn test_complete() {
+        assert!(is_complete("(a (b c))"));
+        assert!(is_complete("(a (b c)) ; (d"));
+        assert!(!is_complete("(a (b c)"));
+        assert!(!is_complete("(a |)"));
+        assert!(!is_complete("(a \")\" "));
+        assert!(is_complete("(a \")\")"));
+    }
+}
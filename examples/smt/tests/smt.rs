@@ -1,8 +1,10 @@
 use aries::backtrack::Backtrack;
 use aries::core::state::OptDomain;
-use aries::core::Lit;
+use aries::core::{IntCst, Lit, VarRef};
 use aries::model::extensions::AssignmentExt;
 use aries::model::lang::expr::*;
+use aries::model::lang::linear::LinearSum;
+use aries::model::lang::scheduling::Task;
 use aries::model::lang::IVar;
 use itertools::Itertools;
 
@@ -30,6 +32,129 @@ fn sat() {
     assert!(solver.solve().unwrap().is_none());
 }
 
+#[test]
+fn sat_with_assumptions() {
+    let mut model = Model::new();
+    let a = model.new_bvar("a").true_lit();
+    let b = model.new_bvar("b").true_lit();
+    let c = model.new_bvar("c").true_lit();
+    let x = model.new_ivar(0, 10, "x");
+
+    let mut solver = Solver::new(model);
+    solver.enforce(implies(a, x.geq(5)), []);
+    solver.enforce(implies(b, x.leq(3)), []);
+
+    let sol = solver.solve_with_assumptions(&[a, c]).unwrap().unwrap();
+    assert!(sol.entails(a) && sol.entails(c));
+    assert!(sol.entails(x.geq(5)));
+
+    let core = solver.solve_with_assumptions(&[c, a, b]).unwrap().err().unwrap();
+    assert_eq!(
+        core.iter().copied().sorted().collect_vec(),
+        [a, b].into_iter().sorted().collect_vec()
+    );
+
+    let core = solver.solve_with_assumptions(&[b, c, a]).unwrap().err().unwrap();
+    assert_eq!(
+        core.iter().copied().sorted().collect_vec(),
+        [a, b].into_iter().sorted().collect_vec()
+    );
+
+    let sol = solver.solve_with_assumptions(&[b]).unwrap().unwrap();
+    assert!(sol.entails(b));
+    assert!(sol.entails(x.leq(3)));
+
+    // assumptions do not persist across calls
+    solver.reset();
+    solver.enforce(a, []);
+    assert!(solver.solve().unwrap().is_some());
+    let core = solver.solve_with_assumptions(&[c, b]).unwrap().err().unwrap();
+    assert_eq!(core, vec![b]);
+
+    // unsatisfiable regardless of the assumptions
+    solver.reset();
+    solver.enforce(b, []);
+    let core = solver.solve_with_assumptions(&[c]).unwrap().err().unwrap();
+    assert!(core.is_empty());
+}
+
+#[test]
+fn push_pop() {
+    let mut model = Model::new();
+    let a = model.new_bvar("a").true_lit();
+    let x = model.new_ivar(0, 10, "x");
+    let y = model.new_ivar(0, 10, "y");
+    let z = model.new_ivar(0, 10, "z");
+
+    let mut solver = Solver::new(model);
+    solver.enforce(lt(x, y), []);
+    solver.enforce(implies(a, x.geq(5)), []);
+    assert_eq!(solver.num_assertion_levels(), 0);
+
+    solver.push();
+    solver.enforce(LinearSum::of(vec![y]).leq(3), []);
+    let sol = solver.solve().unwrap().unwrap();
+    assert!(sol.entails(y.leq(3)) && sol.entails(!a));
+    // the level is unsatisfiable with `a`, which is the only assumption of the core
+    let core = solver.solve_with_assumptions(&[a]).unwrap().err().unwrap();
+    assert_eq!(core, vec![a]);
+
+    solver.push();
+    solver.enforce(a, []);
+    solver.enforce(all_different([x, y, z]), []);
+    assert_eq!(solver.num_assertion_levels(), 2);
+    assert!(solver.solve().unwrap().is_none());
+    let core = solver.solve_with_assumptions(&[a]).unwrap().err().unwrap();
+    assert!(core.is_empty());
+
+    // constraints of the second level are removed
+    solver.pop();
+    let sol = solver.solve().unwrap().unwrap();
+    assert!(sol.entails(!a));
+    solver.push();
+    let sum = LinearSum::of(vec![x, y, z]);
+    solver.enforce(sum.geq(12), []);
+    solver.enforce((LinearSum::of(vec![y]) - LinearSum::of(vec![z])).leq(-8), []);
+    let sol = solver.solve().unwrap().unwrap();
+    assert!(sol.entails(z.geq(9)));
+    solver.pop();
+
+    // constraints of the first level are removed
+    solver.pop();
+    assert_eq!(solver.num_assertion_levels(), 0);
+    solver.enforce(a, []);
+    let sol = solver.solve().unwrap().unwrap();
+    assert!(sol.entails(x.geq(5)) && sol.entails(y.geq(6)));
+    assert_eq!(solver.minimize(z).unwrap().unwrap().0, 0);
+}
+
+#[test]
+fn push_pop_optimization() {
+    let mut model = Model::new();
+    let x = model.new_ivar(0, 10, "x");
+    let y = model.new_ivar(0, 10, "y");
+    let z = model.new_ivar(0, 10, "z");
+    let mut solver = Solver::new(model);
+
+    // the bound on the objective learnt when maximizing in the level does not outlive it
+    solver.push();
+    solver.enforce(z.leq(3), []);
+    assert_eq!(solver.maximize(z).unwrap().unwrap().0, 3);
+    solver.pop();
+    solver.enforce(z.leq(3), []);
+    assert!(solver.solve().unwrap().is_some());
+
+    // the all-different propagator of the level is removed along with it
+    solver.push();
+    solver.enforce(all_different([x, y, z]), []);
+    assert_eq!(solver.maximize(z).unwrap().unwrap().0, 3);
+    solver.pop();
+    solver.enforce(eq(x, y), []);
+    solver.enforce(eq(y, z), []);
+    let sol = solver.solve().unwrap().unwrap();
+    assert_eq!(sol.var_domain(x).lb, sol.var_domain(z).lb);
+}
+
 #[test]
 fn diff_logic() {
     let mut model = Model::new();
@@ -44,6 +169,217 @@ fn diff_logic() {
     assert!(solver.solve().unwrap().is_none());
 }
 
+#[test]
+fn all_different_constraint() {
+    let mut model = Model::new();
+    let vars: Vec<IVar> = (0..3).map(|i| model.new_ivar(0, 2, format!("x{i}"))).collect();
+    let scope = model.new_bvar("scope").true_lit();
+    let extra = model.new_ivar(0, 2, "extra");
+    let vars_ref: Vec<VarRef> = vars.iter().map(|&v| v.into()).collect();
+
+    let mut solver = Solver::new(model);
+    solver.enforce(all_different(vars.clone()), []);
+    let solutions = solver.enumerate(&vars_ref).unwrap();
+    assert_eq!(solutions.len(), 6);
+    assert!(solutions.iter().all(|sol| sol.iter().all_unique()));
+
+    // pigeonhole, only satisfiable if the constraint is not active
+    solver.reset();
+    let mut pigeons = vars.clone();
+    pigeons.push(extra);
+    solver.enforce(all_different(pigeons), [scope]);
+    assert!(solver.solve().unwrap().is_some());
+    assert_eq!(solver.model.boolean_value_of(scope), Some(false));
+    solver.reset();
+    solver.enforce(scope, []);
+    assert!(solver.solve().unwrap().is_none());
+}
+
+#[test]
+fn all_different_optionals() {
+    let mut model = Model::new();
+    let prez: Vec<Lit> = (0..4).map(|i| model.new_bvar(format!("p{i}")).true_lit()).collect();
+    let vars: Vec<IVar> = (0..4)
+        .map(|i| model.new_optional_ivar(0, 2, prez[i], format!("x{i}")))
+        .collect();
+
+    let mut solver = Solver::new(model);
+    solver.enforce(all_different(vars.clone()), []);
+    let sol = solver.solve().unwrap().unwrap();
+    // at most three variables can be present
+    let present: Vec<IntCst> = (0..4)
+        .filter(|&i| sol.entails(prez[i]))
+        .map(|i| sol.var_domain(vars[i]).lb)
+        .collect();
+    assert!(present.len() <= 3);
+    assert!(present.iter().all_unique());
+
+    solver.reset();
+    solver.enforce_all(prez.iter().copied().take(3), []);
+    assert!(solver.solve().unwrap().is_some());
+    solver.reset();
+    solver.enforce(prez[3], []);
+    assert!(solver.solve().unwrap().is_none());
+}
+
+#[test]
+fn no_overlap_constraint() {
+    let mut model = Model::new();
+    let durations: [IntCst; 3] = [2, 3, 1];
+    // tasks must all fit in the interval [0, 6)
+    let starts: Vec<IVar> = durations
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| model.new_ivar(0, 6 - d, format!("s{i}")))
+        .collect();
+    let prez = model.new_bvar("prez").true_lit();
+    let extra = model.new_optional_ivar(0, 5, prez, "extra");
+    let starts_ref: Vec<VarRef> = starts.iter().map(|&v| v.into()).collect();
+
+    let mut solver = Solver::new(model);
+    let mut tasks: Vec<Task> = (0..3).map(|i| Task::new(starts[i], durations[i], 1)).collect();
+    tasks.push(Task::optional(extra, 1, 1, prez));
+    solver.enforce(no_overlap(tasks), []);
+    // one solution for each ordering of the tasks
+    let solutions = solver.enumerate(&starts_ref).unwrap();
+    assert_eq!(solutions.len(), 6);
+    for sol in &solutions {
+        let mut intervals: Vec<(IntCst, IntCst)> = (0..3).map(|i| (sol[i], sol[i] + durations[i])).collect();
+        intervals.sort();
+        assert!(intervals.windows(2).all(|w| w[0].1 <= w[1].0));
+    }
+
+    // no room left for the optional task
+    solver.reset();
+    solver.enforce(prez, []);
+    assert!(solver.solve().unwrap().is_none());
+}
+
+#[test]
+fn table_constraint() {
+    let mut model = Model::new();
+    let x = model.new_ivar(0, 10, "x");
+    let y = model.new_ivar(0, 10, "y");
+    let z = model.new_ivar(0, 10, "z");
+    let prez = model.new_bvar("prez").true_lit();
+    let opt = model.new_optional_ivar(0, 10, prez, "opt");
+    let tuples = vec![
+        vec![1, 2, 3, 0],
+        vec![1, 3, 2, 1],
+        vec![2, 2, 2, 2],
+        vec![4, 5, 6, 3],
+        vec![7, 0, 1, 4],
+    ];
+    model.enforce(table([x, y, z, opt], tuples.clone()), []);
+    model.enforce(neq(x, 7), []);
+
+    let mut solver = Solver::new(model.clone());
+    let vars: Vec<VarRef> = [x, y, z].iter().map(|&v| v.into()).collect();
+    let solutions = solver.enumerate(&vars).unwrap();
+    assert_eq!(solutions.len(), 4);
+    for sol in &solutions {
+        assert!(tuples.iter().any(|t| t[0..3] == sol[..]));
+    }
+
+    // when present, the optional variable must be consistent with the tuple
+    let mut solver = Solver::new(model);
+    solver.enforce(prez, []);
+    solver.enforce(geq(opt, 2), [prez]);
+    let vars = [x.into(), y.into(), z.into(), opt.into(), prez.variable()];
+    let mut solutions = solver.enumerate(&vars).unwrap();
+    solutions.sort();
+    assert_eq!(solutions, vec![vec![2, 2, 2, 2, 1], vec![4, 5, 6, 3, 1]]);
+}
+
+#[test]
+fn element_constraint() {
+    let mut model = Model::new();
+    let array = [5, 1, 8, 3, 1];
+    let i = model.new_ivar(-10, 10, "i");
+    let x = model.new_ivar(0, 6, "x");
+    model.enforce(element(array, i, x), []);
+
+    let mut solver = Solver::new(model);
+    let mut solutions = solver.enumerate(&[i.into(), x.into()]).unwrap();
+    solutions.sort();
+    assert_eq!(solutions, vec![vec![0, 5], vec![1, 1], vec![3, 3], vec![4, 1]]);
+}
+
+/// Checks that the solutions of a binary constraint are exactly the pairs of values of `x` and `y` satisfying `pred`.
+fn check_binary_solutions(
+    constraint: impl Fn(IVar, IVar) -> aries::reif::ReifExpr,
+    pred: impl Fn(IntCst, IntCst) -> bool,
+) {
+    let mut model = Model::new();
+    let x = model.new_ivar(-7, 7, "x");
+    let y = model.new_ivar(-7, 7, "y");
+    model.enforce(constraint(x, y), []);
+    let mut solver = Solver::new(model);
+    let mut solutions = solver.enumerate(&[x.into(), y.into()]).unwrap();
+    solutions.sort();
+    let expected = (-7..=7)
+        .cartesian_product(-7..=7)
+        .filter(|&(x, y)| pred(x, y))
+        .map(|(x, y)| vec![x, y])
+        .collect_vec();
+    assert_eq!(solutions, expected);
+}
+
+#[test]
+fn nonlinear_constraints() {
+    check_binary_solutions(|x, y| eq_mul(y, x, x).into(), |x, y| y == x * x);
+    check_binary_solutions(|x, y| eq_div(y, x, 3).into(), |x, y| y == x / 3);
+    check_binary_solutions(|x, y| eq_div(y, x, -2).into(), |x, y| y == x / -2);
+    check_binary_solutions(|x, y| eq_mod(y, x, 4).into(), |x, y| y == x % 4);
+    check_binary_solutions(|x, y| eq_abs(y, x).into(), |x, y| y == x.abs());
+
+    let mut model = Model::new();
+    let x = model.new_ivar(-3, 3, "x");
+    let y = model.new_ivar(-3, 3, "y");
+    let z = model.new_ivar(-10, 10, "z");
+    let min = model.new_ivar(-10, 10, "min");
+    let max = model.new_ivar(-10, 10, "max");
+    model.enforce(eq_mul(z, x, y), []);
+    model.enforce(eq_min(min, [x, y, z]), []);
+    model.enforce(eq_max(max, [x, y, z]), []);
+    let mut solver = Solver::new(model);
+    let mut solutions = solver
+        .enumerate(&[x.into(), y.into(), z.into(), min.into(), max.into()])
+        .unwrap();
+    solutions.sort();
+    let expected = (-3..=3)
+        .cartesian_product(-3..=3)
+        .map(|(x, y)| {
+            let z = x * y;
+            vec![x, y, z, x.min(y).min(z), x.max(y).max(z)]
+        })
+        .collect_vec();
+    assert_eq!(solutions, expected);
+}
+
+#[test]
+fn optional_product() {
+    let mut model = Model::new();
+    let prez = model.new_presence_variable(Lit::TRUE, "prez").true_lit();
+    let x = model.new_optional_ivar(-5, 5, prez, "x");
+    let y = model.new_ivar(2, 3, "y");
+    let z = model.new_ivar(7, 10, "z");
+    model.enforce(eq_mul(z, x, y), [prez]);
+
+    // when absent, the product is unconstrained
+    let mut solver = Solver::new(model.clone());
+    let solutions = solver.enumerate(&[prez.variable(), y.into(), z.into()]).unwrap();
+    assert_eq!(solutions.iter().filter(|s| s[0] == 0).count(), 2 * 4);
+
+    let mut solver = Solver::new(model);
+    solver.enforce(prez, []);
+    let mut solutions = solver
+        .enumerate(&[x.into(), y.into(), z.into(), prez.variable()])
+        .unwrap();
+    solutions.sort();
+    assert_eq!(solutions, vec![vec![3, 3, 9, 1], vec![4, 2, 8, 1], vec![5, 2, 10, 1]]);
+}
+
 #[test]
 fn minimize() {
     let mut model = Model::new();
@@ -0,0 +1,1043 @@
+//! Encoding of an XCSP3 instance into an aries [Model].
+//!
+//! Boolean sub-expressions of intension constraints are reified into literals and linear arithmetic is mapped onto
+//! linear constraints. Other arithmetic operators (e.g. `mul`, `abs` or `max`) are total functions whose result is
+//! represented by an auxiliary variable, whose definition is enforced even when the expression appears in a reified
+//! context.
+
+use crate::parser::*;
+use anyhow::*;
+use aries::core::{IntCst, Lit, LongCst, INT_CST_MAX, INT_CST_MIN};
+use aries::model::extensions::AssignmentExt;
+use aries::model::lang::expr::*;
+use aries::model::lang::linear::{LinearSum, LinearTerm};
+use aries::model::lang::scheduling::Task;
+use aries::model::lang::{IAtom, IVar};
+use aries::reif::{ReifExpr, Reifiable};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+pub type Model = aries::model::Model<String>;
+
+/// Maximum number of tuples obtained by the expansion of the wildcards of an extension constraint.
+const MAX_EXPANDED_TUPLES: usize = 1_000_000;
+
+pub struct Objective {
+    /// Expression to minimize, which is the negation of the objective of the instance for maximization problems.
+    pub minimized: IAtom,
+    /// Objective of the instance, whose value is reported for each solution.
+    pub value: IAtom,
+}
+
+/// Result of the encoding of an XCSP3 instance.
+pub struct Encoding {
+    pub model: Model,
+    /// Variables of the instance, in the order of their declaration.
+    pub variables: Vec<(String, IVar)>,
+    pub objective: Option<Objective>,
+}
+
+pub fn encode(instance: &Instance) -> Result<Encoding> {
+    let mut encoder = Encoder {
+        model: Model::new(),
+        vars: HashMap::new(),
+        bool_views: HashMap::new(),
+        num_aux: 0,
+    };
+    let mut variables = Vec::with_capacity(instance.variables.len());
+    for (name, domain) in &instance.variables {
+        let var = encoder.declare(name, domain)?;
+        variables.push((name.clone(), var));
+    }
+    for (i, constraint) in instance.constraints.iter().enumerate() {
+        encoder
+            .post(constraint)
+            .with_context(|| format!("In constraint {i}: {constraint:?}"))?;
+    }
+    let objective = match &instance.objective {
+        Some(obj) => {
+            let value = encoder.objective(&obj.expr).context("In objective")?;
+            let minimized = if obj.minimize {
+                value
+            } else {
+                let (lb, ub) = encoder.model.domain_of(value);
+                let negated = encoder.model.new_ivar(-ub, -lb, "negated_objective".to_string());
+                let sum = LinearSum::from(value) + negated;
+                encoder.model.enforce(sum.clone().leq(0), []);
+                encoder.model.enforce(sum.geq(0), []);
+                negated.into()
+            };
+            Some(Objective { minimized, value })
+        }
+        None => None,
+    };
+    Ok(Encoding {
+        model: encoder.model,
+        variables,
+        objective,
+    })
+}
+
+/// A linear expression `Σ factor * var + constant`.
+#[derive(Clone, Debug, Default)]
+struct Lin {
+    terms: Vec<(IntCst, IVar)>,
+    constant: IntCst,
+}
+
+impl Lin {
+    fn constant(constant: IntCst) -> Lin {
+        Lin {
+            terms: Vec::new(),
+            constant,
+        }
+    }
+
+    fn atom(atom: IAtom) -> Lin {
+        let terms = if atom.var == IVar::ZERO {
+            Vec::new()
+        } else {
+            vec![(1, atom.var)]
+        };
+        Lin {
+            terms,
+            constant: atom.shift,
+        }
+    }
+
+    fn as_constant(&self) -> Option<IntCst> {
+        self.terms.is_empty().then_some(self.constant)
+    }
+
+    fn plus(mut self, other: Lin) -> Result<Lin> {
+        self.terms.extend(other.terms);
+        self.constant = self.constant.checked_add(other.constant).context("Integer overflow")?;
+        Ok(self)
+    }
+
+    fn times(self, k: IntCst) -> Result<Lin> {
+        let overflow = || anyhow!("Integer overflow");
+        Ok(Lin {
+            terms: self
+                .terms
+                .into_iter()
+                .map(|(f, v)| Ok((f.checked_mul(k).ok_or_else(overflow)?, v)))
+                .collect::<Result<_>>()?,
+            constant: self.constant.checked_mul(k).ok_or_else(overflow)?,
+        })
+    }
+
+    fn minus(self, other: Lin) -> Result<Lin> {
+        self.plus(other.times(-1)?)
+    }
+
+    fn sum(&self) -> LinearSum {
+        let mut sum = LinearSum::zero();
+        for &(f, v) in &self.terms {
+            sum += LinearTerm::int(f, v, Lit::TRUE);
+        }
+        sum + self.constant
+    }
+}
+
+struct Encoder {
+    model: Model,
+    vars: HashMap<String, IVar>,
+    /// Integer variables in `[0, 1]` that are equal to a literal.
+    bool_views: HashMap<Lit, IVar>,
+    num_aux: usize,
+}
+
+impl Encoder {
+    /* ============================= Variables ============================== */
+
+    fn declare(&mut self, name: &str, domain: &Domain) -> Result<IVar> {
+        let (lb, ub) = domain.bounds().context("Empty domain")?;
+        ensure!(
+            INT_CST_MIN <= lb && ub <= INT_CST_MAX,
+            "Domain of {name} exceeds the supported range"
+        );
+        let var = self.model.new_ivar(lb, ub, name.to_string());
+        if domain.intervals().len() > 1 {
+            let in_domain = self.in_set(var.into(), domain);
+            self.model.enforce(in_domain, []);
+        }
+        self.vars.insert(name.to_string(), var);
+        Ok(var)
+    }
+
+    /// Creates an auxiliary variable with the given bounds, clamped to the range of representable values.
+    fn new_aux(&mut self, lb: LongCst, ub: LongCst) -> IVar {
+        let clamp = |v: LongCst| v.clamp(INT_CST_MIN as LongCst, INT_CST_MAX as LongCst) as IntCst;
+        self.num_aux += 1;
+        self.model
+            .new_ivar(clamp(lb), clamp(ub), format!("_aux{}", self.num_aux))
+    }
+
+    fn bounds(&self, atom: IAtom) -> (LongCst, LongCst) {
+        let (lb, ub) = self.model.domain_of(atom);
+        (lb as LongCst, ub as LongCst)
+    }
+
+    fn lin_bounds(&self, lin: &Lin) -> (LongCst, LongCst) {
+        let mut lb = lin.constant as LongCst;
+        let mut ub = lin.constant as LongCst;
+        for &(f, v) in &lin.terms {
+            let (vlb, vub) = self.bounds(v.into());
+            let (a, b) = (f as LongCst * vlb, f as LongCst * vub);
+            lb += a.min(b);
+            ub += a.max(b);
+        }
+        (lb, ub)
+    }
+
+    fn term(&self, term: &Term) -> Result<IAtom> {
+        match term {
+            Term::Int(i) => Ok(IAtom::from(*i)),
+            Term::Var(name) => self
+                .vars
+                .get(name)
+                .map(|v| IAtom::from(*v))
+                .with_context(|| format!("Unknown variable: {name}")),
+        }
+    }
+
+    fn terms(&self, terms: &[Term]) -> Result<Vec<IAtom>> {
+        terms.iter().map(|t| self.term(t)).collect()
+    }
+
+    fn par_int(&self, term: &Term) -> Result<IntCst> {
+        match self.model.domain_of(self.term(term)?) {
+            (lb, ub) if lb == ub => Ok(lb),
+            _ => bail!("Expected a fixed integer but got {term:?}"),
+        }
+    }
+
+    fn par_ints(&self, terms: &[Term]) -> Result<Vec<IntCst>> {
+        terms.iter().map(|t| self.par_int(t)).collect()
+    }
+
+    /// Returns a variable that is equal to the given integer atom, creating it if necessary.
+    fn ivar(&mut self, atom: IAtom) -> IVar {
+        if atom.shift == 0 && atom.var != IVar::ZERO {
+            atom.var
+        } else {
+            let (lb, ub) = self.bounds(atom);
+            let var = self.new_aux(lb, ub);
+            self.model.enforce(eq(var, atom), []);
+            var
+        }
+    }
+
+    /// Returns an atom that is equal to the linear expression, introducing an auxiliary variable if necessary.
+    fn lin_atom(&mut self, lin: Lin) -> IAtom {
+        match lin.terms.as_slice() {
+            [] => IAtom::from(lin.constant),
+            [(1, v)] => IAtom::new(*v, lin.constant),
+            _ => {
+                let (lb, ub) = self.lin_bounds(&lin);
+                let var = self.new_aux(lb, ub);
+                let sum = lin
+                    .minus(Lin::atom(var.into()))
+                    .expect("no overflow on unit factors")
+                    .sum();
+                self.model.enforce(sum.clone().leq(0), []);
+                self.model.enforce(sum.geq(0), []);
+                var.into()
+            }
+        }
+    }
+
+    /// Returns an integer variable in `[0, 1]` that is equal to 1 iff the literal is true.
+    fn bool_view(&mut self, lit: Lit) -> IVar {
+        if let Some(view) = self.bool_views.get(&lit) {
+            return *view;
+        }
+        let view = self.new_aux(0, 1);
+        self.model.bind(geq(view, 1), lit);
+        self.bool_views.insert(lit, view);
+        view
+    }
+
+    /// Expression that is true iff the value of the atom is in the set.
+    fn in_set(&mut self, atom: IAtom, set: &Domain) -> ReifExpr {
+        let intervals: Vec<Lit> = set
+            .intervals()
+            .iter()
+            .map(|&(lb, ub)| self.model.reify(and([atom.ge_lit(lb), atom.le_lit(ub)])))
+            .collect();
+        or(intervals).into()
+    }
+
+    /// Literal that is true iff all expressions are true.
+    fn reify_all(&mut self, exprs: Vec<ReifExpr>) -> Lit {
+        match exprs.len() {
+            1 => self.model.reify(exprs.into_iter().next().unwrap()),
+            _ => {
+                let lits = exprs.into_iter().map(|e| self.model.reify(e)).collect_vec();
+                self.model.reify(and(lits))
+            }
+        }
+    }
+
+    /* ============================= Expressions ============================ */
+
+    fn args<'a>(&self, args: &'a [Expr], n: usize, op: &str) -> Result<&'a [Expr]> {
+        ensure!(args.len() == n, "Operator {op} expects {n} arguments");
+        Ok(args)
+    }
+
+    /// Linear form of an integer expression.
+    fn lin(&mut self, expr: &Expr) -> Result<Lin> {
+        match expr {
+            Expr::Call(op, args) => match op.as_str() {
+                "add" => args.iter().try_fold(Lin::default(), |acc, a| acc.plus(self.lin(a)?)),
+                "sub" => {
+                    let args = self.args(args, 2, op)?;
+                    self.lin(&args[0])?.minus(self.lin(&args[1])?)
+                }
+                "neg" => self.lin(&self.args(args, 1, op)?[0])?.times(-1),
+                "mul" if args.len() == 2 => {
+                    let (a, b) = (self.lin(&args[0])?, self.lin(&args[1])?);
+                    match (a.as_constant(), b.as_constant()) {
+                        (Some(k), _) => b.times(k),
+                        (_, Some(k)) => a.times(k),
+                        _ => Ok(Lin::atom(self.int(expr)?)),
+                    }
+                }
+                _ => Ok(Lin::atom(self.int(expr)?)),
+            },
+            _ => Ok(Lin::atom(self.int(expr)?)),
+        }
+    }
+
+    /// Returns an atom whose value is the value of the integer expression.
+    fn int(&mut self, expr: &Expr) -> Result<IAtom> {
+        let (op, args) = match expr {
+            Expr::Int(i) => return Ok(IAtom::from(*i)),
+            Expr::Var(name) => return self.term(&Term::Var(name.clone())),
+            Expr::Call(op, args) => (op.as_str(), args.as_slice()),
+        };
+        let atom = match op {
+            "add" | "sub" | "neg" => {
+                let lin = self.lin(expr)?;
+                self.lin_atom(lin)
+            }
+            "mul" if args.len() != 2 => {
+                let (first, rest) = args.split_first().context("Operator mul expects arguments")?;
+                let rest = Expr::Call("mul".to_string(), rest.to_vec());
+                return self.int(&Expr::Call("mul".to_string(), vec![first.clone(), rest]));
+            }
+            "mul" => {
+                let (a, b) = (self.lin(&args[0])?, self.lin(&args[1])?);
+                match (a.as_constant(), b.as_constant()) {
+                    (Some(k), _) => self.lin_atom(b.times(k)?),
+                    (_, Some(k)) => self.lin_atom(a.times(k)?),
+                    _ => {
+                        let (a, b) = (self.lin_atom(a), self.lin_atom(b));
+                        self.product(a, b)?
+                    }
+                }
+            }
+            "sqr" => {
+                let a = self.int(&self.args(args, 1, op)?[0])?;
+                self.product(a, a)?
+            }
+            "pow" => {
+                let args = self.args(args, 2, op)?;
+                let exponent = self.lin(&args[1])?.as_constant().context("Non-constant exponent")?;
+                ensure!(exponent >= 0, "Negative exponent");
+                let base = self.int(&args[0])?;
+                let mut result = IAtom::from(1);
+                for _ in 0..exponent {
+                    result = self.product(result, base)?;
+                }
+                result
+            }
+            "abs" | "dist" => {
+                let value = if op == "abs" {
+                    self.int(&self.args(args, 1, op)?[0])?
+                } else {
+                    let args = self.args(args, 2, op)?;
+                    let lin = self.lin(&args[0])?.minus(self.lin(&args[1])?)?;
+                    self.lin_atom(lin)
+                };
+                let (lb, ub) = self.bounds(value);
+                let (abs_lb, abs_ub) = if lb >= 0 {
+                    (lb, ub)
+                } else if ub <= 0 {
+                    (-ub, -lb)
+                } else {
+                    (0, ub.max(-lb))
+                };
+                let abs = self.new_aux(abs_lb, abs_ub);
+                let value = self.ivar(value);
+                self.model.enforce(eq_abs(abs, value), []);
+                abs.into()
+            }
+            "min" | "max" => {
+                ensure!(!args.is_empty(), "Operator {op} expects arguments");
+                let vals = args.iter().map(|a| self.int(a)).collect::<Result<Vec<_>>>()?;
+                let bounds = vals.iter().map(|v| self.bounds(*v)).collect_vec();
+                let (lb, ub) = if op == "min" {
+                    (bounds.iter().map(|b| b.0).min(), bounds.iter().map(|b| b.1).min())
+                } else {
+                    (bounds.iter().map(|b| b.0).max(), bounds.iter().map(|b| b.1).max())
+                };
+                let result = self.new_aux(lb.unwrap(), ub.unwrap());
+                let vars = vals.into_iter().map(|v| self.ivar(v)).collect_vec();
+                if op == "min" {
+                    self.model.enforce(eq_min(result, vars), []);
+                } else {
+                    self.model.enforce(eq_max(result, vars), []);
+                }
+                result.into()
+            }
+            "div" | "mod" => {
+                let args = self.args(args, 2, op)?;
+                let divisor = self
+                    .lin(&args[1])?
+                    .as_constant()
+                    .context("Only divisions by a constant are supported")?;
+                ensure!(divisor != 0, "Division by zero");
+                let numerator = self.int(&args[0])?;
+                let (lb, ub) = self.bounds(numerator);
+                let numerator = self.ivar(numerator);
+                let d = divisor as LongCst;
+                if op == "div" {
+                    let (a, b) = (lb / d, ub / d);
+                    let result = self.new_aux(a.min(b), a.max(b));
+                    self.model.enforce(eq_div(result, numerator, divisor), []);
+                    result.into()
+                } else {
+                    let m = d.abs() - 1;
+                    let result = self.new_aux(if lb < 0 { -m } else { 0 }, if ub > 0 { m } else { 0 });
+                    self.model.enforce(eq_mod(result, numerator, divisor), []);
+                    result.into()
+                }
+            }
+            "if" => {
+                let args = self.args(args, 3, op)?;
+                let cond = self.lit(&args[0])?;
+                let (a, b) = (self.int(&args[1])?, self.int(&args[2])?);
+                let ((alb, aub), (blb, bub)) = (self.bounds(a), self.bounds(b));
+                let result = self.new_aux(alb.min(blb), aub.max(bub));
+                let is_a = self.model.reify(eq(result, a));
+                let is_b = self.model.reify(eq(result, b));
+                self.model.enforce(implies(cond, is_a), []);
+                self.model.enforce(implies(!cond, is_b), []);
+                result.into()
+            }
+            _ => {
+                // boolean expression used as an integer
+                let lit = self.lit(expr)?;
+                self.bool_view(lit).into()
+            }
+        };
+        Ok(atom)
+    }
+
+    /// Atom equal to `a * b`.
+    fn product(&mut self, a: IAtom, b: IAtom) -> Result<IAtom> {
+        let ((alb, aub), (blb, bub)) = (self.bounds(a), self.bounds(b));
+        let corners = [alb * blb, alb * bub, aub * blb, aub * bub];
+        let result = self.new_aux(*corners.iter().min().unwrap(), *corners.iter().max().unwrap());
+        let (a, b) = (self.ivar(a), self.ivar(b));
+        self.model.enforce(eq_mul(result, a, b), []);
+        Ok(result.into())
+    }
+
+    /// Literal that is true iff the boolean expression is true.
+    fn lit(&mut self, expr: &Expr) -> Result<Lit> {
+        match expr {
+            Expr::Int(0) => Ok(Lit::FALSE),
+            Expr::Int(_) => Ok(Lit::TRUE),
+            Expr::Var(_) => {
+                let atom = self.int(expr)?;
+                match self.model.domain_of(atom) {
+                    (lb, ub) if lb >= 0 && ub <= 1 => Ok(atom.ge_lit(1)),
+                    _ => bail!("Variable used as a boolean does not have a 0/1 domain: {expr:?}"),
+                }
+            }
+            _ => {
+                let e = self.bool_expr(expr)?;
+                Ok(self.model.reify(e))
+            }
+        }
+    }
+
+    fn lits(&mut self, exprs: &[Expr]) -> Result<Vec<Lit>> {
+        exprs.iter().map(|e| self.lit(e)).collect()
+    }
+
+    /// Reifiable expression that is equivalent to the boolean expression.
+    fn bool_expr(&mut self, expr: &Expr) -> Result<ReifExpr> {
+        let Expr::Call(op, args) = expr else {
+            return Ok(self.lit(expr)?.into());
+        };
+        let op = op.as_str();
+        let e: ReifExpr = match op {
+            "lt" | "le" | "gt" | "ge" | "eq" | "ne" if args.len() == 2 => {
+                let diff = self.lin(&args[0])?.minus(self.lin(&args[1])?)?;
+                let cond = self.compare(diff, op, true)?;
+                self.reify_all(cond).into()
+            }
+            "eq" => {
+                // all arguments are equal
+                let (first, rest) = args.split_first().context("Operator eq expects arguments")?;
+                let first = self.lin(first)?;
+                let mut conjuncts = Vec::new();
+                for other in rest {
+                    let diff = first.clone().minus(self.lin(other)?)?;
+                    conjuncts.extend(self.compare(diff, "eq", true)?);
+                }
+                self.reify_all(conjuncts).into()
+            }
+            "not" => (!self.lit(&self.args(args, 1, op)?[0])?).into(),
+            "and" => and(self.lits(args)?).into(),
+            "or" => or(self.lits(args)?).into(),
+            "xor" => {
+                // chain of exclusive or, where `parity` is true if an odd number of literals are true
+                let mut parity = Lit::FALSE;
+                for l in self.lits(args)? {
+                    parity = self.model.reify(neq(parity, l));
+                }
+                parity.into()
+            }
+            "iff" => {
+                let lits = self.lits(args)?;
+                let (first, rest) = lits.split_first().context("Operator iff expects arguments")?;
+                let equalities = rest
+                    .iter()
+                    .map(|l| {
+                        let e = eq(*first, *l).decompose(&mut self.model);
+                        self.model.reify(e)
+                    })
+                    .collect_vec();
+                and(equalities).into()
+            }
+            "imp" => {
+                let args = self.args(args, 2, op)?;
+                implies(self.lit(&args[0])?, self.lit(&args[1])?).into()
+            }
+            "in" | "notin" => {
+                let args = self.args(args, 2, op)?;
+                let values = match &args[1] {
+                    Expr::Call(s, values) if s == "set" => values
+                        .iter()
+                        .map(|v| self.lin(v)?.as_constant().context("Non-constant element in set"))
+                        .collect::<Result<Vec<_>>>()?,
+                    e => bail!("Expected a set but got {e:?}"),
+                };
+                let x = self.int(&args[0])?;
+                let in_set = self.in_set(x, &Domain::from_values(values));
+                if op == "in" {
+                    in_set
+                } else {
+                    !in_set
+                }
+            }
+            "if" => {
+                let args = self.args(args, 3, op)?;
+                let cond = self.lit(&args[0])?;
+                let (a, b) = (self.lit(&args[1])?, self.lit(&args[2])?);
+                let when_true = self.model.reify(and([cond, a]));
+                let when_false = self.model.reify(and([!cond, b]));
+                or([when_true, when_false]).into()
+            }
+            _ => {
+                // integer expression used as a boolean
+                let value = self.int(expr)?;
+                let (lb, ub) = self.model.domain_of(value);
+                ensure!(lb >= 0 && ub <= 1, "Expected a boolean expression but got {expr:?}");
+                value.ge_lit(1).into()
+            }
+        };
+        Ok(e)
+    }
+
+    /// Rewrites the linear expression so that its comparison to a constant can be reified, which is only supported
+    /// by the solver for a single variable or for the difference of two variables.
+    fn reifiable(&mut self, value: Lin) -> Lin {
+        match value.terms.as_slice() {
+            [] | [(1 | -1, _)] => value,
+            [(1, x), (-1, y)] | [(-1, x), (1, y)] if x != y => value,
+            _ => {
+                let constant = value.constant;
+                let atom = self.lin_atom(Lin { constant: 0, ..value });
+                Lin {
+                    terms: vec![(1, atom.var)],
+                    constant: atom.shift + constant,
+                }
+            }
+        }
+    }
+
+    /// Conjunction of expressions equivalent to `value <op> 0`.
+    /// If `reified` is true, the expressions are meant to be reified rather than enforced.
+    fn compare(&mut self, value: Lin, op: &str, reified: bool) -> Result<Vec<ReifExpr>> {
+        let value = if reified || op == "ne" {
+            self.reifiable(value)
+        } else {
+            value
+        };
+        let sum = value.sum();
+        Ok(match op {
+            "lt" => vec![sum.leq(-1).into()],
+            "le" => vec![sum.leq(0).into()],
+            "gt" => vec![sum.geq(1).into()],
+            "ge" => vec![sum.geq(0).into()],
+            "eq" => vec![sum.clone().leq(0).into(), sum.geq(0).into()],
+            "ne" => {
+                let le = self.model.reify(sum.clone().leq(-1));
+                let ge = self.model.reify(sum.geq(1));
+                vec![or([le, ge]).into()]
+            }
+            _ => bail!("Unknown comparison operator: {op}"),
+        })
+    }
+
+    /// Conjunction of expressions equivalent to the condition applied to the value.
+    /// If `reified` is true, the expressions are meant to be reified rather than enforced.
+    fn condition(&mut self, value: Lin, condition: &Condition, reified: bool) -> Result<Vec<ReifExpr>> {
+        let value = if reified || condition.op == Operator::NotIn {
+            self.reifiable(value)
+        } else {
+            value
+        };
+        let op = match condition.op {
+            Operator::Lt => "lt",
+            Operator::Le => "le",
+            Operator::Ge => "ge",
+            Operator::Gt => "gt",
+            Operator::Eq => "eq",
+            Operator::Ne => "ne",
+            Operator::In | Operator::NotIn => {
+                let Operand::Set(set) = &condition.operand else {
+                    bail!("Expected a set in condition")
+                };
+                let in_set = match set.intervals() {
+                    &[(lb, ub)] => {
+                        let sum = value.sum();
+                        vec![sum.clone().geq(lb).into(), sum.leq(ub).into()]
+                    }
+                    _ => {
+                        let atom = self.lin_atom(value);
+                        vec![self.in_set(atom, set)]
+                    }
+                };
+                return Ok(if condition.op == Operator::In {
+                    in_set
+                } else {
+                    vec![(!self.reify_all(in_set)).into()]
+                });
+            }
+        };
+        let Operand::Term(rhs) = &condition.operand else {
+            bail!("Expected a value in condition")
+        };
+        let diff = value.minus(Lin::atom(self.term(rhs)?))?;
+        self.compare(diff, op, reified)
+    }
+
+    /* ============================ Constraints ============================= */
+
+    fn enforce_all(&mut self, exprs: Vec<ReifExpr>) {
+        for e in exprs {
+            self.model.enforce(e, []);
+        }
+    }
+
+    /// Enforces a boolean expression, splitting conjunctions and comparisons into several constraints.
+    fn enforce_expr(&mut self, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::Call(op, args) if op == "and" => {
+                for a in args {
+                    self.enforce_expr(a)?;
+                }
+            }
+            Expr::Call(op, args) if ["lt", "le", "gt", "ge", "eq"].contains(&op.as_str()) && args.len() == 2 => {
+                let diff = self.lin(&args[0])?.minus(self.lin(&args[1])?)?;
+                let exprs = self.compare(diff, op, false)?;
+                self.enforce_all(exprs);
+            }
+            _ => {
+                let e = self.bool_expr(expr)?;
+                self.model.enforce(e, []);
+            }
+        }
+        Ok(())
+    }
+
+    fn post(&mut self, constraint: &Constraint) -> Result<()> {
+        match constraint {
+            Constraint::Intension(expr) => self.enforce_expr(expr)?,
+            Constraint::Unary { var, values, supports } => {
+                let x = self.term(var)?;
+                let in_set = self.in_set(x, values);
+                self.model.enforce(if *supports { in_set } else { !in_set }, []);
+            }
+            Constraint::Extension { list, tuples, supports } => {
+                let vars = self.terms(list)?.into_iter().map(|x| self.ivar(x)).collect_vec();
+                if *supports {
+                    let tuples = self.expand_wildcards(&vars, tuples)?;
+                    self.model.enforce(table(vars, tuples), []);
+                } else {
+                    for tuple in tuples {
+                        // at least one variable differs from the forbidden tuple
+                        let differs = vars
+                            .iter()
+                            .zip(tuple)
+                            .filter_map(|(&x, v)| v.map(|v| [x.lt(v), x.gt(v)]))
+                            .flatten()
+                            .collect_vec();
+                        self.model.enforce(or(differs), []);
+                    }
+                }
+            }
+            Constraint::Sum {
+                list,
+                coeffs,
+                condition,
+            } => {
+                let mut sum = Lin::default();
+                for (x, c) in list.iter().zip(coeffs) {
+                    let x = self.term(x)?;
+                    let term = match self.par_int(c) {
+                        Result::Ok(c) => Lin::atom(x).times(c)?,
+                        Err(_) => {
+                            let c = self.term(c)?;
+                            Lin::atom(self.product(x, c)?)
+                        }
+                    };
+                    sum = sum.plus(term)?;
+                }
+                let exprs = self.condition(sum, condition, false)?;
+                self.enforce_all(exprs);
+            }
+            Constraint::AllDifferent { list, except } => {
+                let xs = self.terms(list)?;
+                if except.is_empty() {
+                    let vars = xs.into_iter().map(|x| self.ivar(x)).collect_vec();
+                    self.model.enforce(all_different(vars), []);
+                } else {
+                    let except = Domain::from_values(except.iter().copied());
+                    let excepted = xs
+                        .iter()
+                        .map(|&x| {
+                            let e = self.in_set(x, &except);
+                            self.model.reify(e)
+                        })
+                        .collect_vec();
+                    for (i, j) in (0..xs.len()).tuple_combinations() {
+                        let different = self.model.reify(neq(xs[i], xs[j]));
+                        self.model.enforce(or([different, excepted[i], excepted[j]]), []);
+                    }
+                }
+            }
+            Constraint::Element {
+                list,
+                start_index,
+                index,
+                condition,
+            } => self.element(list, *start_index, index.as_ref(), condition)?,
+            Constraint::Ordered {
+                list,
+                lengths,
+                operator,
+            } => {
+                let op = match operator {
+                    Operator::Lt => "lt",
+                    Operator::Le => "le",
+                    Operator::Gt => "gt",
+                    _ => "ge",
+                };
+                for (i, (x, y)) in list.iter().tuple_windows().enumerate() {
+                    let (x, y) = (self.term(x)?, self.term(y)?);
+                    let length = self.term(&lengths[i])?;
+                    let diff = Lin::atom(x).plus(Lin::atom(length))?.minus(Lin::atom(y))?;
+                    let exprs = self.compare(diff, op, false)?;
+                    self.enforce_all(exprs);
+                }
+            }
+            Constraint::NoOverlap {
+                origins,
+                lengths,
+                zero_ignored,
+            } => self.no_overlap(origins, lengths, *zero_ignored)?,
+            Constraint::Cumulative {
+                origins,
+                lengths,
+                heights,
+                condition,
+            } => {
+                let capacity = match (condition.op, &condition.operand) {
+                    (Operator::Le, Operand::Term(c)) => self.par_int(c),
+                    (Operator::Lt, Operand::Term(c)) => self.par_int(c).map(|c| c - 1),
+                    _ => bail!("Only cumulative constraints with a (le,c) condition are supported"),
+                }
+                .context("Only cumulative constraints with a constant capacity are supported")?;
+                let lengths = self.par_ints(lengths).context("Only fixed lengths are supported")?;
+                let heights = self.par_ints(heights).context("Only fixed heights are supported")?;
+                ensure!(
+                    origins.len() == lengths.len() && origins.len() == heights.len(),
+                    "Tasks parameters of different sizes"
+                );
+                let mut tasks = Vec::with_capacity(origins.len());
+                for (i, origin) in origins.iter().enumerate() {
+                    let start = self.term(origin)?;
+                    let start = self.ivar(start);
+                    tasks.push(Task::new(start, lengths[i], heights[i]));
+                }
+                self.model.enforce(cumulative(tasks, capacity), []);
+            }
+            Constraint::Instantiation { list, values } => {
+                ensure!(list.len() == values.len(), "Variables and values of different sizes");
+                for (x, v) in list.iter().zip(values) {
+                    let x = self.term(x)?;
+                    self.model.enforce(eq(x, *v), []);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the wildcards of the tuples by all values in the domain of the corresponding variable.
+    fn expand_wildcards(&self, vars: &[IVar], tuples: &[Vec<Option<IntCst>>]) -> Result<Vec<Vec<IntCst>>> {
+        let mut expanded = Vec::with_capacity(tuples.len());
+        for tuple in tuples {
+            let values = tuple.iter().zip(vars).map(|(v, &x)| match v {
+                Some(v) => vec![*v],
+                None => {
+                    let (lb, ub) = self.model.domain_of(x);
+                    (lb..=ub).collect()
+                }
+            });
+            for t in values.multi_cartesian_product() {
+                expanded.push(t);
+                ensure!(
+                    expanded.len() <= MAX_EXPANDED_TUPLES,
+                    "Too many tuples after expansion of wildcards"
+                );
+            }
+        }
+        Ok(expanded)
+    }
+
+    fn element(&mut self, list: &[Term], start_index: IntCst, index: Option<&Term>, cond: &Condition) -> Result<()> {
+        let xs = self.terms(list)?;
+        ensure!(!xs.is_empty(), "Element of an empty list");
+        let Some(index) = index else {
+            // some element of the list satisfies the condition
+            let mut disjuncts = Vec::with_capacity(xs.len());
+            for x in xs {
+                let exprs = self.condition(Lin::atom(x), cond, true)?;
+                disjuncts.push(self.reify_all(exprs));
+            }
+            self.model.enforce(or(disjuncts), []);
+            return Ok(());
+        };
+        // zero-based index
+        let index = Lin::atom(self.term(index)?).minus(Lin::constant(start_index))?;
+        let index = self.lin_atom(index);
+        self.model.enforce(geq(index, 0), []);
+        self.model.enforce(leq(index, xs.len() as IntCst - 1), []);
+
+        let value = match (cond.op, &cond.operand) {
+            (Operator::Eq, Operand::Term(v)) => self.term(v)?,
+            _ => {
+                let bounds = xs.iter().map(|x| self.bounds(*x)).collect_vec();
+                let lb = bounds.iter().map(|b| b.0).min().unwrap();
+                let ub = bounds.iter().map(|b| b.1).max().unwrap();
+                let value = self.new_aux(lb, ub);
+                let exprs = self.condition(Lin::atom(value.into()), cond, false)?;
+                self.enforce_all(exprs);
+                value.into()
+            }
+        };
+        let array = xs.iter().map(|&x| self.model.domain_of(x)).collect_vec();
+        if array.iter().all(|(lb, ub)| lb == ub) {
+            let array = array.into_iter().map(|(v, _)| v).collect_vec();
+            let (index, value) = (self.ivar(index), self.ivar(value));
+            self.model.enforce(element(array, index, value), []);
+        } else {
+            for (i, x) in xs.into_iter().enumerate() {
+                let selected = self.model.reify(eq(index, i as IntCst));
+                let equal = self.model.reify(eq(x, value));
+                self.model.enforce(implies(selected, equal), []);
+            }
+        }
+        Ok(())
+    }
+
+    fn no_overlap(&mut self, origins: &[Term], lengths: &[Term], zero_ignored: bool) -> Result<()> {
+        ensure!(origins.len() == lengths.len(), "Origins and lengths of different sizes");
+        let starts = self.terms(origins)?;
+        let durations = self.terms(lengths)?;
+        if let Result::Ok(fixed) = self.par_ints(lengths) {
+            if fixed.iter().all(|&d| d > 0) || zero_ignored {
+                let mut tasks = Vec::with_capacity(starts.len());
+                for (&s, d) in starts.iter().zip(fixed) {
+                    if d > 0 {
+                        let s = self.ivar(s);
+                        tasks.push(Task::new(s, d, 1));
+                    }
+                }
+                self.model.enforce(no_overlap(tasks), []);
+                return Ok(());
+            }
+        }
+        // pairwise decomposition: one of the tasks ends before the other starts
+        for (i, j) in (0..starts.len()).tuple_combinations() {
+            let mut disjuncts = Vec::with_capacity(4);
+            for (a, b) in [(i, j), (j, i)] {
+                let end_before_start = Lin::atom(starts[a])
+                    .plus(Lin::atom(durations[a]))?
+                    .minus(Lin::atom(starts[b]))?;
+                let end_before_start = self.reifiable(end_before_start);
+                disjuncts.push(self.model.reify(end_before_start.sum().leq(0)));
+                if zero_ignored {
+                    disjuncts.push(self.model.reify(leq(durations[a], 0)));
+                }
+            }
+            self.model.enforce(or(disjuncts), []);
+        }
+        Ok(())
+    }
+
+    fn objective(&mut self, objective: &ObjectiveExpr) -> Result<IAtom> {
+        match objective {
+            ObjectiveExpr::Expr(e) => self.int(e),
+            ObjectiveExpr::Sum { list, coeffs } => {
+                let mut sum = Lin::default();
+                for (x, c) in list.iter().zip(coeffs) {
+                    let x = self.term(x)?;
+                    sum = sum.plus(Lin::atom(x).times(self.par_int(c)?)?)?;
+                }
+                Ok(self.lin_atom(sum))
+            }
+            ObjectiveExpr::Minimum(list) | ObjectiveExpr::Maximum(list) => {
+                let op = if matches!(objective, ObjectiveExpr::Minimum(_)) {
+                    "min"
+                } else {
+                    "max"
+                };
+                let args = list
+                    .iter()
+                    .map(|t| match t {
+                        Term::Int(i) => Expr::Int(*i),
+                        Term::Var(v) => Expr::Var(v.clone()),
+                    })
+                    .collect();
+                self.int(&Expr::Call(op.to_string(), args))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aries::core::VarRef;
+    use aries::solver::Solver;
+
+    fn instance(variables: &str, constraints: &str, objectives: &str) -> Instance {
+        let input = format!(
+            "<instance format=\"XCSP3\" type=\"CSP\"><variables>{variables}</variables>\
+             <constraints>{constraints}</constraints>{objectives}</instance>"
+        );
+        Instance::parse(&input).unwrap()
+    }
+
+    /// Returns all solutions of the problem, as the value of its variables.
+    fn solutions(variables: &str, constraints: &str) -> Vec<Vec<IntCst>> {
+        let encoding = encode(&instance(variables, constraints, "")).unwrap();
+        let vars: Vec<VarRef> = encoding.variables.iter().map(|(_, v)| (*v).into()).collect();
+        let mut solver = Solver::new(encoding.model);
+        let mut sols = solver.enumerate(&vars).unwrap();
+        sols.sort();
+        sols
+    }
+
+    #[test]
+    fn test_intension() {
+        let vars = "<var id=\"x\"> 0..3 </var><var id=\"y\"> 0 2 3 </var><var id=\"b\"> 0 1 </var>";
+        let sols = solutions(vars, "<intension> iff(b, lt(add(x,1),y)) </intension><intension> ne(mul(2,x),y) </intension><intension> or(b, eq(x,3)) </intension>");
+        assert_eq!(
+            sols,
+            vec![
+                vec![0, 2, 1],
+                vec![0, 3, 1],
+                vec![1, 3, 1],
+                vec![3, 0, 0],
+                vec![3, 2, 0],
+                vec![3, 3, 0]
+            ]
+        );
+        let sols = solutions(
+            vars,
+            "<intension> eq(x, max(y, mul(b, 3))) </intension><intension> in(dist(x, y), set(1, 3)) </intension>",
+        );
+        assert_eq!(sols, vec![vec![3, 0, 1], vec![3, 2, 1]]);
+        // reification of linear constraints over more than two variables
+        let sols = solutions(
+            vars,
+            "<intension> imp(ge(add(x,y,mul(3,b)),6), eq(x,y)) </intension><intension> gt(x,2) </intension>",
+        );
+        assert_eq!(sols, vec![vec![3, 0, 0], vec![3, 2, 0], vec![3, 3, 0], vec![3, 3, 1]]);
+    }
+
+    #[test]
+    fn test_extension() {
+        let vars = "<array id=\"x\" size=\"[2]\"> 0..2 </array>";
+        let supports = "<extension><list> x[] </list><supports> (0,*)(2,1) </supports></extension>";
+        assert_eq!(
+            solutions(vars, supports),
+            vec![vec![0, 0], vec![0, 1], vec![0, 2], vec![2, 1]]
+        );
+        let conflicts = "<extension><list> x[] </list><conflicts> (*,0)(1,*) </conflicts></extension>\
+            <extension><list> x[0] </list><supports> 0..1 </supports></extension>";
+        assert_eq!(solutions(vars, conflicts), vec![vec![0, 1], vec![0, 2]]);
+    }
+
+    #[test]
+    fn test_globals() {
+        let vars = "<array id=\"x\" size=\"[3]\"> 1..3 </array><var id=\"i\"> 0..5 </var>";
+        let constraints = "<allDifferent> x[] </allDifferent>\
+            <sum><list> x[0] x[1] </list><coeffs> 1 2 </coeffs><condition> (le,6) </condition></sum>\
+            <element><list startIndex=\"1\"> x[] </list><index> i </index><value> 3 </value></element>";
+        assert_eq!(
+            solutions(vars, constraints),
+            vec![vec![1, 2, 3, 3], vec![2, 1, 3, 3], vec![3, 1, 2, 1]]
+        );
+        let constraints = "<ordered><list> x[] </list><operator> le </operator></ordered>\
+            <intension> eq(i, 0) </intension>\
+            <noOverlap><origins> x[0] x[2] </origins><lengths> 2 1 </lengths></noOverlap>";
+        assert_eq!(
+            solutions(vars, constraints),
+            vec![vec![1, 1, 3, 0], vec![1, 2, 3, 0], vec![1, 3, 3, 0]]
+        );
+    }
+
+    #[test]
+    fn test_maximize() {
+        let vars = "<var id=\"x\"> 0..10 </var><var id=\"y\"> 0..10 </var>";
+        let constraints = "<sum><list> x y </list><coeffs> 3 2 </coeffs><condition> (le,12) </condition></sum>";
+        let obj = "<objectives><maximize> add(x, mul(y, 2)) </maximize></objectives>";
+        let encoding = encode(&instance(vars, constraints, obj)).unwrap();
+        let objective = encoding.objective.unwrap();
+        let mut solver = Solver::new(encoding.model);
+        let (cost, sol) = solver.minimize(objective.minimized).unwrap().unwrap();
+        assert_eq!(cost, -12);
+        assert_eq!(sol.var_domain(objective.value).lb, 12);
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let vars = "<var id=\"x\"> 0..3 </var><var id=\"y\"> 1..3 </var>";
+        let inst = instance(vars, "<intension> eq(div(x, y), 1) </intension>", "");
+        assert!(encode(&inst).is_err());
+        let inst = instance(vars, "<intension> and(x, y) </intension>", "");
+        assert!(encode(&inst).is_err());
+    }
+}
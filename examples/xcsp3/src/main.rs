@@ -0,0 +1,104 @@
+//! XCSP3 front-end of aries, following the output format of the XCSP3 competition.
+//!
+//! The final status is given by an `s` line (`SATISFIABLE`, `OPTIMUM FOUND`, `UNSATISFIABLE`, `UNKNOWN` or
+//! `UNSUPPORTED`), the best solution found is given in `v` lines and the objective value of each improved solution
+//! is reported on an `o` line as soon as it is found.
+
+mod encode;
+mod parser;
+
+use crate::encode::{encode, Encoding};
+use crate::parser::Instance;
+use anyhow::*;
+use aries::model::extensions::{AssignmentExt, SavedAssignment};
+use aries::solver::parallel::{ParSolver, Solution, SolverResult};
+use aries::solver::search::activity::{ActivityBrancher, BranchingParams};
+use aries::solver::Solver;
+use itertools::Itertools;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "xcsp3-aries")]
+struct Opt {
+    /// XCSP3 instance to solve.
+    file: PathBuf,
+    /// Time limit in seconds.
+    #[structopt(short = "t", long = "timeout")]
+    timeout: Option<u64>,
+    /// Number of workers to be run in parallel.
+    #[structopt(short = "p", long = "threads", default_value = "1")]
+    threads: usize,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+    let start = Instant::now();
+    let deadline = opt.timeout.map(|s| start + Duration::from_secs(s));
+    ensure!(opt.threads >= 1, "At least one worker is needed");
+
+    let input = std::fs::read_to_string(&opt.file).context("Could not read file")?;
+    let encoding = match Instance::parse(&input).and_then(|instance| encode(&instance)) {
+        Result::Ok(encoding) => encoding,
+        Err(e) => {
+            for cause in e.chain() {
+                println!("c {cause}");
+            }
+            println!("s UNSUPPORTED");
+            return Ok(());
+        }
+    };
+    println!("c parsed and encoded in {:.3}s", start.elapsed().as_secs_f64());
+
+    let mut solver = ParSolver::new(Box::new(Solver::new(encoding.model.clone())), opt.threads, |id, s| {
+        if id > 0 {
+            // diversify the search of the additional workers
+            s.set_brancher(ActivityBrancher::new_with_params(BranchingParams {
+                prefer_min_value: id % 2 == 0,
+                ..Default::default()
+            }))
+        }
+    });
+    match &encoding.objective {
+        None => match solver.solve(deadline) {
+            SolverResult::Sol(sol) => {
+                println!("s SATISFIABLE");
+                print_solution(&encoding, &sol, false);
+            }
+            SolverResult::Unsat => println!("s UNSATISFIABLE"),
+            SolverResult::Timeout(_) => println!("s UNKNOWN"),
+        },
+        Some(objective) => {
+            let on_improved_solution = |sol: Solution| println!("o {}", sol.var_domain(objective.value).lb);
+            match solver.minimize_with(objective.minimized, on_improved_solution, deadline) {
+                SolverResult::Sol(sol) => {
+                    println!("s OPTIMUM FOUND");
+                    print_solution(&encoding, &sol, true);
+                }
+                SolverResult::Timeout(Some(sol)) => {
+                    println!("s SATISFIABLE");
+                    print_solution(&encoding, &sol, false);
+                }
+                SolverResult::Timeout(None) => println!("s UNKNOWN"),
+                SolverResult::Unsat => println!("s UNSATISFIABLE"),
+            }
+        }
+    }
+    println!("c solved in {:.3}s", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+fn print_solution(encoding: &Encoding, sol: &SavedAssignment, optimal: bool) {
+    let tpe = if optimal { "optimum" } else { "solution" };
+    let cost = match &encoding.objective {
+        Some(obj) => format!(" cost=\"{}\"", sol.var_domain(obj.value).lb),
+        None => String::new(),
+    };
+    let names = encoding.variables.iter().map(|(name, _)| name).join(" ");
+    let values = encoding.variables.iter().map(|(_, v)| sol.var_domain(*v).lb).join(" ");
+    println!("v <instantiation type=\"{tpe}\"{cost}>");
+    println!("v   <list> {names} </list>");
+    println!("v   <values> {values} </values>");
+    println!("v </instantiation>");
+}
@@ -0,0 +1,898 @@
+//! Parser for the XCSP3-core format.
+//!
+//! The XML document is turned into an [Instance] in which all references to variables are resolved into the names
+//! of individual variables (e.g. `x[]` is expanded into `x[0] x[1] ...`) and in which the constraints of each group
+//! have been instantiated with their arguments.
+
+use anyhow::*;
+use aries::core::IntCst;
+use itertools::Itertools;
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+
+/// Domain of an integer variable, as a sorted list of disjoint intervals.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Domain(Vec<(IntCst, IntCst)>);
+
+impl Domain {
+    pub fn from_intervals(mut intervals: Vec<(IntCst, IntCst)>) -> Domain {
+        intervals.retain(|(lb, ub)| lb <= ub);
+        intervals.sort_unstable();
+        let mut merged: Vec<(IntCst, IntCst)> = Vec::with_capacity(intervals.len());
+        for (lb, ub) in intervals {
+            match merged.last_mut() {
+                Some((_, prev_ub)) if lb <= *prev_ub + 1 => *prev_ub = ub.max(*prev_ub),
+                _ => merged.push((lb, ub)),
+            }
+        }
+        Domain(merged)
+    }
+
+    pub fn from_values(values: impl IntoIterator<Item = IntCst>) -> Domain {
+        Domain::from_intervals(values.into_iter().map(|v| (v, v)).collect())
+    }
+
+    /// Parses a domain given as a whitespace separated list of values and ranges, e.g. `1 3..5 8`.
+    fn parse(text: &str) -> Result<Domain> {
+        let intervals = text
+            .split_whitespace()
+            .map(|tok| match tok.split_once("..") {
+                Some((lb, ub)) => Ok((int(lb)?, int(ub)?)),
+                None => int(tok).map(|v| (v, v)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Domain::from_intervals(intervals))
+    }
+
+    /// Smallest and largest values of the domain, or `None` if the domain is empty.
+    pub fn bounds(&self) -> Option<(IntCst, IntCst)> {
+        Some((self.0.first()?.0, self.0.last()?.1))
+    }
+
+    pub fn intervals(&self) -> &[(IntCst, IntCst)] {
+        &self.0
+    }
+}
+
+/// An integer constant or a reference to a variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+    Int(IntCst),
+    Var(String),
+}
+
+/// An expression in functional notation, as found in intension constraints and objectives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Int(IntCst),
+    Var(String),
+    /// Application of an operator to its arguments, e.g. `add(x,y)`.
+    /// Sets of integers are represented by the `set` operator.
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Lt,
+    Le,
+    Ge,
+    Gt,
+    Eq,
+    Ne,
+    In,
+    NotIn,
+}
+
+impl Operator {
+    fn parse(op: &str) -> Result<Operator> {
+        Ok(match op {
+            "lt" => Operator::Lt,
+            "le" => Operator::Le,
+            "ge" => Operator::Ge,
+            "gt" => Operator::Gt,
+            "eq" => Operator::Eq,
+            "ne" => Operator::Ne,
+            "in" => Operator::In,
+            "notin" => Operator::NotIn,
+            _ => bail!("Unknown operator: {op}"),
+        })
+    }
+}
+
+/// Right-hand side of a condition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Term(Term),
+    Set(Domain),
+}
+
+/// A condition, such as `(le,10)`, that must hold for the value computed by a constraint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condition {
+    pub op: Operator,
+    pub operand: Operand,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// A boolean expression that must be true.
+    Intension(Expr),
+    /// The value of the variable must (`supports`) or must not (`!supports`) be in the set.
+    Unary {
+        var: Term,
+        values: Domain,
+        supports: bool,
+    },
+    /// Tuples of allowed (`supports`) or forbidden (`!supports`) values, where `None` is a wildcard `*`.
+    Extension {
+        list: Vec<Term>,
+        tuples: Vec<Vec<Option<IntCst>>>,
+        supports: bool,
+    },
+    Sum {
+        list: Vec<Term>,
+        coeffs: Vec<Term>,
+        condition: Condition,
+    },
+    AllDifferent {
+        list: Vec<Term>,
+        except: Vec<IntCst>,
+    },
+    /// `list[index - start_index]` satisfies the condition,
+    /// or some element of the list satisfies it if there is no index.
+    Element {
+        list: Vec<Term>,
+        start_index: IntCst,
+        index: Option<Term>,
+        condition: Condition,
+    },
+    /// `list[i] + lengths[i] <op> list[i+1]` for all `i`, where `op` is one of `lt`, `le`, `ge`, `gt`.
+    Ordered {
+        list: Vec<Term>,
+        lengths: Vec<Term>,
+        operator: Operator,
+    },
+    NoOverlap {
+        origins: Vec<Term>,
+        lengths: Vec<Term>,
+        /// If true, tasks of null length are not constrained.
+        zero_ignored: bool,
+    },
+    Cumulative {
+        origins: Vec<Term>,
+        lengths: Vec<Term>,
+        heights: Vec<Term>,
+        condition: Condition,
+    },
+    /// Each variable of the list takes the corresponding value.
+    Instantiation {
+        list: Vec<Term>,
+        values: Vec<IntCst>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ObjectiveExpr {
+    Expr(Expr),
+    Sum { list: Vec<Term>, coeffs: Vec<Term> },
+    Minimum(Vec<Term>),
+    Maximum(Vec<Term>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Objective {
+    pub minimize: bool,
+    pub expr: ObjectiveExpr,
+}
+
+/// An XCSP3 instance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instance {
+    /// All variables of the instance with their domains, in the order of their declaration.
+    pub variables: Vec<(String, Domain)>,
+    pub constraints: Vec<Constraint>,
+    pub objective: Option<Objective>,
+}
+
+impl Instance {
+    pub fn parse(input: &str) -> Result<Instance> {
+        let doc = Document::parse(input).context("Invalid XML document")?;
+        let root = doc.root_element();
+        ensure!(root.has_tag_name("instance"), "Expected an <instance> element");
+        if let Some(format) = root.attribute("format") {
+            ensure!(format == "XCSP3", "Unsupported format: {format}");
+        }
+        let mut parser = Parser {
+            arrays: HashMap::new(),
+            domains: HashMap::new(),
+            instance: Instance {
+                variables: Vec::new(),
+                constraints: Vec::new(),
+                objective: None,
+            },
+        };
+        for node in elements(root) {
+            match node.tag_name().name() {
+                "variables" => parser.variables(node)?,
+                "constraints" => {
+                    for c in elements(node) {
+                        parser.constraint(c, &Args::default())?
+                    }
+                }
+                "objectives" => parser.objectives(node)?,
+                "annotations" => {}
+                tag => bail!("Unsupported element: <{tag}>"),
+            }
+        }
+        Ok(parser.instance)
+    }
+}
+
+/// Arguments of a constraint template in a group, replacing the `%i` and `%...` placeholders.
+#[derive(Default)]
+struct Args {
+    values: Vec<String>,
+    /// Index of the first argument of `%...`, one after the largest `%i` of the template.
+    rest_start: usize,
+}
+
+impl Args {
+    /// Replaces all placeholders of the text, `%...` being replaced by the remaining arguments joined by `sep`.
+    fn substitute(&self, text: &str, sep: &str) -> Result<String> {
+        if self.values.is_empty() {
+            return Ok(text.to_string());
+        }
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('%') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start + 1..];
+            if let Some(after) = rest.strip_prefix("...") {
+                let remaining = self.values.get(self.rest_start..).unwrap_or_default();
+                result.push_str(&remaining.join(sep));
+                rest = after;
+            } else {
+                let len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+                let i: usize = rest[..len].parse().context("Invalid placeholder")?;
+                let value = self.values.get(i).with_context(|| format!("Missing argument %{i}"))?;
+                result.push_str(value);
+                rest = &rest[len..];
+            }
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+}
+
+struct Parser {
+    /// Dimensions of each array of variables.
+    arrays: HashMap<String, Vec<usize>>,
+    domains: HashMap<String, Domain>,
+    instance: Instance,
+}
+
+fn elements<'a, 'i>(node: Node<'a, 'i>) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children().filter(|n| n.is_element())
+}
+
+fn child<'a, 'i>(node: Node<'a, 'i>, tag: &str) -> Option<Node<'a, 'i>> {
+    elements(node).find(|n| n.has_tag_name(tag))
+}
+
+/// Concatenation of the text nodes directly below the node.
+fn text(node: Node) -> String {
+    node.children()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .join(" ")
+}
+
+fn int(s: &str) -> Result<IntCst> {
+    s.trim().parse().with_context(|| format!("Invalid integer: {s}"))
+}
+
+fn is_int(s: &str) -> bool {
+    s.strip_prefix(['-', '+'])
+        .unwrap_or(s)
+        .chars()
+        .all(|c| c.is_ascii_digit())
+        && !s.is_empty()
+}
+
+impl Parser {
+    /* ============================= Variables ============================== */
+
+    fn variables(&mut self, node: Node) -> Result<()> {
+        for decl in elements(node) {
+            let id = decl.attribute("id").context("Variable without id")?;
+            if let Some(tpe) = decl.attribute("type") {
+                ensure!(tpe == "integer", "Unsupported variable type: {tpe}");
+            }
+            match decl.tag_name().name() {
+                "var" => {
+                    let domain = match decl.attribute("as") {
+                        Some(other) => self.domains.get(other).cloned().context("Unknown variable in 'as'")?,
+                        None => Domain::parse(&text(decl))?,
+                    };
+                    self.declare(id.to_string(), domain)?;
+                }
+                "array" => self.array(id, decl)?,
+                tag => bail!("Unsupported element: <{tag}>"),
+            }
+        }
+        Ok(())
+    }
+
+    fn declare(&mut self, name: String, domain: Domain) -> Result<()> {
+        ensure!(domain.bounds().is_some(), "Empty domain for variable {name}");
+        ensure!(
+            self.domains.insert(name.clone(), domain.clone()).is_none(),
+            "Duplicate variable {name}"
+        );
+        self.instance.variables.push((name, domain));
+        Ok(())
+    }
+
+    fn array(&mut self, id: &str, node: Node) -> Result<()> {
+        let size = node.attribute("size").context("Array without size")?;
+        let dims = size
+            .trim()
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .context("Invalid array size")?
+            .split("][")
+            .map(|d| d.trim().parse::<usize>().context("Invalid array size"))
+            .collect::<Result<Vec<_>>>()?;
+        self.arrays.insert(id.to_string(), dims.clone());
+        let cells = dims
+            .iter()
+            .map(|&d| 0..d)
+            .multi_cartesian_product()
+            .map(|idx| cell_name(id, &idx))
+            .collect_vec();
+
+        let domains = elements(node).filter(|n| n.has_tag_name("domain")).collect_vec();
+        if domains.is_empty() {
+            let domain = Domain::parse(&text(node))?;
+            for cell in cells {
+                self.declare(cell, domain.clone())?;
+            }
+            return Ok(());
+        }
+        // cells may be given different domains, cells that are not mentioned do not exist
+        let mut cell_domains: HashMap<String, Domain> = HashMap::new();
+        for d in domains {
+            let domain = Domain::parse(&text(d))?;
+            let targets = d.attribute("for").context("Domain without 'for'")?;
+            if targets.trim() == "others" {
+                for cell in &cells {
+                    cell_domains.entry(cell.clone()).or_insert_with(|| domain.clone());
+                }
+            } else {
+                for target in targets.split_whitespace() {
+                    for cell in self.expand(target)? {
+                        cell_domains.insert(cell, domain.clone());
+                    }
+                }
+            }
+        }
+        for cell in cells {
+            if let Some(domain) = cell_domains.remove(&cell) {
+                self.declare(cell, domain)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Expands a reference to a variable or to part of an array (e.g. `x[1][]` or `x[0..2]`)
+    /// into the names of the existing variables it denotes.
+    fn expand(&self, reference: &str) -> Result<Vec<String>> {
+        let Some(open) = reference.find('[') else {
+            return Ok(vec![reference.to_string()]);
+        };
+        let name = &reference[..open];
+        let dims = self
+            .arrays
+            .get(name)
+            .with_context(|| format!("Unknown array: {name}"))?;
+        let indices = reference[open..]
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .with_context(|| format!("Invalid reference: {reference}"))?
+            .split("][")
+            .collect_vec();
+        ensure!(indices.len() == dims.len(), "Wrong number of indices in {reference}");
+        let ranges = indices
+            .iter()
+            .zip(dims)
+            .map(|(idx, &dim)| {
+                let (lb, ub) = match idx.split_once("..") {
+                    _ if idx.is_empty() => (0, dim.saturating_sub(1)),
+                    Some((lb, ub)) => (lb.parse()?, ub.parse()?),
+                    None => (idx.parse()?, idx.parse()?),
+                };
+                ensure!(ub < dim, "Index out of bounds in {reference}");
+                Ok(lb..=ub)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ranges
+            .into_iter()
+            .multi_cartesian_product()
+            .map(|idx| cell_name(name, &idx))
+            .filter(|cell| self.domains.contains_key(cell))
+            .collect())
+    }
+
+    /// Checks that the variable exists and returns it.
+    fn var(&self, name: &str) -> Result<String> {
+        ensure!(self.domains.contains_key(name), "Unknown variable: {name}");
+        Ok(name.to_string())
+    }
+
+    /* ============================ Constraints ============================= */
+
+    fn constraint(&mut self, node: Node, args: &Args) -> Result<()> {
+        let tag = node.tag_name().name();
+        let constraint = match tag {
+            "block" => {
+                for c in elements(node).filter(|c| !c.has_tag_name("annotations")) {
+                    self.constraint(c, args)?;
+                }
+                return Ok(());
+            }
+            "group" => return self.group(node),
+            "intension" => {
+                let expr = match child(node, "function") {
+                    Some(function) => text(function),
+                    None => text(node),
+                };
+                Constraint::Intension(self.expr(&args.substitute(&expr, ",")?)?)
+            }
+            "extension" => {
+                let list = self.terms(&self.content(node, "list", args)?)?;
+                let (tuples, supports) = match (child(node, "supports"), child(node, "conflicts")) {
+                    (Some(s), None) => (args.substitute(&text(s), " ")?, true),
+                    (None, Some(c)) => (args.substitute(&text(c), " ")?, false),
+                    _ => bail!("Extension constraint requires either supports or conflicts"),
+                };
+                if list.len() == 1 {
+                    Constraint::Unary {
+                        var: list[0].clone(),
+                        values: Domain::parse(&tuples)?,
+                        supports,
+                    }
+                } else {
+                    Constraint::Extension {
+                        tuples: parse_tuples(&tuples, list.len())?,
+                        list,
+                        supports,
+                    }
+                }
+            }
+            "sum" => {
+                let list = self.terms(&self.content(node, "list", args)?)?;
+                let coeffs = match child(node, "coeffs") {
+                    Some(c) => self.terms(&args.substitute(&text(c), " ")?)?,
+                    None => vec![Term::Int(1); list.len()],
+                };
+                ensure!(
+                    coeffs.len() == list.len(),
+                    "Coefficients and variables of different sizes"
+                );
+                let condition = self.condition(&self.content(node, "condition", args)?)?;
+                Constraint::Sum {
+                    list,
+                    coeffs,
+                    condition,
+                }
+            }
+            "allDifferent" => {
+                ensure!(
+                    elements(node).filter(|n| n.has_tag_name("list")).count() <= 1 && child(node, "matrix").is_none(),
+                    "Only allDifferent on a single list is supported"
+                );
+                let list = self.terms(&self.content(node, "list", args)?)?;
+                let except = match child(node, "except") {
+                    Some(e) => args
+                        .substitute(&text(e), " ")?
+                        .split_whitespace()
+                        .map(int)
+                        .collect::<Result<_>>()?,
+                    None => Vec::new(),
+                };
+                Constraint::AllDifferent { list, except }
+            }
+            "element" => {
+                let list_node = child(node, "list").context("Missing list")?;
+                let start_index = list_node.attribute("startIndex").map(int).transpose()?.unwrap_or(0);
+                let list = self.terms(&args.substitute(&text(list_node), " ")?)?;
+                let index = match child(node, "index") {
+                    Some(i) => Some(self.term(args.substitute(&text(i), " ")?.trim())?),
+                    None => None,
+                };
+                let condition = match (child(node, "value"), child(node, "condition")) {
+                    (Some(v), None) => Condition {
+                        op: Operator::Eq,
+                        operand: Operand::Term(self.term(args.substitute(&text(v), " ")?.trim())?),
+                    },
+                    (None, Some(c)) => self.condition(&args.substitute(&text(c), " ")?)?,
+                    _ => bail!("Element constraint requires either a value or a condition"),
+                };
+                Constraint::Element {
+                    list,
+                    start_index,
+                    index,
+                    condition,
+                }
+            }
+            "ordered" => {
+                let list = self.terms(&self.content(node, "list", args)?)?;
+                let lengths = match child(node, "lengths") {
+                    Some(l) => self.terms(&args.substitute(&text(l), " ")?)?,
+                    None => vec![Term::Int(0); list.len().saturating_sub(1)],
+                };
+                ensure!(lengths.len() + 1 >= list.len(), "Missing lengths");
+                let operator = Operator::parse(self.content(node, "operator", args)?.trim())?;
+                ensure!(
+                    matches!(operator, Operator::Lt | Operator::Le | Operator::Ge | Operator::Gt),
+                    "Invalid operator for ordered: {operator:?}"
+                );
+                Constraint::Ordered {
+                    list,
+                    lengths,
+                    operator,
+                }
+            }
+            "noOverlap" => {
+                let origins = self.content(node, "origins", args)?;
+                ensure!(!origins.contains('('), "Only one-dimensional noOverlap is supported");
+                let zero_ignored = node.attribute("zeroIgnored") != Some("false");
+                Constraint::NoOverlap {
+                    origins: self.terms(&origins)?,
+                    lengths: self.terms(&self.content(node, "lengths", args)?)?,
+                    zero_ignored,
+                }
+            }
+            "cumulative" => {
+                ensure!(child(node, "ends").is_none(), "Cumulative with ends is not supported");
+                Constraint::Cumulative {
+                    origins: self.terms(&self.content(node, "origins", args)?)?,
+                    lengths: self.terms(&self.content(node, "lengths", args)?)?,
+                    heights: self.terms(&self.content(node, "heights", args)?)?,
+                    condition: self.condition(&self.content(node, "condition", args)?)?,
+                }
+            }
+            "instantiation" => Constraint::Instantiation {
+                list: self.terms(&self.content(node, "list", args)?)?,
+                values: self
+                    .content(node, "values", args)?
+                    .split_whitespace()
+                    .map(int)
+                    .collect::<Result<_>>()?,
+            },
+            _ => bail!("Unsupported constraint: <{tag}>"),
+        };
+        self.instance.constraints.push(constraint);
+        Ok(())
+    }
+
+    /// Returns the content of the child element with the given tag. For the main list of a constraint
+    /// (`tag = "list"`), the content may also be given directly in the constraint element.
+    fn content(&self, node: Node, tag: &str, args: &Args) -> Result<String> {
+        let text = match child(node, tag) {
+            Some(c) => text(c),
+            None if tag == "list" => text(node),
+            None => bail!("Missing <{tag}> in <{}>", node.tag_name().name()),
+        };
+        args.substitute(&text, " ")
+    }
+
+    /// Instantiates the constraint template of the group with each of its lists of arguments.
+    fn group(&mut self, node: Node) -> Result<()> {
+        let mut children = elements(node).filter(|c| !c.has_tag_name("annotations"));
+        let template = children.next().context("Empty group")?;
+        let placeholders = template
+            .descendants()
+            .filter(|n| n.is_text())
+            .filter_map(|n| n.text())
+            .flat_map(|t| {
+                t.split('%')
+                    .skip(1)
+                    .map(|s| s.split(|c: char| !c.is_ascii_digit()).next())
+            })
+            .filter_map(|i| i?.parse::<usize>().ok())
+            .max();
+        let rest_start = placeholders.map_or(0, |i| i + 1);
+        for args in children {
+            ensure!(args.has_tag_name("args"), "Unexpected element in group");
+            let values = text(args)
+                .split_whitespace()
+                .map(|tok| {
+                    if is_int(tok) {
+                        Ok(vec![tok.to_string()])
+                    } else {
+                        self.expand(tok)
+                    }
+                })
+                .flatten_ok()
+                .collect::<Result<Vec<_>>>()?;
+            self.constraint(template, &Args { values, rest_start })?;
+        }
+        Ok(())
+    }
+
+    fn term(&self, tok: &str) -> Result<Term> {
+        if is_int(tok) {
+            Ok(Term::Int(int(tok)?))
+        } else {
+            Ok(Term::Var(self.var(tok)?))
+        }
+    }
+
+    /// Parses a whitespace separated list of integers and references to variables.
+    fn terms(&self, text: &str) -> Result<Vec<Term>> {
+        let mut terms = Vec::new();
+        for tok in text.split_whitespace() {
+            if is_int(tok) {
+                terms.push(Term::Int(int(tok)?));
+            } else {
+                for var in self.expand(tok)? {
+                    terms.push(Term::Var(self.var(&var)?));
+                }
+            }
+        }
+        Ok(terms)
+    }
+
+    /// Parses a condition such as `(le,10)`, `(eq,x)` or `(in,1..5)`.
+    fn condition(&self, text: &str) -> Result<Condition> {
+        let inner = text
+            .trim()
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .with_context(|| format!("Invalid condition: {text}"))?;
+        let (op, operand) = inner.split_once(',').context("Invalid condition")?;
+        let op = Operator::parse(op.trim())?;
+        let operand = operand.trim();
+        let operand = if matches!(op, Operator::In | Operator::NotIn) {
+            let set = operand
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .or_else(|| operand.strip_prefix("set(").and_then(|s| s.strip_suffix(')')))
+                .unwrap_or(operand);
+            Operand::Set(Domain::parse(&set.replace(',', " "))?)
+        } else {
+            Operand::Term(self.term(operand)?)
+        };
+        Ok(Condition { op, operand })
+    }
+
+    /// Parses an expression in functional notation, e.g. `eq(add(x[0],1),y)`.
+    fn expr(&self, text: &str) -> Result<Expr> {
+        let tokens = tokenize(text)?;
+        let mut pos = 0;
+        let expr = self.parse_expr(&tokens, &mut pos)?;
+        ensure!(pos == tokens.len(), "Trailing characters in expression: {text}");
+        Ok(expr)
+    }
+
+    fn parse_expr(&self, tokens: &[String], pos: &mut usize) -> Result<Expr> {
+        let tok = tokens.get(*pos).context("Unexpected end of expression")?;
+        *pos += 1;
+        if is_int(tok) {
+            return Ok(Expr::Int(int(tok)?));
+        }
+        if tokens.get(*pos).map(String::as_str) != Some("(") {
+            return match tok.as_str() {
+                "true" => Ok(Expr::Int(1)),
+                "false" => Ok(Expr::Int(0)),
+                _ => Ok(Expr::Var(self.var(tok)?)),
+            };
+        }
+        *pos += 1;
+        let mut args = Vec::new();
+        while tokens.get(*pos).map(String::as_str) != Some(")") {
+            if !args.is_empty() {
+                ensure!(tokens.get(*pos).map(String::as_str) == Some(","), "Expected ','");
+                *pos += 1;
+            }
+            args.push(self.parse_expr(tokens, pos)?);
+        }
+        *pos += 1;
+        Ok(Expr::Call(tok.clone(), args))
+    }
+
+    /* ============================= Objectives ============================= */
+
+    fn objectives(&mut self, node: Node) -> Result<()> {
+        let mut objectives = elements(node);
+        let Some(obj) = objectives.next() else {
+            return Ok(());
+        };
+        ensure!(
+            objectives.next().is_none(),
+            "Multi-objective instances are not supported"
+        );
+        let minimize = match obj.tag_name().name() {
+            "minimize" => true,
+            "maximize" => false,
+            tag => bail!("Unsupported objective: <{tag}>"),
+        };
+        let no_args = Args::default();
+        let expr = match obj.attribute("type") {
+            None | Some("expression") if child(obj, "list").is_none() => ObjectiveExpr::Expr(self.expr(&text(obj))?),
+            None | Some("sum") => {
+                let list = self.terms(&self.content(obj, "list", &no_args)?)?;
+                let coeffs = match child(obj, "coeffs") {
+                    Some(c) => self.terms(&text(c))?,
+                    None => vec![Term::Int(1); list.len()],
+                };
+                ensure!(
+                    coeffs.len() == list.len(),
+                    "Coefficients and variables of different sizes"
+                );
+                ObjectiveExpr::Sum { list, coeffs }
+            }
+            Some("minimum") => ObjectiveExpr::Minimum(self.terms(&self.content(obj, "list", &no_args)?)?),
+            Some("maximum") => ObjectiveExpr::Maximum(self.terms(&self.content(obj, "list", &no_args)?)?),
+            Some(tpe) => bail!("Unsupported objective type: {tpe}"),
+        };
+        self.instance.objective = Some(Objective { minimize, expr });
+        Ok(())
+    }
+}
+
+/// Name of the cell of an array, e.g. `x[1][2]`.
+fn cell_name(array: &str, indices: &[usize]) -> String {
+    format!("{array}{}", indices.iter().map(|i| format!("[{i}]")).join(""))
+}
+
+/// Splits an expression into identifiers, integers and the symbols `(`, `)` and `,`.
+fn tokenize(text: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if c == '(' || c == ')' || c == ',' || c.is_whitespace() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else if c.is_ascii_alphanumeric() || "_[]-+.".contains(c) {
+            current.push(c);
+        } else {
+            bail!("Unexpected character '{c}' in expression: {text}");
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+/// Parses a list of tuples such as `(1,2,3)(2,*,4)`.
+fn parse_tuples(text: &str, arity: usize) -> Result<Vec<Vec<Option<IntCst>>>> {
+    let mut tuples = Vec::new();
+    for tuple in text.split(')').map(str::trim).filter(|t| !t.is_empty()) {
+        let tuple = tuple
+            .strip_prefix('(')
+            .with_context(|| format!("Invalid tuple: {tuple})"))?;
+        let values = tuple
+            .split(',')
+            .map(|v| match v.trim() {
+                "*" => Ok(None),
+                v => int(v).map(Some),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(values.len() == arity, "Tuple ({tuple}) does not have {arity} elements");
+        tuples.push(values);
+    }
+    Ok(tuples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"
+<instance format="XCSP3" type="COP">
+  <variables>
+    <var id="y"> 1 3..5 </var>
+    <array id="x" size="[2][3]"> 0..9 </array>
+    <array id="z" size="[3]">
+      <domain for="z[0]"> 0 1 </domain>
+      <domain for="others"> 2..4 </domain>
+    </array>
+  </variables>
+  <constraints>
+    <intension> eq(add(x[0][0],y),z[1]) </intension>
+    <allDifferent> x[1][] </allDifferent>
+    <group>
+      <extension>
+        <list> %0 %1 </list>
+        <supports> (1,*)(2,3) </supports>
+      </extension>
+      <args> x[0][1] x[0][2] </args>
+      <args> z[0] 4 </args>
+    </group>
+    <sum>
+      <list> x[0..1][0] </list>
+      <coeffs> 2 -1 </coeffs>
+      <condition> (in,1..3) </condition>
+    </sum>
+  </constraints>
+  <objectives>
+    <maximize type="maximum"> z[] </maximize>
+  </objectives>
+</instance>"#;
+        let inst = Instance::parse(input).unwrap();
+        assert_eq!(inst.variables.len(), 1 + 6 + 3);
+        assert_eq!(inst.variables[0].1, Domain(vec![(1, 1), (3, 5)]));
+        assert_eq!(inst.variables[1].0, "x[0][0]");
+        let var = |name: &str| Term::Var(name.to_string());
+        let c = &inst.constraints;
+        assert_eq!(c.len(), 5);
+        assert_eq!(
+            c[0],
+            Constraint::Intension(Expr::Call(
+                "eq".to_string(),
+                vec![
+                    Expr::Call(
+                        "add".to_string(),
+                        vec![Expr::Var("x[0][0]".to_string()), Expr::Var("y".to_string())]
+                    ),
+                    Expr::Var("z[1]".to_string())
+                ]
+            ))
+        );
+        assert_eq!(
+            c[1],
+            Constraint::AllDifferent {
+                list: vec![var("x[1][0]"), var("x[1][1]"), var("x[1][2]")],
+                except: vec![]
+            }
+        );
+        assert_eq!(
+            c[3],
+            Constraint::Extension {
+                list: vec![var("z[0]"), Term::Int(4)],
+                tuples: vec![vec![Some(1), None], vec![Some(2), Some(3)]],
+                supports: true
+            }
+        );
+        assert_eq!(
+            c[4],
+            Constraint::Sum {
+                list: vec![var("x[0][0]"), var("x[1][0]")],
+                coeffs: vec![Term::Int(2), Term::Int(-1)],
+                condition: Condition {
+                    op: Operator::In,
+                    operand: Operand::Set(Domain(vec![(1, 3)]))
+                }
+            }
+        );
+        assert_eq!(
+            inst.objective,
+            Some(Objective {
+                minimize: false,
+                expr: ObjectiveExpr::Maximum(vec![var("z[0]"), var("z[1]"), var("z[2]")])
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        let wrap = |body: &str| format!("<instance format=\"XCSP3\" type=\"CSP\">{body}</instance>");
+        let unknown_var = wrap("<variables><var id=\"x\"> 0..1 </var></variables><constraints><intension> eq(x,y) </intension></constraints>");
+        assert!(Instance::parse(&unknown_var).is_err());
+        let out_of_bounds = wrap("<variables><array id=\"x\" size=\"[2]\"> 0..1 </array></variables><constraints><allDifferent> x[0..2] </allDifferent></constraints>");
+        assert!(Instance::parse(&out_of_bounds).is_err());
+        let unsupported =
+            wrap("<variables><var id=\"x\"> 0..1 </var></variables><constraints><circuit> x </circuit></constraints>");
+        assert!(Instance::parse(&unsupported).is_err());
+        assert!(Instance::parse("<instance><variables>").is_err());
+    }
+}
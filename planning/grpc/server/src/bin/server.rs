@@ -1,4 +1,4 @@
-use anyhow::{bail, ensure, Context, Error};
+use anyhow::{bail, Context, Error};
 use aries::model::extensions::SavedAssignment;
 use aries_grpc_server::chronicles::problem_to_chronicles;
 use aries_grpc_server::serialize::{engine, serialize_plan};
@@ -148,30 +148,32 @@ fn solve_blocking(
         .with_context(|| format!("In problem {}/{}", &problem.domain_name, &problem.problem_name))?;
     let bounded = htn_mode && hierarchical_is_non_recursive(&base_problem) || base_problem.templates.is_empty();
 
-    ensure!(problem.metrics.len() <= 1, "Unsupported: multiple metrics provided.");
-    let metric = if !conf.optimal {
-        None
-    } else if let Some(metric) = problem.metrics.first() {
-        match up::metric::MetricKind::try_from(metric.kind) {
-            Ok(MetricKind::MinimizeActionCosts) => Some(Metric::ActionCosts),
-            Ok(MetricKind::MinimizeSequentialPlanLength) => Some(Metric::PlanLength),
-            Ok(MetricKind::MinimizeMakespan) => Some(Metric::Makespan),
-            Ok(MetricKind::MinimizeExpressionOnFinalState) => Some(Metric::MinimizeVar(
-                base_problem
-                    .context
-                    .metric_final_value()
-                    .context("Trying to minimize an empty expression metric.")?,
-            )),
-            Ok(MetricKind::MaximizeExpressionOnFinalState) => Some(Metric::MaximizeVar(
-                base_problem
-                    .context
-                    .metric_final_value()
-                    .context("Trying to maximize an empty expression metric.")?,
-            )),
-            _ => bail!("Unsupported metric kind with ID: {}", metric.kind),
-        }
+    // metrics are optimized in lexicographic order
+    let metrics = if !conf.optimal {
+        Vec::new()
     } else {
-        None
+        problem
+            .metrics
+            .iter()
+            .map(|metric| match up::metric::MetricKind::try_from(metric.kind) {
+                Ok(MetricKind::MinimizeActionCosts) => Ok(Metric::ActionCosts),
+                Ok(MetricKind::MinimizeSequentialPlanLength) => Ok(Metric::PlanLength),
+                Ok(MetricKind::MinimizeMakespan) => Ok(Metric::Makespan),
+                Ok(MetricKind::MinimizeExpressionOnFinalState) => Ok(Metric::MinimizeVar(
+                    base_problem
+                        .context
+                        .metric_final_value()
+                        .context("Trying to minimize an empty expression metric.")?,
+                )),
+                Ok(MetricKind::MaximizeExpressionOnFinalState) => Ok(Metric::MaximizeVar(
+                    base_problem
+                        .context
+                        .metric_final_value()
+                        .context("Trying to maximize an empty expression metric.")?,
+                )),
+                _ => bail!("Unsupported metric kind with ID: {}", metric.kind),
+            })
+            .collect::<Result<Vec<_>, Error>>()?
     };
 
     let max_depth = conf.max_depth;
@@ -195,7 +197,8 @@ fn solve_blocking(
         min_depth,
         max_depth,
         &conf.strategies,
-        metric,
+        &[],
+        &metrics,
         htn_mode,
         on_new_solution,
         deadline,
@@ -206,7 +209,7 @@ fn solve_blocking(
                 "************* SOLUTION FOUND **************\n\n{}",
                 solver::format_plan(&finite_problem, &plan, htn_mode)?
             );
-            let status = if metric.is_some() && bounded {
+            let status = if !metrics.is_empty() && bounded {
                 up::plan_generation_result::Status::SolvedOptimally
             } else {
                 up::plan_generation_result::Status::SolvedSatisficing
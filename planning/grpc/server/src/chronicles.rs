@@ -188,7 +188,7 @@ fn build_context(problem: &Problem) -> Result<Ctx, Error> {
             min_eps.numerator == 1,
             "Only support epsilons with numerator equals to 1"
         );
-        let scale: i32 = min_eps.denominator.try_into()?;
+        let scale: IntCst = min_eps.denominator.try_into()?;
         TIME_SCALE.set(scale);
     }
 
@@ -249,7 +249,6 @@ pub fn problem_to_chronicles(problem: &Problem) -> Result<aries_planning::chroni
 
     let init_ch = factory.build_instance(ChronicleOrigin::Original)?;
 
-    ensure!(problem.metrics.len() <= 1, "No support for multiple metrics.");
     let action_costs = problem
         .metrics
         .iter()
@@ -766,13 +765,17 @@ impl<'a> ChronicleFactory<'a> {
 
     /// Final value to minimize is converted to a condition at the chronicle end time
     fn add_final_value_metric(&mut self, metrics: &[Metric]) -> Result<(), Error> {
-        ensure!(metrics.len() <= 1, "Unsupported: multiple metrics provided.");
-        let Some(metric) = metrics.first() else {
-            return Ok(()); // no metric to handle
-        };
-        if let Ok(MetricKind::MinimizeExpressionOnFinalState | MetricKind::MaximizeExpressionOnFinalState) =
-            MetricKind::try_from(metric.kind)
-        {
+        let mut final_value_metrics = metrics.iter().filter(|metric| {
+            matches!(
+                MetricKind::try_from(metric.kind),
+                Ok(MetricKind::MinimizeExpressionOnFinalState | MetricKind::MaximizeExpressionOnFinalState)
+            )
+        });
+        if let Some(metric) = final_value_metrics.next() {
+            ensure!(
+                final_value_metrics.next().is_none(),
+                "Unsupported: multiple metrics on the final value of an expression."
+            );
             let expr = metric
                 .expression
                 .as_ref()
@@ -1174,6 +1177,19 @@ impl<'a> ChronicleFactory<'a> {
                             self.chronicle.constraints.push(Constraint::linear_eq_zero(sum));
                             Ok(value.into())
                         }
+                        "up:times" => {
+                            ensure!(params.len() >= 2, "`*` operator should have at least 2 arguments");
+                            let mut product = self.as_int_variable(params[0])?;
+                            for &param in &params[1..] {
+                                let factor = self.as_int_variable(param)?;
+                                let value: IVar = self
+                                    .create_variable(Type::UNBOUNDED_INT, VarType::Reification)
+                                    .try_into()?;
+                                self.chronicle.constraints.push(Constraint::mul(value, product, factor));
+                                product = value;
+                            }
+                            Ok(product.into())
+                        }
                         _ => bail!("Unsupported operator {operator}"),
                     }
                 }
@@ -1182,6 +1198,22 @@ impl<'a> ChronicleFactory<'a> {
         }
     }
 
+    /// Returns an integer variable that is equal to the given atom, creating a new variable if the atom is not one.
+    fn as_int_variable(&mut self, atom: Atom) -> Result<IVar, Error> {
+        match atom {
+            Atom::Int(IAtom { var, shift: 0 }) if var != IVar::ZERO => Ok(var),
+            Atom::Int(_) => {
+                let var: IVar = self
+                    .create_variable(Type::UNBOUNDED_INT, VarType::Reification)
+                    .try_into()?;
+                let sum = LinearSum::try_from(atom)? - LinearSum::from(var);
+                self.chronicle.constraints.push(Constraint::linear_eq_zero(sum));
+                Ok(var)
+            }
+            _ => bail!("Only integer operands are supported in products: {atom:?}"),
+        }
+    }
+
     fn read_state_variable(&mut self, expr: &Expression, span: Option<Span>) -> Result<StateVar, Error> {
         ensure!(
             expr.atom.is_none(),
@@ -1592,10 +1624,10 @@ fn kind(e: &Expression) -> Result<ExpressionKind, Error> {
     ExpressionKind::try_from(e.kind).with_context(|| format!("Unknown expression kind id: {}", e.kind))
 }
 
-fn as_int(e: &Expression) -> Result<i32, Error> {
+fn as_int(e: &Expression) -> Result<IntCst, Error> {
     if kind(e)? == ExpressionKind::Constant && e.r#type.starts_with("up:integer") {
         match e.atom.as_ref().unwrap().content.as_ref().unwrap() {
-            Content::Int(i) => Ok(*i as i32),
+            Content::Int(i) => Ok(*i as IntCst),
             _ => bail!("Malformed message"),
         }
     } else {
@@ -1,10 +1,12 @@
 use anyhow::{Context, Result};
 use aries::core::state::Domains;
+use aries::solver::search::restarts::Restarts;
 use aries::utils::input::Input;
+use aries_planners::flexible::extract_flexible_schedule;
 use aries_planners::solver::{format_plan, solve, SolverResult};
 use aries_planners::solver::{Metric, Strat};
 use aries_planning::chronicles::analysis::hierarchy::hierarchical_is_non_recursive;
-use aries_planning::chronicles::FiniteProblem;
+use aries_planning::chronicles::{ChronicleLabel, FiniteProblem};
 use aries_planning::parsing::pddl::{find_domain_of, parse_pddl_domain, parse_pddl_problem, PddlFeature};
 use aries_planning::parsing::pddl_to_chronicles;
 use std::fs::File;
@@ -27,6 +29,12 @@ pub struct Opt {
     #[structopt(long = "output", short = "o")]
     plan_out_file: Option<PathBuf>,
 
+    /// If set, a flexible schedule of the final plan will be written to the file in JSON.
+    /// The schedule is a minimal dispatchable temporal network over the start and end of the actions, allowing
+    /// an executive to dispatch them online while exploiting the slack of the plan.
+    #[structopt(long = "flexible-output")]
+    flexible_out_file: Option<PathBuf>,
+
     /// Minimum depth of the instantiation. (depth of HTN tree or number of standalone actions with the same name).
     #[structopt(long)]
     min_depth: Option<u32>,
@@ -36,8 +44,16 @@ pub struct Opt {
 
     /// If set, the solver will attempt to optimize a particular metric, until a proven optimal solution is found.
     /// Possible values: "makespan", "plan-length", "action-costs"
-    #[structopt(long = "optimize")]
-    optimize: Option<Metric>,
+    /// Several comma-separated metrics are optimized in lexicographic order (e.g. "plan-length,makespan").
+    #[structopt(long = "optimize", require_delimiter = true)]
+    optimize: Vec<Metric>,
+
+    /// Comma-separated list of durative actions whose duration is chosen by the environment rather than by the planner.
+    /// The returned plan is dynamically controllable: its temporal network (the temporal constraints of its actions,
+    /// with the bounds of the problem) can be executed whatever these durations. Only the plan is returned, not an
+    /// execution strategy: the printed schedule is a single execution, for durations picked by the solver.
+    #[structopt(long = "uncontrollable", require_delimiter = true)]
+    uncontrollable: Vec<String>,
 
     /// When used in conjunction with `--output`, each plan found will be written to the output file.
     /// The previous plan, if any will be overwritten.
@@ -49,6 +65,12 @@ pub struct Opt {
     #[structopt(long = "strategy", short = "s")]
     strategies: Vec<Strat>,
 
+    /// If provided, overrides the restart policy of the strategies.
+    /// Possible values: "none", "geom[:<conflicts>[:<ratio>]]", "luby[:<unit>]", "glucose", "glucose-blocking".
+    /// When repeated, the policies are distributed among the strategies run in parallel.
+    #[structopt(long = "restarts", number_of_values = 1)]
+    restarts: Vec<Restarts>,
+
     /// Logging level to use: one of "error", "warn", "info", "debug", "trace"
     #[structopt(short, long, default_value = "info")]
     log_level: tracing::Level,
@@ -95,7 +117,22 @@ fn main() -> Result<()> {
     let dom = parse_pddl_domain(dom)?;
     let prob = parse_pddl_problem(prob)?;
 
-    let spec = pddl_to_chronicles(&dom, &prob)?;
+    let mut spec = pddl_to_chronicles(&dom, &prob)?;
+
+    for action in &opt.uncontrollable {
+        let action = action.to_lowercase();
+        let mut found = false;
+        for template in &mut spec.templates {
+            if matches!(&template.label, ChronicleLabel::Action(name) if name.to_lowercase() == action) {
+                anyhow::ensure!(
+                    template.chronicle.make_duration_uncontrollable(),
+                    "Action {action} has no duration and cannot be made uncontrollable"
+                );
+                found = true;
+            }
+        }
+        anyhow::ensure!(found, "Unknown action {action} in option `--uncontrollable`");
+    }
 
     // true if we are doing HTN planning, false otherwise
     let htn_mode = dom.features.contains(&PddlFeature::Hierarchy);
@@ -131,7 +168,8 @@ fn main() -> Result<()> {
         min_depth,
         max_depth,
         &opt.strategies,
-        opt.optimize,
+        &opt.restarts,
+        &opt.optimize,
         htn_mode,
         |pb, sol| print_plan(pb, &sol, anytime_out_file.as_ref()),
         None,
@@ -140,6 +178,10 @@ fn main() -> Result<()> {
     match result {
         SolverResult::Sol((finite_problem, assignment)) => {
             print_plan(&finite_problem, &assignment, opt.plan_out_file.as_ref());
+            if let Some(flexible_out_file) = &opt.flexible_out_file {
+                let schedule = extract_flexible_schedule(&finite_problem, &assignment)?;
+                std::fs::write(flexible_out_file, schedule.to_json())?;
+            }
             anyhow::ensure!(!opt.unsat, "Solution found to an unsat problem.");
         }
         SolverResult::Unsat => {
@@ -11,8 +11,9 @@ use aries::core::state::Conflict;
 use aries::core::*;
 use aries::model::extensions::{AssignmentExt, Shaped};
 use aries::model::lang::linear::{LinearSum, LinearTerm};
+use aries::model::lang::scheduling;
 use aries::model::lang::{expr::*, Kind, Type};
-use aries::model::lang::{FAtom, FVar, IAtom, Variable};
+use aries::model::lang::{FAtom, FVar, IAtom, SAtom, Variable};
 use aries::solver::Solver;
 use aries_planning::chronicles::constraints::encode_constraint;
 use aries_planning::chronicles::printer::Printer;
@@ -42,6 +43,11 @@ pub static RESOURCE_CONSTRAINT_TIMEPOINTS: EnvParam<bool> =
 pub static RELAXED_TEMPORAL_CONSTRAINT: EnvParam<bool> =
     EnvParam::new("ARIES_LCP_RELAXED_TEMPORAL_CONSTRAINT_TASK_METHOD", "false");
 
+/// Parameter that activates the redundant no-overlap constraints between the transitions of the assignments
+/// to a same ground state variable. The parameter is loaded from the environment variable `ARIES_LCP_NO_OVERLAP`.
+/// Possible values are `false` and `true` (default).
+pub static NO_OVERLAP_CONSTRAINTS: EnvParam<bool> = EnvParam::new("ARIES_LCP_NO_OVERLAP", "true");
+
 /// For each chronicle template into the `spec`, appends `num_instances` instances into the `pb`.
 pub fn populate_with_template_instances<F: Fn(&ChronicleTemplate) -> Option<u32>>(
     pb: &mut FiniteProblem,
@@ -467,7 +473,8 @@ pub fn add_metric(pb: &FiniteProblem, model: &mut Model, metric: Metric) -> IAto
 
 pub struct EncodedProblem {
     pub model: Model,
-    pub objective: Option<IAtom>,
+    /// Variables to minimize, one for each metric, in order of priority.
+    pub objectives: Vec<IAtom>,
     /// Metadata associated to variables and literals in the encoded problem.
     pub encoding: Encoding,
 }
@@ -493,9 +500,9 @@ fn unifiable_sv(model: &Model, sv1: &StateVar, sv2: &StateVar) -> bool {
 }
 
 /// Encodes a finite problem.
-/// If a metric is given, it will return along with the model an `IAtom` that should be minimized
+/// It returns along with the model an `IAtom` to minimize for each of the given metrics.
 /// Returns an error if the encoded problem is found to be unsatisfiable.
-pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result<EncodedProblem, Conflict> {
+pub fn encode(pb: &FiniteProblem, metrics: &[Metric]) -> std::result::Result<EncodedProblem, Conflict> {
     let mut encoding = Encoding::default();
     let encode_span = tracing::span!(tracing::Level::DEBUG, "ENCODING");
     let _x = encode_span.enter();
@@ -648,6 +655,11 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
         }
         tracing::debug!(%num_coherence_constraints);
 
+        if NO_OVERLAP_CONSTRAINTS.get() {
+            let num_no_overlap_constraints = encode_no_overlap_constraints(&mut solver, &effs);
+            tracing::debug!(%num_no_overlap_constraints);
+        }
+
         solver.propagate()?;
     }
 
@@ -812,14 +824,18 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
         }
     }
 
-    let metric = metric.map(|metric| add_metric(pb, &mut solver.model, metric));
-
     symmetry::add_symmetry_breaking(pb, &mut solver.model, &encoding);
 
+    // the variables of the objectives are created last, so that the other variables do not depend on the metrics
+    let objectives = metrics
+        .iter()
+        .map(|&metric| add_metric(pb, &mut solver.model, metric))
+        .collect();
+
     tracing::debug!("Done.");
     Ok(EncodedProblem {
         model: solver.model,
-        objective: metric,
+        objectives,
         encoding,
     })
 }
@@ -828,6 +844,44 @@ pub fn encode(pb: &FiniteProblem, metric: Option<Metric>) -> std::result::Result
 /*                            Resource Constraints                            */
 /* ========================================================================== */
 
+/// Encodes the redundant constraints that the transitions of the assignments to a same ground state variable
+/// do not overlap, which is implied by the coherence constraints.
+/// Only transitions of fixed duration are considered, as tasks of variable duration are not supported by the
+/// no-overlap constraint.
+/// Returns the number of no-overlap constraints.
+fn encode_no_overlap_constraints(solver: &mut Box<Solver<VarLabel>>, effs: &[(EffID, Lit, &Effect)]) -> usize {
+    // transitions on each (state variable, time scale), in the order of the effects
+    let mut groups: HashMap<(&StateVar, IntCst), usize> = HashMap::new();
+    let mut transitions: Vec<Vec<scheduling::Task>> = Vec::new();
+    for &(_, prez, eff) in effs {
+        if !is_assignment(eff) || solver.model.entails(!prez) {
+            continue;
+        }
+        if !eff.state_var.args.iter().all(|arg| matches!(arg, SAtom::Cst(_))) {
+            continue;
+        }
+        let (start, end) = (eff.transition_start, eff.transition_end);
+        if start.denom != end.denom {
+            continue;
+        }
+        if let Some(task) = scheduling::Task::spanning(start.num, end.num, 1, prez) {
+            let group = *groups.entry((&eff.state_var, start.denom)).or_insert_with(|| {
+                transitions.push(Vec::new());
+                transitions.len() - 1
+            });
+            transitions[group].push(task);
+        }
+    }
+    let mut num_constraints = 0;
+    for tasks in transitions {
+        if tasks.iter().filter(|task| task.duration > 0).count() >= 2 {
+            solver.enforce(no_overlap(tasks), []);
+            num_constraints += 1;
+        }
+    }
+    num_constraints
+}
+
 fn encode_resource_constraints(
     solver: &mut Box<Solver<VarLabel>>,
     effs: &[(EffID, Lit, &Effect)],
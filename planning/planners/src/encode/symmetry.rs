@@ -171,11 +171,12 @@ fn add_plan_space_symmetry_breaking(pb: &FiniteProblem, model: &mut Model, encod
         if sort_by_hierarchy_level {
             let sort_key = |c: &CondID| {
                 // get the level, reserving the lvl 0 for non-templates
+                // (ties are broken by condition so that the encoding does not depend on the order of the set)
                 if let Some(template) = template_id(c.instance_id) {
                     let lvl = pb.meta.action_hierarchy[&template];
-                    (lvl + 1, c.instance_id)
+                    (lvl + 1, *c)
                 } else {
-                    (0, c.instance_id)
+                    (0, *c)
                 }
             };
             conds.into_iter().sorted_by_key(sort_key).collect_vec()
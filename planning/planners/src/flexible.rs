@@ -0,0 +1,681 @@
+//! Extraction of flexible schedules from plans, in which the actions can be dispatched online with some slack.
+
+use crate::encode::{encode, EncodedProblem};
+use crate::fmt::{extract_action, format_action_name};
+use crate::solver::init_solver;
+use anyhow::*;
+use aries::core::state::{Cause, Domains};
+use aries::core::{IntCst, Lit, Rational, VarRef};
+use aries::model::extensions::Shaped;
+use aries::model::lang::FAtom;
+use aries::model::{Constraint, Model};
+use aries::reif::{DifferenceExpression, ReifExpr};
+use aries_planning::chronicles::{ChronicleKind, FiniteProblem, VarLabel, VarType};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
+/// A constraint `timepoints[target] - timepoints[source] <= weight` of a [FlexibleSchedule].
+#[derive(Copy, Clone, Debug)]
+pub struct ScheduleConstraint {
+    pub source: usize,
+    pub target: usize,
+    pub weight: Rational,
+}
+
+/// A schedule of the actions of a plan in the form of a minimal dispatchable temporal network.
+/// Any execution that assigns times to the timepoints while respecting the constraints is a valid plan
+/// for the problem it was extracted from.
+#[derive(Clone, Debug)]
+pub struct FlexibleSchedule {
+    /// Names of the timepoints, the first one being the origin of the plan at time `0`,
+    /// followed by the start and end of each action.
+    pub timepoints: Vec<String>,
+    pub constraints: Vec<ScheduleConstraint>,
+}
+
+impl FlexibleSchedule {
+    /// Serializes the schedule in JSON.
+    /// Weights are exact rationals, written as an object with a `numerator` and a `denominator`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str("{\n  \"timepoints\": [");
+        for (i, tp) in self.timepoints.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            write!(out, "{sep}\n    {}", json_string(tp)).unwrap();
+        }
+        out.push_str("\n  ],\n  \"constraints\": [");
+        for (i, c) in self.constraints.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            write!(
+                out,
+                "{sep}\n    {{ \"source\": {}, \"target\": {}, \"weight\": {{ \"numerator\": {}, \"denominator\": {} }} }}",
+                c.source,
+                c.target,
+                c.weight.numer(),
+                c.weight.denom()
+            )
+            .unwrap();
+        }
+        out.push_str("\n  ]\n}\n");
+        out
+    }
+}
+
+/// Returns the JSON string literal representing `s`.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // other control characters must be escaped by their code point
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Returns whether the variable represents a point in time.
+fn is_temporal(label: Option<&VarLabel>) -> bool {
+    matches!(
+        label,
+        Some(VarLabel(
+            _,
+            VarType::Horizon
+                | VarType::Makespan
+                | VarType::ChronicleStart
+                | VarType::ChronicleEnd
+                | VarType::EffectEnd
+                | VarType::TaskStart(_)
+                | VarType::TaskEnd(_)
+        ))
+    )
+}
+
+/// Extracts a flexible schedule for the plan of the `solution` to the `problem`.
+///
+/// The problem is encoded again and all non-temporal variables (presence of actions, parameters,
+/// ordering choices, ...) are fixed to their value in the solution. The temporal constraints that are not
+/// difference constraints are replaced by difference constraints that hold in the solution (see
+/// [`record_temporal_choices`]). The temporal network that results from these choices is then compiled into a
+/// minimal dispatchable network over the start and end timepoints of the actions.
+///
+/// Fails if the plan depends on a temporal constraint that cannot be represented in a temporal network.
+pub fn extract_flexible_schedule(problem: &FiniteProblem, solution: &Domains) -> Result<FlexibleSchedule> {
+    let EncodedProblem { mut model, .. } = encode(problem, &[]).map_err(|_| anyhow!("The problem is unsatisfiable"))?;
+
+    let mut temporal: HashSet<VarRef> = HashSet::new();
+    let mut add_time = |t: FAtom| {
+        temporal.insert(VarRef::from(t.num.var));
+    };
+    for ch in &problem.chronicles {
+        add_time(ch.chronicle.start);
+        add_time(ch.chronicle.end);
+        for cond in &ch.chronicle.conditions {
+            add_time(cond.start);
+            add_time(cond.end);
+        }
+        for eff in &ch.chronicle.effects {
+            add_time(eff.transition_start);
+            add_time(eff.transition_end);
+        }
+        for task in &ch.chronicle.subtasks {
+            add_time(task.start);
+            add_time(task.end);
+        }
+    }
+    let vars: Vec<VarRef> = model.state.variables().collect();
+    for var in vars {
+        if var == VarRef::ZERO || temporal.contains(&var) || is_temporal(model.get_label(var)) {
+            continue;
+        }
+        if solution.present(var) == Some(true) {
+            let (lb, ub) = solution.bounds(var);
+            let fixed = model.state.set_lb(var, lb, Cause::Decision).is_ok()
+                && model.state.set_ub(var, ub, Cause::Decision).is_ok();
+            ensure!(fixed, "The solution is not compatible with the problem");
+        }
+    }
+    record_temporal_choices(&mut model, solution)?;
+    let mut solver = init_solver(model);
+    ensure!(
+        solver.propagate().is_ok(),
+        "The solution is not compatible with the problem"
+    );
+
+    let timepoints = plan_timepoints(problem, solution)?;
+    let denom = problem.origin.denom;
+    ensure!(
+        VarRef::from(problem.origin.num.var) == VarRef::ZERO,
+        "The origin of the plan is not a constant"
+    );
+    ensure!(
+        timepoints.iter().all(|(_, t)| t.denom == denom),
+        "Timepoints with different denominators"
+    );
+    let tp_vars: Vec<VarRef> = timepoints.iter().map(|(_, t)| VarRef::from(t.num.var)).collect();
+    let Some(network) = solver
+        .reasoners
+        .diff
+        .dispatchable_network(&tp_vars, &solver.model.state)
+    else {
+        bail!("The temporal network of the plan is inconsistent")
+    };
+
+    // timepoints associated to each variable of the network
+    let mut on_var: BTreeMap<VarRef, Vec<usize>> = BTreeMap::new();
+    for (i, &var) in tp_vars.iter().enumerate() {
+        on_var.entry(var).or_default().push(i);
+    }
+    let shift = |i: usize| timepoints[i].1.num.shift;
+    let mut constraints = Vec::new();
+    let mut add = |source: usize, target: usize, weight: IntCst| {
+        constraints.push(ScheduleConstraint {
+            source,
+            target,
+            weight: Rational::new(weight + shift(target) - shift(source), denom),
+        })
+    };
+    // timepoints on the same variable are at a fixed distance from one another
+    for tps in on_var.values() {
+        for (&a, &b) in tps.iter().zip(tps.iter().skip(1)) {
+            add(a, b, 0);
+            add(b, a, 0);
+        }
+    }
+    for e in &network.edges {
+        for &source in &on_var[&e.source] {
+            for &target in &on_var[&e.target] {
+                add(source, target, e.weight);
+            }
+        }
+    }
+    Ok(FlexibleSchedule {
+        timepoints: timepoints.into_iter().map(|(name, _)| name).collect(),
+        constraints,
+    })
+}
+
+/// Returns the named timepoints of a plan: the origin, followed by the start and end of each action.
+fn plan_timepoints(problem: &FiniteProblem, solution: &Domains) -> Result<Vec<(String, FAtom)>> {
+    let mut timepoints = vec![(String::from("origin"), problem.origin)];
+    for ch in &problem.chronicles {
+        if solution.value(ch.chronicle.presence) != Some(true) {
+            continue;
+        }
+        if !matches!(ch.chronicle.kind, ChronicleKind::Action | ChronicleKind::DurativeAction) {
+            continue;
+        }
+        let start = ch.chronicle.start;
+        match extract_action(problem, ch, solution) {
+            (instance, None) => {
+                let name = format_action_name(&instance, &problem.model);
+                timepoints.push((format!("start {name}"), start));
+                timepoints.push((format!("end {name}"), ch.chronicle.end));
+            }
+            (instance, Some(unrolled)) => {
+                // the original actions of a rolled-up action are at a fixed delay from its start
+                let delayed = |delay: Rational| {
+                    let shift = delay * start.denom;
+                    ensure!(shift.is_integer(), "Delay {delay} is not a multiple of the time scale");
+                    Ok(FAtom::new(start.num + shift.to_integer(), start.denom))
+                };
+                for a in unrolled {
+                    let name = format_action_name(&a, &problem.model);
+                    let action_start = delayed(a.start - instance.start)?;
+                    let action_end = delayed(a.start + a.duration - instance.start)?;
+                    timepoints.push((format!("start {name}"), action_start));
+                    timepoints.push((format!("end {name}"), action_end));
+                }
+            }
+        }
+    }
+    Ok(timepoints)
+}
+
+/// A choice of a solution that makes a temporal constraint hold in the temporal network of the plan.
+enum TemporalChoice {
+    /// The literal holds.
+    Lit(Lit),
+    /// The difference constraint holds.
+    Diff(DifferenceExpression),
+}
+
+/// Ensures that the temporal network of a `model`, whose non-temporal variables are fixed to their value in
+/// the `solution`, entails all its temporal constraints, so that any consistent assignment of the timepoints
+/// satisfies the model.
+///
+/// Each constraint over timepoints that is not a difference constraint is replaced by difference constraints
+/// that hold in the solution: disjunctions that are not satisfied by the fixed variables are restricted to the
+/// values of their literals in the solution, equalities and no-overlap constraints to the relative order of
+/// their timepoints in the solution, and linear constraints are reduced to difference constraints on their
+/// variables that are not fixed.
+/// Fails if a constraint cannot be represented by difference constraints (e.g. a cumulative constraint
+/// over timepoints).
+fn record_temporal_choices(model: &mut Model<VarLabel>, solution: &Domains) -> Result<()> {
+    let state = &model.state;
+    let free = |var: VarRef| state.lb(var) < state.ub(var);
+    let present = |var: VarRef| solution.present(var) == Some(true);
+    let value = |var: VarRef| solution.lb(var);
+    let mut choices: Vec<TemporalChoice> = Vec::new();
+    let record = |lit: Lit, choices: &mut Vec<TemporalChoice>| {
+        let var = lit.variable();
+        if free(var) && present(var) {
+            let lit = if solution.entails(lit) { lit } else { !lit };
+            choices.push(TemporalChoice::Lit(lit));
+        }
+    };
+    // records the relative order of `a` and `b` in the solution
+    let order = |a: VarRef, b: VarRef, choices: &mut Vec<TemporalChoice>| {
+        if a == b || !(free(a) || free(b)) || !present(a) || !present(b) {
+            return;
+        }
+        let diff = |b, a, ub| TemporalChoice::Diff(DifferenceExpression::new(b, a, ub));
+        match value(a).cmp(&value(b)) {
+            Ordering::Less => choices.push(diff(a, b, -1)),
+            Ordering::Greater => choices.push(diff(b, a, -1)),
+            Ordering::Equal => choices.extend([diff(a, b, 0), diff(b, a, 0)]),
+        }
+    };
+
+    for constraint in &model.shape.constraints {
+        let Constraint::Reified(expr, reif) = constraint;
+        if !present(reif.variable()) {
+            continue;
+        }
+        record(*reif, &mut choices);
+        let holds = solution.entails(*reif);
+        match expr {
+            ReifExpr::Lit(lit) => record(*lit, &mut choices),
+            ReifExpr::MaxDiff(_) | ReifExpr::ContingentLink(_) => {}
+            ReifExpr::Or(lits) | ReifExpr::And(lits) => {
+                // at least one literal must hold (or not hold for a conjunction) in a disjunctive constraint
+                let conjunction = matches!(expr, ReifExpr::And(_));
+                let disjunctive = holds != conjunction;
+                let satisfied = disjunctive
+                    && lits
+                        .iter()
+                        .any(|&l| !free(l.variable()) && state.entails(if conjunction { !l } else { l }));
+                if !satisfied {
+                    for &l in lits {
+                        record(l, &mut choices);
+                    }
+                }
+            }
+            &ReifExpr::Eq(a, b) | &ReifExpr::Neq(a, b) => order(a, b, &mut choices),
+            &ReifExpr::EqVal(a, val) | &ReifExpr::NeqVal(a, val) => {
+                if free(a) && present(a) {
+                    let (lb, ub) = (Lit::geq(a, val), Lit::leq(a, val));
+                    record(lb, &mut choices);
+                    record(ub, &mut choices);
+                }
+            }
+            ReifExpr::Linear(lin) => {
+                if !holds {
+                    continue;
+                }
+                let mut ub = lin.upper_bound;
+                // factor of each variable that is not fixed
+                let mut factors: BTreeMap<VarRef, IntCst> = BTreeMap::new();
+                for term in &lin.sum {
+                    match state.value(term.lit) {
+                        Some(true) if free(term.var) => *factors.entry(term.var).or_default() += term.factor,
+                        Some(true) => ub -= term.factor * state.lb(term.var),
+                        Some(false) => {}
+                        None => bail!("The plan depends on a temporal constraint with optional terms: {lin}"),
+                    }
+                }
+                let terms: Vec<(IntCst, VarRef)> = factors
+                    .into_iter()
+                    .filter(|&(_, factor)| factor != 0)
+                    .map(|(var, factor)| (factor, var))
+                    .collect();
+                match terms.as_slice() {
+                    [] => {}
+                    &[(factor, x)] if factor > 0 => {
+                        choices.push(TemporalChoice::Lit(Lit::leq(x, ub.div_euclid(factor))))
+                    }
+                    &[(factor, x)] => choices.push(TemporalChoice::Lit(Lit::geq(x, -ub.div_euclid(-factor)))),
+                    &[(f1, x1), (f2, x2)] if f1 == -f2 => {
+                        // `f * (b - a) <= ub` with `f > 0`
+                        let (f, b, a) = if f1 > 0 { (f1, x1, x2) } else { (f2, x2, x1) };
+                        choices.push(TemporalChoice::Diff(DifferenceExpression::new(b, a, ub.div_euclid(f))));
+                    }
+                    _ => bail!("The plan depends on a linear constraint over timepoints: {lin}"),
+                }
+            }
+            ReifExpr::NoOverlap(no_overlap) => {
+                let tasks: Vec<_> = no_overlap
+                    .tasks
+                    .iter()
+                    .filter(|task| task.duration > 0 && solution.entails(task.presence))
+                    .collect();
+                for (i, t1) in tasks.iter().enumerate() {
+                    for t2 in &tasks[i + 1..] {
+                        let (a, b) = (VarRef::from(t1.start.var), VarRef::from(t2.start.var));
+                        if a == b || !(free(a) || free(b)) {
+                            continue;
+                        }
+                        // the task that comes first in the solution ends before the start of the other
+                        let (first, second) = if solution.lb(a) + t1.start.shift <= solution.lb(b) + t2.start.shift {
+                            (t1, t2)
+                        } else {
+                            (t2, t1)
+                        };
+                        let delay = second.start.shift - first.start.shift - first.duration;
+                        let (first, second) = (VarRef::from(first.start.var), VarRef::from(second.start.var));
+                        choices.push(TemporalChoice::Diff(DifferenceExpression::new(first, second, delay)));
+                    }
+                }
+            }
+            _ => {
+                let depends_on_timepoints = global_variables(expr).into_iter().any(|var| free(var) && present(var));
+                ensure!(
+                    !depends_on_timepoints,
+                    "The plan depends on a temporal constraint that cannot be represented in a temporal network: {expr}"
+                );
+            }
+        }
+    }
+
+    for choice in choices {
+        match choice {
+            TemporalChoice::Lit(lit) => {
+                ensure!(
+                    model.state.set(lit, Cause::Decision).is_ok(),
+                    "The solution is not compatible with the problem"
+                );
+            }
+            TemporalChoice::Diff(diff) => {
+                let scope = [model.state.presence(diff.a), model.state.presence(diff.b)];
+                model.enforce(ReifExpr::MaxDiff(diff), scope);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Variables of a global constraint that is not handled when recording the temporal choices of a solution.
+fn global_variables(expr: &ReifExpr) -> Vec<VarRef> {
+    match expr {
+        ReifExpr::AllDifferent(vars) => vars.clone(),
+        ReifExpr::Cumulative(cumulative) => cumulative
+            .tasks
+            .iter()
+            .flat_map(|task| [VarRef::from(task.start.var), task.presence.variable()])
+            .collect(),
+        ReifExpr::Table(table) => table.vars.clone(),
+        ReifExpr::Element(elem) => vec![elem.index, elem.value],
+        ReifExpr::Mul(mul) => vec![mul.product, mul.lhs, mul.rhs],
+        ReifExpr::Div(div) => vec![div.quotient, div.numerator],
+        ReifExpr::Mod(m) => vec![m.remainder, m.numerator],
+        ReifExpr::MinMax(min_max) => min_max.vars.iter().copied().chain([min_max.value]).collect(),
+        ReifExpr::Abs(abs) => vec![abs.value, abs.var],
+        ReifExpr::PseudoBoolean(pb) => pb.terms.iter().map(|(_, lit)| lit.variable()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve, SolverResult};
+    use aries::model::extensions::AssignmentExt;
+    use aries::utils::input::Input;
+    use aries_planning::parsing::pddl::{parse_pddl_domain, parse_pddl_problem};
+    use aries_planning::parsing::pddl_to_chronicles;
+
+    const DOMAIN: &str = "(define (domain sequence)
+        (:requirements :durative-actions)
+        (:predicates (a-done) (b-done))
+        (:durative-action do-a
+            :parameters ()
+            :duration (= ?duration 2)
+            :condition ()
+            :effect (at end (a-done)))
+        (:durative-action do-b
+            :parameters ()
+            :duration (= ?duration 3)
+            :condition (at start (a-done))
+            :effect (at end (b-done))))";
+
+    const PROBLEM: &str = "(define (problem sequence-1)
+        (:domain sequence)
+        (:init)
+        (:goal (b-done)))";
+
+    #[test]
+    fn test_to_json() {
+        let schedule = FlexibleSchedule {
+            timepoints: vec!["origin".to_string(), "start \"a\\b\"\u{1}".to_string()],
+            constraints: vec![
+                ScheduleConstraint {
+                    source: 0,
+                    target: 1,
+                    weight: Rational::new(5, 2),
+                },
+                ScheduleConstraint {
+                    source: 1,
+                    target: 0,
+                    weight: Rational::from_integer(-1),
+                },
+            ],
+        };
+        let expected = r#"{
+  "timepoints": [
+    "origin",
+    "start \"a\\b\"\u0001"
+  ],
+  "constraints": [
+    { "source": 0, "target": 1, "weight": { "numerator": 5, "denominator": 2 } },
+    { "source": 1, "target": 0, "weight": { "numerator": -1, "denominator": 1 } }
+  ]
+}
+"#;
+        assert_eq!(schedule.to_json(), expected);
+    }
+
+    /// Two robots share a single door that only one of them can hold at a time.
+    const DOOR_DOMAIN: &str = "(define (domain door)
+        (:requirements :typing :durative-actions)
+        (:types robot)
+        (:predicates (door-free) (passed ?r - robot))
+        (:durative-action pass
+            :parameters (?r - robot)
+            :duration (and (>= ?duration 2) (<= ?duration 5))
+            :condition (at start (door-free))
+            :effect (and
+                (at start (not (door-free)))
+                (at end (door-free))
+                (at end (passed ?r)))))";
+
+    const DOOR_PROBLEM: &str = "(define (problem door-1)
+        (:domain door)
+        (:objects r1 r2 - robot)
+        (:init (door-free))
+        (:goal (and (passed r1) (passed r2))))";
+
+    /// Returns true if executing the plan of the `solution` with its timepoints (see [plan_timepoints]) at
+    /// the given `times` is valid, i.e., if the problem has a solution with the same actions at these times.
+    fn is_valid_dispatch(problem: &FiniteProblem, solution: &Domains, times: &[Rational]) -> Result<bool> {
+        let EncodedProblem { mut model, .. } =
+            encode(problem, &[]).map_err(|_| anyhow!("The problem is unsatisfiable"))?;
+        let mut fix = |var: VarRef, value: IntCst| {
+            model.state.set_lb(var, value, Cause::Decision).is_ok()
+                && model.state.set_ub(var, value, Cause::Decision).is_ok()
+        };
+        // actions of the plan and their parameters
+        for ch in &problem.chronicles {
+            let prez = ch.chronicle.presence.variable();
+            let mut vars = vec![prez];
+            if solution.entails(ch.chronicle.presence) {
+                vars.extend(ch.chronicle.name.iter().map(|atom| atom.variable()));
+            }
+            for var in vars {
+                if !fix(var, solution.lb(var)) {
+                    return Ok(false);
+                }
+            }
+        }
+        for ((_, t), &time) in plan_timepoints(problem, solution)?.iter().zip(times) {
+            let value = time * t.denom;
+            ensure!(value.is_integer(), "Time {time} is not a multiple of the time scale");
+            let value = value.to_integer() - t.num.shift;
+            let var = VarRef::from(t.num.var);
+            let valid = if var == VarRef::ZERO {
+                value == 0
+            } else {
+                fix(var, value)
+            };
+            if !valid {
+                return Ok(false);
+            }
+        }
+        let mut solver = init_solver(model);
+        Ok(solver.solve().map_err(|_| anyhow!("Search interrupted"))?.is_some())
+    }
+
+    /// Returns random assignments of the timepoints of the schedule that are consistent with its constraints,
+    /// with times that are multiples of `1/denom`.
+    #[allow(clippy::needless_range_loop)]
+    fn sample_dispatches(schedule: &FlexibleSchedule, denom: IntCst, num_samples: usize) -> Vec<Vec<Rational>> {
+        use rand::rngs::SmallRng;
+        use rand::{Rng, SeedableRng};
+
+        // all-pairs shortest paths: `dist[i][j]` is the maximal delay between the timepoints `i` and `j`
+        let n = schedule.timepoints.len();
+        let mut dist: Vec<Vec<Option<Rational>>> = vec![vec![None; n]; n];
+        for (i, row) in dist.iter_mut().enumerate() {
+            row[i] = Some(Rational::from_integer(0));
+        }
+        for c in &schedule.constraints {
+            let d = &mut dist[c.source][c.target];
+            *d = Some(d.map_or(c.weight, |d| d.min(c.weight)));
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    if let (Some(ik), Some(kj)) = (dist[i][k], dist[k][j]) {
+                        let d = &mut dist[i][j];
+                        *d = Some(d.map_or(ik + kj, |d| d.min(ik + kj)));
+                    }
+                }
+            }
+        }
+        assert!(
+            (0..n).all(|i| dist[i][i] == Some(Rational::from_integer(0))),
+            "Inconsistent schedule"
+        );
+
+        // assigning the timepoints one by one in the intervals allowed by the ones already assigned is
+        // backtrack-free on a network with all shortest paths
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut dispatches = Vec::with_capacity(num_samples);
+        for _ in 0..num_samples {
+            let mut times: Vec<Rational> = vec![Rational::from_integer(0)];
+            for i in 1..n {
+                let lb = (0..i).filter_map(|j| dist[i][j].map(|d| times[j] - d)).max().unwrap();
+                let ub = (0..i).filter_map(|j| dist[j][i].map(|d| times[j] + d)).min().unwrap();
+                let (lb, ub) = ((lb * denom).ceil().to_integer(), (ub * denom).floor().to_integer());
+                // favor small delays to keep the dispatches close to one another
+                let ub = ub.min(lb + 10 * denom);
+                times.push(Rational::new(rng.gen_range(lb..=ub), denom));
+            }
+            dispatches.push(times);
+        }
+        dispatches
+    }
+
+    fn check_flexible_schedule(domain: &str, problem: &str) -> Result<()> {
+        let dom = parse_pddl_domain(Input::from_string(domain))?;
+        let prob = parse_pddl_problem(Input::from_string(problem))?;
+        let spec = pddl_to_chronicles(&dom, &prob)?;
+        let SolverResult::Sol((problem, solution)) = solve(spec, 0, 2, &[], &[], &[], false, |_, _| {}, None)? else {
+            bail!("No plan found")
+        };
+        let schedule = extract_flexible_schedule(&problem, &solution)?;
+        ensure!(schedule.timepoints.len() == plan_timepoints(&problem, &solution)?.len());
+
+        // the plan found by the solver is a dispatch of the schedule
+        let times: Vec<Rational> = plan_timepoints(&problem, &solution)?
+            .iter()
+            .map(|(_, t)| solution.f_domain(*t).lb())
+            .collect();
+        assert!(is_valid_dispatch(&problem, &solution, &times)?);
+
+        let dispatches = sample_dispatches(&schedule, problem.origin.denom, 20);
+        // the schedule is flexible: it has other dispatches than the plan of the solver
+        assert!(dispatches.iter().any(|dispatch| dispatch != &times));
+        for dispatch in dispatches {
+            assert!(
+                is_valid_dispatch(&problem, &solution, &dispatch)?,
+                "Invalid dispatch {dispatch:?} of the schedule {}",
+                schedule.to_json()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_temporal_choices() -> Result<()> {
+        use aries::model::lang::expr::{leq, or};
+        use aries::model::lang::linear::LinearSum;
+        use aries_planning::chronicles::Container;
+
+        let mut model: Model<VarLabel> = Model::new();
+        let a = model.new_ivar(0, 100, Container::Instance(0).var(VarType::ChronicleStart));
+        let b = model.new_ivar(0, 100, Container::Instance(1).var(VarType::ChronicleStart));
+        model.enforce(or([a.leq(10), b.geq(50)]), []);
+        model.enforce(leq(a, b), []);
+        let mut solution = model.state.clone();
+        solution.set_lb(a, 5, Cause::Decision).unwrap();
+        solution.set_ub(a, 5, Cause::Decision).unwrap();
+        solution.set_lb(b, 20, Cause::Decision).unwrap();
+        solution.set_ub(b, 20, Cause::Decision).unwrap();
+
+        // the disjunction is restricted to the values of its literals in the solution
+        let mut recorded = model.clone();
+        record_temporal_choices(&mut recorded, &solution)?;
+        assert!(recorded.state.entails(a.leq(10)));
+        assert!(recorded.state.entails(b.lt(50)));
+
+        // a sum of timepoints cannot be represented in a temporal network
+        let mut unsupported = model.clone();
+        unsupported.enforce((LinearSum::from(a) + b).leq(60), []);
+        assert!(record_temporal_choices(&mut unsupported, &solution).is_err());
+
+        // a linear constraint over timepoints whose other variables are fixed is a difference constraint
+        let d = model.new_ivar(0, 50, Container::Instance(0).var(VarType::Parameter("d".to_string())));
+        model.state.set_lb(d, 10, Cause::Decision).unwrap();
+        model.state.set_ub(d, 10, Cause::Decision).unwrap();
+        model.enforce((LinearSum::from(b) - a - d).geq(0), []);
+        let mut recorded = model.clone();
+        record_temporal_choices(&mut recorded, &solution)?;
+        let mut solver = init_solver(recorded);
+        solver.propagate().map_err(|_| anyhow!("Inconsistent model"))?;
+        let (a, b) = (VarRef::from(a), VarRef::from(b));
+        let network = solver
+            .reasoners
+            .diff
+            .dispatchable_network(&[a, b], &solver.model.state)
+            .unwrap();
+        assert!(network
+            .edges
+            .iter()
+            .any(|e| (e.source, e.target, e.weight) == (b, a, -10)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_flexible_schedule() -> Result<()> {
+        check_flexible_schedule(DOMAIN, PROBLEM)?;
+        check_flexible_schedule(DOOR_DOMAIN, DOOR_PROBLEM)
+    }
+}
@@ -1,8 +1,8 @@
 //! Functions related to printing and formatting (partial) plans.
 
 use anyhow::*;
+use aries::core::Rational;
 use itertools::Itertools;
-use num_rational::Rational32;
 use std::fmt::Write;
 
 use crate::Model;
@@ -215,52 +215,72 @@ pub fn extract_plan(problem: &FiniteProblem, ass: &SavedAssignment) -> Result<Ve
             ChronicleKind::Problem | ChronicleKind::Method => continue,
             _ => {}
         }
-        let start = ass.f_domain(ch.chronicle.start).lb();
-        let end = ass.f_domain(ch.chronicle.end).lb();
-        let duration = end - start;
-        let name = format_atom(&ch.chronicle.name[0], &problem.model, ass);
-        let params = ch.chronicle.name[1..]
-            .iter()
-            .map(|atom| ass.evaluate(*atom).unwrap())
-            .collect_vec();
-
-        let instance = ActionInstance {
-            name,
-            params,
-            start,
-            duration,
-        };
-
-        // if the action corresponds to a rolled-up action, unroll it in the solution
-        let roll_compil = match ch.origin {
-            ChronicleOrigin::FreeAction { template_id, .. } => problem.meta.action_rolling.get(&template_id),
-            _ => None,
-        };
-        if let Some(roll_compil) = roll_compil {
-            plan.extend(roll_compil.unroll(&instance))
-        } else {
-            plan.push(instance);
+        match extract_action(problem, ch, ass) {
+            (_, Some(unrolled)) => plan.extend(unrolled),
+            (instance, None) => plan.push(instance),
         }
     }
     plan.sort_by_key(|a| a.start);
     Ok(plan)
 }
 
-fn str(r: Rational32) -> String {
+/// Returns the action instance of a chronicle in the solution and, if the action corresponds to a rolled-up
+/// action, the sequence of original actions it stands for.
+pub(crate) fn extract_action(
+    problem: &FiniteProblem,
+    ch: &ChronicleInstance,
+    ass: &SavedAssignment,
+) -> (ActionInstance, Option<Vec<ActionInstance>>) {
+    let start = ass.f_domain(ch.chronicle.start).lb();
+    let end = ass.f_domain(ch.chronicle.end).lb();
+    let duration = end - start;
+    let name = format_atom(&ch.chronicle.name[0], &problem.model, ass);
+    let params = ch.chronicle.name[1..]
+        .iter()
+        .map(|atom| ass.evaluate(*atom).unwrap())
+        .collect_vec();
+
+    let instance = ActionInstance {
+        name,
+        params,
+        start,
+        duration,
+    };
+
+    let roll_compil = match ch.origin {
+        ChronicleOrigin::FreeAction { template_id, .. } => problem.meta.action_rolling.get(&template_id),
+        _ => None,
+    };
+    let unrolled = roll_compil.map(|roll_compil| roll_compil.unroll(&instance));
+    (instance, unrolled)
+}
+
+/// Formats the name and parameters of an action, e.g., `(move r1 l1 l2)`.
+pub(crate) fn format_action_name(action: &ActionInstance, model: &Model) -> String {
+    let mut out = format!("({}", action.name);
+    for &p in &action.params {
+        write!(out, " {}", format_cst(p, model)).unwrap();
+    }
+    out.push(')');
+    out
+}
+
+fn str(r: Rational) -> String {
     let scale = TIME_SCALE.get();
     if scale % r.denom() != 0 {
         // default to formatting float
         return format!("{:.3}", *r.numer() as f32 / *r.denom() as f32);
     }
     let r_scaled = (r * scale).to_integer();
-    let int_part = r_scaled / scale;
-    let decimal_part = r_scaled % scale;
+    let sign = if r_scaled < 0 { "-" } else { "" };
+    let int_part = (r_scaled / scale).abs();
+    let decimal_part = (r_scaled % scale).abs();
 
     match scale {
-        1 => format!("{int_part}"),
-        10 => format!("{int_part}.{decimal_part:0<1}"),
-        100 => format!("{int_part}.{decimal_part:0<2}"),
-        1000 => format!("{int_part}.{decimal_part:0<3}"),
+        1 => format!("{sign}{int_part}"),
+        10 => format!("{sign}{int_part}.{decimal_part:0>1}"),
+        100 => format!("{sign}{int_part}.{decimal_part:0>2}"),
+        1000 => format!("{sign}{int_part}.{decimal_part:0>3}"),
         _ => format!("{:.3}", *r.numer() as f32 / *r.denom() as f32), // default to formatting float
     }
 }
@@ -271,11 +291,11 @@ pub fn format_pddl_plan(problem: &FiniteProblem, ass: &SavedAssignment) -> Resul
     for a in &plan {
         let start = str(a.start);
         let duration = str(a.duration);
-        write!(out, "{start:>5}: ({}", a.name)?;
-        for &p in &a.params {
-            write!(out, " {}", format_cst(p, &problem.model))?;
-        }
-        writeln!(out, ") [{duration}]")?;
+        writeln!(
+            out,
+            "{start:>5}: {} [{duration}]",
+            format_action_name(a, &problem.model)
+        )?;
     }
     Ok(out)
 }
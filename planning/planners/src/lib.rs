@@ -2,6 +2,7 @@ use aries_planning::chronicles::VarLabel;
 
 pub mod encode;
 pub mod encoding;
+pub mod flexible;
 pub mod fmt;
 pub mod search;
 pub mod solver;
@@ -5,20 +5,26 @@ use crate::search::{ForwardSearcher, ManualCausalSearch};
 use crate::Solver;
 use anyhow::Result;
 use aries::core::state::Domains;
-use aries::core::{IntCst, Lit, VarRef, INT_CST_MAX};
+use aries::core::{IntCst, Lit, VarRef};
 use aries::model::extensions::{AssignmentExt, SavedAssignment};
+use aries::model::lang::expr::or;
 use aries::model::lang::IAtom;
 use aries::model::Model;
 use aries::reasoners::stn::theory::{StnConfig, TheoryPropagationLevel};
+use aries::solver::lns::{Lns, Neighbourhood, TemporalWindow};
 use aries::solver::parallel::Solution;
 use aries::solver::search::activity::*;
 use aries::solver::search::conflicts::ConflictBasedBrancher;
 use aries::solver::search::lexical::LexicalMinValue;
+use aries::solver::search::restarts::Restarts;
 use aries::solver::search::{Brancher, SearchControl};
 use aries_planning::chronicles::printer::Printer;
 use aries_planning::chronicles::Problem;
 use aries_planning::chronicles::*;
 use env_param::EnvParam;
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
@@ -81,7 +87,8 @@ pub fn solve(
     min_depth: u32,
     max_depth: u32,
     strategies: &[Strat],
-    metric: Option<Metric>,
+    restarts: &[Restarts],
+    metrics: &[Metric],
     htn_mode: bool,
     on_new_sol: impl Fn(&FiniteProblem, Arc<SavedAssignment>) + Clone,
     deadline: Option<Instant>,
@@ -98,7 +105,8 @@ pub fn solve(
 
     let metadata = Arc::new(analysis::analyse(&base_problem));
 
-    let mut best_cost = INT_CST_MAX + 1;
+    // costs of the best plan found so far, one for each metric
+    let mut best_costs: Option<Vec<IntCst>> = None;
 
     let start = Instant::now();
     for depth in min_depth..=max_depth {
@@ -132,21 +140,21 @@ pub fn solve(
         let result = solve_finite_problem(
             pb.clone(),
             strategies,
-            metric,
+            restarts,
+            metrics,
             htn_mode,
             on_new_valid_assignment,
             deadline,
-            best_cost - 1,
+            best_costs.as_deref(),
         );
         println!("  [{:.3}s] Solved", start.elapsed().as_secs_f32());
 
         let result = result.map(|assignment| (pb, assignment));
         match result {
             SolverResult::Unsat => {} // continue (increase depth)
-            SolverResult::Sol((_, (_, cost))) if metric.is_some() && depth < max_depth => {
-                let cost = cost.expect("Not cost provided in optimization problem");
-                assert!(cost < best_cost);
-                best_cost = cost; // continue with new cost bound
+            SolverResult::Sol((_, (_, costs))) if !metrics.is_empty() && depth < max_depth => {
+                assert!(best_costs.as_ref().is_none_or(|best| &costs < best));
+                best_costs = Some(costs); // continue with new cost bound
             }
             other => return Ok(other.map(|(pb, (ass, _))| (pb, ass))),
         }
@@ -154,6 +162,22 @@ pub fn solve(
     Ok(SolverResult::Unsat)
 }
 
+/// Requires the values of the `objectives` to be lexicographically smaller than `bounds`.
+///
+/// For each objective `o_k`, either one of the previous objectives is strictly better than its bound,
+/// or `o_k` does not exceed its own bound. Additionally, at least one objective must be strictly better than its bound.
+fn enforce_lexicographic_improvement(model: &mut Model<VarLabel>, objectives: &[IAtom], bounds: &[IntCst]) {
+    debug_assert_eq!(objectives.len(), bounds.len());
+    let mut improved = Vec::with_capacity(objectives.len());
+    for (objective, &bound) in objectives.iter().zip(bounds) {
+        let mut clause = improved.clone();
+        clause.push(objective.le_lit(bound));
+        model.enforce(or(clause), []);
+        improved.push(objective.lt_lit(bound));
+    }
+    model.enforce(or(improved), []);
+}
+
 /// This function mimics the instantiation of the subproblem, run the propagation and prints the result.
 /// and exits immediately.
 ///
@@ -161,7 +185,7 @@ pub fn solve(
 ///
 /// Returns true if the propagation succeeded.
 fn propagate_and_print(pb: &FiniteProblem) -> bool {
-    let Ok(EncodedProblem { model, .. }) = encode(pb, None) else {
+    let Ok(EncodedProblem { model, .. }) = encode(pb, &[]) else {
         println!("==> Invalid model");
         return false;
     };
@@ -233,6 +257,8 @@ pub enum Strat {
     Forward,
     /// Search strategy that first tries to solve causal links.
     Causal,
+    /// Activity-based search, where optimization is carried out by Large Neighbourhood Search (LNS).
+    Lns,
 }
 
 /// An activity-based variable selection heuristics that delays branching on temporal variables.
@@ -257,7 +283,15 @@ impl Heuristic<VarLabel> for ActivityBoolFirstHeuristic {
 
 impl Strat {
     /// Configure the given solver to follow the strategy.
-    pub fn adapt_solver(self, solver: &mut Solver, problem: Arc<FiniteProblem>, encoding: Arc<Encoding>) {
+    ///
+    /// If a restart policy is given, it replaces the default restart strategy of the brancher.
+    pub fn adapt_solver(
+        self,
+        solver: &mut Solver,
+        problem: Arc<FiniteProblem>,
+        encoding: Arc<Encoding>,
+        restarts: Option<&Restarts>,
+    ) {
         match self {
             Strat::Activity => {
                 // nothing, activity based search is the default configuration
@@ -273,15 +307,27 @@ impl Strat {
                 solver.set_brancher(ForwardSearcher::new(problem));
                 solver.reasoners.diff.config.theory_propagation = TheoryPropagationLevel::Bounds;
             }
+            Strat::Lns => {
+                solver.set_brancher(ActivityBrancher::new_with_heuristic(ActivityBoolFirstHeuristic));
+                solver.set_lns(chronicles_lns(&problem));
+            }
             Strat::Causal => {
-                let strat = causal_brancher(problem, encoding);
+                let restarts = restarts.cloned().unwrap_or(Restarts::Geometric {
+                    allowed_conflicts: 50,
+                    increase_ratio: 1.3,
+                });
+                let strat = causal_brancher(problem, encoding, &restarts);
                 solver.set_brancher_boxed(strat);
+                return;
             }
         }
+        if let Some(restarts) = restarts {
+            solver.set_restart_policy(restarts.policy());
+        }
     }
 }
 
-fn causal_brancher(problem: Arc<FiniteProblem>, encoding: Arc<Encoding>) -> Brancher<VarLabel> {
+fn causal_brancher(problem: Arc<FiniteProblem>, encoding: Arc<Encoding>, restarts: &Restarts) -> Brancher<VarLabel> {
     use aries::solver::search::combinators::CombinatorExt;
     let branching_literals: Vec<Lit> = encoding.tags.iter().map(|&(_, l)| l).collect();
 
@@ -307,7 +353,63 @@ fn causal_brancher(problem: Arc<FiniteProblem>, encoding: Arc<Encoding>) -> Bran
     let lexical = Box::new(LexicalMinValue::new());
     let strat = causal.clone_to_box().and_then(conflict).and_then(act).and_then(lexical);
 
-    strat.with_restarts(50, 1.3)
+    strat.with_restart_policy(restarts.policy())
+}
+
+/// Neighbourhood that relaxes all chronicles instantiated from a randomly selected template.
+#[derive(Clone)]
+struct TemplateNeighbourhood {
+    /// Variables of the chronicles of each template.
+    templates: Vec<Vec<VarRef>>,
+}
+
+impl Neighbourhood for TemplateNeighbourhood {
+    fn select(
+        &mut self,
+        _vars: &[VarRef],
+        _incumbent: &SavedAssignment,
+        rng: &mut SmallRng,
+        relaxed: &mut Vec<VarRef>,
+    ) {
+        if let Some(template) = self.templates.choose(rng) {
+            relaxed.extend_from_slice(template);
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Neighbourhood> {
+        Box::new(self.clone())
+    }
+}
+
+/// Builds an LNS optimizer over the variables of all non-original chronicles, whose neighbourhoods relax the chronicles
+/// of a given template or the ones starting in a given time window.
+fn chronicles_lns(problem: &FiniteProblem) -> Lns {
+    let mut vars = Vec::new();
+    let mut templates: HashMap<usize, Vec<VarRef>> = HashMap::new();
+    let mut activities = Vec::new();
+    for ch in &problem.chronicles {
+        let template_id = match ch.origin {
+            ChronicleOrigin::Original => continue,
+            ChronicleOrigin::FreeAction { template_id, .. } | ChronicleOrigin::Refinement { template_id, .. } => {
+                template_id
+            }
+        };
+        let ch_vars: Vec<VarRef> = ch
+            .parameters
+            .iter()
+            .map(|p| p.variable())
+            .filter(|&v| v != VarRef::ZERO)
+            .collect();
+        vars.extend_from_slice(&ch_vars);
+        templates.entry(template_id).or_default().extend_from_slice(&ch_vars);
+        activities.push((ch.chronicle.start.num.var.into(), ch_vars));
+    }
+    let templates = TemplateNeighbourhood {
+        templates: templates.into_values().collect(),
+    };
+    Lns::new(vars, 500)
+        .with_neighbourhood(templates)
+        .with_neighbourhood(TemporalWindow::new(activities, 0.3))
 }
 
 impl FromStr for Strat {
@@ -320,6 +422,7 @@ impl FromStr for Strat {
             "3" | "act-bool" | "activity-bool" => Ok(Strat::ActivityBool),
             "4" | "act-bool-light" | "activity-bool-light" => Ok(Strat::ActivityBoolLight),
             "causal" => Ok(Strat::Causal),
+            "lns" => Ok(Strat::Lns),
             _ => Err(format!("Unknown search strategy: {s}")),
         }
     }
@@ -330,29 +433,37 @@ impl FromStr for Strat {
 /// If more than one strategy is given, each strategy will have its own solver run on a dedicated thread.
 /// If no strategy is given, then a default set of strategies will be automatically selected.
 ///
-/// If a valid solution of the subproblem is found, the solver will return a satisfying assignment.
+/// If restart policies are given, the i-th strategy uses the `i % restarts.len()`-th policy.
+///
+/// If metrics are given, they are minimized in lexicographic order and the plan must improve
+/// lexicographically on the `best_costs`, if any.
+///
+/// If a valid solution of the subproblem is found, the solver will return a satisfying assignment,
+/// together with the value of each metric.
+#[allow(clippy::too_many_arguments)]
 fn solve_finite_problem(
     pb: Arc<FiniteProblem>,
     strategies: &[Strat],
-    metric: Option<Metric>,
+    restarts: &[Restarts],
+    metrics: &[Metric],
     htn_mode: bool,
     on_new_solution: impl Fn(Arc<SavedAssignment>),
     deadline: Option<Instant>,
-    cost_upper_bound: IntCst,
-) -> SolverResult<(Solution, Option<IntCst>)> {
+    best_costs: Option<&[IntCst]>,
+) -> SolverResult<(Solution, Vec<IntCst>)> {
     if PRINT_INITIAL_PROPAGATION.get() {
         propagate_and_print(&pb);
     }
     let Ok(EncodedProblem {
         mut model,
-        objective: metric,
+        objectives,
         encoding,
-    }) = encode(&pb, metric)
+    }) = encode(&pb, metrics)
     else {
         return SolverResult::Unsat;
     };
-    if let Some(metric) = metric {
-        model.enforce(metric.le_lit(cost_upper_bound), []);
+    if let Some(best_costs) = best_costs {
+        enforce_lexicographic_improvement(&mut model, &objectives, best_costs);
     }
     let solver = init_solver(model);
     let encoding = Arc::new(encoding);
@@ -366,19 +477,20 @@ fn solve_finite_problem(
         &GEN_DEFAULT_STRATEGIES
     };
     let mut solver = aries::solver::parallel::ParSolver::new(solver, strats.len(), |id, s| {
-        strats[id].adapt_solver(s, pb.clone(), encoding.clone())
+        let restarts = (!restarts.is_empty()).then(|| &restarts[id % restarts.len()]);
+        strats[id].adapt_solver(s, pb.clone(), encoding.clone(), restarts)
     });
 
-    let result = if let Some(metric) = metric {
-        solver.minimize_with(metric, on_new_solution, deadline)
-    } else {
-        solver.solve(deadline)
+    let result = match objectives.as_slice() {
+        [] => solver.solve(deadline),
+        [objective] => solver.minimize_with(*objective, on_new_solution, deadline),
+        objectives => solver.minimize_lexicographic_with(objectives, on_new_solution, deadline),
     };
 
-    // tag result with cost
+    // tag result with costs
     let result = result.map(|s| {
-        let cost = metric.map(|metric| s.domain_of(metric).0);
-        (s, cost)
+        let costs = objectives.iter().map(|&objective| s.domain_of(objective).0).collect();
+        (s, costs)
     });
 
     if let SolverResult::Sol(_) = result {
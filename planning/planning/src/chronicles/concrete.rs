@@ -4,7 +4,7 @@ use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
-use crate::chronicles::constraints::Constraint;
+use crate::chronicles::constraints::{Constraint, ConstraintType};
 use crate::chronicles::Fluent;
 use aries::core::{IntCst, Lit, VarRef};
 use aries::model::lang::linear::{LinearSum, LinearTerm};
@@ -587,6 +587,20 @@ impl Chronicle {
 
         vars.0
     }
+
+    /// Marks the duration of this chronicle as chosen by the environment rather than by the planner
+    /// (see [Duration::Contingent](crate::chronicles::constraints::Duration::Contingent)).
+    /// Returns false if the chronicle has no duration constraint.
+    pub fn make_duration_uncontrollable(&mut self) -> bool {
+        let mut found = false;
+        for c in &mut self.constraints {
+            if let ConstraintType::Duration(d) = &c.tpe {
+                c.tpe = ConstraintType::Duration(d.clone().uncontrollable());
+                found = true;
+            }
+        }
+        found
+    }
 }
 
 impl Debug for Chronicle {
@@ -1,9 +1,10 @@
 use super::*;
-use aries::core::Lit;
+use aries::core::{IntCst, Lit, VarRef};
 use aries::model::extensions::AssignmentExt;
 use aries::model::lang::expr::*;
+use aries::model::lang::extensional::Table as TableConstraint;
 use aries::model::lang::linear::LinearSum;
-use aries::model::lang::{Cst, Type};
+use aries::model::lang::{Cst, IVar, SAtom, Type};
 use aries::model::Label;
 use itertools::Itertools;
 use std::fmt::Debug;
@@ -12,6 +13,11 @@ use ConstraintType::*;
 /// If true, redundant constraints will be added to table constraints to improve the propagation
 static TABLE_STRONG_PROPAGATION: EnvParam<bool> = EnvParam::new("ARIES_TABLE_STRONG_PROPAGATION", "false");
 
+/// If true, table constraints will be posted as a global constraint handled by a dedicated propagator,
+/// instead of being decomposed into clauses.
+/// Deactivated by default as the search currently benefits from the literals introduced by the decomposition.
+static TABLE_GLOBAL_CONSTRAINT: EnvParam<bool> = EnvParam::new("ARIES_TABLE_GLOBAL_CONSTRAINT", "false");
+
 /// Generic representation of a constraint on a set of variables
 #[derive(Debug, Clone)]
 pub struct Constraint {
@@ -103,6 +109,15 @@ impl Constraint {
         }
     }
 
+    /// Constrains `product` to be equal to `lhs * rhs`.
+    pub fn mul(product: IVar, lhs: IVar, rhs: IVar) -> Constraint {
+        Constraint {
+            variables: vec![product.into(), lhs.into(), rhs.into()],
+            tpe: ConstraintType::Mul,
+            value: None,
+        }
+    }
+
     pub fn table(variables: Vec<Atom>, values: Arc<Table<Cst>>) -> Self {
         Constraint {
             variables,
@@ -134,6 +149,8 @@ pub enum ConstraintType {
     Or,
     /// A linear sum that must equals zero
     LinearEq(LinearSum),
+    /// The first variable is the product of the two others.
+    Mul,
 }
 
 impl Substitute for ConstraintType {
@@ -144,8 +161,12 @@ impl Substitute for ConstraintType {
                 lb: substitution.sub_linear_sum(lb),
                 ub: substitution.sub_linear_sum(ub),
             }),
+            Duration(Duration::Contingent { lb, ub }) => ConstraintType::Duration(Duration::Contingent {
+                lb: substitution.sub_linear_sum(lb),
+                ub: substitution.sub_linear_sum(ub),
+            }),
             LinearEq(sum) => LinearEq(substitution.sub_linear_sum(sum)),
-            InTable(_) | Lt | Leq | Eq | Neq | Or => self.clone(), // no variables in those variants
+            InTable(_) | Lt | Leq | Eq | Neq | Or | Mul => self.clone(), // no variables in those variants
         }
     }
 }
@@ -197,6 +218,35 @@ pub enum Duration {
     Fixed(LinearSum),
     /// The duration must be between the lower and the upper bound (inclusive)
     Bounded { lb: LinearSum, ub: LinearSum },
+    /// The duration is between the lower and upper bound (inclusive) but is chosen by the environment
+    /// and only observed when the chronicle ends.
+    /// Both bounds must be constant, and the chronicle must have distinct start and end timepoints.
+    Contingent { lb: LinearSum, ub: LinearSum },
+}
+
+impl Duration {
+    /// Returns a version of this duration that is chosen by the environment (see [Duration::Contingent]).
+    pub fn uncontrollable(self) -> Duration {
+        match self {
+            Duration::Fixed(d) => Duration::Contingent { lb: d.clone(), ub: d },
+            Duration::Bounded { lb, ub } | Duration::Contingent { lb, ub } => Duration::Contingent { lb, ub },
+        }
+    }
+}
+
+/// Converts a constant linear sum into a number of time units, rounded down or up as requested.
+fn time_units(sum: &LinearSum, time_denom: IntCst, round_up: bool) -> IntCst {
+    assert!(
+        sum.terms().is_empty(),
+        "Non constant bound of contingent duration: {sum:?}"
+    );
+    let num = sum.constant() * time_denom;
+    let denom = sum.denom();
+    if round_up {
+        num.div_euclid(denom) + (num.rem_euclid(denom) != 0) as IntCst
+    } else {
+        num.div_euclid(denom)
+    }
 }
 
 /// Update the given model to enforce the constraints.
@@ -218,7 +268,8 @@ pub fn encode_constraint<L: Label>(
     match &constraint.tpe {
         ConstraintType::InTable(table) => {
             assert!(model.entails(value)); // tricky to determine the appropriate validity scope, only support enforcing
-            enforce_table_constraint(model, &constraint.variables, table.as_ref(), presence);
+            let global = TABLE_GLOBAL_CONSTRAINT.get();
+            enforce_table_constraint(model, &constraint.variables, table.as_ref(), presence, global);
         }
         ConstraintType::Lt => match constraint.variables.as_slice() {
             &[a, b] => match (a, b) {
@@ -293,6 +344,18 @@ pub fn encode_constraint<L: Label>(
                     model.bind(lb_sum.geq(LinearSum::zero()), value);
                     model.bind(ub_sum.leq(LinearSum::zero()), value);
                 }
+                Duration::Contingent { lb, ub } => {
+                    assert!(model.entails(value), "Reified contingent durations are not supported");
+                    assert_ne!(
+                        start.num.var, end.num.var,
+                        "Contingent duration of an instantaneous chronicle"
+                    );
+                    assert_eq!(start.denom, end.denom);
+                    // round outwards so that all durations the environment may choose are considered
+                    let lb = time_units(lb, start.denom, false);
+                    let ub = time_units(ub, start.denom, true);
+                    model.enforce(contingent_link(start.num, end.num, lb, ub), [presence]);
+                }
             };
             // Redundant constraint to enforce the precedence between start and end.
             // This form ensures that the precedence in posted in the STN.
@@ -310,10 +373,97 @@ pub fn encode_constraint<L: Label>(
             model.enforce(sum.clone().leq(LinearSum::zero()), [presence]);
             model.enforce(sum.clone().geq(LinearSum::zero()), [presence]);
         }
+        ConstraintType::Mul => {
+            assert!(model.entails(value)); // reified products are not supported, only enforcing
+            let var = |atom: Atom| match atom {
+                Atom::Int(IAtom { var, shift: 0 }) => var,
+                _ => panic!("Invalid operand of product constraint: {atom:?}"),
+            };
+            match constraint.variables.as_slice() {
+                &[product, lhs, rhs] => model.enforce(eq_mul(var(product), var(lhs), var(rhs)), [presence]),
+                x => panic!("Invalid variable pattern for product constraint: {:?}", x),
+            }
+        }
     }
 }
 
-fn enforce_table_constraint<L: Label>(model: &mut Model<L>, vars: &[Atom], table: &Table<Cst>, presence: Lit) {
+/// Attempts to convert the table constraint into a global table constraint over integer variables.
+/// Constant atoms are removed by only keeping the compatible lines, while symbols are replaced by their integer value.
+///
+/// Returns `None` if one of the atoms cannot be represented as an integer variable (e.g. a fixed-point variable).
+fn as_global_table<L: Label>(model: &Model<L>, vars: &[Atom], table: &Table<Cst>) -> Option<TableConstraint> {
+    /// A column of the global table constraint: the value of `var` is the value in the table, shifted by `shift`.
+    enum Column {
+        Var { var: VarRef, shift: IntCst },
+        Cst(IntCst),
+    }
+    let mut columns = Vec::with_capacity(vars.len());
+    for &atom in vars {
+        let column = match atom {
+            Atom::Sym(SAtom::Var(svar)) => Column::Var {
+                var: svar.var,
+                shift: 0,
+            },
+            Atom::Sym(SAtom::Cst(sym)) => Column::Cst(sym.sym.int_value()),
+            Atom::Int(IAtom { var, shift }) if var == IVar::ZERO => Column::Cst(shift),
+            // the value of `var + shift` is the value in the table
+            Atom::Int(IAtom { var, shift }) => Column::Var { var: var.into(), shift },
+            Atom::Bool(l) if l == Lit::TRUE => Column::Cst(1),
+            Atom::Bool(l) if l == Lit::FALSE => Column::Cst(0),
+            // a literal `v >= 1` on a boolean variable `v` is equivalent to its value
+            Atom::Bool(l) if l == l.variable().geq(1) && model.state.bounds(l.variable()) == (0, 1) => Column::Var {
+                var: l.variable(),
+                shift: 0,
+            },
+            _ => return None,
+        };
+        columns.push(column);
+    }
+    let value = |cst: Cst| match cst {
+        Cst::Sym(sym) => Some(sym.sym.int_value()),
+        Cst::Int(i) => Some(i),
+        Cst::Bool(b) => Some(b as IntCst),
+        Cst::Fixed(_) => None,
+    };
+    let mut tuples = Vec::with_capacity(table.lines().count());
+    'lines: for line in table.lines() {
+        let mut tuple = Vec::with_capacity(vars.len());
+        for (column, &cst) in columns.iter().zip(line) {
+            let value = value(cst)?;
+            match *column {
+                Column::Var { shift, .. } => tuple.push(value - shift),
+                Column::Cst(cst) if cst == value => {}
+                Column::Cst(_) => continue 'lines,
+            }
+        }
+        tuples.push(tuple);
+    }
+    let vars = columns
+        .iter()
+        .filter_map(|c| match *c {
+            Column::Var { var, .. } => Some(var),
+            Column::Cst(_) => None,
+        })
+        .collect();
+    Some(TableConstraint::new(vars, tuples))
+}
+
+/// Enforces that the values of the atoms form a line of the table.
+/// If `global` is true, the constraint is posted as a global table constraint when possible (see [`as_global_table`]),
+/// and is otherwise decomposed into clauses.
+fn enforce_table_constraint<L: Label>(
+    model: &mut Model<L>,
+    vars: &[Atom],
+    table: &Table<Cst>,
+    presence: Lit,
+    global: bool,
+) {
+    if global {
+        if let Some(global) = as_global_table(model, vars, table) {
+            model.enforce(global, [presence]);
+            return;
+        }
+    }
     let redundant_constraints = TABLE_STRONG_PROPAGATION.get();
 
     let mut supported_by_a_line: Vec<Lit> = Vec::with_capacity(256);
@@ -448,3 +598,84 @@ fn enforce_table_constraint<L: Label>(model: &mut Model<L>, vars: &[Atom], table
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aries::model::symbols::{SymbolTable, TypedSym};
+    use aries::model::types::TypeHierarchy;
+    use aries::reif::ReifExpr;
+    use aries::solver::Solver;
+    use aries::utils::input::Sym;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_global_table() {
+        let types = TypeHierarchy::new(vec![(Sym::from("loc"), None)]).unwrap();
+        let instances = ["l1", "l2", "l3"].map(|l| (Sym::from(l), Sym::from("loc"))).to_vec();
+        let symbols = SymbolTable::new(types, instances).unwrap();
+        let loc = symbols.types.id_of("loc").unwrap();
+        let sym = |name: &str| TypedSym::new(symbols.id(name).unwrap(), loc);
+        let (l1, l2, l3) = (sym("l1"), sym("l2"), sym("l3"));
+
+        let mut model: Model<String> = Model::new_with_symbols(Arc::new(symbols));
+        let s = model.new_sym_var(loc, "s");
+        let i = model.new_ivar(0, 5, "i");
+        let b = model.new_bvar("b");
+        // the last two columns are constants, that only select the compatible lines
+        let vars: Vec<Atom> = vec![
+            s.into(),
+            (i + 2).into(),
+            b.true_lit().into(),
+            SAtom::from(l1).into(),
+            IAtom::from(3).into(),
+        ];
+        let int = Type::Int { lb: 0, ub: 10 };
+        let mut table = Table::new(
+            "test".to_string(),
+            vec![Type::Sym(loc), int, Type::Bool, Type::Sym(loc), int],
+        );
+        let line = |s: TypedSym, i: IntCst, b: bool, s2: TypedSym, i2: IntCst| {
+            [Cst::Sym(s), Cst::Int(i), Cst::Bool(b), Cst::Sym(s2), Cst::Int(i2)]
+        };
+        table.push(&line(l1, 2, true, l1, 3));
+        table.push(&line(l2, 4, false, l1, 3));
+        table.push(&line(l3, 5, true, l2, 3));
+        table.push(&line(l3, 6, false, l1, 4));
+        table.push(&line(l2, 7, true, l1, 3));
+        table.push(&line(l1, 8, true, l1, 3)); // `i` would be out of its domain
+
+        let global = as_global_table(&model, &vars, &table).unwrap();
+        assert_eq!(global.vars, vec![s.into(), i.into(), b.into()]);
+        // constant columns are removed with the incompatible lines, and the values are shifted
+        let value = |s: TypedSym| s.sym.int_value();
+        let tuples = vec![
+            vec![value(l1), 0, 1],
+            vec![value(l2), 2, 0],
+            vec![value(l2), 5, 1],
+            vec![value(l1), 6, 1],
+        ];
+        assert_eq!(global.tuples.as_ref(), &tuples);
+
+        let solutions = |model: Model<String>| {
+            let mut solver = Solver::new(model);
+            let mut solutions = solver.enumerate(&[s.into(), i.into(), b.into()]).unwrap();
+            solutions.sort();
+            solutions
+        };
+        let expected = tuples[..3].to_vec();
+        let mut with_global = model.clone();
+        enforce_table_constraint(&mut with_global, &vars, &table, Lit::TRUE, true);
+        assert!(with_global
+            .shape
+            .constraints
+            .iter()
+            .any(|c| matches!(c, aries::model::Constraint::Reified(ReifExpr::Table(_), _))));
+        assert_eq!(solutions(with_global), expected);
+
+        // same solutions with the decomposition into clauses
+        let mut decomposed = model.clone();
+        enforce_table_constraint(&mut decomposed, &vars, &table, Lit::TRUE, false);
+        assert_eq!(solutions(decomposed), expected);
+    }
+}
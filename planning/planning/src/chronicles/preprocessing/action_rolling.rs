@@ -6,6 +6,7 @@ use crate::chronicles::{
     Substitute, Substitution, Time, VarType, TIME_SCALE,
 };
 use aries::core::state::Term;
+use aries::core::Rational;
 use aries::core::{IntCst, Lit, VarRef, INT_CST_MAX};
 use aries::model::extensions::partial_assignment::{PartialAssignment, PartialAssignmentBuilder};
 use aries::model::lang::linear::LinearSum;
@@ -13,7 +14,6 @@ use aries::model::lang::{Atom, Cst, FAtom, IAtom};
 use aries::model::Model;
 use aries::solver::Solver;
 use itertools::Itertools;
-use num_rational::Rational32;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
@@ -400,6 +400,14 @@ pub fn rollup_actions(pb: &mut Problem) {
     println!("Rolling, rolling, rolling... Rawhide!");
     for ch_id in 0..pb.templates.len() {
         let a = &pb.templates[ch_id];
+        if a.chronicle
+            .constraints
+            .iter()
+            .any(|c| matches!(c.tpe, ConstraintType::Duration(Duration::Contingent { .. })))
+        {
+            // the rolled-up action would have a fixed duration, losing the uncontrollability
+            continue;
+        }
 
         let Some((tr, ch)) = extract_single_transition(a.clone()) else {
             continue;
@@ -467,7 +475,7 @@ impl RollCompilation {
         // now rebuild the sequence of actions that was rolled-up
         let mut actions = Vec::with_capacity(path.len());
 
-        let epsilon = Rational32::new(1, TIME_SCALE.get());
+        let epsilon = Rational::new(1, TIME_SCALE.get());
         let mut next_start = action.start;
         for (src, tgt, dur) in path {
             let mut instance = action.clone();
@@ -475,7 +483,7 @@ impl RollCompilation {
             instance.params[tgt_param_index] = tgt;
 
             instance.start = next_start;
-            let dur = Rational32::new(dur, TIME_SCALE.get()) - epsilon;
+            let dur = Rational::new(dur, TIME_SCALE.get()) - epsilon;
             instance.duration = dur;
             next_start = next_start + dur + epsilon;
             actions.push(instance);
@@ -298,6 +298,13 @@ impl<'a> Printer<'a> {
                         self.linear_sum(ub);
                         print!("]");
                     }
+                    Duration::Contingent { lb, ub } => {
+                        print!("contingent [");
+                        self.linear_sum(lb);
+                        print!(", ");
+                        self.linear_sum(ub);
+                        print!("]");
+                    }
                 }
             }
             ConstraintType::Or => {
@@ -307,6 +314,9 @@ impl<'a> Printer<'a> {
                 self.linear_sum(sum);
                 print!("= 0");
             }
+            ConstraintType::Mul => {
+                print!("*=")
+            }
         }
         print!(" ");
         self.list(&c.variables);
@@ -503,23 +503,53 @@ fn read_chronicle_template(
 
     // handle duration element from durative actions
     if let Some(dur) = pddl.duration() {
-        // currently, we only support constraint of the form `(= ?duration <i32>)`
+        // we support constraints of the form `(= ?duration <int>)`, `(<= ?duration <int>)`, `(>= ?duration <int>)`
+        // and conjunctions thereof
         // TODO: extend durations constraints, to support the full PDDL spec
-        let mut dur = dur.as_list_iter().unwrap();
-        //Check for first two elements
-        dur.pop_known_atom("=")?;
-        dur.pop_known_atom("?duration")?;
-
-        let dur_atom = dur.pop_atom()?;
-        let duration = LinearSum::constant_int(
-            dur_atom
+        let mut lb: Option<IntCst> = None;
+        let mut ub: Option<IntCst> = None;
+        let conjuncts: Vec<&SExpr> = match dur.as_application("and") {
+            Some(conjuncts) => conjuncts.iter().collect(),
+            None => vec![dur],
+        };
+        for conjunct in conjuncts {
+            let mut c = conjunct
+                .as_list_iter()
+                .ok_or_else(|| conjunct.invalid("Expected a duration constraint"))?;
+            let op = c.pop_atom()?.clone();
+            c.pop_known_atom("?duration")?;
+            let value_atom = c.pop_atom()?;
+            let value = value_atom
                 .canonical_str()
-                .parse::<i32>()
-                .map_err(|_| dur_atom.invalid("Expected an integer"))?,
-        );
-        ch.constraints.push(Constraint::duration(Duration::Fixed(duration)));
-        if let Ok(x) = dur.pop() {
-            return Err(x.invalid("Unexpected").into());
+                .parse::<IntCst>()
+                .map_err(|_| value_atom.invalid("Expected an integer"))?;
+            if let Ok(x) = c.pop() {
+                return Err(x.invalid("Unexpected").into());
+            }
+            match op.canonical_str() {
+                "=" => {
+                    lb = Some(lb.map_or(value, |l| l.max(value)));
+                    ub = Some(ub.map_or(value, |u| u.min(value)));
+                }
+                ">=" => lb = Some(lb.map_or(value, |l| l.max(value))),
+                "<=" => ub = Some(ub.map_or(value, |u| u.min(value))),
+                _ => {
+                    return Err(op
+                        .invalid("Unsupported duration constraint, expected one of `=`, `<=`, `>=`")
+                        .into())
+                }
+            }
+        }
+        let ub = ub.ok_or_else(|| dur.invalid("Missing upper bound on the duration"))?;
+        let lb = lb.unwrap_or(0);
+        if lb == ub {
+            ch.constraints
+                .push(Constraint::duration(Duration::Fixed(LinearSum::constant_int(lb))));
+        } else {
+            ch.constraints.push(Constraint::duration(Duration::Bounded {
+                lb: LinearSum::constant_int(lb),
+                ub: LinearSum::constant_int(ub),
+            }));
         }
     }
 
@@ -1018,7 +1048,7 @@ fn read_init_state(expr: &SExpr, t: impl Fn(&sexpr::SAtom) -> Result<SAtom>, con
                     .pop_atom()?
                     .clone()
                     .canonical_str()
-                    .parse::<i32>()
+                    .parse::<IntCst>()
                     .map_err(|_| l.invalid("Expected an integer"))?;
                 if let Some(unexpected) = l.next() {
                     return Err(unexpected.invalid("Unexpected expr").into());
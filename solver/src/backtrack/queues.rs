@@ -239,6 +239,13 @@ impl<V> ObsTrail<V> {
     pub fn num_events(&self) -> u32 {
         self.len() as u32
     }
+
+    /// Returns the identifier of the last backtrack, which is incremented on each backtrack, together with the
+    /// index of the first event it undid. Returns `None` if no backtrack occurred.
+    pub fn last_backtrack(&self) -> Option<(u64, EventIndex)> {
+        self.last_backtrack.map(|bt| (bt.id, bt.next_read))
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -248,15 +255,8 @@ impl<V> ObsTrail<V> {
     }
 
     pub fn decision_level(&self, id: EventIndex) -> DecLvl {
-        let mut dl = self.current_decision_level();
-        while dl > DecLvl::ROOT {
-            if id >= self.backtrack_points[dl] {
-                return dl;
-            } else {
-                dl -= 1;
-            }
-        }
-        dl
+        // the decision level is the number of backtrack points before the event
+        DecLvl::new(self.backtrack_points.partition_point(|&first_event| first_event <= id) as u32)
     }
 
     pub fn decision_level_class(&self, id: EventIndex) -> DecisionLevelClass {
@@ -1,5 +1,5 @@
-use crate::core::IntCst;
-use num_rational::Rational32;
+use crate::core::Rational;
+use crate::core::{IntCst, LongCst};
 use std::fmt::{Display, Formatter};
 
 #[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Debug)]
@@ -13,8 +13,8 @@ impl IntDomain {
     }
 
     /// Returns the number of elements in the domain.
-    pub fn size(&self) -> i64 {
-        (self.ub as i64) - (self.lb as i64) + 1
+    pub fn size(&self) -> LongCst {
+        (self.ub as LongCst) - (self.lb as LongCst) + 1
     }
 
     /// Returns true if the domain contains exactly one value.
@@ -92,11 +92,11 @@ impl FixedDomain {
         self.num.is_empty()
     }
 
-    pub fn lb(&self) -> Rational32 {
-        Rational32::new(self.num.lb, self.denom)
+    pub fn lb(&self) -> Rational {
+        Rational::new(self.num.lb, self.denom)
     }
-    pub fn ub(&self) -> Rational32 {
-        Rational32::new(self.num.lb, self.denom)
+    pub fn ub(&self) -> Rational {
+        Rational::new(self.num.lb, self.denom)
     }
 
     pub fn lb_f32(&self) -> f32 {
@@ -6,7 +6,7 @@ use crate::core::state::event::Event;
 use crate::core::state::int_domains::IntDomains;
 use crate::core::state::{Cause, Explainer, Explanation, InvalidUpdate, OptDomain};
 use crate::core::*;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
 use std::fmt::{Debug, Formatter};
 
 /// Structure that contains the domains of optional variable.
@@ -552,6 +552,105 @@ impl Domains {
         }
     }
 
+    /// Removes redundant literals from a conflict clause produced by [`Domains::refine_explanation`]
+    /// (recursive clause minimization). Returns the number of literals that were removed.
+    ///
+    /// A literal `l` of the clause is redundant if `!l` is implied by the negation of the other literals of the clause.
+    /// This is the case if all literals in the explanation of `!l` are either:
+    ///  - entailed at the root decision level,
+    ///  - entailed by the negation of a clause literal, made true by the same event,
+    ///  - themselves redundant (recursively).
+    ///
+    /// Literals of the current decision level (i.e. the asserted literal) are always kept.
+    /// Explanations are requested in the current state, as done by [`Domains::implying_literals`], and are thus only
+    /// exploited for inferences whose explainer [supports late explanations](Explainer::supports_late_explanations).
+    /// To ensure that the minimized clause remains a valid consequence even when such an explanation involves literals
+    /// that were inferred later, a literal is only considered redundant if all literals of its explanation were
+    /// made true by strictly earlier events.
+    pub fn minimize_clause(&self, conflict: &mut Conflict, explainer: &mut impl Explainer) -> usize {
+        let current_level = self.current_decision_level();
+        // negation of the clause literals, indexed by the event that made them true
+        let conjuncts: HashMap<EventIndex, Lit> = conflict
+            .clause
+            .literals()
+            .iter()
+            .filter(|&&l| self.entails(!l))
+            .filter_map(|&l| Some((self.implying_event(!l)?, !l)))
+            .collect();
+        let mut cache = HashMap::new();
+        let mut kept = Vec::with_capacity(conflict.clause.len());
+        for &l in conflict.clause.literals() {
+            let redundant = self.entails(!l)
+                && match self.implying_event(!l) {
+                    Some(ev) => {
+                        self.trail().decision_level(ev) != current_level
+                            && self.is_redundant(!l, ev, &conjuncts, &mut cache, explainer, 0)
+                    }
+                    None => true, // false at the root
+                };
+            if !redundant {
+                kept.push(l);
+            }
+        }
+        let num_removed = conflict.clause.len() - kept.len();
+        if num_removed > 0 {
+            conflict.clause = Disjunction::new(kept);
+        }
+        num_removed
+    }
+
+    /// Returns true if the entailed literal `lit`, made true by the event `event`, is implied by the root and
+    /// the `conjuncts` of the clause being minimized, through a chain of explanations.
+    fn is_redundant(
+        &self,
+        lit: Lit,
+        event: EventIndex,
+        conjuncts: &HashMap<EventIndex, Lit>,
+        cache: &mut HashMap<Lit, bool>,
+        explainer: &mut impl Explainer,
+        depth: u32,
+    ) -> bool {
+        const MAX_DEPTH: u32 = 64;
+        if let Some(&redundant) = cache.get(&lit) {
+            return redundant;
+        }
+        if depth > MAX_DEPTH {
+            return false;
+        }
+        let explanation = match self.get_event(event).cause {
+            // the literal results from a decision, possibly through the emptying of the domain of an optional variable
+            Origin::Direct(DirectOrigin::Decision | DirectOrigin::Encoding)
+            | Origin::PresenceOfEmptyDomain(_, DirectOrigin::Decision | DirectOrigin::Encoding) => None,
+            // the inference cannot be explained in the current state
+            Origin::Direct(DirectOrigin::ExternalInference(cause))
+            | Origin::PresenceOfEmptyDomain(_, DirectOrigin::ExternalInference(cause))
+                if !explainer.supports_late_explanations(cause) =>
+            {
+                None
+            }
+            _ => self.implying_literals(lit, explainer),
+        };
+        let redundant = match explanation {
+            None => false,
+            Some(explanation) => explanation.into_iter().all(|premise| {
+                if !self.entails(premise) {
+                    return false;
+                }
+                match self.implying_event(premise) {
+                    None => true,
+                    Some(ev) if self.trail().decision_level(ev) == DecLvl::ROOT => true,
+                    Some(ev) if ev >= event => false,
+                    Some(ev) => match conjuncts.get(&ev) {
+                        Some(conjunct) if conjunct.entails(premise) => true,
+                        _ => self.is_redundant(premise, ev, conjuncts, cache, explainer, depth + 1),
+                    },
+                }
+            }),
+        };
+        cache.insert(lit, redundant);
+        redundant
+    }
+
     /// Returns all decisions that were taken since the root decision level.
     pub fn decisions(&self) -> Vec<(DecLvl, Lit)> {
         let mut decs = Vec::new();
@@ -566,6 +665,79 @@ impl Domains {
         decs
     }
 
+    /// Given a literal `lit` that is entailed in the current state, returns a set of decisions `d_1 ... d_n`
+    /// such that `(d_1 & ... & d_n) => lit`.
+    ///
+    /// This is typically used to extract an unsat core when solving under assumptions: if all decisions
+    /// on the trail are assumptions, the result is the subset of assumptions responsible for `lit`.
+    ///
+    /// Note that a backtrack (potentially over several decision levels) will occur in the process.
+    /// This is necessary to provide explainers with the exact state in which their inferences were made.
+    /// The caller is responsible for backtracking all other components to a level at or below the one
+    /// of the domains after this call (typically the root).
+    pub fn decisions_implying(&mut self, lit: Lit, explainer: &mut impl Explainer) -> Vec<Lit> {
+        debug_assert!(self.entails(lit));
+        // fallback: the set of all decisions is always a valid (but not minimal) result
+        let all_decisions: Vec<Lit> = self.decisions().into_iter().map(|(_, dec)| dec).collect();
+        let mut result = Vec::with_capacity(all_decisions.len());
+
+        self.queue.clear();
+        let mut explanation = Explanation::with_capacity(8);
+        explanation.push(lit);
+        loop {
+            for l in explanation.lits.drain(..) {
+                if !self.entails(l) {
+                    // literal from an eager propagation that cannot be traced back to decisions,
+                    // fallback to the conservative result
+                    return all_decisions;
+                }
+                if let Some(loc) = self.implying_event(l) {
+                    if self.trail().decision_level(loc) != DecLvl::ROOT {
+                        self.queue.push(InQueueLit { cause: loc, lit: l })
+                    }
+                }
+            }
+            let Some(mut l) = self.queue.pop() else {
+                return result;
+            };
+            // several literals may be explained by the same event, keep the most general one
+            while let Some(next) = self.queue.peek() {
+                if next.cause == l.cause {
+                    let l2 = self.queue.pop().unwrap();
+                    if l2.lit.entails(l.lit) {
+                        l = l2;
+                    }
+                } else {
+                    break;
+                }
+            }
+
+            let event_lit = self.get_event(l.cause).new_literal();
+            // undo all events up to (and including) the one that made `l` true, possibly crossing decision levels
+            let cause = loop {
+                let last = EventIndex::new(self.trail().len() - 1);
+                if self.trail().decision_level(last) < self.current_decision_level() {
+                    // the current decision level has no event left, leave it
+                    self.restore_last();
+                } else {
+                    let cause = self.undo_last_event();
+                    if last == l.cause {
+                        break cause;
+                    }
+                }
+            };
+            match cause {
+                Origin::DECISION => result.push(event_lit),
+                Origin::PresenceOfEmptyDomain(decided, DirectOrigin::Decision) => {
+                    // the decision would have emptied the domain of an optional variable, making it absent
+                    result.push(decided);
+                    explanation.push(!decided);
+                }
+                cause => self.add_implying_literals_to_explanation(l.lit, cause, &mut explanation, explainer),
+            }
+        }
+    }
+
     /// Computes literals `l_1 ... l_n` such that:
     ///  - `l_1 & ... & l_n => literal`
     ///  - each `l_i` is entailed at the current level.
@@ -956,6 +1128,71 @@ mod tests {
         assert_eq!(clause, expected);
     }
 
+    #[test]
+    fn test_clause_minimization() {
+        let mut model = Domains::new();
+        let a = Lit::geq(model.new_var(0, 1), 1);
+        let b = Lit::geq(model.new_var(0, 1), 1);
+        let n = model.new_var(0, 10);
+        let m = model.new_var(0, 10);
+
+        // constraint 0: "a => (n <= 4)"
+        // constraint 1: "(n <= 6) => (m <= 3)"
+        let cause_n = Cause::inference(ReasonerId::Sat, 0u32);
+        let cause_m = Cause::inference(ReasonerId::Sat, 1u32);
+
+        struct Expl {
+            a: Lit,
+            n: VarRef,
+            late: bool,
+        }
+        impl Explainer for Expl {
+            fn explain(&mut self, cause: InferenceCause, _: Lit, _: &Domains, explanation: &mut Explanation) {
+                match cause.payload {
+                    0 => explanation.push(self.a),
+                    1 => explanation.push(Lit::leq(self.n, 6)),
+                    _ => panic!("unexpected payload"),
+                }
+            }
+            fn supports_late_explanations(&self, _: InferenceCause) -> bool {
+                self.late
+            }
+        }
+
+        model.save_state();
+        model.decide(a).unwrap();
+        model.set_ub(n, 4, cause_n).unwrap();
+        model.set_ub(m, 3, cause_m).unwrap();
+        model.save_state();
+        model.decide(b).unwrap();
+
+        // conflict: b & (m <= 3) & (n <= 4) => false
+        let conflict = |model: &mut Domains, network: &mut Expl| {
+            let mut explanation = Explanation::new();
+            explanation.lits.extend([b, Lit::leq(m, 3), Lit::leq(n, 4)]);
+            model.refine_explanation(explanation, network)
+        };
+        let literals = |conflict: &Conflict| conflict.literals().iter().copied().collect::<HashSet<_>>();
+
+        let mut network = Expl { a, n, late: false };
+        let mut clause = conflict(&mut model, &mut network);
+        assert_eq!(model.minimize_clause(&mut clause, &mut network), 0);
+        assert_eq!(literals(&clause), [!b, Lit::gt(m, 3), Lit::gt(n, 4)].into());
+
+        // (m <= 3) is implied by the stronger (n <= 4)
+        network.late = true;
+        let mut clause = conflict(&mut model, &mut network);
+        assert_eq!(model.minimize_clause(&mut clause, &mut network), 1);
+        assert_eq!(literals(&clause), [!b, Lit::gt(n, 4)].into());
+
+        // with `a` in the clause, (n <= 4) is redundant as well
+        let mut explanation = Explanation::new();
+        explanation.lits.extend([b, Lit::leq(m, 3), Lit::leq(n, 4), a]);
+        let mut clause = model.refine_explanation(explanation, &mut network);
+        assert_eq!(model.minimize_clause(&mut clause, &mut network), 2);
+        assert_eq!(literals(&clause), [!b, !a].into());
+    }
+
     #[test]
     fn test_optional_propagation_error() {
         let mut model = Domains::new();
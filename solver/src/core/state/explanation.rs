@@ -41,6 +41,14 @@ impl Default for Explanation {
 
 pub trait Explainer {
     fn explain(&mut self, cause: InferenceCause, literal: Lit, model: &Domains, explanation: &mut Explanation);
+
+    /// Returns true if the inferences with the given cause can be explained in any later state where the inferred
+    /// literal still holds, and not only in the exact state where the inference was made.
+    ///
+    /// This is required for exploiting explanations outside of conflict analysis (e.g. for clause minimization).
+    fn supports_late_explanations(&self, _cause: InferenceCause) -> bool {
+        false
+    }
 }
 
 /// A provides an explainer for a standalone theory. useful for testing purposes.
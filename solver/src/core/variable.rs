@@ -3,7 +3,29 @@ use crate::create_ref_type;
 use std::{fmt::Debug, hash::Hash};
 
 /// Type representing an integer constant.
+///
+/// Its width is 32 bits by default and 64 bits when the `i64` feature is enabled,
+/// which is needed for problems with large horizons (e.g. long schedules with a fine time resolution).
+#[cfg(not(feature = "i64"))]
 pub type IntCst = i32;
+/// Type representing an integer constant.
+///
+/// Its width is 32 bits by default and 64 bits when the `i64` feature is enabled,
+/// which is needed for problems with large horizons (e.g. long schedules with a fine time resolution).
+#[cfg(feature = "i64")]
+pub type IntCst = i64;
+
+/// Integer type twice as wide as [IntCst], used for intermediate computations
+/// (e.g. the product of a factor and a bound) that may overflow an [IntCst].
+#[cfg(not(feature = "i64"))]
+pub type LongCst = i64;
+/// Integer type twice as wide as [IntCst], used for intermediate computations
+/// (e.g. the product of a factor and a bound) that may overflow an [IntCst].
+#[cfg(feature = "i64")]
+pub type LongCst = i128;
+
+/// Rational number whose numerator and denominator are [IntCst].
+pub type Rational = num_rational::Ratio<IntCst>;
 
 /// Overflow tolerant max value for integer constants.
 /// It is used as a default for the upper bound of integer variable domains
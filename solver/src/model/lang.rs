@@ -2,10 +2,14 @@ mod atom;
 mod boolean;
 mod cst;
 pub mod expr;
+pub mod extensional;
 mod fixed;
 mod int;
 pub mod linear;
+pub mod nonlinear;
+pub mod pseudo_boolean;
 pub mod reification;
+pub mod scheduling;
 mod sym;
 mod validity_scope;
 mod variables;
@@ -81,6 +85,8 @@ pub enum ConversionError {
     /// there is a variable but its value is modified. For instance, this would
     /// occur when trying to convert the atoms representing `!v` or `v + 1` for some variable `v`.
     NotPure,
+    /// The result of the conversion is not representable as an `IntCst`.
+    Overflow,
 }
 
 impl std::fmt::Display for ConversionError {
@@ -92,6 +98,7 @@ impl std::fmt::Display for ConversionError {
             ConversionError::NotPure => write!(f, "not a pure"),
             ConversionError::NotExpression => write!(f, "not an expression"),
             ConversionError::NotLiteral => write!(f, "not a bound"),
+            ConversionError::Overflow => write!(f, "integer overflow"),
         }
     }
 }
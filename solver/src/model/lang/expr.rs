@@ -1,6 +1,15 @@
+//! Builders of the expressions of the modeling language.
+//!
+//! The global constraints (all-different, cumulative, no-overlap, contingent links, table, element, non-linear
+//! arithmetic and pseudo-Boolean constraints) can only be enforced and not reified (see [`ReifExpr::is_global`]).
+
 use crate::core::literals::Disjunction;
 use crate::core::*;
-use crate::model::lang::{Atom, FAtom, IAtom, SAtom};
+use crate::model::lang::extensional::{Element, Table};
+use crate::model::lang::nonlinear::{Abs, Div, MinMax, Mod, Mul};
+use crate::model::lang::pseudo_boolean::PseudoBoolean;
+use crate::model::lang::scheduling::{ContingentLink, Cumulative, NoOverlap, Task};
+use crate::model::lang::{Atom, ConversionError, FAtom, IAtom, IVar, SAtom};
 use crate::model::{Label, Model};
 use crate::reif::{DifferenceExpression, ReifExpr, Reifiable};
 use env_param::EnvParam;
@@ -67,6 +76,140 @@ pub fn implies(a: impl Into<Lit>, b: impl Into<Lit>) -> Or {
     or([!a.into(), b.into()])
 }
 
+/// Constraint that all present variables take distinct values. Absent variables are ignored.
+pub fn all_different(vars: impl IntoIterator<Item = impl Into<IVar>>) -> AllDifferent {
+    AllDifferent(vars.into_iter().map(|v| v.into()).collect())
+}
+
+/// Cumulative resource constraint: at any time, the total demand of the present tasks executing
+/// at this time must not exceed the capacity.
+pub fn cumulative(tasks: impl IntoIterator<Item = Task>, capacity: IntCst) -> Cumulative {
+    Cumulative {
+        tasks: tasks.into_iter().collect(),
+        capacity,
+    }
+}
+
+/// Unary resource constraint: no two present tasks can overlap in time.
+pub fn no_overlap(tasks: impl IntoIterator<Item = Task>) -> NoOverlap {
+    NoOverlap {
+        tasks: tasks.into_iter().collect(),
+    }
+}
+
+/// Contingent link: the duration `contingent - activation` is in `[lb, ub]` but chosen by the environment,
+/// and the temporal network must be dynamically controllable (see [ContingentLink]).
+pub fn contingent_link(
+    activation: impl Into<IAtom>,
+    contingent: impl Into<IAtom>,
+    lb: IntCst,
+    ub: IntCst,
+) -> ContingentLink {
+    let activation = activation.into();
+    let contingent = contingent.into();
+    // (c.var + c.shift) - (a.var + a.shift) in [lb, ub]
+    let shift = contingent.shift - activation.shift;
+    ContingentLink {
+        activation: activation.var.into(),
+        contingent: contingent.var.into(),
+        lb: lb - shift,
+        ub: ub - shift,
+    }
+}
+
+/// Table constraint: the present variables must take the values of one of the `tuples`.
+/// Each tuple gives a value for each variable, in the same order.
+pub fn table(vars: impl IntoIterator<Item = impl Into<IVar>>, tuples: Vec<Vec<IntCst>>) -> Table {
+    Table::new(vars.into_iter().map(|v| VarRef::from(v.into())).collect(), tuples)
+}
+
+/// Element constraint: `value = array[index]`, where the first element of the array has index 0.
+/// The constraint has no effect if either the index or the value is absent.
+pub fn element(array: impl Into<Vec<IntCst>>, index: impl Into<IVar>, value: impl Into<IVar>) -> Element {
+    Element {
+        array: array.into(),
+        index: index.into().into(),
+        value: value.into().into(),
+    }
+}
+
+/// Constraint `product = lhs * rhs`.
+pub fn eq_mul(product: impl Into<IVar>, lhs: impl Into<IVar>, rhs: impl Into<IVar>) -> Mul {
+    Mul {
+        product: product.into().into(),
+        lhs: lhs.into().into(),
+        rhs: rhs.into().into(),
+    }
+}
+
+/// Constraint `quotient = numerator / denominator`, where the division is truncated towards zero.
+pub fn eq_div(quotient: impl Into<IVar>, numerator: impl Into<IVar>, denominator: IntCst) -> Div {
+    assert_ne!(denominator, 0, "Division by zero");
+    Div {
+        quotient: quotient.into().into(),
+        numerator: numerator.into().into(),
+        denominator,
+    }
+}
+
+/// Constraint `remainder = numerator mod denominator`, where the remainder has the sign of the numerator.
+pub fn eq_mod(remainder: impl Into<IVar>, numerator: impl Into<IVar>, denominator: IntCst) -> Mod {
+    assert_ne!(denominator, 0, "Division by zero");
+    Mod {
+        remainder: remainder.into().into(),
+        numerator: numerator.into().into(),
+        denominator,
+    }
+}
+
+/// Constraint `value = min(vars)`.
+pub fn eq_min(value: impl Into<IVar>, vars: impl IntoIterator<Item = impl Into<IVar>>) -> MinMax {
+    min_max(value, vars, true)
+}
+
+/// Constraint `value = max(vars)`.
+pub fn eq_max(value: impl Into<IVar>, vars: impl IntoIterator<Item = impl Into<IVar>>) -> MinMax {
+    min_max(value, vars, false)
+}
+
+fn min_max(value: impl Into<IVar>, vars: impl IntoIterator<Item = impl Into<IVar>>, minimum: bool) -> MinMax {
+    let vars: Vec<VarRef> = vars.into_iter().map(|v| VarRef::from(v.into())).collect();
+    assert!(!vars.is_empty(), "Minimum or maximum of an empty set of variables");
+    MinMax {
+        value: value.into().into(),
+        vars,
+        minimum,
+    }
+}
+
+/// Constraint `value = |var|`.
+pub fn eq_abs(value: impl Into<IVar>, var: impl Into<IVar>) -> Abs {
+    Abs {
+        value: value.into().into(),
+        var: var.into().into(),
+    }
+}
+
+/// Pseudo-Boolean constraint `sum_i w_i * l_i <= upper_bound`, where a literal counts for `1` if true and `0` otherwise.
+///
+/// Returns an overflow error if the normalized constraint cannot be represented (see [`PseudoBoolean::leq`]).
+pub fn pb_leq(
+    terms: impl IntoIterator<Item = (IntCst, Lit)>,
+    upper_bound: IntCst,
+) -> Result<PseudoBoolean, ConversionError> {
+    PseudoBoolean::leq(terms, upper_bound)
+}
+
+/// Pseudo-Boolean constraint `sum_i w_i * l_i >= lower_bound`, where a literal counts for `1` if true and `0` otherwise.
+///
+/// Returns an overflow error if the normalized constraint cannot be represented (see [`PseudoBoolean::geq`]).
+pub fn pb_geq(
+    terms: impl IntoIterator<Item = (IntCst, Lit)>,
+    lower_bound: IntCst,
+) -> Result<PseudoBoolean, ConversionError> {
+    PseudoBoolean::geq(terms, lower_bound)
+}
+
 pub struct Or(Box<[Lit]>);
 
 impl From<Or> for ReifExpr {
@@ -86,6 +229,80 @@ impl From<And> for ReifExpr {
     }
 }
 
+pub struct AllDifferent(Box<[IVar]>);
+
+impl From<Cumulative> for ReifExpr {
+    fn from(value: Cumulative) -> Self {
+        ReifExpr::Cumulative(value)
+    }
+}
+
+impl From<NoOverlap> for ReifExpr {
+    fn from(value: NoOverlap) -> Self {
+        ReifExpr::NoOverlap(value)
+    }
+}
+
+impl From<ContingentLink> for ReifExpr {
+    fn from(value: ContingentLink) -> Self {
+        ReifExpr::ContingentLink(value)
+    }
+}
+
+impl From<Table> for ReifExpr {
+    fn from(value: Table) -> Self {
+        ReifExpr::Table(value)
+    }
+}
+
+impl From<Element> for ReifExpr {
+    fn from(value: Element) -> Self {
+        ReifExpr::Element(value)
+    }
+}
+
+impl From<Mul> for ReifExpr {
+    fn from(value: Mul) -> Self {
+        ReifExpr::Mul(value)
+    }
+}
+
+impl From<Div> for ReifExpr {
+    fn from(value: Div) -> Self {
+        ReifExpr::Div(value)
+    }
+}
+
+impl From<Mod> for ReifExpr {
+    fn from(value: Mod) -> Self {
+        ReifExpr::Mod(value)
+    }
+}
+
+impl From<MinMax> for ReifExpr {
+    fn from(value: MinMax) -> Self {
+        ReifExpr::MinMax(value)
+    }
+}
+
+impl From<Abs> for ReifExpr {
+    fn from(value: Abs) -> Self {
+        ReifExpr::Abs(value)
+    }
+}
+
+impl From<PseudoBoolean> for ReifExpr {
+    fn from(value: PseudoBoolean) -> Self {
+        ReifExpr::PseudoBoolean(value)
+    }
+}
+
+impl From<AllDifferent> for ReifExpr {
+    fn from(value: AllDifferent) -> Self {
+        ReifExpr::AllDifferent(value.0.iter().map(|&v| VarRef::from(v)).collect())
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct Leq(IAtom, IAtom);
 
@@ -0,0 +1,56 @@
+use crate::core::state::Domains;
+use crate::core::{IntCst, VarRef};
+use std::sync::Arc;
+
+/// Constraint that the values of the variables match one of the tuples of a table.
+/// Absent variables are ignored: a present variable is only required to have a value appearing in a tuple
+/// that is compatible with the values of the other present variables.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Table {
+    pub vars: Vec<VarRef>,
+    /// Allowed tuples, each of them giving a value for each variable of the constraint.
+    pub tuples: Arc<Vec<Vec<IntCst>>>,
+}
+
+impl Table {
+    pub fn new(vars: Vec<VarRef>, tuples: Vec<Vec<IntCst>>) -> Self {
+        assert!(
+            tuples.iter().all(|tuple| tuple.len() == vars.len()),
+            "Tuple with the wrong number of values"
+        );
+        Table {
+            vars,
+            tuples: Arc::new(tuples),
+        }
+    }
+
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        self.tuples.iter().any(|tuple| {
+            self.vars
+                .iter()
+                .zip(tuple)
+                .all(|(&var, &value)| !assignment.present(var).unwrap() || assignment.lb(var) == value)
+        })
+    }
+}
+
+/// Constraint `value = array[index]`, where `array` is an array of constants, whose first element has index 0.
+/// The constraint has no effect if either `index` or `value` is absent.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Element {
+    pub array: Vec<IntCst>,
+    pub index: VarRef,
+    pub value: VarRef,
+}
+
+impl Element {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        if !assignment.present(self.index).unwrap() || !assignment.present(self.value).unwrap() {
+            return true;
+        }
+        let index = assignment.lb(self.index);
+        0 <= index && (index as usize) < self.array.len() && self.array[index as usize] == assignment.lb(self.value)
+    }
+}
@@ -1,6 +1,6 @@
+use crate::core::Rational;
 use crate::core::{IntCst, VarRef};
 use crate::model::lang::{ConversionError, IAtom, IVar};
-use num_rational::Rational32;
 use std::cmp::Ordering;
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Debug;
@@ -92,18 +92,18 @@ impl PartialOrd for FAtom {
     }
 }
 
-impl From<Rational32> for FAtom {
-    fn from(value: Rational32) -> Self {
+impl From<Rational> for FAtom {
+    fn from(value: Rational) -> Self {
         let num = IAtom::from(*value.numer());
         FAtom::new(num, *value.denom())
     }
 }
-impl TryFrom<FAtom> for Rational32 {
+impl TryFrom<FAtom> for Rational {
     type Error = ConversionError;
 
     fn try_from(value: FAtom) -> Result<Self, Self::Error> {
         let num = IntCst::try_from(value.num)?;
-        Ok(Rational32::new(num, value.denom))
+        Ok(Rational::new(num, value.denom))
     }
 }
 
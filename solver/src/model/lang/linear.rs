@@ -1201,7 +1201,7 @@ mod tests {
         let lit0 = Lit::TRUE;
         let lit1 = var1.leq(5);
 
-        let item = |factor: i32, var: VarRef, lit: Lit| NFLinearSumItem { var, factor, lit };
+        let item = |factor: IntCst, var: VarRef, lit: Lit| NFLinearSumItem { var, factor, lit };
 
         let obj = NFLinearLeq {
             sum: vec![
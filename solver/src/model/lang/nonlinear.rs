@@ -0,0 +1,112 @@
+use crate::core::state::Domains;
+use crate::core::{IntCst, LongCst, VarRef};
+
+/// Returns the value of all variables in the (fully instantiated) assignment,
+/// or `None` if one of them is absent.
+fn values<const N: usize>(vars: [VarRef; N], assignment: &Domains) -> Option<[IntCst; N]> {
+    if vars.iter().all(|&v| assignment.present(v).unwrap()) {
+        Some(vars.map(|v| assignment.lb(v)))
+    } else {
+        None
+    }
+}
+
+/// Constraint `product = lhs * rhs`.
+/// The constraint has no effect if one of the variables is absent.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Mul {
+    pub product: VarRef,
+    pub lhs: VarRef,
+    pub rhs: VarRef,
+}
+
+impl Mul {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        match values([self.product, self.lhs, self.rhs], assignment) {
+            Some([product, lhs, rhs]) => product as LongCst == lhs as LongCst * rhs as LongCst,
+            None => true,
+        }
+    }
+}
+
+/// Constraint `quotient = numerator / denominator`, where the division is truncated towards zero
+/// and the denominator is a non-zero constant.
+/// The constraint has no effect if one of the variables is absent.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Div {
+    pub quotient: VarRef,
+    pub numerator: VarRef,
+    pub denominator: IntCst,
+}
+
+impl Div {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        match values([self.quotient, self.numerator], assignment) {
+            Some([quotient, numerator]) => quotient == numerator / self.denominator,
+            None => true,
+        }
+    }
+}
+
+/// Constraint `remainder = numerator mod denominator`, where the denominator is a non-zero constant.
+/// The remainder has the sign of the numerator, consistently with a division truncated towards zero.
+/// The constraint has no effect if one of the variables is absent.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Mod {
+    pub remainder: VarRef,
+    pub numerator: VarRef,
+    pub denominator: IntCst,
+}
+
+impl Mod {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        match values([self.remainder, self.numerator], assignment) {
+            Some([remainder, numerator]) => remainder == numerator % self.denominator,
+            None => true,
+        }
+    }
+}
+
+/// Constraint `value = max(vars)`, or `value = min(vars)` if `minimum` is true.
+/// The constraint has no effect if one of the variables is absent.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MinMax {
+    pub value: VarRef,
+    pub vars: Vec<VarRef>,
+    /// If true, `value` is the minimum of the variables. Otherwise, it is their maximum.
+    pub minimum: bool,
+}
+
+impl MinMax {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        let all_vars = self.vars.iter().chain([&self.value]);
+        if !all_vars.clone().all(|&v| assignment.present(v).unwrap()) {
+            return true;
+        }
+        let values = self.vars.iter().map(|&v| assignment.lb(v));
+        let expected = if self.minimum { values.min() } else { values.max() };
+        expected == Some(assignment.lb(self.value))
+    }
+}
+
+/// Constraint `value = |var|`.
+/// The constraint has no effect if one of the variables is absent.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Abs {
+    pub value: VarRef,
+    pub var: VarRef,
+}
+
+impl Abs {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        match values([self.value, self.var], assignment) {
+            Some([value, var]) => value == var.abs(),
+            None => true,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::core::state::Domains;
+use crate::core::{IntCst, Lit, LongCst};
+use crate::model::lang::ConversionError;
+
+/// Pseudo-Boolean constraint `sum_i w_i * l_i <= upper_bound`, where each `l_i` is a literal counting
+/// for `1` if true and `0` otherwise.
+///
+/// The constraint is kept in a normal form where all weights are strictly positive:
+/// a term `w * l` with a negative weight is rewritten into `w + (-w) * !l`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PseudoBoolean {
+    pub terms: Vec<(IntCst, Lit)>,
+    pub upper_bound: IntCst,
+}
+
+impl PseudoBoolean {
+    /// Builds the normalized form of the constraint `sum_i w_i * l_i <= upper_bound`.
+    ///
+    /// Returns an overflow error if the normalized upper bound is not representable as an `IntCst`.
+    pub fn leq(terms: impl IntoIterator<Item = (IntCst, Lit)>, upper_bound: IntCst) -> Result<Self, ConversionError> {
+        Self::normalize(
+            terms.into_iter().map(|(w, l)| (w as LongCst, l)),
+            upper_bound as LongCst,
+        )
+    }
+
+    /// Builds the normalized form of the constraint `sum_i w_i * l_i >= lower_bound`.
+    ///
+    /// Returns an overflow error if the normalized upper bound is not representable as an `IntCst`.
+    pub fn geq(terms: impl IntoIterator<Item = (IntCst, Lit)>, lower_bound: IntCst) -> Result<Self, ConversionError> {
+        Self::normalize(
+            terms.into_iter().map(|(w, l)| (-(w as LongCst), l)),
+            -(lower_bound as LongCst),
+        )
+    }
+
+    /// Normalizes `sum_i w_i * l_i <= upper_bound`, the bound being accumulated with a larger integer type
+    /// so that the conversion back to an `IntCst` can be checked.
+    fn normalize(
+        terms: impl IntoIterator<Item = (LongCst, Lit)>,
+        upper_bound: LongCst,
+    ) -> Result<Self, ConversionError> {
+        let to_int = |w: LongCst| IntCst::try_from(w).map_err(|_| ConversionError::Overflow);
+        let mut normalized = Vec::new();
+        let mut upper_bound = upper_bound;
+        for (weight, lit) in terms {
+            if weight > 0 {
+                normalized.push((to_int(weight)?, lit));
+            } else if weight < 0 {
+                normalized.push((to_int(-weight)?, !lit));
+                upper_bound -= weight;
+            }
+        }
+        Ok(PseudoBoolean {
+            terms: normalized,
+            upper_bound: to_int(upper_bound)?,
+        })
+    }
+
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        let sum: LongCst = self
+            .terms
+            .iter()
+            .filter(|(_, l)| assignment.entails(*l))
+            .map(|&(w, _)| w as LongCst)
+            .sum();
+        sum <= self.upper_bound as LongCst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::VarRef;
+
+    #[test]
+    fn test_normalization() {
+        let a = VarRef::from(1u32).geq(1);
+        let b = VarRef::from(2u32).geq(1);
+
+        // 2a - 3b <= 1   <=>   2a + 3!b <= 4
+        let pb = PseudoBoolean::leq([(2, a), (-3, b), (0, a)], 1).unwrap();
+        assert_eq!(pb.terms, vec![(2, a), (3, !b)]);
+        assert_eq!(pb.upper_bound, 4);
+
+        // 2a + 3b >= 2   <=>   2!a + 3!b <= 3
+        let pb = PseudoBoolean::geq([(2, a), (3, b)], 2).unwrap();
+        assert_eq!(pb.terms, vec![(2, !a), (3, !b)]);
+        assert_eq!(pb.upper_bound, 3);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let a = VarRef::from(1u32).geq(1);
+        let b = VarRef::from(2u32).geq(1);
+
+        // the normalized bound `IntCst::MAX + 2` is not representable
+        assert!(PseudoBoolean::leq([(-2, a), (1, b)], IntCst::MAX).is_err());
+        // the weight of `-IntCst::MIN * !a` is not representable
+        assert!(PseudoBoolean::leq([(IntCst::MIN, a)], IntCst::MIN).is_err());
+        assert!(PseudoBoolean::geq([(1, a)], IntCst::MIN).is_err());
+
+        let pb = PseudoBoolean::geq([(-1, a), (IntCst::MAX, b)], 0).unwrap();
+        assert_eq!(pb.terms, vec![(1, a), (IntCst::MAX, !b)]);
+        assert_eq!(pb.upper_bound, IntCst::MAX);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::core::state::Domains;
+use crate::core::{IntCst, Lit, VarRef};
+use crate::model::lang::IAtom;
+use std::fmt::{Debug, Formatter};
+
+/// A task to be executed on a resource, starting at `start` and executing over `[start, start + duration)`.
+/// The task is only considered when its `presence` literal is true.
+///
+/// The duration of a task is fixed: tasks of variable duration are not supported (see [Task::spanning]).
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Task {
+    pub start: IAtom,
+    pub duration: IntCst,
+    /// Amount of the resource used by the task during its execution.
+    pub demand: IntCst,
+    /// Literal that is true iff the task is present. It should imply the presence of the `start` variable.
+    pub presence: Lit,
+}
+
+impl Task {
+    /// A task that is always present.
+    pub fn new(start: impl Into<IAtom>, duration: IntCst, demand: IntCst) -> Self {
+        Self::optional(start, duration, demand, Lit::TRUE)
+    }
+
+    /// A task that is only present when `presence` is true.
+    pub fn optional(start: impl Into<IAtom>, duration: IntCst, demand: IntCst, presence: Lit) -> Self {
+        assert!(duration >= 0, "Negative task duration");
+        assert!(demand >= 0, "Negative task demand");
+        Task {
+            start: start.into(),
+            duration,
+            demand,
+            presence,
+        }
+    }
+
+    /// A task executing over `[start, end)` that is only present when `presence` is true.
+    ///
+    /// Returns `None` if the duration `end - start` is not a constant, i.e., if `start` and `end` are not
+    /// on the same variable, as tasks of variable duration are not supported.
+    pub fn spanning(start: impl Into<IAtom>, end: impl Into<IAtom>, demand: IntCst, presence: Lit) -> Option<Self> {
+        let start = start.into();
+        let end = end.into();
+        if start.var != end.var || end.shift < start.shift {
+            return None;
+        }
+        Some(Self::optional(start, end.shift - start.shift, demand, presence))
+    }
+
+    /// Variable whose value determines the start of the task.
+    pub fn start_var(&self) -> VarRef {
+        self.start.var.into()
+    }
+
+    /// Start time of the task in a (fully instantiated) assignment.
+    pub(crate) fn start_value(&self, assignment: &Domains) -> IntCst {
+        assignment.lb(self.start_var()) + self.start.shift
+    }
+}
+
+impl Debug for Task {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{:?}]{:?}+{} (x{})",
+            self.presence, self.start, self.duration, self.demand
+        )
+    }
+}
+
+/// Constraint that, at any point in time, the sum of the demands of all present tasks executing at
+/// this time does not exceed the capacity.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Cumulative {
+    pub tasks: Vec<Task>,
+    pub capacity: IntCst,
+}
+
+impl Cumulative {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        // (time, demand change) for all present tasks
+        let mut events: Vec<(IntCst, IntCst)> = Vec::with_capacity(self.tasks.len() * 2);
+        for task in &self.tasks {
+            if assignment.entails(task.presence) {
+                let start = task.start_value(assignment);
+                events.push((start, task.demand));
+                events.push((start + task.duration, -task.demand));
+            }
+        }
+        // process demand decreases before increases at the same time
+        events.sort();
+        let mut usage = 0;
+        for (_, change) in events {
+            usage += change;
+            if usage > self.capacity {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Constraint that no two present tasks overlap in time, i.e., that the tasks are executed on a unary resource.
+/// The demands of the tasks are ignored.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct NoOverlap {
+    pub tasks: Vec<Task>,
+}
+
+impl NoOverlap {
+    /// Returns true if the constraint holds in the (fully instantiated) assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        let mut intervals: Vec<(IntCst, IntCst)> = self
+            .tasks
+            .iter()
+            .filter(|task| task.duration > 0 && assignment.entails(task.presence))
+            .map(|task| {
+                let start = task.start_value(assignment);
+                (start, start + task.duration)
+            })
+            .collect();
+        intervals.sort();
+        intervals.windows(2).all(|w| w[0].1 <= w[1].0)
+    }
+}
+
+/// Contingent link between two timepoints: the duration `contingent - activation` is chosen by the
+/// environment in `[lb, ub]`, and is only known when the `contingent` timepoint occurs.
+///
+/// In addition to constraining the duration, it requires the temporal network to be dynamically controllable:
+/// all other timepoints must be schedulable online, whatever the durations chosen by the environment.
+/// The network is made of the active temporal constraints (including the ones activated by the search) and of the
+/// bounds of the timepoints at the root. The bounds set by the search are not part of it: they only pick the execution
+/// found in a solution, which is therefore not guaranteed to be executable against every choice of the environment.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ContingentLink {
+    pub activation: VarRef,
+    pub contingent: VarRef,
+    pub lb: IntCst,
+    pub ub: IntCst,
+}
+
+impl ContingentLink {
+    /// Returns true if the duration of the link is within its bounds in the (fully instantiated) assignment.
+    /// Dynamic controllability cannot be checked from a single assignment.
+    pub(crate) fn eval(&self, assignment: &Domains) -> bool {
+        if assignment.present(self.activation) == Some(true) && assignment.present(self.contingent) == Some(true) {
+            let duration = assignment.lb(self.contingent) - assignment.lb(self.activation);
+            self.lb <= duration && duration <= self.ub
+        } else {
+            true
+        }
+    }
+}
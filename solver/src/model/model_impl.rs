@@ -44,6 +44,8 @@ pub struct ModelShape<Lbl> {
     pub constraints: Vec<Constraint>,
     pub labels: VariableLabels<Lbl>,
     pub conjunctive_scopes: Scopes,
+    /// Soft constraints of the model: a literal that should hold, with the weight paid if it does not.
+    pub soft_constraints: Vec<(Lit, IntCst)>,
 }
 
 impl<Lbl: Label> ModelShape<Lbl> {
@@ -59,6 +61,7 @@ impl<Lbl: Label> ModelShape<Lbl> {
             constraints: Default::default(),
             labels: Default::default(),
             conjunctive_scopes: Default::default(),
+            soft_constraints: Default::default(),
         }
     }
 
@@ -278,6 +281,20 @@ impl<Lbl: Label> Model<Lbl> {
         FVar::new(ivar, denom)
     }
 
+    /// Creates a new boolean variable without label, for auxiliary variables introduced by the solver itself.
+    pub(crate) fn new_auxiliary_bvar(&mut self) -> BVar {
+        let dvar = self.state.new_var(0, 1);
+        self.shape.set_type(dvar, Type::Bool);
+        BVar::new(dvar)
+    }
+
+    /// Creates a new integer variable without label, for auxiliary variables introduced by the solver itself.
+    pub(crate) fn new_auxiliary_ivar(&mut self, lb: IntCst, ub: IntCst) -> IVar {
+        let dvar = self.state.new_var(lb, ub);
+        self.shape.set_type(dvar, Type::Int { lb, ub });
+        IVar::new(dvar)
+    }
+
     pub fn new_optional_ivar(&mut self, lb: IntCst, ub: IntCst, presence: Lit, label: impl Into<Lbl>) -> IVar {
         self.create_ivar(lb, ub, Some(presence), label)
     }
@@ -369,7 +386,7 @@ impl<Lbl: Label> Model<Lbl> {
     /// instance will be returned.
     pub fn reify<Expr: Reifiable<Lbl>>(&mut self, expr: Expr) -> Lit {
         let decomposed = expr.decompose(self);
-        self.reify_core(decomposed, false)
+        self.reify_core(decomposed)
     }
 
     fn simplify(&self, expr: &mut ReifExpr) {
@@ -426,9 +443,7 @@ impl<Lbl: Label> Model<Lbl> {
     }
 
     /// Reify the given expression.
-    /// If `use_tautology` is true, then the tautology of the scope will be used (meaning that the expression will
-    /// be constrained to always evaluate to true!).
-    pub(crate) fn reify_core(&mut self, mut expr: ReifExpr, use_tautology: bool) -> Lit {
+    pub(crate) fn reify_core(&mut self, mut expr: ReifExpr) -> Lit {
         self.simplify(&mut expr);
 
         if let Some(l) = self.shape.expressions.interned(&expr) {
@@ -440,13 +455,9 @@ impl<Lbl: Label> Model<Lbl> {
                 |l| self.state.entails(l),
             );
             let scope = self.new_conjunctive_presence_variable(scope);
-            let lit = if use_tautology {
-                self.get_tautology_of_scope(scope)
-            } else {
-                let var = self.state.new_optional_var(0, 1, scope);
-                self.shape.set_type(var, Type::Bool);
-                var.geq(1)
-            };
+            let var = self.state.new_optional_var(0, 1, scope);
+            self.shape.set_type(var, Type::Bool);
+            let lit = var.geq(1);
             self.shape.expressions.intern_as(expr.clone(), lit);
             self.shape.add_reification_constraint(lit, expr);
 
@@ -495,6 +506,42 @@ impl<Lbl: Label> Model<Lbl> {
         }
     }
 
+    /// Adds a soft constraint: the expression should hold, and `weight` is paid if it does not.
+    /// Returns the literal that is true if the expression holds.
+    ///
+    /// Soft constraints are accounted for by the objective returned by [`Model::violation_cost`].
+    pub fn add_soft<Expr: Reifiable<Lbl>>(&mut self, expr: Expr, weight: IntCst) -> Lit {
+        assert!(weight >= 0, "Negative weight for a soft constraint: {weight}");
+        let lit = self.reify(expr);
+        if weight > 0 {
+            self.shape.soft_constraints.push((lit, weight));
+        }
+        lit
+    }
+
+    /// Creates a variable whose value is the total weight of the violated soft constraints, to be minimized.
+    ///
+    /// Soft constraints added after this call are not taken into account by the returned variable.
+    /// Returns an overflow error if the total weight of the soft constraints cannot be represented.
+    pub fn violation_cost(&mut self) -> Result<IVar, ConversionError> {
+        let max_cost = self
+            .shape
+            .soft_constraints
+            .iter()
+            .try_fold(0 as IntCst, |acc, (_, w)| {
+                acc.checked_add(*w).filter(|&sum| sum <= INT_CST_MAX)
+            })
+            .ok_or(ConversionError::Overflow)?;
+        let cost = self.new_auxiliary_ivar(0, max_cost);
+        let mut sum = linear::LinearSum::zero();
+        for &(lit, weight) in &self.shape.soft_constraints {
+            sum += linear::LinearTerm::constant_int(weight, !lit);
+        }
+        self.enforce(sum.clone().leq(cost), []);
+        self.enforce(sum.geq(cost), []);
+        Ok(cost)
+    }
+
     /// Record that `b <=> literal`
     pub fn bind<Expr: Reifiable<Lbl>>(&mut self, expr: Expr, value: Lit) {
         let mut expr = expr.decompose(self);
@@ -516,16 +563,21 @@ impl<Lbl: Label> Model<Lbl> {
         if let Some(reified) = self.shape.expressions.interned(&expr) {
             // expression already reified, unify it with expected value
             self.bind_literals(value, reified)
+        } else if self.entails(value) && expr.is_global() {
+            // global constraint that can only be enforced, post it directly in the scope of the literal
+            // without interning it, as its validity scope may be larger than the one of the literal
+            self.shape.add_reification_constraint(value, expr);
         } else if expression_scope == self.presence_literal(value.variable()) {
             // not yet reified and compatible scopes, propose our literal as the reification
             self.shape.expressions.intern_as(expr.clone(), value);
             self.shape.add_reification_constraint(value, expr);
+        } else if self.entails(value) && matches!(expr, ReifExpr::Linear(_)) {
+            // linear constraint enforced in a scope smaller than its validity scope, post it directly in the scope
+            // of the literal as we do not handle reified linear constraints in general
+            self.shape.add_reification_constraint(value, expr);
         } else {
             // not yet reified but our literal cannot be used directly because it has a different scope
-            // if the literal is already true for a linear constraint, use the tautology of the expression scope as reification
-            // this is done because we do not handle reified linear constraint for the moment
-            let use_tautology = self.entails(value) && matches!(expr, ReifExpr::Linear(_));
-            let reified = self.reify_core(expr, use_tautology);
+            let reified = self.reify_core(expr);
             self.bind_literals(value, reified);
         }
     }
@@ -0,0 +1,195 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, SignedVar, VarRef};
+use crate::reasoners::cp::{geq, lb, leq, ub, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// Constraint `value = |var|`.
+///
+/// The propagator reasons symmetrically on the views `+var` and `-var`, exploiting that `value = max(var, -var)`:
+///  - `value` is non-negative and in the interval between the largest lower bound and the largest upper bound of the views,
+///  - no view can exceed the upper bound of `value`,
+///  - if a view cannot reach `-lb(value)`, the other one must be at least `lb(value)`.
+#[derive(Clone, Debug)]
+pub(super) struct Abs {
+    pub value: VarRef,
+    pub var: VarRef,
+    /// Presence literals of the variables. The constraint is only propagated once they are all present.
+    pub presences: Vec<Lit>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl Abs {
+    fn views(&self) -> [SignedVar; 2] {
+        [SignedVar::plus(self.var), SignedVar::minus(self.var)]
+    }
+
+    fn propagate_once(&self, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let z = SignedVar::plus(self.value);
+        let [pos, neg] = self.views();
+        let mut modified = domains.set(geq(z, 0.max(lb(pos, domains)).max(lb(neg, domains))), cause)?;
+        modified |= domains.set(leq(z, ub(pos, domains).max(ub(neg, domains))), cause)?;
+
+        let uz = ub(z, domains);
+        let lz = lb(z, domains);
+        for (v, other) in [(pos, neg), (neg, pos)] {
+            modified |= domains.set(leq(v, uz), cause)?;
+            if lz > 0 && ub(other, domains) < lz {
+                // `other` cannot reach `lz`, thus `v` must
+                modified |= domains.set(geq(v, lz), cause)?;
+            }
+        }
+        Ok(modified)
+    }
+
+    /// Explains the literal `v <= k`, returning false if no explanation could be built
+    /// (i.e., the literal was not inferred by this propagator).
+    fn explain_upper_bound(
+        &self,
+        v: SignedVar,
+        k: IntCst,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) -> bool {
+        let z = SignedVar::plus(self.value);
+        let entailed = |l: Lit| domains.entails(l);
+        if v == z {
+            // value <= k  <-  var <= k  &  -var <= k
+            for view in self.views() {
+                out_explanation.push(leq(view, k));
+            }
+            true
+        } else if v == -z {
+            // value >= -k  <-  var >= -k  |  -var >= -k
+            if -k <= 0 {
+                return true;
+            }
+            match self.views().into_iter().find(|&view| entailed(geq(view, -k))) {
+                Some(view) => {
+                    out_explanation.push(geq(view, -k));
+                    true
+                }
+                None => false,
+            }
+        } else if entailed(leq(z, k)) {
+            // view <= k  <-  value <= k
+            out_explanation.push(leq(z, k));
+            true
+        } else if lb(z, domains) >= -k && entailed(leq(v, lb(z, domains) - 1)) {
+            // view <= -lb(value)  <-  value >= lb(value)  &  view < lb(value)
+            // (i.e. the opposite view is at least lb(value)), the requested literal might be weaker
+            let lz = lb(z, domains);
+            out_explanation.push(geq(z, lz));
+            out_explanation.push(leq(v, lz - 1));
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Propagator for Abs {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        context.add_watch(self.value, id);
+        context.add_watch(self.var, id);
+        for &prez in &self.presences {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) || !self.presences.iter().all(|&p| domains.entails(p)) {
+            return Ok(());
+        }
+        while self.propagate_once(domains, cause)? {}
+        Ok(())
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        for l in self.presences.iter().chain([&self.active]) {
+            if *l != Lit::TRUE {
+                out_explanation.push(*l);
+            }
+        }
+        let k = literal.bound_value().as_int();
+        let explained = self.explain_upper_bound(literal.svar(), k, domains, out_explanation);
+        assert!(explained, "Cannot explain the literal {literal:?}");
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn abs(value: VarRef, var: VarRef) -> Abs {
+        Abs {
+            value,
+            var,
+            presences: vec![],
+            active: Lit::TRUE,
+        }
+    }
+
+    #[test]
+    fn test_abs() {
+        let mut d = Domains::new();
+        let x = d.new_var(-5, 3);
+        let z = d.new_var(-10, 10);
+        let c = abs(z, x);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(z), (0, 5));
+
+        d.save_state();
+        d.set_ub(z, 2, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (-2, 2));
+        check_explanations(&c, &d);
+
+        d.restore_last();
+        d.save_state();
+        d.set_lb(x, 2, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(z), (2, 3));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_abs_sign() {
+        let mut d = Domains::new();
+        let x = d.new_var(-5, 3);
+        let z = d.new_var(4, 10);
+        let c = abs(z, x);
+        // x cannot reach 4, thus it must be at most -4
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (-5, -4));
+        assert_eq!(d.bounds(z), (4, 5));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [active] => z = |x|, only propagated once `x` is present
+        let mut d = Domains::new();
+        let z = d.new_var(-2, 5);
+        let x = d.new_var(-6, 4);
+        let px = d.new_var(0, 1);
+        let active = d.new_var(0, 1);
+        let c = Abs {
+            presences: vec![px.geq(1)],
+            active: active.geq(1),
+            ..abs(z, x)
+        };
+        let is_solution = |s: &[IntCst]| s[2] == 0 || s[3] == 0 || s[0] == s[1].abs();
+        check_propagator(&c, &d, &[z, x, px, active], is_solution, 200);
+    }
+}
@@ -0,0 +1,391 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, LongCst, SignedVar, VarRef};
+use crate::reasoners::cp::{geq, lb, leq, ub, Priority, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// A set of variables whose domains are all included in the interval `[lb, ub]`.
+/// If the set contains as many variables as there are values in the interval, it forms a *Hall interval*:
+/// all values in the interval are taken by a variable of the set.
+struct HallSet {
+    lb: IntCst,
+    ub: IntCst,
+    /// Number of variables whose domains are included in the interval.
+    num_members: usize,
+    /// Position of the last member in the views of the [HallSets] it belongs to.
+    last: usize,
+}
+
+impl HallSet {
+    fn capacity(&self) -> LongCst {
+        self.ub as LongCst - self.lb as LongCst + 1
+    }
+}
+
+/// Hall sets of a set of views, whose members are identified in the views sorted by increasing upper bound.
+struct HallSets {
+    /// Bounds of the views with the index of their variable (in the propagator), sorted by increasing upper bound.
+    views: Vec<(IntCst, IntCst, usize)>,
+    sets: Vec<HallSet>,
+}
+
+impl HallSets {
+    /// Returns the index (in the propagator) of the variables whose domains are included in the interval of the set.
+    /// They are the views up to the last member of the set whose lower bound is in the interval.
+    fn members<'a>(&'a self, set: &'a HallSet) -> impl Iterator<Item = usize> + 'a {
+        self.views[..=set.last]
+            .iter()
+            .filter(move |(lb, _, _)| *lb >= set.lb)
+            .map(|(_, _, i)| *i)
+    }
+}
+
+/// Constraint enforcing that all present variables take distinct values.
+/// Absent variables are ignored.
+///
+/// The propagator enforces bounds consistency by detecting Hall intervals.
+/// All reasoning is done on the lower bounds of the variables, and applied on both `+x` and `-x`
+/// to reason on upper bounds.
+#[derive(Clone, Debug)]
+pub(super) struct AllDifferent {
+    pub vars: Vec<VarRef>,
+    /// Presence literal of each variable in `vars`.
+    pub presences: Vec<Lit>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl AllDifferent {
+    /// Returns the view of the i-th variable on the given side (`+x_i` if positive, `-x_i` otherwise).
+    fn view(&self, i: usize, positive: bool) -> SignedVar {
+        if positive {
+            SignedVar::plus(self.vars[i])
+        } else {
+            SignedVar::minus(self.vars[i])
+        }
+    }
+
+    /// Returns all Hall sets of the views `{+x_i}` (or `{-x_i}`) whose members are known to be present,
+    /// ignoring the variable at index `excluded` if any.
+    ///
+    /// The sets are ordered by increasing lower bound, then increasing upper bound.
+    /// Only the first set whose capacity is exceeded (if any) is returned, as it represents a conflict.
+    ///
+    /// The views are sorted once by upper bound. For each candidate lower bound `a`, a single sweep over
+    /// the sorted views builds the sets `[a, b]` incrementally, which gives an `O(n²)` complexity.
+    /// The members of each set are not stored but identified in the sorted views (see [`HallSets::members`]).
+    fn hall_sets(&self, positive: bool, excluded: Option<usize>, domains: &Domains) -> HallSets {
+        // bounds of all views that must take a distinct value, sorted by increasing upper bound
+        let mut bounds: Vec<(IntCst, IntCst, usize)> = (0..self.vars.len())
+            .filter(|&i| Some(i) != excluded && domains.entails(self.presences[i]))
+            .map(|i| {
+                let v = self.view(i, positive);
+                (lb(v, domains), ub(v, domains), i)
+            })
+            .collect();
+        bounds.sort_unstable_by_key(|(_, ub, _)| *ub);
+        let mut candidate_lbs: Vec<IntCst> = bounds.iter().map(|(lb, _, _)| *lb).collect();
+        candidate_lbs.sort_unstable();
+        candidate_lbs.dedup();
+
+        let mut sets = Vec::new();
+        for &a in &candidate_lbs {
+            // number of variables whose domain is included in [a, b], for increasing values of b
+            let mut num_members = 0;
+            for (j, &(lb, b, _)) in bounds.iter().enumerate() {
+                if lb < a {
+                    continue;
+                }
+                num_members += 1;
+                if bounds.get(j + 1).is_some_and(|(_, next_ub, _)| *next_ub == b) {
+                    continue; // only consider the set once all variables with the same upper bound are added
+                }
+                let set = HallSet {
+                    lb: a,
+                    ub: b,
+                    num_members,
+                    last: j,
+                };
+                if num_members as LongCst > set.capacity() {
+                    // pigeonhole: the set cannot be satisfied, only return it
+                    return HallSets {
+                        views: bounds,
+                        sets: vec![set],
+                    };
+                }
+                if num_members as LongCst == set.capacity() {
+                    sets.push(set);
+                }
+            }
+        }
+        HallSets { views: bounds, sets }
+    }
+
+    /// Pushes to the explanation the literals stating that all members of the set are present
+    /// and included in the set's interval.
+    fn explain_hall_set(
+        &self,
+        sets: &HallSets,
+        set: &HallSet,
+        positive: bool,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) {
+        if self.active != Lit::TRUE {
+            out_explanation.push(self.active);
+        }
+        for i in sets.members(set) {
+            let v = self.view(i, positive);
+            out_explanation.push(geq(v, set.lb));
+            out_explanation.push(leq(v, set.ub));
+            if self.presences[i] != Lit::TRUE {
+                out_explanation.push(self.presences[i]);
+            }
+        }
+    }
+
+    /// Updates the lower bounds of the views `{+x_i}` (or `{-x_i}`), so that no variable outside a Hall set
+    /// takes a value in the Hall interval.
+    /// Returns true if a domain was modified.
+    fn propagate_lower_bounds(
+        &self,
+        positive: bool,
+        domains: &mut Domains,
+        cause: Cause,
+    ) -> Result<bool, Contradiction> {
+        let mut modified = false;
+        let hall_sets = self.hall_sets(positive, None, domains);
+        for set in &hall_sets.sets {
+            if set.num_members as LongCst > set.capacity() {
+                let mut explanation = Explanation::with_capacity(set.num_members * 3 + 1);
+                self.explain_hall_set(&hall_sets, set, positive, domains, &mut explanation);
+                return Err(Contradiction::Explanation(explanation));
+            }
+            for i in 0..self.vars.len() {
+                if domains.entails(!self.presences[i]) {
+                    continue;
+                }
+                let v = self.view(i, positive);
+                let v_lb = lb(v, domains);
+                // members of the set are exactly the present variables whose domain is included in the interval
+                let is_member = domains.entails(self.presences[i]) && set.lb <= v_lb && ub(v, domains) <= set.ub;
+                // the bound can only be updated if the variable is known to be part of the constraint
+                // or if making the variable absent would make it absent from the constraint
+                let updatable =
+                    domains.entails(self.presences[i]) || self.presences[i] == domains.presence(v.variable());
+                if !is_member && updatable && set.lb <= v_lb && v_lb <= set.ub {
+                    // the variable cannot take any value in the Hall interval, skip it
+                    // if the update empties the domain, the variable will be made absent (or a conflict is raised)
+                    modified |= domains.set(geq(v, set.ub + 1), cause)?;
+                }
+            }
+        }
+        Ok(modified)
+    }
+}
+
+impl Propagator for AllDifferent {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        for &v in &self.vars {
+            context.add_watch(v, id);
+        }
+        for &prez in &self.presences {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) {
+            return Ok(());
+        }
+        // run until a fixpoint is reached on both lower and upper bounds
+        loop {
+            let lbs_modified = self.propagate_lower_bounds(true, domains, cause)?;
+            let ubs_modified = self.propagate_lower_bounds(false, domains, cause)?;
+            if !lbs_modified && !ubs_modified {
+                return Ok(());
+            }
+        }
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        // the literal was inferred by increasing the lower bound of the view `v` (either `+x` or `-x`)
+        // to a value `k` because its previous lower bound was in a Hall interval [a, b] with `b >= k-1`
+        let v = -literal.svar();
+        let k = -literal.bound_value().as_int();
+        let positive = v.is_plus();
+        let v_lb = lb(v, domains);
+        // the variable may appear several times in the constraint, pick the one that is not part of the Hall set
+        for excluded in (0..self.vars.len()).filter(|&i| self.vars[i] == v.variable()) {
+            let hall_sets = self.hall_sets(positive, Some(excluded), domains);
+            let hall_set = hall_sets
+                .sets
+                .iter()
+                .find(|set| set.lb <= v_lb && v_lb <= set.ub && k <= set.ub + 1);
+            if let Some(set) = hall_set {
+                out_explanation.push(geq(v, set.lb));
+                let presence = self.presences[excluded];
+                if presence != Lit::TRUE && presence != domains.presence(v.variable()) {
+                    out_explanation.push(presence);
+                }
+                self.explain_hall_set(&hall_sets, set, positive, domains, out_explanation);
+                return;
+            }
+        }
+        panic!("No Hall interval explains the literal {literal:?}")
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Expensive
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn alldiff(vars: &[VarRef], active: Lit, d: &Domains) -> AllDifferent {
+        AllDifferent {
+            vars: vars.to_vec(),
+            presences: vars.iter().map(|&v| d.presence(v)).collect(),
+            active,
+        }
+    }
+
+    fn check_bounds(v: VarRef, d: &Domains, lb: IntCst, ub: IntCst) {
+        assert_eq!(d.bounds(v), (lb, ub));
+    }
+
+    #[test]
+    fn test_hall_interval() {
+        let mut d = Domains::new();
+        let x = d.new_var(1, 2);
+        let y = d.new_var(1, 2);
+        let z = d.new_var(1, 4);
+        let w = d.new_var(0, 3);
+        let c = alldiff(&[x, y, z, w], Lit::TRUE, &d);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(x, &d, 1, 2);
+        check_bounds(y, &d, 1, 2);
+        check_bounds(z, &d, 3, 4);
+        // w cannot take values in [1, 2], but 0 remains possible
+        check_bounds(w, &d, 0, 3);
+
+        d.save_state();
+        d.set_lb(w, 1, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(w, &d, 3, 3);
+        check_bounds(z, &d, 4, 4);
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_upper_bounds() {
+        let mut d = Domains::new();
+        let x = d.new_var(4, 5);
+        let y = d.new_var(4, 5);
+        let z = d.new_var(0, 5);
+        let c = alldiff(&[x, y, z], Lit::TRUE, &d);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(z, &d, 0, 3);
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_conflict() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 1);
+        let y = d.new_var(0, 1);
+        let z = d.new_var(0, 5);
+        let c = alldiff(&[x, y, z], Lit::TRUE, &d);
+        d.save_state();
+        d.set_ub(z, 1, Cause::Decision).unwrap();
+        match c.propagate(&mut d, cause()) {
+            Err(Contradiction::Explanation(expl)) => {
+                assert!(expl.lits.iter().all(|&l| d.entails(l)));
+                assert!(expl.lits.contains(&z.leq(1)));
+            }
+            _ => panic!("Expected a conflict"),
+        }
+    }
+
+    #[test]
+    fn test_optional_variables() {
+        let mut d = Domains::new();
+        let px = d.new_var(0, 1).geq(1);
+        let py = d.new_var(0, 1).geq(1);
+        let x = d.new_optional_var(1, 1, px);
+        let y = d.new_optional_var(1, 1, py);
+        let z = d.new_var(1, 2);
+        let c = alldiff(&[x, y, z], Lit::TRUE, &d);
+
+        // presence of x and y are unknown, they are not considered
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(z, &d, 1, 2);
+
+        // x present, z must be 2 and y cannot be present
+        d.save_state();
+        d.set(px, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(z, &d, 2, 2);
+        assert!(d.entails(!py));
+        check_explanations(&c, &d);
+
+        // x absent, no propagation on z
+        d.restore_last();
+        d.save_state();
+        d.set(!px, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(z, &d, 1, 2);
+    }
+
+    #[test]
+    fn test_inactive() {
+        let mut d = Domains::new();
+        let active = d.new_var(0, 1).geq(1);
+        let x = d.new_var(1, 1);
+        let y = d.new_var(1, 2);
+        let c = alldiff(&[x, y], active, &d);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(y, &d, 1, 2);
+        d.set(active, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        check_bounds(y, &d, 2, 2);
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // x, y, z in [0, 3], w in [1, 2], with y and z only constrained when their presence literal holds
+        let mut d = Domains::new();
+        let x = d.new_var(0, 3);
+        let y = d.new_var(0, 3);
+        let z = d.new_var(0, 3);
+        let w = d.new_var(1, 2);
+        let py = d.new_var(0, 1);
+        let pz = d.new_var(0, 1);
+        let c = AllDifferent {
+            vars: vec![x, y, z, w],
+            presences: vec![Lit::TRUE, py.geq(1), pz.geq(1), Lit::TRUE],
+            active: Lit::TRUE,
+        };
+        let is_solution = |s: &[IntCst]| {
+            let present: Vec<IntCst> = [(s[0], true), (s[1], s[4] == 1), (s[2], s[5] == 1), (s[3], true)]
+                .iter()
+                .filter(|(_, p)| *p)
+                .map(|(v, _)| *v)
+                .collect();
+            (0..present.len()).all(|i| (0..i).all(|j| present[i] != present[j]))
+        };
+        check_propagator(&c, &d, &[x, y, z, w, py, pz], is_solution, 100);
+    }
+}
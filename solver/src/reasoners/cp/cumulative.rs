@@ -0,0 +1,673 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, LongCst};
+use crate::model::lang::scheduling::Task;
+use crate::reasoners::cp::task::TaskView;
+use crate::reasoners::cp::{Priority, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// A maximal interval `[start, end)` over which the set of tasks executing with a compulsory part is constant.
+struct ProfileSegment {
+    start: IntCst,
+    end: IntCst,
+    height: IntCst,
+}
+
+/// Cumulative resource: at any point in time, the sum of the demands of all present tasks executing
+/// at this time must not exceed the capacity.
+///
+/// The propagator implements time-tabling: it builds the resource profile induced by the compulsory parts
+/// of the tasks that are known to be present and pushes the other tasks out of the time windows where
+/// they would exceed the capacity.
+/// All inferences are explained with pointwise explanations: a set of tasks that necessarily execute
+/// at a given time point and whose total demand prevents any other task to execute at this time.
+///
+/// Once time-tabling reaches a fixed point, energetic reasoning is performed over a set of time windows:
+/// the constraint fails if the minimal energy (duration times demand) that the present tasks must spend in a window
+/// exceeds the energy available in it, and otherwise the tasks that cannot execute early in the window without
+/// exceeding the energy left by the others are pushed towards its end.
+#[derive(Clone, Debug)]
+pub(super) struct Cumulative {
+    pub tasks: Vec<Task>,
+    pub capacity: IntCst,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl Cumulative {
+    /// Returns views of all tasks that may consume the resource.
+    fn views(&self, forward: bool) -> impl Iterator<Item = TaskView> + '_ {
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.duration > 0 && t.demand > 0)
+            .map(move |(id, t)| TaskView::new(t, id, forward))
+    }
+
+    /// Returns the views of all present tasks that necessarily execute at time `t`, excluding the one with the given id.
+    fn tasks_executing_at(
+        &self,
+        t: IntCst,
+        forward: bool,
+        excluded: Option<usize>,
+        domains: &Domains,
+    ) -> Vec<TaskView> {
+        self.views(forward)
+            .filter(|task| Some(task.id) != excluded && domains.entails(task.presence))
+            .filter(|task| {
+                task.compulsory_part(domains)
+                    .is_some_and(|(start, end)| start <= t && t < end)
+            })
+            .collect()
+    }
+
+    /// Builds the profile of the resource induced by the compulsory parts of the present tasks.
+    /// Only segments with a non-zero height are returned, ordered by increasing start time.
+    fn profile(&self, forward: bool, domains: &Domains) -> Vec<ProfileSegment> {
+        let mut events: Vec<(IntCst, IntCst)> = Vec::with_capacity(self.tasks.len() * 2);
+        for task in self.views(forward) {
+            if domains.entails(task.presence) {
+                if let Some((start, end)) = task.compulsory_part(domains) {
+                    events.push((start, task.demand));
+                    events.push((end, -task.demand));
+                }
+            }
+        }
+        events.sort_unstable();
+        let mut segments = Vec::with_capacity(events.len());
+        let mut height = 0;
+        for (i, &(time, change)) in events.iter().enumerate() {
+            height += change;
+            match events.get(i + 1) {
+                Some(&(next_time, _)) if next_time > time && height > 0 => segments.push(ProfileSegment {
+                    start: time,
+                    end: next_time,
+                    height,
+                }),
+                _ => {}
+            }
+        }
+        segments
+    }
+
+    /// Adds to the explanation the literals stating that the tasks are present and necessarily executing
+    /// at time `t`. Only a subset of the tasks whose total demand exceeds `min_demand` is used.
+    fn explain_usage_at(
+        &self,
+        t: IntCst,
+        mut tasks: Vec<TaskView>,
+        min_demand: IntCst,
+        out_explanation: &mut Explanation,
+    ) {
+        if self.active != Lit::TRUE {
+            out_explanation.push(self.active);
+        }
+        // favor tasks with large demands to keep the explanation small
+        tasks.sort_by_key(|task| -task.demand);
+        let mut usage = 0;
+        for task in tasks {
+            if usage > min_demand {
+                break;
+            }
+            usage += task.demand;
+            out_explanation.push(task.starts_at_or_after(t + 1 - task.duration));
+            out_explanation.push(task.starts_at_or_before(t));
+            if task.presence != Lit::TRUE {
+                out_explanation.push(task.presence);
+            }
+        }
+        debug_assert!(usage > min_demand);
+    }
+
+    /// Explains that the task must start at or after `k`.
+    ///
+    /// This is inferred when the task would otherwise overlap with a time point `t >= k - 1` where the resource
+    /// is saturated, `t` being before the earliest completion time of the task.
+    /// Returns false if no such time point exists.
+    fn explain_start(
+        &self,
+        task: &TaskView,
+        k: IntCst,
+        forward: bool,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) -> bool {
+        let ect = task.ect(domains);
+        // the resource usage only increases at the start of a compulsory part, which restricts the candidate time points
+        let candidates = self
+            .views(forward)
+            .filter_map(|other| other.compulsory_part(domains).map(|(start, _)| start))
+            .filter(|&t| k - 1 < t && t < ect);
+        for t in std::iter::once(k - 1).chain(candidates) {
+            if t >= ect {
+                continue;
+            }
+            let executing = self.tasks_executing_at(t, forward, Some(task.id), domains);
+            let usage: IntCst = executing.iter().map(|task| task.demand).sum();
+            if usage + task.demand > self.capacity {
+                out_explanation.push(task.starts_at_or_after(t + 1 - task.duration));
+                self.explain_usage_at(t, executing, self.capacity - task.demand, out_explanation);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Minimal energy that the task must spend in the time window `[t1, t2)`, given the current bounds of its start.
+    /// Energies are computed on 64 bits to avoid overflows on large time windows.
+    fn min_energy_in(task: &TaskView, t1: IntCst, t2: IntCst, domains: &Domains) -> LongCst {
+        let left_shifted = task.ect(domains) as LongCst - t1 as LongCst;
+        let right_shifted = t2 as LongCst - task.lst(domains) as LongCst;
+        let overlap = (task.duration as LongCst)
+            .min(t2 as LongCst - t1 as LongCst)
+            .min(left_shifted)
+            .min(right_shifted);
+        overlap.max(0) * task.demand as LongCst
+    }
+
+    /// Candidate windows `[t1, t2)` of the energetic reasoning: they start at the earliest start time of a task
+    /// and end at the latest completion time of another one.
+    fn energy_windows(tasks: &[TaskView], domains: &Domains) -> Vec<(IntCst, IntCst)> {
+        let mut starts: Vec<IntCst> = tasks.iter().map(|task| task.est(domains)).collect();
+        let mut ends: Vec<IntCst> = tasks.iter().map(|task| task.lct(domains)).collect();
+        starts.sort_unstable();
+        starts.dedup();
+        ends.sort_unstable();
+        ends.dedup();
+        starts
+            .iter()
+            .flat_map(|&t1| ends.iter().filter(move |&&t2| t2 > t1).map(move |&t2| (t1, t2)))
+            .collect()
+    }
+
+    /// Earliest start time of the task imposed by the window `[t1, t2)`, in which the other tasks leave only
+    /// `slack` energy. The task may then execute at most `m = slack / demand` time units in the window: if it cannot
+    /// complete before `t1 + m`, it must start at or after `t2 - m`.
+    /// Returns `None` if the window does not restrict the start of the task.
+    fn energetic_est(task: &TaskView, t1: IntCst, t2: IntCst, slack: LongCst, domains: &Domains) -> Option<IntCst> {
+        if slack < 0 {
+            return None; // overload, detected separately
+        }
+        let max_overlap = slack / task.demand as LongCst;
+        if max_overlap >= (task.duration as LongCst).min(t2 as LongCst - t1 as LongCst) {
+            return None;
+        }
+        let max_overlap = max_overlap as IntCst;
+        let est = t2 - max_overlap;
+        if task.ect(domains) > t1 + max_overlap && task.est(domains) < est {
+            Some(est)
+        } else {
+            None
+        }
+    }
+
+    /// Adds to the explanation the bounds of the tasks of largest energy in a window, until their total energy
+    /// exceeds `min_energy`.
+    fn explain_energy(
+        &self,
+        mut energies: Vec<(LongCst, &TaskView)>,
+        min_energy: LongCst,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) {
+        if self.active != Lit::TRUE {
+            out_explanation.push(self.active);
+        }
+        energies.retain(|(energy, _)| *energy > 0);
+        energies.sort_by_key(|(energy, _)| -energy);
+        let mut energy = 0;
+        for (task_energy, task) in energies {
+            if energy > min_energy {
+                break;
+            }
+            energy += task_energy;
+            out_explanation.push(task.starts_at_or_after(task.est(domains)));
+            out_explanation.push(task.starts_at_or_before(task.lst(domains)));
+            if task.presence != Lit::TRUE {
+                out_explanation.push(task.presence);
+            }
+        }
+        debug_assert!(energy > min_energy);
+    }
+
+    /// Energetic reasoning: in any window `[t1, t2)`, the present tasks must spend at most the `capacity * (t2 - t1)`
+    /// energy available. Fails if their minimal energy in a window exceeds it, and otherwise pushes the start of the
+    /// tasks that would exceed the energy left by the others (see [`Cumulative::energetic_est`]).
+    ///
+    /// This is `O(n^3)` in the number of tasks. The reasoning stops as soon as a domain is modified, so that each
+    /// inference is made on the windows of the domains in which it is explained.
+    /// Returns true if a domain was modified.
+    fn energetic_reasoning(&self, forward: bool, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let present: Vec<TaskView> = self
+            .views(forward)
+            .filter(|task| domains.entails(task.presence))
+            .collect();
+        for (t1, t2) in Self::energy_windows(&present, domains) {
+            let available = self.capacity as LongCst * (t2 as LongCst - t1 as LongCst);
+            let energies: Vec<(LongCst, &TaskView)> = present
+                .iter()
+                .map(|task| (Self::min_energy_in(task, t1, t2, domains), task))
+                .collect();
+            let required: LongCst = energies.iter().map(|(energy, _)| energy).sum();
+            if required > available {
+                let mut explanation = Explanation::new();
+                self.explain_energy(energies, available, domains, &mut explanation);
+                return Err(Contradiction::Explanation(explanation));
+            }
+            for task in self.views(forward) {
+                if domains.entails(!task.presence) {
+                    continue;
+                }
+                let own = if domains.entails(task.presence) {
+                    Self::min_energy_in(&task, t1, t2, domains)
+                } else {
+                    0
+                };
+                if let Some(est) = Self::energetic_est(&task, t1, t2, available - (required - own), domains) {
+                    if task.set_est(est, domains, cause)? {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Explains that the task must start at or after `k` by energetic reasoning, looking for a window in which
+    /// the other present tasks leave too little energy for the task to start before `k`.
+    /// Returns false if no such window exists.
+    fn explain_energetic_start(
+        &self,
+        task: &TaskView,
+        k: IntCst,
+        forward: bool,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) -> bool {
+        let present: Vec<TaskView> = self
+            .views(forward)
+            .filter(|task| domains.entails(task.presence))
+            .collect();
+        for (t1, t2) in Self::energy_windows(&present, domains) {
+            let available = self.capacity as LongCst * (t2 as LongCst - t1 as LongCst);
+            let energies: Vec<(LongCst, &TaskView)> = present
+                .iter()
+                .filter(|other| other.id != task.id)
+                .map(|other| (Self::min_energy_in(other, t1, t2, domains), other))
+                .collect();
+            let slack = available - energies.iter().map(|(energy, _)| energy).sum::<LongCst>();
+            match Self::energetic_est(task, t1, t2, slack, domains) {
+                Some(est) if est >= k => {
+                    // the task cannot complete before `t1 + max_overlap` and the others leave less than
+                    // `demand * (max_overlap + 1)` energy in the window
+                    let max_overlap = t2 - est;
+                    out_explanation.push(task.starts_at_or_after(t1 + max_overlap + 1 - task.duration));
+                    let min_energy = available - task.demand as LongCst * (max_overlap as LongCst + 1);
+                    self.explain_energy(energies, min_energy, domains, out_explanation);
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Detects overloads of the resource and updates the start time of the tasks so that they do not execute
+    /// in a segment of the profile where their demand would exceed the capacity.
+    /// Returns true if a domain was modified.
+    fn propagate_direction(&self, forward: bool, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let profile = self.profile(forward, domains);
+        if let Some(overload) = profile.iter().find(|seg| seg.height > self.capacity) {
+            let t = overload.start;
+            let tasks = self.tasks_executing_at(t, forward, None, domains);
+            let mut explanation = Explanation::with_capacity(tasks.len() * 3 + 1);
+            self.explain_usage_at(t, tasks, self.capacity, &mut explanation);
+            return Err(Contradiction::Explanation(explanation));
+        }
+
+        let mut modified = false;
+        for task in self.views(forward) {
+            if domains.entails(!task.presence) {
+                continue;
+            }
+            // contribution of the task to the profile, that must be ignored when checking whether the task can be placed
+            let own_part = if domains.entails(task.presence) {
+                task.compulsory_part(domains)
+            } else {
+                None
+            };
+            let own_usage = |seg: &ProfileSegment| match own_part {
+                Some((start, end)) if start <= seg.start && seg.end <= end => task.demand,
+                _ => 0,
+            };
+            for seg in &profile {
+                let est = task.est(domains);
+                if seg.end <= est {
+                    continue; // segment entirely before the task
+                }
+                if est + task.duration <= seg.start {
+                    break; // task can be placed before the segment (and all following ones)
+                }
+                if seg.height - own_usage(seg) + task.demand > self.capacity {
+                    // the task cannot execute in the segment, push it to the first time point where it would
+                    // not overlap it, or to a point it would not overlap anymore with its current start time
+                    // (in which case it will be pushed again on the same segment)
+                    let t = (seg.end - 1).min(est + task.duration - 1);
+                    modified |= task.set_est(t + 1, domains, cause)?;
+                    if domains.entails(!task.presence) || task.est(domains) <= t {
+                        // the task is absent, or its start could not be updated because its presence is unknown
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(modified)
+    }
+}
+
+impl Propagator for Cumulative {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        for task in &self.tasks {
+            context.add_watch(task.start_var(), id);
+            if task.presence != Lit::TRUE {
+                context.add_watch(task.presence.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) {
+            return Ok(());
+        }
+        loop {
+            let forward_modified = self.propagate_direction(true, domains, cause)?;
+            let backward_modified = self.propagate_direction(false, domains, cause)?;
+            if forward_modified || backward_modified {
+                continue;
+            }
+            // time-tabling reached a fixed point, fall back to the more expensive energetic reasoning
+            if !self.energetic_reasoning(true, domains, cause)? && !self.energetic_reasoning(false, domains, cause)? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        for forward in [true, false] {
+            for task in self.views(forward) {
+                if literal.svar() == -task.start && self.tasks[task.id].start_var() == literal.variable() {
+                    // literal states that the task starts at or after `k`
+                    let k = -literal.bound_value().as_int() + task.offset;
+                    if self.explain_start(&task, k, forward, domains, out_explanation)
+                        || self.explain_energetic_start(&task, k, forward, domains, out_explanation)
+                    {
+                        // the start of the task is only constrained if the task is present
+                        if task.presence != Lit::TRUE && task.presence != domains.presence(literal.variable()) {
+                            out_explanation.push(task.presence);
+                        }
+                        return;
+                    }
+                } else if literal == !task.presence {
+                    // the task is absent because it would need to start after its latest start time
+                    let lst = task.lst(domains);
+                    if self.explain_start(&task, lst + 1, forward, domains, out_explanation)
+                        || self.explain_energetic_start(&task, lst + 1, forward, domains, out_explanation)
+                    {
+                        out_explanation.push(task.starts_at_or_before(lst));
+                        return;
+                    }
+                }
+            }
+        }
+        panic!("Cannot explain the literal {literal:?}")
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Expensive
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::core::VarRef;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn task(start: VarRef, duration: IntCst, demand: IntCst, presence: Lit) -> Task {
+        Task {
+            start: start.into(),
+            duration,
+            demand,
+            presence,
+        }
+    }
+
+    fn cumulative(tasks: Vec<Task>, capacity: IntCst) -> Cumulative {
+        Cumulative {
+            tasks,
+            capacity,
+            active: Lit::TRUE,
+        }
+    }
+
+    #[test]
+    fn test_timetable_lower_bounds() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 0);
+        let b = d.new_var(0, 10);
+        let c = d.new_var(0, 10);
+        let cumul = cumulative(
+            vec![
+                task(a, 5, 2, Lit::TRUE),
+                task(b, 3, 1, Lit::TRUE),
+                task(c, 3, 0, Lit::TRUE),
+            ],
+            2,
+        );
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(a), (0, 0));
+        assert_eq!(d.bounds(b), (5, 10));
+        // no demand, not affected
+        assert_eq!(d.bounds(c), (0, 10));
+        check_explanations(&cumul, &d);
+    }
+
+    #[test]
+    fn test_timetable_upper_bounds() {
+        let mut d = Domains::new();
+        let a = d.new_var(5, 5);
+        let b = d.new_var(0, 7);
+        let cumul = cumulative(vec![task(a, 5, 2, Lit::TRUE), task(b, 3, 1, Lit::TRUE)], 2);
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(b), (0, 2));
+        check_explanations(&cumul, &d);
+    }
+
+    #[test]
+    fn test_chained_compulsory_parts() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 1);
+        let b = d.new_var(0, 10);
+        let c = d.new_var(0, 10);
+        // `a` has a compulsory part in [1, 4), that pushes `b` after 4, which then pushes `c` after its compulsory part
+        let cumul = cumulative(
+            vec![
+                task(a, 4, 1, Lit::TRUE),
+                task(b, 4, 1, Lit::TRUE),
+                task(c, 4, 1, Lit::TRUE),
+            ],
+            1,
+        );
+        d.save_state();
+        d.set_ub(b, 5, Cause::Decision).unwrap();
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(b), (4, 5));
+        assert_eq!(d.bounds(c), (8, 10));
+        check_explanations(&cumul, &d);
+    }
+
+    #[test]
+    fn test_overload() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 2);
+        let b = d.new_var(0, 2);
+        let c = d.new_var(0, 10);
+        let cumul = cumulative(
+            vec![
+                task(a, 5, 2, Lit::TRUE),
+                task(b, 5, 2, Lit::TRUE),
+                task(c, 5, 2, Lit::TRUE),
+            ],
+            3,
+        );
+        d.save_state();
+        match cumul.propagate(&mut d, cause()) {
+            Err(Contradiction::Explanation(expl)) => {
+                assert!(expl.lits.iter().all(|&l| d.entails(l)));
+                assert!(expl.lits.contains(&a.leq(2)));
+                assert!(expl.lits.contains(&b.leq(2)));
+                assert!(!expl.lits.iter().any(|l| l.variable() == c));
+            }
+            _ => panic!("Expected an overload"),
+        }
+    }
+
+    #[test]
+    fn test_optional_tasks() {
+        let mut d = Domains::new();
+        let pa = d.new_var(0, 1).geq(1);
+        let pb = d.new_var(0, 1).geq(1);
+        let a = d.new_var(5, 5);
+        let b = d.new_var(3, 4);
+        let cumul = cumulative(vec![task(a, 5, 2, pa), task(b, 3, 1, pb)], 2);
+
+        // presence of `a` is unknown, no propagation
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(b), (3, 4));
+        assert_eq!(d.value(pb), None);
+
+        // `a` is present, `b` cannot be placed and must be absent
+        d.save_state();
+        d.set(pa, Cause::Decision).unwrap();
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert!(d.entails(!pb));
+        check_explanations(&cumul, &d);
+
+        // `b` is present, `a` must be absent
+        d.restore_last();
+        d.save_state();
+        d.set(pb, Cause::Decision).unwrap();
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert!(d.entails(!pa));
+        check_explanations(&cumul, &d);
+    }
+
+    #[test]
+    fn test_energetic_overload() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 2);
+        let b = d.new_var(0, 2);
+        let c = d.new_var(0, 2);
+        let e = d.new_var(0, 10);
+        // no compulsory parts, but 3 tasks of duration 2 must execute in [0, 4)
+        let cumul = cumulative(
+            vec![
+                task(a, 2, 1, Lit::TRUE),
+                task(b, 2, 1, Lit::TRUE),
+                task(c, 2, 1, Lit::TRUE),
+                task(e, 2, 1, Lit::TRUE),
+            ],
+            1,
+        );
+        match cumul.propagate(&mut d, cause()) {
+            Err(Contradiction::Explanation(expl)) => {
+                assert!(expl.lits.iter().all(|&l| d.entails(l)));
+                assert!(!expl.lits.iter().any(|l| l.variable() == e));
+            }
+            _ => panic!("Expected an overload"),
+        }
+    }
+
+    #[test]
+    fn test_energetic_adjustment() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 2);
+        let b = d.new_var(0, 2);
+        let c = d.new_var(0, 10);
+        let e = d.new_var(0, 3);
+        let pe = d.new_var(0, 1).geq(1);
+        // no compulsory parts, but `a` and `b` fill the window [0, 4) in which `c` and `e` cannot start
+        let cumul = cumulative(
+            vec![
+                task(a, 2, 1, Lit::TRUE),
+                task(b, 2, 1, Lit::TRUE),
+                task(c, 2, 1, Lit::TRUE),
+                task(e, 2, 1, pe),
+            ],
+            1,
+        );
+        d.save_state();
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(c), (4, 10));
+        assert!(d.entails(!pe));
+        check_explanations(&cumul, &d);
+
+        // symmetric case, where `c` must complete before the window [6, 10)
+        let mut d = Domains::new();
+        let a = d.new_var(6, 8);
+        let b = d.new_var(6, 8);
+        let c = d.new_var(0, 9);
+        let cumul = cumulative(
+            vec![
+                task(a, 2, 1, Lit::TRUE),
+                task(b, 2, 1, Lit::TRUE),
+                task(c, 2, 1, Lit::TRUE),
+            ],
+            1,
+        );
+        d.save_state();
+        assert!(cumul.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(c), (0, 4));
+        check_explanations(&cumul, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 5);
+        let b = d.new_var(0, 5);
+        let c = d.new_var(0, 5);
+        let e = d.new_var(0, 5);
+        // presence of the optional tasks, the start variables being always present
+        let pc = d.new_var(0, 1);
+        let pe = d.new_var(0, 1);
+        let cumul = cumulative(
+            vec![
+                task(a, 3, 2, Lit::TRUE),
+                task(b, 2, 1, Lit::TRUE),
+                task(c, 3, 2, pc.geq(1)),
+                task(e, 2, 2, pe.geq(1)),
+            ],
+            3,
+        );
+        let durations = [3, 2, 3, 2];
+        let demands = [2, 1, 2, 2];
+        let is_solution = |values: &[IntCst]| {
+            let present = [true, true, values[4] == 1, values[5] == 1];
+            (0..8).all(|t| {
+                let usage: IntCst = (0..4)
+                    .filter(|&i| present[i] && values[i] <= t && t < values[i] + durations[i])
+                    .map(|i| demands[i])
+                    .sum();
+                usage <= 3
+            })
+        };
+        check_propagator(&cumul, &d, &[a, b, c, e, pc, pe], is_solution, 200);
+    }
+}
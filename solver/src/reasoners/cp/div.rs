@@ -0,0 +1,382 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, LongCst, SignedVar, VarRef, INT_CST_MAX, INT_CST_MIN};
+use crate::reasoners::cp::{geq, lb, leq, ub, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+fn clamp(value: LongCst) -> IntCst {
+    value.clamp(INT_CST_MIN as LongCst, INT_CST_MAX as LongCst) as IntCst
+}
+
+/// Pushes the presence literals, the activity literal and both bounds of the variables to the explanation.
+fn explain_bounds(
+    vars: &[VarRef],
+    presences: &[Lit],
+    active: Lit,
+    domains: &Domains,
+    out_explanation: &mut Explanation,
+) {
+    for &l in presences.iter().chain([&active]) {
+        if l != Lit::TRUE {
+            out_explanation.push(l);
+        }
+    }
+    for &v in vars {
+        let (lb, ub) = domains.bounds(v);
+        out_explanation.push(v.geq(lb));
+        out_explanation.push(v.leq(ub));
+    }
+}
+
+/// Constraint `quotient = numerator / denominator` where the division is truncated towards zero
+/// and the denominator is a non-zero constant.
+///
+/// The quotient is a non-decreasing function of the numerator, which allows exchanging the bounds of the two variables.
+/// A negative denominator is handled by reasoning on the view `-quotient`.
+#[derive(Clone, Debug)]
+pub(super) struct Div {
+    pub quotient: VarRef,
+    pub numerator: VarRef,
+    pub denominator: IntCst,
+    /// Presence literals of the variables. The constraint is only propagated once they are all present.
+    pub presences: Vec<Lit>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl Div {
+    /// View of the quotient of the numerator by the absolute value of the denominator.
+    fn quotient_view(&self) -> SignedVar {
+        if self.denominator > 0 {
+            SignedVar::plus(self.quotient)
+        } else {
+            SignedVar::minus(self.quotient)
+        }
+    }
+
+    /// Smallest numerator whose quotient is `q`.
+    fn smallest_numerator(&self, q: IntCst) -> LongCst {
+        let (q, d) = (q as LongCst, self.denominator.abs() as LongCst);
+        if q > 0 {
+            q * d
+        } else {
+            q * d - d + 1
+        }
+    }
+
+    /// Largest numerator whose quotient is `q`.
+    fn largest_numerator(&self, q: IntCst) -> LongCst {
+        let (q, d) = (q as LongCst, self.denominator.abs() as LongCst);
+        if q >= 0 {
+            q * d + d - 1
+        } else {
+            q * d
+        }
+    }
+
+    fn propagate_once(&self, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let q = self.quotient_view();
+        let d = self.denominator.abs();
+        let (lx, ux) = domains.bounds(self.numerator);
+        let mut modified = domains.set(geq(q, lx / d), cause)?;
+        modified |= domains.set(leq(q, ux / d), cause)?;
+
+        let lx = self.smallest_numerator(lb(q, domains));
+        let ux = self.largest_numerator(ub(q, domains));
+        modified |= domains.set_lb(self.numerator, clamp(lx), cause)?;
+        modified |= domains.set_ub(self.numerator, clamp(ux), cause)?;
+        Ok(modified)
+    }
+}
+
+impl Propagator for Div {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        context.add_watch(self.quotient, id);
+        context.add_watch(self.numerator, id);
+        for &prez in &self.presences {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) || !self.presences.iter().all(|&p| domains.entails(p)) {
+            return Ok(());
+        }
+        while self.propagate_once(domains, cause)? {}
+        Ok(())
+    }
+
+    fn explain(&self, _literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        explain_bounds(
+            &[self.quotient, self.numerator],
+            &self.presences,
+            self.active,
+            domains,
+            out_explanation,
+        );
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+/// Constraint `remainder = numerator mod denominator` where the denominator is a non-zero constant
+/// and the remainder has the sign of the numerator.
+///
+/// Once the sign of the numerator is known, the bounds of the numerator are moved to the closest value whose
+/// remainder is in the bounds of the remainder variable. A non-positive numerator is handled by reasoning on the
+/// views `-numerator` and `-remainder`.
+#[derive(Clone, Debug)]
+pub(super) struct Mod {
+    pub remainder: VarRef,
+    pub numerator: VarRef,
+    pub denominator: IntCst,
+    /// Presence literals of the variables. The constraint is only propagated once they are all present.
+    pub presences: Vec<Lit>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl Mod {
+    /// Propagates the constraint, assuming that the (view of the) numerator `x` is non-negative.
+    fn propagate_non_negative(
+        &self,
+        x: SignedVar,
+        r: SignedVar,
+        domains: &mut Domains,
+        cause: Cause,
+    ) -> Result<bool, Contradiction> {
+        let d = self.denominator.abs() as LongCst;
+        let (lx, ux) = (lb(x, domains) as LongCst, ub(x, domains) as LongCst);
+        debug_assert!(lx >= 0);
+        let mut modified = false;
+        if lx / d == ux / d {
+            // all possible values of the numerator have the same quotient
+            let base = lx - lx % d;
+            modified |= domains.set(geq(r, clamp(lx - base)), cause)?;
+            modified |= domains.set(leq(r, clamp(ux - base)), cause)?;
+        }
+        let (lr, ur) = (lb(r, domains) as LongCst, ub(r, domains) as LongCst);
+
+        // smallest value of the numerator whose remainder is in [lr, ur]
+        let base = lx - lx % d;
+        let new_lx = if lx % d < lr {
+            base + lr
+        } else if lx % d > ur {
+            base + d + lr
+        } else {
+            lx
+        };
+        // largest value of the numerator whose remainder is in [lr, ur]
+        let base = ux - ux % d;
+        let new_ux = if ux % d > ur {
+            base + ur
+        } else if ux % d < lr {
+            base - d + ur
+        } else {
+            ux
+        };
+        modified |= domains.set(geq(x, clamp(new_lx)), cause)?;
+        modified |= domains.set(leq(x, clamp(new_ux)), cause)?;
+        Ok(modified)
+    }
+
+    fn propagate_once(&self, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let d = self.denominator.abs();
+        let (x, r) = (self.numerator, self.remainder);
+        // |r| < |d|
+        let mut modified = domains.set_lb(r, -(d - 1), cause)?;
+        modified |= domains.set_ub(r, d - 1, cause)?;
+
+        // the remainder has the sign of the numerator and a smaller absolute value
+        let (lx, ux) = domains.bounds(x);
+        modified |= domains.set_lb(r, lx.min(0), cause)?;
+        modified |= domains.set_ub(r, ux.max(0), cause)?;
+        let (lr, ur) = domains.bounds(r);
+        if lr > 0 {
+            modified |= domains.set_lb(x, lr, cause)?;
+        }
+        if ur < 0 {
+            modified |= domains.set_ub(x, ur, cause)?;
+        }
+
+        let (lx, ux) = domains.bounds(x);
+        if lx >= 0 {
+            modified |= self.propagate_non_negative(SignedVar::plus(x), SignedVar::plus(r), domains, cause)?;
+        } else if ux <= 0 {
+            modified |= self.propagate_non_negative(SignedVar::minus(x), SignedVar::minus(r), domains, cause)?;
+        }
+        Ok(modified)
+    }
+}
+
+impl Propagator for Mod {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        context.add_watch(self.remainder, id);
+        context.add_watch(self.numerator, id);
+        for &prez in &self.presences {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) || !self.presences.iter().all(|&p| domains.entails(p)) {
+            return Ok(());
+        }
+        while self.propagate_once(domains, cause)? {}
+        Ok(())
+    }
+
+    fn explain(&self, _literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        explain_bounds(
+            &[self.remainder, self.numerator],
+            &self.presences,
+            self.active,
+            domains,
+            out_explanation,
+        );
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    #[test]
+    fn test_div() {
+        let mut d = Domains::new();
+        let x = d.new_var(-20, 20);
+        let q = d.new_var(-100, 100);
+        let c = Div {
+            quotient: q,
+            numerator: x,
+            denominator: 3,
+            presences: vec![],
+            active: Lit::TRUE,
+        };
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(q), (-6, 6));
+
+        d.save_state();
+        d.set_lb(q, 2, Cause::Decision).unwrap();
+        d.set_ub(q, 4, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (6, 14));
+        check_explanations(&c, &d);
+
+        d.restore_last();
+        d.save_state();
+        d.set_lb(q, -1, Cause::Decision).unwrap();
+        d.set_ub(q, 0, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (-5, 2));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_div_negative_denominator() {
+        let mut d = Domains::new();
+        let x = d.new_var(7, 20);
+        let q = d.new_var(-100, 100);
+        let c = Div {
+            quotient: q,
+            numerator: x,
+            denominator: -3,
+            presences: vec![],
+            active: Lit::TRUE,
+        };
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(q), (-6, -2));
+
+        d.save_state();
+        d.set_lb(q, -3, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (7, 11));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_mod() {
+        let mut d = Domains::new();
+        let x = d.new_var(-20, 20);
+        let r = d.new_var(-100, 100);
+        let c = Mod {
+            remainder: r,
+            numerator: x,
+            denominator: 5,
+            presences: vec![],
+            active: Lit::TRUE,
+        };
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(r), (-4, 4));
+
+        // positive remainder: positive numerator
+        d.save_state();
+        d.set_lb(r, 2, Cause::Decision).unwrap();
+        d.set_ub(r, 3, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (2, 18));
+        check_explanations(&c, &d);
+
+        d.save_state();
+        d.set_lb(x, 14, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (17, 18));
+        check_explanations(&c, &d);
+
+        // negative numerator, in a single block
+        d.restore_last();
+        d.restore_last();
+        d.save_state();
+        d.set_lb(x, -13, Cause::Decision).unwrap();
+        d.set_ub(x, -11, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(r), (-3, -1));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [active] => q = x / k and [active] => r = x mod k, only propagated once `x` is present
+        let mut d = Domains::new();
+        let x = d.new_var(-7, 7);
+        let q = d.new_var(-4, 3);
+        let r = d.new_var(-3, 2);
+        let px = d.new_var(0, 1);
+        let active = d.new_var(0, 1);
+        for k in [-3, -2, 1, 2, 3] {
+            let div = Div {
+                quotient: q,
+                numerator: x,
+                denominator: k,
+                presences: vec![px.geq(1)],
+                active: active.geq(1),
+            };
+            let is_solution = |s: &[IntCst]| s[2] == 0 || s[3] == 0 || s[1] == s[0] / k;
+            check_propagator(&div, &d, &[x, q, px, active], is_solution, 100);
+
+            let rem = Mod {
+                remainder: r,
+                numerator: x,
+                denominator: k,
+                presences: vec![px.geq(1)],
+                active: active.geq(1),
+            };
+            let is_solution = |s: &[IntCst]| s[2] == 0 || s[3] == 0 || s[1] == s[0] % k;
+            check_propagator(&rem, &d, &[x, r, px, active], is_solution, 100);
+        }
+    }
+}
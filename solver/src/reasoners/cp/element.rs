@@ -0,0 +1,251 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, VarRef};
+use crate::reasoners::cp::{Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// Element constraint `value = array[index]`, where `array` is an array of constants.
+///
+/// The propagator maintains bounds consistency on both variables:
+///  - the index is restricted to positions of the array whose element is in the bounds of `value`,
+///  - `value` is restricted to the smallest and largest elements at a possible position of the index.
+///
+/// As the constraint has no effect if one of the variables is absent, each variable is only restricted if the other one is present.
+#[derive(Clone, Debug)]
+pub(super) struct Element {
+    pub array: Vec<IntCst>,
+    pub index: VarRef,
+    pub index_presence: Lit,
+    pub value: VarRef,
+    pub value_presence: Lit,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl Element {
+    fn last_index(&self) -> IntCst {
+        self.array.len() as IntCst - 1
+    }
+
+    fn get(&self, index: IntCst) -> IntCst {
+        self.array[index as usize]
+    }
+
+    /// Pushes the literals of the explanation that are common to all inferences on `var`.
+    fn explain_common(&self, var: VarRef, out_explanation: &mut Explanation) {
+        let other_presence = if var == self.index {
+            self.value_presence
+        } else {
+            self.index_presence
+        };
+        for l in [self.active, other_presence] {
+            if l != Lit::TRUE {
+                out_explanation.push(l);
+            }
+        }
+    }
+
+    /// Explains that all positions in `[first, last]` have an element outside the bounds of `value`.
+    fn explain_positions(&self, first: IntCst, last: IntCst, domains: &Domains, out_explanation: &mut Explanation) {
+        let (lb, ub) = domains.bounds(self.value);
+        let below = (first..=last).map(|i| self.get(i)).filter(|&e| e < lb).max();
+        let above = (first..=last).map(|i| self.get(i)).filter(|&e| e > ub).min();
+        if let Some(e) = below {
+            out_explanation.push(self.value.geq(e + 1));
+        }
+        if let Some(e) = above {
+            out_explanation.push(self.value.leq(e - 1));
+        }
+    }
+
+    fn propagate_once(&self, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let mut modified = false;
+        if domains.entails(self.value_presence) {
+            // the index must be a position of the array
+            modified |= domains.set_lb(self.index, 0, cause)?;
+            modified |= domains.set_ub(self.index, self.last_index(), cause)?;
+
+            // restrict the index to positions whose element is in the bounds of the value
+            let (lb, ub) = domains.bounds(self.value);
+            let compatible = |i: &IntCst| (lb..=ub).contains(&self.get(*i));
+            // the bounds of an absent index may be outside the array
+            let (first, last) = domains.bounds(self.index);
+            let (first, last) = (first.max(0), last.min(self.last_index()));
+            match (first..=last).find(compatible) {
+                Some(i) => {
+                    modified |= domains.set_lb(self.index, i, cause)?;
+                    let j = (first..=last).rev().find(compatible).unwrap();
+                    modified |= domains.set_ub(self.index, j, cause)?;
+                }
+                None => {
+                    modified |= domains.set_lb(self.index, last + 1, cause)?;
+                }
+            }
+        }
+        if domains.entails(self.index_presence) {
+            // restrict the value to the elements at possible positions of the index
+            let (first, last) = domains.bounds(self.index);
+            let (first, last) = (first.max(0), last.min(self.last_index()));
+            if first <= last {
+                let min = (first..=last).map(|i| self.get(i)).min().unwrap();
+                let max = (first..=last).map(|i| self.get(i)).max().unwrap();
+                modified |= domains.set_lb(self.value, min, cause)?;
+                modified |= domains.set_ub(self.value, max, cause)?;
+            } else {
+                let ub = domains.ub(self.value);
+                modified |= domains.set_lb(self.value, ub + 1, cause)?;
+            }
+        }
+        Ok(modified)
+    }
+}
+
+impl Propagator for Element {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        context.add_watch(self.index, id);
+        context.add_watch(self.value, id);
+        for prez in [self.index_presence, self.value_presence] {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) {
+            return Ok(());
+        }
+        while self.propagate_once(domains, cause)? {}
+        Ok(())
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        let var = literal.variable();
+        self.explain_common(var, out_explanation);
+        let upper = literal.svar().is_plus();
+        let bound = if upper {
+            literal.bound_value().as_int()
+        } else {
+            -literal.bound_value().as_int()
+        };
+        let (first, last) = domains.bounds(self.index);
+        if var == self.index {
+            if upper {
+                // index <= bound: positions in ]bound, last] are incompatible with the value
+                if bound < self.last_index() {
+                    out_explanation.push(self.index.leq(last));
+                    self.explain_positions(bound + 1, last.min(self.last_index()), domains, out_explanation);
+                }
+            } else {
+                // index >= bound: positions in [first, bound[ are incompatible with the value
+                if bound > 0 {
+                    out_explanation.push(self.index.geq(first));
+                    self.explain_positions(first.max(0), bound - 1, domains, out_explanation);
+                }
+            }
+        } else {
+            debug_assert_eq!(var, self.value);
+            // the value is the element at one of the positions allowed by the index
+            out_explanation.push(self.index.geq(first));
+            out_explanation.push(self.index.leq(last));
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn element(array: &[IntCst], index: VarRef, value: VarRef, d: &Domains) -> Element {
+        Element {
+            array: array.to_vec(),
+            index,
+            index_presence: d.presence(index),
+            value,
+            value_presence: d.presence(value),
+            active: Lit::TRUE,
+        }
+    }
+
+    #[test]
+    fn test_propagation() {
+        let mut d = Domains::new();
+        let i = d.new_var(-5, 10);
+        let x = d.new_var(-10, 10);
+        let c = element(&[3, 7, 1, 5, 9], i, x, &d);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(i), (0, 4));
+        assert_eq!(d.bounds(x), (1, 9));
+
+        d.save_state();
+        d.set_ub(x, 6, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(i), (0, 3));
+        assert_eq!(d.bounds(x), (1, 6));
+        check_explanations(&c, &d);
+
+        d.save_state();
+        d.set_lb(i, 1, Cause::Decision).unwrap();
+        d.set_lb(x, 2, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(i), (3, 3));
+        assert_eq!(d.bounds(x), (5, 5));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_conflict() {
+        let mut d = Domains::new();
+        let i = d.new_var(0, 4);
+        let x = d.new_var(10, 20);
+        let c = element(&[3, 7, 1, 5, 9], i, x, &d);
+        assert!(c.propagate(&mut d, cause()).is_err());
+    }
+
+    #[test]
+    fn test_optional() {
+        let mut d = Domains::new();
+        let p = d.new_var(0, 1).geq(1);
+        let i = d.new_optional_var(0, 4, p);
+        let x = d.new_var(10, 20);
+        let c = element(&[3, 7, 1, 5, 9], i, x, &d);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        // nothing can be inferred on the value as long as the index may be absent
+        assert_eq!(d.bounds(x), (10, 20));
+
+        // the value is present and incompatible with all elements: the index must be absent
+        assert!(d.entails(!p));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [active] => value = array[index], with an optional index whose domain exceeds the array and an optional value
+        let mut d = Domains::new();
+        let pi = d.new_var(0, 1);
+        let pv = d.new_var(0, 1);
+        let active = d.new_var(0, 1);
+        let i = d.new_optional_var(-1, 3, pi.geq(1));
+        let x = d.new_optional_var(0, 4, pv.geq(1));
+        let array = [3, 1, 4];
+        let c = Element {
+            array: array.to_vec(),
+            index: i,
+            index_presence: pi.geq(1),
+            value: x,
+            value_presence: pv.geq(1),
+            active: active.geq(1),
+        };
+        let is_solution = |s: &[IntCst]| {
+            s[2] == 0 || s[3] == 0 || s[4] == 0 || (0..3).contains(&s[0]) && array[s[0] as usize] == s[1]
+        };
+        check_propagator(&c, &d, &[i, x, pi, pv, active], is_solution, 300);
+    }
+}
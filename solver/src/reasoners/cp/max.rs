@@ -0,0 +1,229 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, SignedVar};
+use crate::reasoners::cp::{geq, lb, leq, ub, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// Constraint `value = max(vars)` over signed variables.
+/// The constraint `value = min(vars)` is represented as `-value = max(-vars)`.
+///
+/// The propagator maintains bounds consistency:
+///  - `value` is in the interval between the largest lower bound and the largest upper bound of the variables,
+///  - no variable can exceed the upper bound of `value`,
+///  - if a single variable can reach the lower bound of `value`, it must be greater than or equal to it.
+#[derive(Clone, Debug)]
+pub(super) struct Max {
+    pub value: SignedVar,
+    pub vars: Vec<SignedVar>,
+    /// Presence literals of the variables. The constraint is only propagated once they are all present.
+    pub presences: Vec<Lit>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl Max {
+    fn propagate_once(&self, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let max_lb = self.vars.iter().map(|&v| lb(v, domains)).max().unwrap();
+        let max_ub = self.vars.iter().map(|&v| ub(v, domains)).max().unwrap();
+        let mut modified = domains.set(geq(self.value, max_lb), cause)?;
+        modified |= domains.set(leq(self.value, max_ub), cause)?;
+
+        let value_ub = ub(self.value, domains);
+        for &v in &self.vars {
+            modified |= domains.set(leq(v, value_ub), cause)?;
+        }
+
+        let value_lb = lb(self.value, domains);
+        let mut supports = self.vars.iter().filter(|&&v| ub(v, domains) >= value_lb);
+        if let (Some(&support), None) = (supports.next(), supports.next()) {
+            modified |= domains.set(geq(support, value_lb), cause)?;
+        }
+        Ok(modified)
+    }
+
+    /// Explains the literal `v <= k`, returning false if no explanation could be built
+    /// (i.e., the literal was not inferred by this propagator).
+    fn explain_upper_bound(
+        &self,
+        v: SignedVar,
+        k: IntCst,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) -> bool {
+        let entailed = |l: Lit| domains.entails(l);
+        if v == self.value && self.vars.iter().all(|&x| entailed(leq(x, k))) {
+            // value <= k  <-  forall i, x_i <= k
+            for &x in &self.vars {
+                out_explanation.push(leq(x, k));
+            }
+            return true;
+        }
+        if v == -self.value {
+            // value >= -k  <-  x_i >= -k
+            if let Some(&x) = self.vars.iter().find(|&&x| entailed(geq(x, -k))) {
+                out_explanation.push(geq(x, -k));
+                return true;
+            }
+        }
+        if self.vars.contains(&v) && entailed(leq(self.value, k)) {
+            // x_i <= k  <-  value <= k
+            out_explanation.push(leq(self.value, k));
+            return true;
+        }
+        let value_lb = lb(self.value, domains);
+        for (j, &x) in self.vars.iter().enumerate() {
+            // x_j >= lb(value)  <-  value >= lb(value)  &  forall i != j, x_i < lb(value)
+            // the requested literal `x_j >= -k` might be weaker than the inferred one
+            if x == -v && value_lb >= -k {
+                let others = self.vars.iter().enumerate().filter(|&(i, _)| i != j);
+                if others.clone().all(|(_, &y)| entailed(leq(y, value_lb - 1))) {
+                    out_explanation.push(geq(self.value, value_lb));
+                    for (_, &y) in others {
+                        out_explanation.push(leq(y, value_lb - 1));
+                    }
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+impl Propagator for Max {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        context.add_watch(self.value.variable(), id);
+        for v in &self.vars {
+            context.add_watch(v.variable(), id);
+        }
+        for &prez in &self.presences {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) || !self.presences.iter().all(|&p| domains.entails(p)) {
+            return Ok(());
+        }
+        while self.propagate_once(domains, cause)? {}
+        Ok(())
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        for l in self.presences.iter().chain([&self.active]) {
+            if *l != Lit::TRUE {
+                out_explanation.push(*l);
+            }
+        }
+        let k = literal.bound_value().as_int();
+        let explained = self.explain_upper_bound(literal.svar(), k, domains, out_explanation);
+        assert!(explained, "Cannot explain the literal {literal:?}");
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::core::VarRef;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn max(value: VarRef, vars: &[VarRef], minimum: bool) -> Max {
+        let view = |v: VarRef| {
+            if minimum {
+                SignedVar::minus(v)
+            } else {
+                SignedVar::plus(v)
+            }
+        };
+        Max {
+            value: view(value),
+            vars: vars.iter().map(|&v| view(v)).collect(),
+            presences: vec![],
+            active: Lit::TRUE,
+        }
+    }
+
+    #[test]
+    fn test_max() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 5);
+        let y = d.new_var(2, 8);
+        let z = d.new_var(1, 3);
+        let m = d.new_var(-10, 10);
+        let c = max(m, &[x, y, z], false);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(m), (2, 8));
+
+        d.save_state();
+        d.set_ub(m, 4, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (0, 4));
+        assert_eq!(d.bounds(y), (2, 4));
+        check_explanations(&c, &d);
+
+        // only y can reach the lower bound of m
+        d.save_state();
+        d.set_lb(m, 4, Cause::Decision).unwrap();
+        d.set_ub(x, 3, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(y), (4, 4));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_min() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 5);
+        let y = d.new_var(2, 8);
+        let m = d.new_var(-10, 10);
+        let c = max(m, &[x, y], true);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(m), (0, 5));
+
+        d.save_state();
+        d.set_lb(m, 3, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (3, 5));
+        assert_eq!(d.bounds(y), (3, 8));
+        check_explanations(&c, &d);
+
+        d.save_state();
+        d.set_ub(m, 4, Cause::Decision).unwrap();
+        d.set_lb(y, 5, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (3, 4));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [active] => z = max(x, y, w) and [active] => z = min(x, y, w), only propagated once `w` is present
+        let mut d = Domains::new();
+        let z = d.new_var(-1, 3);
+        let x = d.new_var(-2, 2);
+        let y = d.new_var(0, 2);
+        let w = d.new_var(-1, 3);
+        let pw = d.new_var(0, 1);
+        let active = d.new_var(0, 1);
+        for minimum in [false, true] {
+            let c = Max {
+                presences: vec![pw.geq(1)],
+                active: active.geq(1),
+                ..max(z, &[x, y, w], minimum)
+            };
+            let is_solution = |s: &[IntCst]| {
+                let values = s[1..4].iter().copied();
+                let expected = if minimum { values.min() } else { values.max() };
+                s[4] == 0 || s[5] == 0 || Some(s[0]) == expected
+            };
+            check_propagator(&c, &d, &[z, x, y, w, pw, active], is_solution, 100);
+        }
+    }
+}
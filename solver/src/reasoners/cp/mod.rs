@@ -4,15 +4,42 @@ use crate::backtrack::{Backtrack, DecLvl, ObsTrailCursor};
 use crate::collections::ref_store::RefVec;
 use crate::collections::*;
 use crate::core::state::{Cause, Domains, Event, Explanation, InferenceCause, InvalidUpdate};
-use crate::core::{IntCst, Lit, SignedVar, VarRef, INT_CST_MAX, INT_CST_MIN};
+use crate::core::{IntCst, Lit, LongCst, SignedVar, UpperBound, VarRef, INT_CST_MAX, INT_CST_MIN};
 use crate::create_ref_type;
 use crate::model::extensions::AssignmentExt;
+use crate::model::lang::extensional;
 use crate::model::lang::linear::NFLinearLeq;
+use crate::model::lang::nonlinear;
+use crate::model::lang::scheduling;
+use crate::reasoners::cp::abs::Abs;
+use crate::reasoners::cp::all_different::AllDifferent;
+use crate::reasoners::cp::cumulative::Cumulative;
+use crate::reasoners::cp::div::{Div, Mod};
+use crate::reasoners::cp::element::Element;
+use crate::reasoners::cp::max::Max;
+use crate::reasoners::cp::mul::Mul;
+use crate::reasoners::cp::no_overlap::NoOverlap;
+use crate::reasoners::cp::pseudo_boolean::PseudoBoolean;
+use crate::reasoners::cp::table::Table;
 use crate::reasoners::{Contradiction, ReasonerId, Theory};
 use anyhow::Context;
 use num_integer::{div_ceil, div_floor};
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+mod abs;
+mod all_different;
+mod cumulative;
+mod div;
+mod element;
+mod max;
+mod mul;
+mod no_overlap;
+mod pseudo_boolean;
+mod table;
+mod task;
+pub mod testing;
 
 // =========== Sum ===========
 
@@ -69,13 +96,13 @@ impl std::fmt::Display for LinearSumLeq {
 }
 
 impl LinearSumLeq {
-    fn get_lower_bound(&self, elem: SumElem, domains: &Domains) -> i64 {
+    fn get_lower_bound(&self, elem: SumElem, domains: &Domains) -> LongCst {
         let int_part = match elem.factor.cmp(&0) {
-            Ordering::Less => domains.ub(elem.var) as i64,
+            Ordering::Less => domains.ub(elem.var) as LongCst,
             Ordering::Equal => 0,
-            Ordering::Greater => domains.lb(elem.var) as i64,
+            Ordering::Greater => domains.lb(elem.var) as LongCst,
         }
-        .saturating_mul(elem.factor as i64);
+        .saturating_mul(elem.factor as LongCst);
 
         match domains.value(elem.lit) {
             Some(true) => int_part,
@@ -83,13 +110,13 @@ impl LinearSumLeq {
             None => 0.min(int_part),
         }
     }
-    fn get_upper_bound(&self, elem: SumElem, domains: &Domains) -> i64 {
+    fn get_upper_bound(&self, elem: SumElem, domains: &Domains) -> LongCst {
         let int_part = match elem.factor.cmp(&0) {
-            Ordering::Less => domains.lb(elem.var) as i64,
+            Ordering::Less => domains.lb(elem.var) as LongCst,
             Ordering::Equal => 0,
-            Ordering::Greater => domains.ub(elem.var) as i64,
+            Ordering::Greater => domains.ub(elem.var) as LongCst,
         }
-        .saturating_mul(elem.factor as i64);
+        .saturating_mul(elem.factor as LongCst);
 
         match domains.value(elem.lit) {
             Some(true) => int_part,
@@ -97,14 +124,14 @@ impl LinearSumLeq {
             None => 0.max(int_part),
         }
     }
-    fn set_ub(&self, elem: SumElem, ub: i64, domains: &mut Domains, cause: Cause) -> Result<bool, InvalidUpdate> {
+    fn set_ub(&self, elem: SumElem, ub: LongCst, domains: &mut Domains, cause: Cause) -> Result<bool, InvalidUpdate> {
         let lit = elem.lit;
         let var = elem.var;
 
         match elem.factor.cmp(&0) {
             Ordering::Less => {
-                let lb = div_ceil(ub, elem.factor as i64);
-                let lb = lb.clamp(INT_CST_MIN as i64, INT_CST_MAX as i64) as i32;
+                let lb = div_ceil(ub, elem.factor as LongCst);
+                let lb = lb.clamp(INT_CST_MIN as LongCst, INT_CST_MAX as LongCst) as IntCst;
 
                 // We need to enforce `lb <= var * lit`
                 // We have two cases to consider depending on the value of `lit` (which may not be fixed yet)
@@ -130,8 +157,8 @@ impl LinearSumLeq {
             }
             Ordering::Equal => unreachable!(),
             Ordering::Greater => {
-                let ub = div_floor(ub, elem.factor as i64);
-                let ub = ub.clamp(INT_CST_MIN as i64, INT_CST_MAX as i64) as i32;
+                let ub = div_floor(ub, elem.factor as LongCst);
+                let ub = ub.clamp(INT_CST_MIN as LongCst, INT_CST_MAX as LongCst) as IntCst;
 
                 // We need to enforce  `var * lit <= ub`
                 // 1) pos:  lit = 0  =>  0 <= ub
@@ -188,14 +215,16 @@ impl Propagator for LinearSumLeq {
     fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
         if domains.entails(self.active) {
             // constraint is active, propagate
-            let sum_lb: i64 = self
+            // all computations are saturating so that very large domains (e.g. with the default bounds
+            // of variables) do not result in an overflow
+            let sum_lb: LongCst = self
                 .elements
                 .iter()
                 .copied()
                 .filter(|e| !domains.entails(!e.lit))
                 .map(|e| self.get_lower_bound(e, domains))
-                .sum();
-            let f = (self.ub as i64) - sum_lb;
+                .fold(0, LongCst::saturating_add);
+            let f = (self.ub as LongCst).saturating_sub(sum_lb);
 
             if f < 0 {
                 // INCONSISTENT
@@ -208,8 +237,8 @@ impl Propagator for LinearSumLeq {
                 let lb = self.get_lower_bound(e, domains);
                 let ub = self.get_upper_bound(e, domains);
                 debug_assert!(lb <= ub);
-                if ub - lb > f {
-                    let new_ub = f + lb;
+                if ub.saturating_sub(lb) > f {
+                    let new_ub = f.saturating_add(lb);
                     match self.set_ub(e, new_ub, domains, cause) {
                         Ok(true) => {}  // domain updated
                         Ok(false) => {} // no-op
@@ -263,19 +292,73 @@ impl Propagator for LinearSumLeq {
     }
 }
 
+// ========== Views ===========
+
+/// Lower bound of a signed variable, i.e., the lower bound of `x` for `+x` and of `-x` for `-x`.
+fn lb(v: SignedVar, domains: &Domains) -> IntCst {
+    -domains.get_bound(-v).as_int()
+}
+
+/// Upper bound of a signed variable, i.e., the upper bound of `x` for `+x` and of `-x` for `-x`.
+fn ub(v: SignedVar, domains: &Domains) -> IntCst {
+    domains.get_bound(v).as_int()
+}
+
+/// Literal `v >= value`
+fn geq(v: SignedVar, value: IntCst) -> Lit {
+    (-v).with_upper_bound(UpperBound::ub(-value))
+}
+
+/// Literal `v <= value`
+fn leq(v: SignedVar, value: IntCst) -> Lit {
+    v.with_upper_bound(UpperBound::ub(value))
+}
+
 // ========== Constraint ===========
 
 create_ref_type!(PropagatorId);
 
-trait Propagator: Send {
+/// Cost of a propagator, that determines when it is triggered with respect to the other propagators.
+///
+/// Cheap propagators are all run to a fixed point before any expensive propagator is considered.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Priority {
+    #[default]
+    Cheap,
+    Expensive,
+}
+
+/// A propagator of the `Cp` reasoner, enforcing a single constraint.
+///
+/// The propagator declares the events it reacts to in [`Propagator::setup`] and is triggered whenever one of them occurs.
+/// All its propagations must be explainable: when asked, [`Propagator::explain`] must provide a set of literals,
+/// entailed when the inference was made, that imply the inferred literal under the constraint.
+/// The [`testing::check_propagator`] function allows checking a propagator against a brute-force enumeration
+/// of the solutions of its constraint.
+pub trait Propagator: Send {
+    /// Registers in `context` the events that should trigger the propagator, identified by `id`.
     fn setup(&self, id: PropagatorId, context: &mut Watches);
+
+    /// Propagates the constraint, using `cause` for all updates of the domains.
+    /// Returns a contradiction if the constraint cannot be satisfied in the current domains.
     fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction>;
+
+    /// Propagates the constraint when `event` is the only watched event that occurred since the last propagation.
+    /// The default implementation ignores the event and fully propagates the constraint.
     fn propagate_event(&self, _event: &Event, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
         self.propagate(domains, cause)
     }
 
+    /// Adds to `out_explanation` a set of literals that, together with the constraint, imply `literal`.
+    /// The domains are in the state in which `literal` was inferred, and all literals of the explanation must be entailed by them.
+    /// `literal` is `Lit::FALSE` when explaining a contradiction.
     fn explain(&self, literal: Lit, state: &Domains, out_explanation: &mut Explanation);
 
+    /// Cost of the propagator, cheap propagators being run first.
+    fn priority(&self) -> Priority {
+        Priority::Cheap
+    }
+
     fn clone_box(&self) -> Box<dyn Propagator>;
 }
 
@@ -301,26 +384,90 @@ impl<T: Propagator + 'static> From<T> for DynPropagator {
 
 // ========= CP =============
 
+/// Associates each propagator with the events that should trigger it.
 #[derive(Clone, Default)]
 pub struct Watches {
     propagations: HashMap<VarRef, Vec<PropagatorId>>,
+    /// Propagators to trigger when a literal becomes true, indexed by the signed variable of the literal.
+    lit_propagations: HashMap<SignedVar, Vec<(Lit, PropagatorId)>>,
     empty: [PropagatorId; 0],
 }
 
 impl Watches {
-    fn add_watch(&mut self, watched: VarRef, propagator_id: PropagatorId) {
+    /// Triggers the propagator on any change of one of the bounds of `watched`.
+    pub fn add_watch(&mut self, watched: VarRef, propagator_id: PropagatorId) {
         self.propagations
             .entry(watched)
             .or_insert_with(|| Vec::with_capacity(4))
             .push(propagator_id)
     }
 
+    /// Triggers the propagator only when `watched` becomes true.
+    pub fn add_lit_watch(&mut self, watched: Lit, propagator_id: PropagatorId) {
+        self.lit_propagations
+            .entry(watched.svar())
+            .or_insert_with(|| Vec::with_capacity(4))
+            .push((watched, propagator_id))
+    }
+
+    /// Removes all watches of the propagators whose identifier is greater or equal to `first`.
+    fn remove_propagators_from(&mut self, first: PropagatorId) {
+        for watchers in self.propagations.values_mut() {
+            watchers.retain(|&id| id < first);
+        }
+        for watchers in self.lit_propagations.values_mut() {
+            watchers.retain(|&(_, id)| id < first);
+        }
+    }
+
     fn get(&self, var_bound: VarRef) -> &[PropagatorId] {
         self.propagations
             .get(&var_bound)
             .map(|v| v.as_slice())
             .unwrap_or(&self.empty)
     }
+
+    /// Returns all propagators that should be triggered by the event.
+    fn triggered_by<'a>(&'a self, event: &'a Event) -> impl Iterator<Item = PropagatorId> + 'a {
+        let lit_watchers = self
+            .lit_propagations
+            .get(&event.affected_bound)
+            .into_iter()
+            .flatten()
+            .filter(|(lit, _)| event.makes_true(*lit))
+            .map(|(_, id)| *id);
+        self.get(event.affected_bound.variable())
+            .iter()
+            .copied()
+            .chain(lit_watchers)
+    }
+}
+
+/// Propagators awaiting propagation, with a FIFO queue for each priority.
+#[derive(Default)]
+struct PropagationQueue {
+    queues: [VecDeque<PropagatorId>; 2],
+    /// For each enqueued propagator, the event that triggered it if it is the only one.
+    triggers: HashMap<PropagatorId, Option<Event>>,
+}
+
+impl PropagationQueue {
+    fn push(&mut self, propagator: PropagatorId, trigger: Option<Event>, priority: Priority) {
+        match self.triggers.entry(propagator) {
+            Entry::Occupied(mut previous) => *previous.get_mut() = None,
+            Entry::Vacant(entry) => {
+                entry.insert(trigger);
+                self.queues[priority as usize].push_back(propagator);
+            }
+        }
+    }
+
+    /// Removes the first propagator of highest priority, together with the event that triggered it, if unique.
+    fn pop(&mut self) -> Option<(PropagatorId, Option<Event>)> {
+        let propagator = self.queues.iter_mut().find_map(|queue| queue.pop_front())?;
+        let trigger = self.triggers.remove(&propagator).flatten();
+        Some((propagator, trigger))
+    }
 }
 
 #[derive(Clone)]
@@ -366,12 +513,164 @@ impl Cp {
         self.add_propagator(propagator);
     }
 
-    fn add_propagator(&mut self, propagator: impl Into<DynPropagator>) {
+    /// Adds a constraint stating that all present variables must take distinct values, when `active` is true.
+    /// Each variable is associated with its presence literal: absent variables are ignored.
+    pub fn add_all_different(&mut self, vars: &[(VarRef, Lit)], active: Lit) {
+        let propagator = AllDifferent {
+            vars: vars.iter().map(|(var, _)| *var).collect(),
+            presences: vars.iter().map(|(_, prez)| *prez).collect(),
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a cumulative resource constraint that is only active when `active` is true.
+    pub fn add_cumulative(&mut self, cumulative: &scheduling::Cumulative, active: Lit) {
+        let propagator = Cumulative {
+            tasks: cumulative.tasks.clone(),
+            capacity: cumulative.capacity,
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a constraint that no two present tasks overlap, that is only active when `active` is true.
+    pub fn add_no_overlap(&mut self, no_overlap: &scheduling::NoOverlap, active: Lit) {
+        let propagator = NoOverlap {
+            tasks: no_overlap.tasks.clone(),
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a table constraint that is only active when `active` is true.
+    /// Each variable is associated with its presence literal: absent variables are ignored.
+    pub fn add_table(&mut self, table: &extensional::Table, presences: Vec<Lit>, active: Lit) {
+        let propagator = Table::new(table.vars.clone(), presences, table.tuples.clone(), active);
+        self.add_propagator(propagator);
+    }
+
+    /// Adds an element constraint `value = array[index]` that is only active when `active` is true.
+    pub fn add_element(
+        &mut self,
+        element: &extensional::Element,
+        index_presence: Lit,
+        value_presence: Lit,
+        active: Lit,
+    ) {
+        let propagator = Element {
+            array: element.array.clone(),
+            index: element.index,
+            index_presence,
+            value: element.value,
+            value_presence,
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a constraint `product = lhs * rhs` that is only active when `active` is true.
+    /// The constraint is ignored if one of the presence literals (of the product, lhs and rhs) is false.
+    pub fn add_mul(&mut self, mul: &nonlinear::Mul, presences: Vec<Lit>, active: Lit) {
+        let propagator = Mul {
+            product: mul.product,
+            lhs: mul.lhs,
+            rhs: mul.rhs,
+            presences,
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a constraint `quotient = numerator / denominator` that is only active when `active` is true.
+    /// The constraint is ignored if one of the presence literals (of the quotient and numerator) is false.
+    pub fn add_div(&mut self, div: &nonlinear::Div, presences: Vec<Lit>, active: Lit) {
+        let propagator = Div {
+            quotient: div.quotient,
+            numerator: div.numerator,
+            denominator: div.denominator,
+            presences,
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a constraint `remainder = numerator mod denominator` that is only active when `active` is true.
+    /// The constraint is ignored if one of the presence literals (of the remainder and numerator) is false.
+    pub fn add_mod(&mut self, m: &nonlinear::Mod, presences: Vec<Lit>, active: Lit) {
+        let propagator = Mod {
+            remainder: m.remainder,
+            numerator: m.numerator,
+            denominator: m.denominator,
+            presences,
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a constraint `value = min(vars)` or `value = max(vars)` that is only active when `active` is true.
+    /// The constraint is ignored if one of the presence literals (of the value and variables) is false.
+    pub fn add_min_max(&mut self, min_max: &nonlinear::MinMax, presences: Vec<Lit>, active: Lit) {
+        // the minimum is encoded as `-value = max(-vars)`
+        let view = |v: VarRef| {
+            if min_max.minimum {
+                SignedVar::minus(v)
+            } else {
+                SignedVar::plus(v)
+            }
+        };
+        let propagator = Max {
+            value: view(min_max.value),
+            vars: min_max.vars.iter().map(|&v| view(v)).collect(),
+            presences,
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a constraint `value = |var|` that is only active when `active` is true.
+    /// The constraint is ignored if one of the presence literals (of the value and variable) is false.
+    pub fn add_abs(&mut self, abs: &nonlinear::Abs, presences: Vec<Lit>, active: Lit) {
+        let propagator = Abs {
+            value: abs.value,
+            var: abs.var,
+            presences,
+            active,
+        };
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a pseudo-Boolean constraint (with strictly positive weights) that is only active when `active` is true.
+    pub fn add_pseudo_boolean(&mut self, pb: &crate::model::lang::pseudo_boolean::PseudoBoolean, active: Lit) {
+        let propagator = PseudoBoolean::new(pb.terms.iter().copied(), pb.upper_bound, active);
+        self.add_propagator(propagator);
+    }
+
+    /// Adds a propagator, that will be triggered on the events it registers in its setup.
+    /// Returns the identifier of the propagator, used as payload of the causes of its inferences.
+    pub fn add_propagator(&mut self, propagator: impl Propagator + 'static) -> PropagatorId {
         // TODO: handle validity scopes
-        let propagator = propagator.into();
+        let propagator = DynPropagator::from(propagator);
         let propagator_id = self.constraints.next_key();
         propagator.constraint.setup(propagator_id, &mut self.watches);
         self.constraints.set_next(propagator_id, propagator);
+        propagator_id
+    }
+
+    /// Returns the identifier that will be given to the next propagator.
+    pub(crate) fn next_propagator_id(&self) -> PropagatorId {
+        self.constraints.next_key()
+    }
+
+    /// Removes all propagators whose identifier is greater or equal to `first`, i.e., all propagators added
+    /// since `first` was returned by [`Cp::next_propagator_id`].
+    /// Must be called at the root, as the removed propagators can no longer explain their inferences.
+    pub(crate) fn remove_propagators_from(&mut self, first: PropagatorId) {
+        assert_eq!(self.saved, DecLvl::ROOT);
+        while self.constraints.next_key() > first {
+            self.constraints.pop();
+        }
+        self.watches.remove_propagators_from(first);
     }
 }
 
@@ -381,31 +680,34 @@ impl Theory for Cp {
     }
 
     fn propagate(&mut self, domains: &mut Domains) -> Result<(), Contradiction> {
-        // list of all propagators to trigger
-        let mut to_propagate = HashSet::with_capacity(64);
+        // propagators to trigger
+        let mut queue = PropagationQueue::default();
 
         // in first propagation, mark everything for propagation
         // NOte: this is might actually be trigger multiple times when going back to the root
         if self.saved == DecLvl::ROOT {
             for (id, p) in self.constraints.entries() {
-                to_propagate.insert(id);
+                queue.push(id, None, p.constraint.priority());
             }
         }
 
-        // add any propagator that watched a changed variable since last propagation
-        while let Some(event) = self.model_events.pop(domains.trail()).copied() {
-            let watchers = self.watches.get(event.affected_bound.variable());
-            for &watcher in watchers {
-                to_propagate.insert(watcher);
+        loop {
+            // add any propagator that watched a change since last propagation
+            while let Some(event) = self.model_events.pop(domains.trail()).copied() {
+                for watcher in self.watches.triggered_by(&event) {
+                    queue.push(watcher, Some(event), self.constraints[watcher].constraint.priority());
+                }
             }
-        }
-
-        for propagator in to_propagate {
+            let Some((propagator, trigger)) = queue.pop() else {
+                return Ok(());
+            };
             let constraint = self.constraints[propagator].constraint.as_ref();
             let cause = self.id.cause(propagator);
-            constraint.propagate(domains, cause)?;
+            match trigger {
+                Some(event) => constraint.propagate_event(&event, domains, cause)?,
+                None => constraint.propagate(domains, cause)?,
+            }
         }
-        Ok(())
     }
 
     fn explain(&mut self, literal: Lit, context: InferenceCause, state: &Domains, out_explanation: &mut Explanation) {
@@ -444,6 +746,8 @@ impl Backtrack for Cp {
 
 #[cfg(test)]
 mod tests {
+    use crate::backtrack::EventIndex;
+    use crate::core::state::{DirectOrigin, Origin};
     use crate::core::UpperBound;
 
     use super::*;
@@ -479,6 +783,33 @@ mod tests {
         assert_eq!(d.ub(v), ub);
     }
 
+    /// Checks that all inferences of the propagator in the domains can be explained by literals that
+    /// were entailed when the inference was made.
+    /// Each explanation is requested in the exact state in which the inference was made.
+    pub(super) fn check_explanations(propagator: &dyn Propagator, d: &Domains) {
+        let mut d = d.clone();
+        while let Some(event) = d.last_event().copied() {
+            let last = EventIndex::new(d.trail().len() - 1);
+            if d.trail().decision_level(last) < d.current_decision_level() {
+                d.restore_last();
+                continue;
+            }
+            let inferred = match d.undo_last_event() {
+                Origin::Direct(DirectOrigin::ExternalInference(_)) => event.new_literal(),
+                Origin::PresenceOfEmptyDomain(lit, DirectOrigin::ExternalInference(_)) => lit,
+                _ => continue,
+            };
+            let mut explanation = Explanation::new();
+            propagator.explain(inferred, &d, &mut explanation);
+            for l in explanation.lits {
+                assert!(
+                    d.entails(l),
+                    "Explanation of {inferred:?} contains {l:?} that is not entailed"
+                );
+            }
+        }
+    }
+
     /* ================================ Tests =============================== */
 
     #[test]
@@ -591,6 +922,48 @@ mod tests {
         check_bounds(&s, c, &d, 25, 25);
     }
 
+    #[test]
+    /// Tests on the constraint `1000*x + 1000*y <= MAX` with variables in `[0, MAX]`,
+    /// where the products do not fit in an `IntCst`
+    fn test_large_factors_constraint() {
+        let mut d = Domains::new();
+        let x = var(0, INT_CST_MAX, 1000, Lit::TRUE, &mut d);
+        let y = var(0, INT_CST_MAX, 1000, Lit::TRUE, &mut d);
+        let s = sum(vec![x, y], INT_CST_MAX, Lit::TRUE);
+
+        assert_eq!(s.get_upper_bound(x, &d), INT_CST_MAX as LongCst * 1000);
+        assert!(s.propagate(&mut d, Cause::Decision).is_ok());
+        check_bounds_var(x.var, &d, 0, INT_CST_MAX / 1000);
+        check_bounds_var(y.var, &d, 0, INT_CST_MAX / 1000);
+    }
+
+    #[test]
+    /// Tests that the sum of many large elements does not overflow, even in the wide integer type
+    fn test_saturating_sum_constraint() {
+        let mut d = Domains::new();
+        let elements: Vec<SumElem> = (0..100)
+            .map(|_| var(INT_CST_MIN, INT_CST_MAX, INT_CST_MAX, Lit::TRUE, &mut d))
+            .collect();
+        let s = sum(elements.clone(), 0, Lit::TRUE);
+        assert!(s.propagate(&mut d, Cause::Decision).is_ok());
+        for e in elements {
+            check_bounds_var(e.var, &d, INT_CST_MIN, INT_CST_MAX);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "i64")]
+    /// Tests on the constraint `x + y <= 10^12` with `y >= 4*10^11`, whose bounds do not fit on 32 bits
+    fn test_wide_domains_constraint() {
+        let mut d = Domains::new();
+        let x = var(0, 2_000_000_000_000, 1, Lit::TRUE, &mut d);
+        let y = var(400_000_000_000, 2_000_000_000_000, 1, Lit::TRUE, &mut d);
+        let s = sum(vec![x, y], 1_000_000_000_000, Lit::TRUE);
+        assert!(s.propagate(&mut d, Cause::Decision).is_ok());
+        check_bounds_var(x.var, &d, 0, 600_000_000_000);
+        check_bounds_var(y.var, &d, 400_000_000_000, 1_000_000_000_000);
+    }
+
     #[test]
     /// Tests on the constraint `2*x + y + 25 <= 10` with variables in `[-100, 100]` and literals != true
     fn test_literals_constraint() {
@@ -758,4 +1131,98 @@ mod tests {
         check_bounds(&s, x, &d, 0, 0);
         check_bounds(&s, c, &d, 0, 5);
     }
+
+    #[test]
+    /// Tests that cheap propagators are dequeued first, and that the trigger is only kept if it is unique
+    fn test_propagation_queue() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        d.set_lb(x, 1, Cause::Decision);
+        d.set_lb(x, 2, Cause::Decision);
+        let e1 = d.trail().events()[d.trail().len() - 2];
+        let e2 = d.trail().events()[d.trail().len() - 1];
+
+        let [p0, p1, p2] = [0usize, 1, 2].map(PropagatorId::from);
+        let mut queue = PropagationQueue::default();
+        queue.push(p0, Some(e1), Priority::Expensive);
+        queue.push(p1, Some(e1), Priority::Cheap);
+        queue.push(p2, Some(e1), Priority::Cheap);
+        queue.push(p1, Some(e2), Priority::Cheap);
+
+        assert_eq!(queue.pop().map(|(p, e)| (p.to_u32(), e.is_some())), Some((1, false)));
+        assert_eq!(queue.pop().map(|(p, e)| (p.to_u32(), e.is_some())), Some((2, true)));
+        assert_eq!(queue.pop().map(|(p, e)| (p.to_u32(), e.is_some())), Some((0, true)));
+        assert!(queue.pop().is_none());
+    }
+
+    /// Calls of `Successor` propagators: the variable they propagate, with the literal of their triggering event.
+    type Calls = std::sync::Arc<std::sync::Mutex<Vec<(VarRef, Option<Lit>)>>>;
+
+    /// Propagator of `from + 1 <= to` on lower bounds only, that records the literal of the event it was triggered by.
+    #[derive(Clone)]
+    struct Successor {
+        from: VarRef,
+        to: VarRef,
+        calls: Calls,
+    }
+
+    impl Propagator for Successor {
+        fn setup(&self, id: PropagatorId, context: &mut Watches) {
+            context.add_watch(self.from, id);
+        }
+        fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+            self.calls.lock().unwrap().push((self.to, None));
+            domains.set_lb(self.to, domains.lb(self.from) + 1, cause)?;
+            Ok(())
+        }
+        fn propagate_event(&self, event: &Event, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+            self.calls.lock().unwrap().push((self.to, Some(event.new_literal())));
+            domains.set_lb(self.to, domains.lb(self.from) + 1, cause)?;
+            Ok(())
+        }
+        fn explain(&self, literal: Lit, _state: &Domains, out_explanation: &mut Explanation) {
+            out_explanation.push(self.from.geq(literal.value()));
+        }
+        fn clone_box(&self) -> Box<dyn Propagator> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    /// Tests that a propagator triggered by a single event is given this event, and that propagation reaches a fixpoint
+    fn test_propagate_event() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let z = d.new_var(0, 10);
+        let calls = Calls::default();
+        let mut cp = Cp::new(ReasonerId::Cp);
+        for (from, to) in [(x, y), (y, z)] {
+            let calls = calls.clone();
+            cp.add_propagator(Successor { from, to, calls });
+        }
+        let take_calls = || std::mem::take(&mut *calls.lock().unwrap());
+
+        // at the root, all propagators are fully propagated
+        cp.propagate(&mut d).unwrap();
+        assert_eq!(take_calls(), [(y, None), (z, None)]);
+        assert_eq!((d.lb(y), d.lb(z)), (1, 2));
+
+        // a decision triggers the first propagator, whose inference triggers the second one
+        d.save_state();
+        cp.save_state();
+        d.set_lb(x, 5, Cause::Decision).unwrap();
+        cp.propagate(&mut d).unwrap();
+        assert_eq!(take_calls(), [(y, Some(x.geq(5))), (z, Some(y.geq(6)))]);
+        assert_eq!((d.lb(y), d.lb(z)), (6, 7));
+
+        // with several events since the last propagation, the propagator is fully propagated
+        d.save_state();
+        cp.save_state();
+        d.set_lb(x, 6, Cause::Decision).unwrap();
+        d.set_lb(x, 7, Cause::Decision).unwrap();
+        cp.propagate(&mut d).unwrap();
+        assert_eq!(take_calls(), [(y, None), (z, Some(y.geq(8)))]);
+        assert_eq!((d.lb(y), d.lb(z)), (8, 9));
+    }
 }
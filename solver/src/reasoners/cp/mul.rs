@@ -0,0 +1,306 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, LongCst, VarRef, INT_CST_MAX, INT_CST_MIN};
+use crate::reasoners::cp::{Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+use num_integer::{div_ceil, div_floor};
+
+fn bounds(var: VarRef, domains: &Domains) -> (LongCst, LongCst) {
+    let (lb, ub) = domains.bounds(var);
+    (lb as LongCst, ub as LongCst)
+}
+
+fn clamp(value: LongCst) -> IntCst {
+    value.clamp(INT_CST_MIN as LongCst, INT_CST_MAX as LongCst) as IntCst
+}
+
+/// Largest integer whose square is less than or equal to `value` (assumed non-negative).
+fn floor_sqrt(value: LongCst) -> LongCst {
+    let mut root = (value as f64).sqrt() as LongCst;
+    while root * root > value {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= value {
+        root += 1;
+    }
+    root
+}
+
+/// Smallest integer whose square is greater than or equal to `value` (assumed non-negative).
+fn ceil_sqrt(value: LongCst) -> LongCst {
+    let root = floor_sqrt(value);
+    if root * root == value {
+        root
+    } else {
+        root + 1
+    }
+}
+
+/// Constraint `product = lhs * rhs`.
+///
+/// The propagator maintains bounds consistency for the product, and restricts each factor to the bounds
+/// of the quotient of the product by the other factor.
+/// When both factors are the same variable, the propagator reasons on the square roots of the product bounds instead.
+/// All inferences are monotonic in the bounds of the three variables, which are all part of the explanation.
+#[derive(Clone, Debug)]
+pub(super) struct Mul {
+    pub product: VarRef,
+    pub lhs: VarRef,
+    pub rhs: VarRef,
+    /// Presence literals of the variables. The constraint is only propagated once they are all present.
+    pub presences: Vec<Lit>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl Mul {
+    fn vars(&self) -> [VarRef; 3] {
+        [self.product, self.lhs, self.rhs]
+    }
+
+    /// Restricts the bounds of the product to the ones of `lhs * rhs`.
+    fn propagate_product(&self, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let (lx, ux) = bounds(self.lhs, domains);
+        let (ly, uy) = bounds(self.rhs, domains);
+        let corners = [lx * ly, lx * uy, ux * ly, ux * uy];
+        let lb = clamp(*corners.iter().min().unwrap());
+        let ub = clamp(*corners.iter().max().unwrap());
+        let mut modified = domains.set_lb(self.product, lb, cause)?;
+        modified |= domains.set_ub(self.product, ub, cause)?;
+        Ok(modified)
+    }
+
+    /// Restricts the bounds of the `factor` to the ones of `product / other`.
+    fn propagate_factor(
+        &self,
+        factor: VarRef,
+        other: VarRef,
+        domains: &mut Domains,
+        cause: Cause,
+    ) -> Result<bool, Contradiction> {
+        let (lz, uz) = bounds(self.product, domains);
+        let (ly, uy) = bounds(other, domains);
+        let mut modified = false;
+        if ly > 0 || uy < 0 {
+            // the other factor has a fixed sign, the quotient is monotonic in both variables
+            let corners = [(lz, ly), (lz, uy), (uz, ly), (uz, uy)];
+            let lb = corners.iter().map(|&(z, y)| div_ceil(z, y)).min().unwrap();
+            let ub = corners.iter().map(|&(z, y)| div_floor(z, y)).max().unwrap();
+            modified |= domains.set_lb(factor, clamp(lb), cause)?;
+            modified |= domains.set_ub(factor, clamp(ub), cause)?;
+        } else if lz > 0 || uz < 0 {
+            // the product is not null, thus the other factor is not null and `|factor| <= |product|`
+            let max = lz.abs().max(uz.abs());
+            modified |= domains.set_lb(factor, clamp(-max), cause)?;
+            modified |= domains.set_ub(factor, clamp(max), cause)?;
+            if ly == 0 {
+                modified |= domains.set_lb(other, 1, cause)?;
+            }
+            if uy == 0 {
+                modified |= domains.set_ub(other, -1, cause)?;
+            }
+        }
+        Ok(modified)
+    }
+
+    /// Propagates the constraint `product = var * var`.
+    fn propagate_square(&self, var: VarRef, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let (lx, ux) = bounds(var, domains);
+        let squares = [lx * lx, ux * ux];
+        let lb = if lx <= 0 && 0 <= ux {
+            0
+        } else {
+            *squares.iter().min().unwrap()
+        };
+        let ub = *squares.iter().max().unwrap();
+        let mut modified = domains.set_lb(self.product, clamp(lb), cause)?;
+        modified |= domains.set_ub(self.product, clamp(ub), cause)?;
+
+        let (lz, uz) = bounds(self.product, domains);
+        // |var| <= sqrt(uz)
+        let max = floor_sqrt(uz);
+        modified |= domains.set_lb(var, clamp(-max), cause)?;
+        modified |= domains.set_ub(var, clamp(max), cause)?;
+        // |var| >= sqrt(lz)
+        let min = ceil_sqrt(lz.max(0));
+        let (lx, ux) = bounds(var, domains);
+        if lx > -min {
+            modified |= domains.set_lb(var, clamp(min), cause)?;
+        }
+        if ux < min {
+            modified |= domains.set_ub(var, clamp(-min), cause)?;
+        }
+        Ok(modified)
+    }
+}
+
+impl Propagator for Mul {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        for v in self.vars() {
+            context.add_watch(v, id);
+        }
+        for &prez in &self.presences {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) || !self.presences.iter().all(|&p| domains.entails(p)) {
+            return Ok(());
+        }
+        if self.lhs == self.rhs {
+            while self.propagate_square(self.lhs, domains, cause)? {}
+            return Ok(());
+        }
+        loop {
+            let mut modified = self.propagate_product(domains, cause)?;
+            modified |= self.propagate_factor(self.lhs, self.rhs, domains, cause)?;
+            modified |= self.propagate_factor(self.rhs, self.lhs, domains, cause)?;
+            if !modified {
+                return Ok(());
+            }
+        }
+    }
+
+    fn explain(&self, _literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        for l in self.presences.iter().chain([&self.active]) {
+            if *l != Lit::TRUE {
+                out_explanation.push(*l);
+            }
+        }
+        for v in self.vars() {
+            let (lb, ub) = domains.bounds(v);
+            out_explanation.push(v.geq(lb));
+            out_explanation.push(v.leq(ub));
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn mul(product: VarRef, lhs: VarRef, rhs: VarRef) -> Mul {
+        Mul {
+            product,
+            lhs,
+            rhs,
+            presences: vec![Lit::TRUE; 3],
+            active: Lit::TRUE,
+        }
+    }
+
+    #[test]
+    fn test_product_bounds() {
+        let mut d = Domains::new();
+        let x = d.new_var(-2, 3);
+        let y = d.new_var(-5, 4);
+        let z = d.new_var(-100, 100);
+        let c = mul(z, x, y);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(z), (-15, 12));
+
+        d.save_state();
+        d.set_lb(x, 1, Cause::Decision).unwrap();
+        d.set_lb(y, 2, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(z), (2, 12));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_factor_bounds() {
+        let mut d = Domains::new();
+        let x = d.new_var(-100, 100);
+        let y = d.new_var(2, 4);
+        let z = d.new_var(7, 13);
+        let c = mul(z, x, y);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        // x in [7/4, 13/2]
+        assert_eq!(d.bounds(x), (2, 6));
+        // z in [4, 24] and y in [7/6, 13/2]
+        assert_eq!(d.bounds(y), (2, 4));
+        assert_eq!(d.bounds(z), (7, 13));
+        check_explanations(&c, &d);
+
+        d.save_state();
+        d.set_ub(y, 2, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (4, 6));
+        assert_eq!(d.bounds(z), (8, 12));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_non_null_product() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(-100, 100);
+        let z = d.new_var(-20, -5);
+        let c = mul(z, x, y);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        // x cannot be null, and y must be negative
+        assert_eq!(d.bounds(x), (1, 10));
+        assert_eq!(d.bounds(y), (-20, -1));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_square() {
+        let mut d = Domains::new();
+        let x = d.new_var(-10, 10);
+        let z = d.new_var(5, 10);
+        let c = mul(z, x, x);
+        d.save_state();
+        d.set_lb(x, 0, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (3, 3));
+        assert_eq!(d.bounds(z), (9, 9));
+        check_explanations(&c, &d);
+
+        d.restore_last();
+        d.save_state();
+        // no integer square in [5, 8]
+        d.set_ub(z, 8, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_err());
+    }
+
+    #[test]
+    fn test_sqrt() {
+        for i in 0..1000 {
+            assert!(floor_sqrt(i).pow(2) <= i && (floor_sqrt(i) + 1).pow(2) > i);
+            assert!(ceil_sqrt(i).pow(2) >= i && (i == 0 || (ceil_sqrt(i) - 1).pow(2) < i));
+        }
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [active] => z = x * y, only propagated once `x` is present
+        let mut d = Domains::new();
+        let z = d.new_var(-5, 5);
+        let x = d.new_var(-2, 2);
+        let y = d.new_var(-2, 3);
+        let px = d.new_var(0, 1);
+        let active = d.new_var(0, 1);
+        let c = Mul {
+            presences: vec![px.geq(1), Lit::TRUE, Lit::TRUE],
+            active: active.geq(1),
+            ..mul(z, x, y)
+        };
+        let is_solution = |s: &[IntCst]| s[3] == 0 || s[4] == 0 || s[0] == s[1] * s[2];
+        check_propagator(&c, &d, &[z, x, y, px, active], is_solution, 300);
+
+        // z = x * x
+        let c = mul(z, x, x);
+        check_propagator(&c, &d, &[z, x], |s| s[0] == s[1] * s[1], 100);
+    }
+}
@@ -0,0 +1,801 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, LongCst};
+use crate::model::lang::scheduling::Task;
+use crate::reasoners::cp::task::TaskView;
+use crate::reasoners::cp::{Priority, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+
+/// Value used as the earliest completion time of an empty set of tasks.
+const NEG_INF: LongCst = LongCst::MIN / 4;
+
+/// Node of a Θ-Λ-tree, summarizing the tasks of its subtree.
+#[derive(Copy, Clone, Debug)]
+struct Node {
+    /// Total duration of the tasks in Θ
+    duration: LongCst,
+    /// Earliest completion time of the tasks in Θ
+    ect: LongCst,
+    /// Maximal total duration of the tasks in Θ, with at most one task of Λ
+    gray_duration: LongCst,
+    /// Maximal earliest completion time of the tasks in Θ, with at most one task of Λ
+    gray_ect: LongCst,
+    /// Task of Λ responsible for `gray_duration`, if any
+    responsible_duration: Option<usize>,
+    /// Task of Λ responsible for `gray_ect`, if any
+    responsible_ect: Option<usize>,
+}
+
+impl Node {
+    const EMPTY: Node = Node {
+        duration: 0,
+        ect: NEG_INF,
+        gray_duration: 0,
+        gray_ect: NEG_INF,
+        responsible_duration: None,
+        responsible_ect: None,
+    };
+
+    fn white(est: LongCst, duration: LongCst) -> Node {
+        Node {
+            duration,
+            ect: est + duration,
+            gray_duration: duration,
+            gray_ect: est + duration,
+            responsible_duration: None,
+            responsible_ect: None,
+        }
+    }
+
+    fn gray(task: usize, est: LongCst, duration: LongCst) -> Node {
+        Node {
+            duration: 0,
+            ect: NEG_INF,
+            gray_duration: duration,
+            gray_ect: est + duration,
+            responsible_duration: Some(task),
+            responsible_ect: Some(task),
+        }
+    }
+
+    fn combine(l: &Node, r: &Node) -> Node {
+        let (gray_duration, responsible_duration) = if l.gray_duration + r.duration >= l.duration + r.gray_duration {
+            (l.gray_duration + r.duration, l.responsible_duration)
+        } else {
+            (l.duration + r.gray_duration, r.responsible_duration)
+        };
+        let (gray_ect, responsible_ect) =
+            if r.gray_ect >= l.ect + r.gray_duration && r.gray_ect >= l.gray_ect + r.duration {
+                (r.gray_ect, r.responsible_ect)
+            } else if l.ect + r.gray_duration >= l.gray_ect + r.duration {
+                (l.ect + r.gray_duration, r.responsible_duration)
+            } else {
+                (l.gray_ect + r.duration, l.responsible_ect)
+            };
+        Node {
+            duration: l.duration + r.duration,
+            ect: r.ect.max(l.ect + r.duration),
+            gray_duration,
+            gray_ect,
+            responsible_duration,
+            responsible_ect,
+        }
+    }
+}
+
+/// Θ-Λ-tree (Vilím, 2008): a balanced binary tree whose leaves are the tasks ordered by earliest start time.
+/// Each task is either excluded, in the set Θ (white) or in the set Λ (gray).
+///
+/// After each update, in `O(log n)`, the tree gives the earliest completion time of Θ, as well as the one of Θ
+/// extended with at most one task of Λ.
+struct ThetaLambdaTree {
+    /// Index of the leaf of each task
+    leaves: Vec<usize>,
+    /// Earliest start time and duration of each task
+    tasks: Vec<(LongCst, LongCst)>,
+    nodes: Vec<Node>,
+}
+
+impl ThetaLambdaTree {
+    /// Creates a new tree with tasks of the given earliest start times and durations, all of them being
+    /// initially excluded.
+    fn new(est: &[IntCst], duration: &[IntCst]) -> Self {
+        let size = est.len().next_power_of_two();
+        let mut order: Vec<usize> = (0..est.len()).collect();
+        order.sort_by_key(|&t| est[t]);
+        let mut leaves = vec![0; est.len()];
+        for (rank, &t) in order.iter().enumerate() {
+            leaves[t] = size + rank;
+        }
+        ThetaLambdaTree {
+            leaves,
+            tasks: (0..est.len())
+                .map(|t| (est[t] as LongCst, duration[t] as LongCst))
+                .collect(),
+            nodes: vec![Node::EMPTY; 2 * size],
+        }
+    }
+
+    fn set_leaf(&mut self, task: usize, node: Node) {
+        let mut i = self.leaves[task];
+        self.nodes[i] = node;
+        while i > 1 {
+            i /= 2;
+            self.nodes[i] = Node::combine(&self.nodes[2 * i], &self.nodes[2 * i + 1]);
+        }
+    }
+
+    /// Adds the task to Θ
+    fn add(&mut self, task: usize) {
+        let (est, duration) = self.tasks[task];
+        self.set_leaf(task, Node::white(est, duration));
+    }
+
+    /// Adds the task to Λ, removing it from Θ if necessary.
+    fn add_gray(&mut self, task: usize) {
+        let (est, duration) = self.tasks[task];
+        self.set_leaf(task, Node::gray(task, est, duration));
+    }
+
+    /// Removes the task from both Θ and Λ.
+    fn remove(&mut self, task: usize) {
+        self.set_leaf(task, Node::EMPTY);
+    }
+
+    /// Returns true if the task is in Θ.
+    fn contains(&self, task: usize) -> bool {
+        self.nodes[self.leaves[task]].duration > 0
+    }
+
+    /// Earliest completion time of Θ
+    fn ect(&self) -> LongCst {
+        self.nodes.get(1).map_or(NEG_INF, |root| root.ect)
+    }
+
+    /// Maximal earliest completion time of Θ extended with at most one task of Λ.
+    fn gray_ect(&self) -> LongCst {
+        self.nodes.get(1).map_or(NEG_INF, |root| root.gray_ect)
+    }
+
+    /// Task of Λ responsible for `gray_ect()`, if any.
+    fn responsible_gray(&self) -> Option<usize> {
+        self.nodes.get(1).and_then(|root| root.responsible_ect)
+    }
+}
+
+/// Bounds of a set of tasks, read once before applying a propagation rule.
+struct Bounds {
+    est: Vec<IntCst>,
+    lst: Vec<IntCst>,
+    duration: Vec<IntCst>,
+    present: Vec<bool>,
+}
+
+impl Bounds {
+    fn new(tasks: &[TaskView], domains: &Domains) -> Self {
+        Bounds {
+            est: tasks.iter().map(|t| t.est(domains)).collect(),
+            lst: tasks.iter().map(|t| t.lst(domains)).collect(),
+            duration: tasks.iter().map(|t| t.duration).collect(),
+            present: tasks.iter().map(|t| domains.entails(t.presence)).collect(),
+        }
+    }
+
+    fn ect(&self, i: usize) -> IntCst {
+        self.est[i] + self.duration[i]
+    }
+
+    fn lct(&self, i: usize) -> IntCst {
+        self.lst[i] + self.duration[i]
+    }
+}
+
+/// A subset `{ t | est(t) >= e }` of a set of tasks.
+struct TaskInterval {
+    /// Lower bound on the earliest start time of all tasks in the subset
+    est: IntCst,
+    duration: IntCst,
+    tasks: Vec<TaskView>,
+}
+
+impl TaskInterval {
+    /// Returns the smallest subset `{ t | est(t) >= e }` of the given tasks that satisfies `pred(e, duration)`,
+    /// where `duration` is the total duration of the subset.
+    fn find(tasks: &[TaskView], domains: &Domains, pred: impl Fn(IntCst, IntCst) -> bool) -> Option<TaskInterval> {
+        let mut tasks = tasks.to_vec();
+        tasks.sort_by_key(|t| -t.est(domains));
+        let mut duration = 0;
+        for (i, task) in tasks.iter().enumerate() {
+            let est = task.est(domains);
+            duration += task.duration;
+            if pred(est, duration) {
+                tasks.truncate(i + 1);
+                return Some(TaskInterval { est, duration, tasks });
+            }
+        }
+        None
+    }
+
+    /// Earliest completion time of the set.
+    fn ect(&self) -> IntCst {
+        self.est + self.duration
+    }
+}
+
+/// Unary resource: no two present tasks can overlap in time.
+///
+/// The propagator implements the `O(n log n)` algorithms of Vilím for overload checking, edge-finding,
+/// detectable precedences and not-last, each of them being applied in both time directions (which gives
+/// the not-first rule from the not-last one).
+/// Tasks whose presence is not known are only considered as candidates for being pushed and are made absent if they cannot
+/// be scheduled.
+///
+/// Explanations are recomputed from the state of the domains when the inference was made: each inference is justified
+/// by a set of present tasks that must all execute before (or after) the updated task.
+#[derive(Clone, Debug)]
+pub(super) struct NoOverlap {
+    pub tasks: Vec<Task>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+}
+
+impl NoOverlap {
+    /// Returns views of all tasks that actually use the resource.
+    fn views(&self, forward: bool) -> impl Iterator<Item = TaskView> + '_ {
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.duration > 0)
+            .map(move |(id, t)| TaskView::new(t, id, forward))
+    }
+
+    /// Returns the views of all tasks that are not known to be absent.
+    fn candidates(&self, forward: bool, domains: &Domains) -> Vec<TaskView> {
+        self.views(forward).filter(|t| !domains.entails(!t.presence)).collect()
+    }
+
+    /// Returns the views of all present tasks, excluding the one with the given id.
+    fn present(&self, forward: bool, excluded: usize, domains: &Domains) -> Vec<TaskView> {
+        self.views(forward)
+            .filter(|t| t.id != excluded && domains.entails(t.presence))
+            .collect()
+    }
+
+    fn push_active(&self, out_explanation: &mut Explanation) {
+        if self.active != Lit::TRUE {
+            out_explanation.push(self.active);
+        }
+    }
+
+    /// Pushes the presence of a task whose start was updated, unless it is implied by the presence of its start
+    /// variable: the bounds of the start are only constrained when the task is present.
+    fn explain_presence(&self, task: &TaskView, domains: &Domains, out_explanation: &mut Explanation) {
+        if task.presence != Lit::TRUE && task.presence != domains.presence(task.start.variable()) {
+            out_explanation.push(task.presence);
+        }
+    }
+
+    /// Pushes the literals stating that all tasks of the set are present and start in `[est, lst]`,
+    /// where `lst` is given for each task.
+    fn explain_set(&self, set: &TaskInterval, lst: impl Fn(&TaskView) -> IntCst, out_explanation: &mut Explanation) {
+        for task in &set.tasks {
+            out_explanation.push(task.starts_at_or_after(set.est));
+            out_explanation.push(task.starts_at_or_before(lst(task)));
+            if task.presence != Lit::TRUE {
+                out_explanation.push(task.presence);
+            }
+        }
+    }
+
+    /// Overload checking and edge-finding.
+    ///
+    /// Present tasks are processed by decreasing latest completion time `L`, Θ being the present tasks with
+    /// `lct <= L`. If the tasks of Θ cannot be executed before `L`, the resource is overloaded.
+    /// If a task `i` (not in Θ) cannot be executed with Θ before `L`, then it must be executed after all tasks
+    /// of Θ, i.e., after `ect(Θ)`.
+    fn edge_finding(&self, tasks: &[TaskView], domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let bounds = Bounds::new(tasks, domains);
+        let mut tree = ThetaLambdaTree::new(&bounds.est, &bounds.duration);
+        let mut by_lct = Vec::with_capacity(tasks.len());
+        for i in 0..tasks.len() {
+            if bounds.present[i] {
+                tree.add(i);
+                by_lct.push(i);
+            } else {
+                tree.add_gray(i);
+            }
+        }
+        by_lct.sort_by_key(|&i| -bounds.lct(i));
+
+        let mut updates = Vec::new();
+        for &j in &by_lct {
+            let lct = bounds.lct(j);
+            if tree.ect() > lct as LongCst {
+                let theta: Vec<TaskView> = by_lct
+                    .iter()
+                    .filter(|&&i| tree.contains(i))
+                    .map(|&i| tasks[i])
+                    .collect();
+                let overloaded = TaskInterval::find(&theta, domains, |est, dur| est + dur > lct)
+                    .expect("No overloaded set of tasks");
+                let mut explanation = Explanation::with_capacity(overloaded.tasks.len() * 3 + 1);
+                self.push_active(&mut explanation);
+                self.explain_set(&overloaded, |t| lct - t.duration, &mut explanation);
+                return Err(Contradiction::Explanation(explanation));
+            }
+            while tree.gray_ect() > lct as LongCst {
+                let i = tree.responsible_gray().expect("No responsible task");
+                let ect = tree.ect() as IntCst;
+                if ect > bounds.est[i] {
+                    updates.push((i, ect));
+                }
+                tree.remove(i);
+            }
+            tree.add_gray(j);
+        }
+
+        let mut modified = false;
+        for (i, est) in updates {
+            modified |= tasks[i].set_est(est, domains, cause)?;
+        }
+        Ok(modified)
+    }
+
+    /// Detectable precedences: if a task `i` cannot complete before the latest start time of a present task `j`,
+    /// then `j` must precede `i`. The task `i` is then pushed after the earliest completion time of all its
+    /// detectable predecessors.
+    fn detectable_precedences(
+        &self,
+        tasks: &[TaskView],
+        domains: &mut Domains,
+        cause: Cause,
+    ) -> Result<bool, Contradiction> {
+        let bounds = Bounds::new(tasks, domains);
+        let mut tree = ThetaLambdaTree::new(&bounds.est, &bounds.duration);
+        let mut by_ect: Vec<usize> = (0..tasks.len()).collect();
+        by_ect.sort_by_key(|&i| bounds.ect(i));
+        let mut by_lst: Vec<usize> = (0..tasks.len()).filter(|&i| bounds.present[i]).collect();
+        by_lst.sort_by_key(|&i| bounds.lst[i]);
+
+        let mut updates = Vec::new();
+        let mut next = 0;
+        for &i in &by_ect {
+            let ect = bounds.ect(i);
+            while next < by_lst.len() && ect > bounds.lst[by_lst[next]] {
+                tree.add(by_lst[next]);
+                next += 1;
+            }
+            let in_tree = tree.contains(i);
+            if in_tree {
+                tree.remove(i);
+            }
+            let predecessors_ect = tree.ect();
+            if predecessors_ect > bounds.est[i] as LongCst {
+                updates.push((i, predecessors_ect as IntCst));
+            }
+            if in_tree {
+                tree.add(i);
+            }
+        }
+
+        let mut modified = false;
+        for (i, est) in updates {
+            modified |= tasks[i].set_est(est, domains, cause)?;
+        }
+        Ok(modified)
+    }
+
+    /// Not-last: if a task `i` cannot be executed after a set Ω of present tasks, then it must end
+    /// before the latest start time of at least one of them.
+    fn not_last(&self, tasks: &[TaskView], domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let bounds = Bounds::new(tasks, domains);
+        let mut tree = ThetaLambdaTree::new(&bounds.est, &bounds.duration);
+        let mut by_lct: Vec<usize> = (0..tasks.len()).collect();
+        by_lct.sort_by_key(|&i| bounds.lct(i));
+        let mut by_lst: Vec<usize> = (0..tasks.len()).filter(|&i| bounds.present[i]).collect();
+        by_lst.sort_by_key(|&i| bounds.lst[i]);
+
+        let mut updates = Vec::new();
+        let mut next = 0;
+        for &i in &by_lct {
+            let lct = bounds.lct(i);
+            while next < by_lst.len() && lct > bounds.lst[by_lst[next]] {
+                tree.add(by_lst[next]);
+                next += 1;
+            }
+            let in_tree = tree.contains(i);
+            if in_tree {
+                tree.remove(i);
+            }
+            if tree.ect() > bounds.lst[i] as LongCst {
+                // task with the largest latest start time in Θ \ {i}
+                let last = by_lst[..next].iter().rev().find(|&&j| j != i);
+                if let Some(&j) = last {
+                    let new_lct = bounds.lst[j];
+                    if new_lct < lct {
+                        updates.push((i, new_lct - tasks[i].duration));
+                    }
+                }
+            }
+            if in_tree {
+                tree.add(i);
+            }
+        }
+
+        let mut modified = false;
+        for (i, lst) in updates {
+            modified |= tasks[i].set_lst(lst, domains, cause)?;
+        }
+        Ok(modified)
+    }
+
+    fn propagate_direction(&self, forward: bool, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        // tasks made absent by one of the rules are ignored by the following ones
+        let tasks = self.candidates(forward, domains);
+        let mut modified = self.edge_finding(&tasks, domains, cause)?;
+        modified |= self.detectable_precedences(&tasks, domains, cause)?;
+        modified |= self.not_last(&tasks, domains, cause)?;
+        Ok(modified)
+    }
+
+    /// Explains that the task must start at or after `k`, because a set of present tasks must be executed before it.
+    /// Returns false if no such set exists.
+    fn explain_est(
+        &self,
+        task: &TaskView,
+        k: IntCst,
+        forward: bool,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) -> bool {
+        let others = self.present(forward, task.id, domains);
+
+        // detectable precedences: all tasks whose latest start time is before the earliest completion time of the task
+        let ect = task.ect(domains);
+        let detectable: Vec<TaskView> = others.iter().filter(|t| t.lst(domains) < ect).copied().collect();
+        if let Some(predecessors) = TaskInterval::find(&detectable, domains, |est, dur| est + dur >= k) {
+            let max_lst = predecessors.tasks.iter().map(|t| t.lst(domains)).max().unwrap();
+            self.push_active(out_explanation);
+            out_explanation.push(task.starts_at_or_after(max_lst + 1 - task.duration));
+            self.explain_set(&predecessors, |t| t.lst(domains), out_explanation);
+            return true;
+        }
+
+        // edge-finding: the task cannot be executed with a set Ω of tasks before `lct(Ω)`, it must thus be executed
+        // after all tasks that complete before `lct(Ω)`
+        let task_est = task.est(domains);
+        let mut lcts: Vec<IntCst> = others.iter().map(|t| t.lct(domains)).collect();
+        lcts.sort_unstable();
+        lcts.dedup();
+        for lct in lcts {
+            let theta: Vec<TaskView> = others.iter().filter(|t| t.lct(domains) <= lct).copied().collect();
+            let Some(predecessors) = TaskInterval::find(&theta, domains, |est, dur| est + dur >= k) else {
+                continue;
+            };
+            let overloaded = TaskInterval::find(&theta, domains, |est, dur| {
+                est.min(task_est) + dur + task.duration > lct
+            });
+            if let Some(mut overloaded) = overloaded {
+                overloaded.est = overloaded.est.min(task_est);
+                self.push_active(out_explanation);
+                out_explanation.push(task.starts_at_or_after(overloaded.est));
+                self.explain_set(&overloaded, |t| lct - t.duration, out_explanation);
+                self.explain_set(&predecessors, |t| lct - t.duration, out_explanation);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Explains that the task must start at or before `k`, because it cannot be executed after a set Ω of present tasks,
+    /// all of them starting at or before `k + duration`.
+    /// Returns false if no such set exists.
+    fn explain_lst(
+        &self,
+        task: &TaskView,
+        k: IntCst,
+        forward: bool,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) -> bool {
+        let lct = k + task.duration;
+        let lst = task.lst(domains);
+        let candidates: Vec<TaskView> = self
+            .present(forward, task.id, domains)
+            .into_iter()
+            .filter(|t| t.lst(domains) <= lct)
+            .collect();
+        if let Some(set) = TaskInterval::find(&candidates, domains, |est, dur| est + dur > lst) {
+            self.push_active(out_explanation);
+            out_explanation.push(task.starts_at_or_before(set.ect() - 1));
+            self.explain_set(&set, |_| lct, out_explanation);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Propagator for NoOverlap {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        for task in &self.tasks {
+            context.add_watch(task.start_var(), id);
+            if task.presence != Lit::TRUE {
+                context.add_watch(task.presence.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) {
+            return Ok(());
+        }
+        loop {
+            let forward_modified = self.propagate_direction(true, domains, cause)?;
+            let backward_modified = self.propagate_direction(false, domains, cause)?;
+            if !forward_modified && !backward_modified {
+                return Ok(());
+            }
+        }
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        for forward in [true, false] {
+            for task in self.views(forward) {
+                if literal.svar() == -task.start {
+                    // literal states that the task starts at or after `k`
+                    let k = -literal.bound_value().as_int() + task.offset;
+                    if self.explain_est(&task, k, forward, domains, out_explanation) {
+                        self.explain_presence(&task, domains, out_explanation);
+                        return;
+                    }
+                } else if literal.svar() == task.start {
+                    // literal states that the task starts at or before `k`
+                    let k = literal.bound_value().as_int() + task.offset;
+                    if self.explain_lst(&task, k, forward, domains, out_explanation) {
+                        self.explain_presence(&task, domains, out_explanation);
+                        return;
+                    }
+                } else if literal == !task.presence {
+                    // the task is absent because it cannot start in its time window
+                    let lst = task.lst(domains);
+                    if self.explain_est(&task, lst + 1, forward, domains, out_explanation) {
+                        out_explanation.push(task.starts_at_or_before(lst));
+                        return;
+                    }
+                    let est = task.est(domains);
+                    if self.explain_lst(&task, est - 1, forward, domains, out_explanation) {
+                        out_explanation.push(task.starts_at_or_after(est));
+                        return;
+                    }
+                }
+            }
+        }
+        panic!("Cannot explain the literal {literal:?}")
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Expensive
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::core::VarRef;
+    use crate::model::lang::IAtom;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn no_overlap(tasks: &[(VarRef, IntCst, Lit)]) -> NoOverlap {
+        NoOverlap {
+            tasks: tasks
+                .iter()
+                .map(|&(start, duration, presence)| Task {
+                    start: start.into(),
+                    duration,
+                    demand: 1,
+                    presence,
+                })
+                .collect(),
+            active: Lit::TRUE,
+        }
+    }
+
+    #[test]
+    fn test_theta_lambda_tree() {
+        let mut tree = ThetaLambdaTree::new(&[0, 2, 11, 5], &[5, 6, 2, 4]);
+        assert_eq!(tree.ect(), NEG_INF);
+        tree.add(0);
+        tree.add(1);
+        assert_eq!(tree.ect(), 11);
+        tree.add(2);
+        assert_eq!(tree.ect(), 13);
+        tree.add_gray(3);
+        assert_eq!(tree.ect(), 13);
+        assert_eq!(tree.gray_ect(), 17);
+        assert_eq!(tree.responsible_gray(), Some(3));
+        tree.remove(1);
+        assert_eq!(tree.ect(), 13);
+        assert_eq!(tree.gray_ect(), 13);
+    }
+
+    #[test]
+    fn test_overload() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 10);
+        let b = d.new_var(0, 10);
+        let c = d.new_var(0, 10);
+        let ctr = no_overlap(&[(a, 3, Lit::TRUE), (b, 3, Lit::TRUE), (c, 3, Lit::TRUE)]);
+        assert!(ctr.propagate(&mut d, cause()).is_ok());
+        d.save_state();
+        d.set_ub(a, 4, Cause::Decision).unwrap();
+        d.set_ub(b, 4, Cause::Decision).unwrap();
+        d.set_ub(c, 5, Cause::Decision).unwrap();
+        match ctr.propagate(&mut d, cause()) {
+            Err(Contradiction::Explanation(expl)) => {
+                assert!(expl.lits.iter().all(|&l| d.entails(l)));
+            }
+            _ => panic!("Expected an overload"),
+        }
+    }
+
+    #[test]
+    fn test_edge_finding() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 6);
+        let b = d.new_var(1, 7);
+        let c = d.new_var(0, 20);
+        let ctr = no_overlap(&[(a, 5, Lit::TRUE), (b, 4, Lit::TRUE), (c, 3, Lit::TRUE)]);
+        d.save_state();
+        assert!(ctr.edge_finding(&ctr.candidates(true, &d), &mut d, cause()).is_ok());
+        // `c` cannot be executed before the end of both `a` and `b`
+        assert_eq!(d.bounds(c), (9, 20));
+        check_explanations(&ctr, &d);
+    }
+
+    #[test]
+    fn test_detectable_precedences() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 2);
+        let b = d.new_var(3, 10);
+        let c = d.new_var(1, 10);
+        let ctr = no_overlap(&[(a, 4, Lit::TRUE), (b, 1, Lit::TRUE), (c, 3, Lit::TRUE)]);
+        d.save_state();
+        assert!(ctr
+            .detectable_precedences(&ctr.candidates(true, &d), &mut d, cause())
+            .is_ok());
+        // neither `b` nor `c` can complete before the latest start of `a`, they must be executed after it
+        assert_eq!(d.bounds(b), (4, 10));
+        assert_eq!(d.bounds(c), (4, 10));
+        check_explanations(&ctr, &d);
+    }
+
+    #[test]
+    fn test_not_last() {
+        let mut d = Domains::new();
+        let a = d.new_var(3, 5);
+        let b = d.new_var(3, 6);
+        let c = d.new_var(0, 5);
+        let ctr = no_overlap(&[(a, 2, Lit::TRUE), (b, 2, Lit::TRUE), (c, 3, Lit::TRUE)]);
+        d.save_state();
+        assert!(ctr.not_last(&ctr.candidates(true, &d), &mut d, cause()).is_ok());
+        // `c` cannot be executed after both `a` and `b`, and must thus end before the latest start of one of them
+        assert_eq!(d.bounds(c), (0, 3));
+        check_explanations(&ctr, &d);
+    }
+
+    #[test]
+    fn test_not_first() {
+        let mut d = Domains::new();
+        let a = d.new_var(2, 4);
+        let b = d.new_var(1, 4);
+        let c = d.new_var(2, 7);
+        let ctr = no_overlap(&[(a, 2, Lit::TRUE), (b, 2, Lit::TRUE), (c, 3, Lit::TRUE)]);
+        d.save_state();
+        assert!(ctr.not_last(&ctr.candidates(false, &d), &mut d, cause()).is_ok());
+        // `c` cannot be executed before both `a` and `b`, and must thus start after the earliest end of one of them
+        assert_eq!(d.bounds(c), (3, 7));
+        check_explanations(&ctr, &d);
+    }
+
+    #[test]
+    fn test_optional_tasks() {
+        let mut d = Domains::new();
+        let pc = d.new_var(0, 1).geq(1);
+        let pd = d.new_var(0, 1).geq(1);
+        let a = d.new_var(0, 0);
+        let b = d.new_var(5, 5);
+        let c = d.new_optional_var(0, 7, pc);
+        let e = d.new_var(0, 7);
+        let ctr = no_overlap(&[(a, 5, Lit::TRUE), (b, 5, Lit::TRUE), (c, 3, pc), (e, 3, pd)]);
+        d.save_state();
+        assert!(ctr.propagate(&mut d, cause()).is_ok());
+        // neither `c` nor `e` can fit in the schedule
+        assert!(d.entails(!pc));
+        assert!(d.entails(!pd));
+        // start time of `e` is independent of the presence of the task and was not modified
+        assert_eq!(d.bounds(e), (0, 7));
+        check_explanations(&ctr, &d);
+    }
+
+    #[test]
+    fn test_optional_tasks_ignored() {
+        let mut d = Domains::new();
+        let pa = d.new_var(0, 1).geq(1);
+        let a = d.new_optional_var(0, 0, pa);
+        let b = d.new_var(0, 10);
+        let ctr = no_overlap(&[(a, 5, pa), (b, 5, Lit::TRUE)]);
+        // `a` may be absent, `b` is not constrained
+        assert!(ctr.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(b), (0, 10));
+
+        d.save_state();
+        d.set(pa, Cause::Decision).unwrap();
+        assert!(ctr.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(b), (5, 10));
+        check_explanations(&ctr, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 6);
+        let b = d.new_var(0, 6);
+        let c = d.new_var(0, 6);
+        let e = d.new_var(0, 6);
+        // presence of the optional tasks, the start variables being always present
+        let pc = d.new_var(0, 1);
+        let pe = d.new_var(0, 1);
+        let durations = [3, 2, 3, 2];
+        let ctr = no_overlap(&[
+            (a, durations[0], Lit::TRUE),
+            (b, durations[1], Lit::TRUE),
+            (c, durations[2], pc.geq(1)),
+            (e, durations[3], pe.geq(1)),
+        ]);
+        let is_solution = |values: &[IntCst]| {
+            let present = [true, true, values[4] == 1, values[5] == 1];
+            (0..4).all(|i| {
+                (0..i).all(|j| {
+                    !present[i]
+                        || !present[j]
+                        || values[i] + durations[i] <= values[j]
+                        || values[j] + durations[j] <= values[i]
+                })
+            })
+        };
+        check_propagator(&ctr, &d, &[a, b, c, e, pc, pe], is_solution, 200);
+    }
+
+    #[test]
+    fn test_shifted_tasks() {
+        let mut d = Domains::new();
+        let a = d.new_var(0, 6);
+        let b = d.new_var(0, 6);
+        // tasks over `[a, a + 2)`, `[a + 3, a + 5)` and `[b + 1, b + 4)`
+        let ctr = NoOverlap {
+            tasks: vec![
+                Task::new(a, 2, 1),
+                Task::spanning(IAtom::from(a) + 3, IAtom::from(a) + 5, 1, Lit::TRUE).unwrap(),
+                Task::spanning(IAtom::from(b) + 1, IAtom::from(b) + 4, 1, Lit::TRUE).unwrap(),
+            ],
+            active: Lit::TRUE,
+        };
+        // tasks of variable duration are rejected
+        assert!(Task::spanning(a, b, 1, Lit::TRUE).is_none());
+
+        let is_solution = |values: &[IntCst]| {
+            let b_start = values[1] + 1;
+            [(values[0], 2), (values[0] + 3, 2)]
+                .iter()
+                .all(|&(s, dur)| s + dur <= b_start || b_start + 3 <= s)
+        };
+        check_propagator(&ctr, &d, &[a, b], is_solution, 100);
+    }
+}
@@ -0,0 +1,381 @@
+use crate::backtrack::EventIndex;
+use crate::core::state::{Cause, Domains, Event, Explanation};
+use crate::core::{IntCst, Lit, LongCst, SignedVar};
+use crate::reasoners::cp::{Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Pseudo-Boolean constraint `sum_i w_i * l_i <= ub` where all weights are strictly positive.
+///
+/// The propagator maintains the slack of the constraint, i.e., `ub` minus the weights of the true literals:
+///  - if the slack is negative, the constraint is violated,
+///  - any undecided literal whose weight exceeds the slack must be false.
+///
+/// The weight of the true literals is maintained incrementally when the propagator is triggered by a single event
+/// (see [`TrueWeight`]) and only recomputed from scratch on a full propagation.
+///
+/// Explanations are clausal: an inference is explained by a subset of the true literals whose weights
+/// are sufficient to justify it, the literals of largest weight being selected first.
+#[derive(Clone, Debug)]
+pub(super) struct PseudoBoolean {
+    /// Terms of the sum, sorted by decreasing weight.
+    terms: Vec<(IntCst, Lit)>,
+    ub: IntCst,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    active: Lit,
+    /// Indices of the terms whose literal is on each signed variable.
+    terms_by_svar: HashMap<SignedVar, Vec<usize>>,
+    /// Weight of the true literals, as of the last propagation.
+    true_weight: RefCell<TrueWeight>,
+}
+
+/// Weight of the true literals of the constraint, synchronized with the domains on each propagation.
+///
+/// The counted literals are stored in the chronological order of the events that made them true.
+/// As backtracking undoes the latest events, the counted literals that are no longer true always form a suffix of
+/// the list, which can be removed when the propagator is next triggered, without being notified of the backtrack.
+/// The backtracks that occurred since the last synchronization are identified from the trail of the domains.
+#[derive(Clone, Debug, Default)]
+struct TrueWeight {
+    /// Counted terms, with the event that made their literal true (none if true at the root).
+    counted: Vec<(usize, Option<EventIndex>)>,
+    /// Whether each term is in `counted`.
+    is_counted: Vec<bool>,
+    /// Sum of the weights of the counted terms.
+    weight: LongCst,
+    /// False if some events might have been missed, in which case the weight must be recomputed from scratch.
+    valid: bool,
+    /// Identifier of the last backtrack of the trail at the last synchronization.
+    last_backtrack: Option<u64>,
+}
+
+impl TrueWeight {
+    fn new(num_terms: usize) -> Self {
+        TrueWeight {
+            is_counted: vec![false; num_terms],
+            ..Default::default()
+        }
+    }
+
+    /// Recomputes the weight from scratch.
+    fn recount(&mut self, terms: &[(IntCst, Lit)], domains: &Domains) {
+        self.counted.clear();
+        self.is_counted.fill(false);
+        self.weight = 0;
+        for term in 0..terms.len() {
+            if domains.entails(terms[term].1) {
+                self.count(term, terms, domains);
+            }
+        }
+        self.valid = true;
+        self.last_backtrack = domains.trail().last_backtrack().map(|(id, _)| id);
+    }
+
+    /// Counts a term whose literal is true, keeping the counted terms in chronological order.
+    fn count(&mut self, term: usize, terms: &[(IntCst, Lit)], domains: &Domains) {
+        debug_assert!(!self.is_counted[term] && domains.entails(terms[term].1));
+        let event = domains.implying_event(terms[term].1);
+        let index = self.counted.iter().rposition(|&(_, e)| e <= event).map_or(0, |i| i + 1);
+        self.counted.insert(index, (term, event));
+        self.is_counted[term] = true;
+        self.weight += terms[term].0 as LongCst;
+    }
+
+    /// Removes the counted terms whose literal was made false by the backtracks since the last synchronization.
+    ///
+    /// After a single backtrack, these are the terms counted from an event that it undid. After several ones,
+    /// the events undone by the earlier backtracks are unknown and the weight is recomputed from scratch.
+    fn backtrack(&mut self, terms: &[(IntCst, Lit)], domains: &Domains) {
+        let last_backtrack = domains.trail().last_backtrack();
+        match last_backtrack {
+            Some((id, _)) if Some(id) == self.last_backtrack => {}
+            Some((id, first_undone)) if id == self.last_backtrack.map_or(0, |prev| prev + 1) => {
+                while let Some(&(term, Some(event))) = self.counted.last() {
+                    if event < first_undone {
+                        break;
+                    }
+                    self.counted.pop();
+                    self.is_counted[term] = false;
+                    self.weight -= terms[term].0 as LongCst;
+                }
+                self.last_backtrack = Some(id);
+            }
+            None => debug_assert!(self.last_backtrack.is_none()),
+            Some(_) => self.recount(terms, domains),
+        }
+    }
+}
+
+impl PseudoBoolean {
+    pub fn new(terms: impl IntoIterator<Item = (IntCst, Lit)>, ub: IntCst, active: Lit) -> Self {
+        let mut terms: Vec<(IntCst, Lit)> = terms.into_iter().collect();
+        debug_assert!(terms.iter().all(|&(w, _)| w > 0), "Non-positive weight in {terms:?}");
+        terms.sort_by_key(|&(w, _)| -w);
+        let mut terms_by_svar: HashMap<SignedVar, Vec<usize>> = HashMap::new();
+        for (i, &(_, l)) in terms.iter().enumerate() {
+            terms_by_svar.entry(l.svar()).or_default().push(i);
+        }
+        let true_weight = RefCell::new(TrueWeight::new(terms.len()));
+        PseudoBoolean {
+            terms,
+            ub,
+            active,
+            terms_by_svar,
+            true_weight,
+        }
+    }
+
+    /// Fixes all undecided literals whose weight exceeds the slack, or fails if the slack is negative.
+    fn propagate_slack(&self, true_weight: LongCst, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        let slack = self.ub as LongCst - true_weight;
+        if slack < 0 {
+            let mut expl = Explanation::new();
+            self.explain(Lit::FALSE, domains, &mut expl);
+            return Err(Contradiction::Explanation(expl));
+        }
+        for &(w, l) in &self.terms {
+            if w as LongCst <= slack {
+                // all remaining terms have a smaller weight
+                break;
+            }
+            if !domains.entails(l) {
+                domains.set(!l, cause)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds to the explanation true literals whose total weight strictly exceeds the threshold.
+    fn explain_weight_above(&self, threshold: LongCst, domains: &Domains, out_explanation: &mut Explanation) {
+        let mut sum: LongCst = 0;
+        for &(w, l) in &self.terms {
+            if sum > threshold {
+                return;
+            }
+            if domains.entails(l) {
+                out_explanation.push(l);
+                sum += w as LongCst;
+            }
+        }
+        assert!(sum > threshold, "The true literals do not justify the inference");
+    }
+}
+
+impl Propagator for PseudoBoolean {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        // propagation only depends on the literals that are true
+        context.add_lit_watch(self.active, id);
+        for &(_, l) in &self.terms {
+            context.add_lit_watch(l, id);
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        let mut state = self.true_weight.borrow_mut();
+        if !domains.entails(self.active) {
+            state.valid = false;
+            return Ok(());
+        }
+        state.recount(&self.terms, domains);
+        let true_weight = state.weight;
+        drop(state);
+        self.propagate_slack(true_weight, domains, cause)
+    }
+
+    fn propagate_event(&self, event: &Event, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        let mut state = self.true_weight.borrow_mut();
+        if !domains.entails(self.active) {
+            // the events are ignored until the constraint is active
+            state.valid = false;
+            return Ok(());
+        }
+        if !state.valid {
+            drop(state);
+            return self.propagate(domains, cause);
+        }
+        state.backtrack(&self.terms, domains);
+        if let Some(terms) = self.terms_by_svar.get(&event.affected_bound) {
+            for &term in terms {
+                if !state.is_counted[term] && domains.entails(self.terms[term].1) {
+                    state.count(term, &self.terms, domains);
+                }
+            }
+        }
+        let true_weight = state.weight;
+        drop(state);
+        self.propagate_slack(true_weight, domains, cause)
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        if self.active != Lit::TRUE {
+            out_explanation.push(self.active);
+        }
+        // weight of the term whose literal was made false, zero for a conflict
+        let weight = self
+            .terms
+            .iter()
+            .filter(|(_, l)| (!*l).entails(literal))
+            .map(|&(w, _)| w)
+            .max()
+            .unwrap_or(0);
+        self.explain_weight_above(self.ub as LongCst - weight as LongCst, domains, out_explanation);
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn new_bool(d: &mut Domains) -> Lit {
+        d.new_var(0, 1).geq(1)
+    }
+
+    #[test]
+    fn test_knapsack() {
+        // 5a + 4b + 3c + 2d <= 7
+        let mut d = Domains::new();
+        let [a, b, c, e] = [(); 4].map(|_| new_bool(&mut d));
+        let pb = PseudoBoolean::new([(2, e), (4, b), (5, a), (3, c)], 7, Lit::TRUE);
+        pb.propagate(&mut d, cause()).unwrap();
+        assert!([a, b, c, e].iter().all(|&l| !d.entails(l) && !d.entails(!l)));
+
+        d.save_state();
+        d.set(b, Cause::Decision).unwrap();
+        pb.propagate(&mut d, cause()).unwrap();
+        // slack of 3: a must be false
+        assert!(d.entails(!a));
+        assert!(!d.entails(!c) && !d.entails(!e));
+        check_explanations(&pb, &d);
+
+        d.save_state();
+        d.set(e, Cause::Decision).unwrap();
+        pb.propagate(&mut d, cause()).unwrap();
+        // slack of 1: c must be false
+        assert!(d.entails(!c));
+        check_explanations(&pb, &d);
+
+        let mut expl = Explanation::new();
+        pb.explain(!c, &d, &mut expl);
+        assert_eq!(expl.lits, vec![b, e]);
+
+        // conflict: b and c cannot both be true
+        d.restore_last();
+        d.save_state();
+        d.set(c, Cause::Decision).unwrap();
+        d.set(e, Cause::Decision).unwrap();
+        let Err(Contradiction::Explanation(expl)) = pb.propagate(&mut d, cause()) else {
+            panic!("Expected a conflict")
+        };
+        assert_eq!(expl.lits, vec![b, c, e]);
+    }
+
+    #[test]
+    fn test_inactive() {
+        let mut d = Domains::new();
+        let active = new_bool(&mut d);
+        let a = new_bool(&mut d);
+        let b = new_bool(&mut d);
+        let pb = PseudoBoolean::new([(1, a), (1, b)], 1, active);
+
+        d.set(a, Cause::Decision).unwrap();
+        pb.propagate(&mut d, cause()).unwrap();
+        assert!(!d.entails(!b));
+
+        d.save_state();
+        d.set(active, Cause::Decision).unwrap();
+        pb.propagate(&mut d, cause()).unwrap();
+        assert!(d.entails(!b));
+        check_explanations(&pb, &d);
+
+        let mut expl = Explanation::new();
+        pb.explain(!b, &d, &mut expl);
+        assert_eq!(expl.lits, vec![active, a]);
+    }
+
+    #[test]
+    fn test_bound_literals() {
+        // 3[x >= 5] + 2[x <= 2] + 2[y >= 1] <= 3
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 3);
+        let pb = PseudoBoolean::new([(3, x.geq(5)), (2, x.leq(2)), (2, y.geq(1))], 3, Lit::TRUE);
+        d.save_state();
+        d.set_lb(y, 1, Cause::Decision).unwrap();
+        pb.propagate(&mut d, cause()).unwrap();
+        assert_eq!(d.bounds(x), (3, 4));
+        check_explanations(&pb, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [active] => 3[x >= 5] + 2[x <= 2] + 2[y >= 1] <= 3, checked against all assignments
+        let mut d = Domains::new();
+        let x = d.new_var(0, 6);
+        let y = d.new_var(0, 2);
+        let active = d.new_var(0, 1);
+        let pb = PseudoBoolean::new([(3, x.geq(5)), (2, x.leq(2)), (2, y.geq(1))], 3, active.geq(1));
+        let weight = |x: IntCst, y: IntCst| 3 * (x >= 5) as IntCst + 2 * (x <= 2) as IntCst + 2 * (y >= 1) as IntCst;
+        check_propagator(&pb, &d, &[x, y, active], |s| s[2] == 0 || weight(s[0], s[1]) <= 3, 200);
+    }
+
+    #[test]
+    fn test_incremental_weight() {
+        // 5a + 4b + 3c + 2d + e <= 9, with the events delivered one at a time, random backtracks and events on other
+        // variables that are not delivered
+        let mut d = Domains::new();
+        let lits = [(); 5].map(|_| new_bool(&mut d));
+        // variables outside the constraint, whose events are not delivered to the propagator
+        let others = [(); 5].map(|_| new_bool(&mut d));
+        let pb = PseudoBoolean::new(lits.iter().zip([5, 4, 3, 2, 1]).map(|(&l, w)| (w, l)), 9, Lit::TRUE);
+        let true_weight = |d: &Domains| -> LongCst {
+            pb.terms
+                .iter()
+                .filter(|(_, l)| d.entails(*l))
+                .map(|&(w, _)| w as LongCst)
+                .sum()
+        };
+        let mut rng = SmallRng::seed_from_u64(0);
+        pb.propagate(&mut d, cause()).unwrap();
+        for _ in 0..2000 {
+            let undecided: Vec<Lit> = lits
+                .iter()
+                .copied()
+                .filter(|&l| !d.entails(l) && !d.entails(!l))
+                .collect();
+            if undecided.is_empty() || rng.gen_bool(0.3) {
+                let levels = rng.gen_range(0..=d.num_saved());
+                for _ in 0..levels {
+                    d.restore_last();
+                }
+                continue;
+            }
+            let lit = undecided[rng.gen_range(0..undecided.len())];
+            d.save_state();
+            for _ in 0..rng.gen_range(0..=2) {
+                let other = others[rng.gen_range(0..others.len())];
+                if !d.entails(other) && !d.entails(!other) {
+                    d.set(other, Cause::Decision).unwrap();
+                }
+            }
+            d.set(if rng.gen_bool(0.7) { lit } else { !lit }, Cause::Decision)
+                .unwrap();
+            let event = *d.last_event().unwrap();
+            if pb.propagate_event(&event, &mut d, cause()).is_err() {
+                d.restore_last();
+                continue;
+            }
+            assert_eq!(pb.true_weight.borrow().weight, true_weight(&d));
+            assert!(true_weight(&d) <= 9);
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use crate::core::state::{Cause, Domains, Explanation};
+use crate::core::{IntCst, Lit, VarRef};
+use crate::reasoners::cp::{Priority, Propagator, PropagatorId, Watches};
+use crate::reasoners::Contradiction;
+use std::sync::Arc;
+
+/// A set of tuples, represented as a bitset over the tuple indices.
+#[derive(Clone, Debug, PartialEq)]
+struct TupleSet {
+    words: Vec<u64>,
+}
+
+impl TupleSet {
+    fn empty(num_tuples: usize) -> Self {
+        TupleSet {
+            words: vec![0; num_tuples.div_ceil(64)],
+        }
+    }
+
+    fn full(num_tuples: usize) -> Self {
+        let mut set = Self::empty(num_tuples);
+        for t in 0..num_tuples {
+            set.insert(t);
+        }
+        set
+    }
+
+    fn insert(&mut self, tuple: usize) {
+        self.words[tuple / 64] |= 1 << (tuple % 64);
+    }
+
+    fn contains(&self, tuple: usize) -> bool {
+        self.words[tuple / 64] & (1 << (tuple % 64)) != 0
+    }
+
+    /// Restricts the set to the tuples in `below` but not in `excluded`.
+    fn intersect_range(&mut self, below: &TupleSet, excluded: Option<&TupleSet>) {
+        for (i, w) in self.words.iter_mut().enumerate() {
+            *w &= below.words[i] & !excluded.map_or(0, |e| e.words[i]);
+        }
+    }
+
+    /// Returns true if a tuple is in the three sets `self`, `below` and not in `excluded`.
+    fn intersects_range(&self, below: &TupleSet, excluded: Option<&TupleSet>) -> bool {
+        (0..self.words.len()).any(|i| self.words[i] & below.words[i] & !excluded.map_or(0, |e| e.words[i]) != 0)
+    }
+}
+
+/// Index of the tuples of a table by the value they give to a column.
+#[derive(Debug)]
+struct Column {
+    /// Distinct values of the column, in increasing order.
+    values: Vec<IntCst>,
+    /// `below[k]` is the set of tuples whose value in the column is less than or equal to `values[k]`
+    below: Vec<TupleSet>,
+}
+
+impl Column {
+    fn new(tuples: &[Vec<IntCst>], col: usize) -> Self {
+        let mut values: Vec<IntCst> = tuples.iter().map(|tuple| tuple[col]).collect();
+        values.sort_unstable();
+        values.dedup();
+        let mut below = vec![TupleSet::empty(tuples.len()); values.len()];
+        for (t, tuple) in tuples.iter().enumerate() {
+            let k = values.binary_search(&tuple[col]).unwrap();
+            below[k].insert(t);
+        }
+        for k in 1..values.len() {
+            let (previous, current) = below.split_at_mut(k);
+            for (w, p) in current[0].words.iter_mut().zip(&previous[k - 1].words) {
+                *w |= p;
+            }
+        }
+        Column { values, below }
+    }
+
+    /// Returns the range `[first, last]` of the indices of the values in `[lb, ub]`, or `None` if there is no such value.
+    fn value_range(&self, lb: IntCst, ub: IntCst) -> Option<(usize, usize)> {
+        let first = self.values.partition_point(|&v| v < lb);
+        let end = self.values.partition_point(|&v| v <= ub);
+        if first < end {
+            Some((first, end - 1))
+        } else {
+            None
+        }
+    }
+
+    /// Set of tuples whose value in the column is in the range of value indices `[first, last]`.
+    fn range(&self, first: usize, last: usize) -> (&TupleSet, Option<&TupleSet>) {
+        (&self.below[last], first.checked_sub(1).map(|k| &self.below[k]))
+    }
+}
+
+/// Immutable data of a table constraint, shared by all clones of the propagator.
+#[derive(Debug)]
+struct TableData {
+    tuples: Arc<Vec<Vec<IntCst>>>,
+    columns: Vec<Column>,
+}
+
+/// Table constraint: the present variables must take the values of one of the tuples.
+///
+/// The propagator is inspired by compact-table: the set of tuples compatible with the domains of the present variables
+/// is computed as a bitset, from which the bounds of each variable are restricted to the values with at least one valid tuple.
+/// As domains are intervals, this achieves bounds consistency.
+/// Only present variables restrict the set of valid tuples. An optional variable with no supported value is made absent.
+///
+/// The lower bound of a variable `x` is updated to `k` because all tuples with `x < k` are invalidated by the bounds of another
+/// present variable, which is recomputed at explanation time.
+#[derive(Clone, Debug)]
+pub(super) struct Table {
+    pub vars: Vec<VarRef>,
+    /// Presence literal of each variable in `vars`.
+    pub presences: Vec<Lit>,
+    /// If true, then the constraint must hold. Otherwise, the constraint is ignored.
+    pub active: Lit,
+    data: Arc<TableData>,
+}
+
+impl Table {
+    pub fn new(vars: Vec<VarRef>, presences: Vec<Lit>, tuples: Arc<Vec<Vec<IntCst>>>, active: Lit) -> Self {
+        let columns = (0..vars.len()).map(|col| Column::new(&tuples, col)).collect();
+        Table {
+            vars,
+            presences,
+            active,
+            data: Arc::new(TableData { tuples, columns }),
+        }
+    }
+
+    fn num_tuples(&self) -> usize {
+        self.data.tuples.len()
+    }
+
+    /// Returns the set of tuples that are compatible with the domains of all present variables.
+    fn valid_tuples(&self, domains: &Domains) -> TupleSet {
+        let mut valid = TupleSet::full(self.num_tuples());
+        for (col, &var) in self.vars.iter().enumerate() {
+            if !domains.entails(self.presences[col]) {
+                continue;
+            }
+            let (lb, ub) = domains.bounds(var);
+            let column = &self.data.columns[col];
+            match column.value_range(lb, ub) {
+                Some((first, last)) => {
+                    let (below, excluded) = column.range(first, last);
+                    valid.intersect_range(below, excluded);
+                }
+                None => return TupleSet::empty(self.num_tuples()),
+            }
+        }
+        valid
+    }
+
+    /// Returns the smallest (or largest if `!lowest`) value of the column in `[lb, ub]` that has a valid tuple.
+    fn supported_bound(&self, col: usize, valid: &TupleSet, lowest: bool, domains: &Domains) -> Option<IntCst> {
+        let (lb, ub) = domains.bounds(self.vars[col]);
+        let column = &self.data.columns[col];
+        let (first, last) = column.value_range(lb, ub)?;
+        let is_supported = |k: usize| {
+            let (below, excluded) = column.range(k, k);
+            valid.intersects_range(below, excluded)
+        };
+        if lowest {
+            (first..=last).find(|&k| is_supported(k)).map(|k| column.values[k])
+        } else {
+            (first..=last)
+                .rev()
+                .find(|&k| is_supported(k))
+                .map(|k| column.values[k])
+        }
+    }
+
+    /// Restricts the bounds of all variables to their supported values.
+    /// Returns true if a domain was modified.
+    fn propagate_bounds(&self, domains: &mut Domains, cause: Cause) -> Result<bool, Contradiction> {
+        let valid = self.valid_tuples(domains);
+        let mut modified = false;
+        for (col, &var) in self.vars.iter().enumerate() {
+            if domains.entails(!self.presences[col]) {
+                continue;
+            }
+            match self.supported_bound(col, &valid, true, domains) {
+                Some(lb) => {
+                    modified |= domains.set_lb(var, lb, cause)?;
+                    let ub = self.supported_bound(col, &valid, false, domains).unwrap();
+                    modified |= domains.set_ub(var, ub, cause)?;
+                }
+                None => {
+                    // no supported value, empty the domain (making the variable absent if it is optional)
+                    let ub = domains.ub(var);
+                    modified |= domains.set_lb(var, ub + 1, cause)?;
+                }
+            }
+        }
+        Ok(modified)
+    }
+
+    /// Explains that no tuple whose value for the column is in `[lb, ub]` is valid,
+    /// each of them being invalidated by the bounds of another present variable.
+    /// Returns false if some tuple is not invalidated.
+    fn explain_unsupported(
+        &self,
+        col: usize,
+        lb: IntCst,
+        ub: IntCst,
+        domains: &Domains,
+        out_explanation: &mut Explanation,
+    ) -> bool {
+        // for each other column, the bounds required to invalidate the tuples
+        let mut required_lb: Vec<Option<IntCst>> = vec![None; self.vars.len()];
+        let mut required_ub: Vec<Option<IntCst>> = vec![None; self.vars.len()];
+        let used = |required_lb: &[Option<IntCst>], required_ub: &[Option<IntCst>], c: usize| {
+            required_lb[c].is_some() || required_ub[c].is_some()
+        };
+        for tuple in self.data.tuples.iter() {
+            if tuple[col] < lb || ub < tuple[col] {
+                continue;
+            }
+            // columns whose bounds invalidate the tuple, favoring the ones already in the explanation
+            let invalidating = (0..self.vars.len()).filter(|&c| {
+                c != col && domains.entails(self.presences[c]) && {
+                    let (var_lb, var_ub) = domains.bounds(self.vars[c]);
+                    tuple[c] < var_lb || var_ub < tuple[c]
+                }
+            });
+            let culprit = invalidating
+                .clone()
+                .find(|&c| used(&required_lb, &required_ub, c))
+                .or_else(|| invalidating.clone().next());
+            let Some(c) = culprit else {
+                return false;
+            };
+            let value = tuple[c];
+            if value < domains.lb(self.vars[c]) {
+                required_lb[c] = Some(required_lb[c].map_or(value + 1, |req| req.max(value + 1)));
+            } else {
+                required_ub[c] = Some(required_ub[c].map_or(value - 1, |req| req.min(value - 1)));
+            }
+        }
+        if self.active != Lit::TRUE {
+            out_explanation.push(self.active);
+        }
+        for c in 0..self.vars.len() {
+            if let Some(req) = required_lb[c] {
+                out_explanation.push(self.vars[c].geq(req));
+            }
+            if let Some(req) = required_ub[c] {
+                out_explanation.push(self.vars[c].leq(req));
+            }
+            if used(&required_lb, &required_ub, c) && self.presences[c] != Lit::TRUE {
+                out_explanation.push(self.presences[c]);
+            }
+        }
+        true
+    }
+}
+
+impl Propagator for Table {
+    fn setup(&self, id: PropagatorId, context: &mut Watches) {
+        context.add_watch(self.active.variable(), id);
+        for &v in &self.vars {
+            context.add_watch(v, id);
+        }
+        for &prez in &self.presences {
+            if prez != Lit::TRUE {
+                context.add_watch(prez.variable(), id);
+            }
+        }
+    }
+
+    fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+        if !domains.entails(self.active) {
+            return Ok(());
+        }
+        while self.propagate_bounds(domains, cause)? {}
+        Ok(())
+    }
+
+    fn explain(&self, literal: Lit, domains: &Domains, out_explanation: &mut Explanation) {
+        let var = literal.variable();
+        let (lb, ub) = domains.bounds(var);
+        for col in (0..self.vars.len()).filter(|&c| self.vars[c] == var) {
+            if literal.svar().is_plus() {
+                // literal `var <= k`: all tuples with a value in `[k+1, ub]` are invalid
+                let k = literal.bound_value().as_int();
+                if self.explain_unsupported(col, k + 1, ub, domains, out_explanation) {
+                    out_explanation.push(var.leq(ub));
+                    return;
+                }
+            } else {
+                // literal `var >= k`: all tuples with a value in `[lb, k-1]` are invalid
+                let k = -literal.bound_value().as_int();
+                if self.explain_unsupported(col, lb, k - 1, domains, out_explanation) {
+                    out_explanation.push(var.geq(lb));
+                    return;
+                }
+            }
+        }
+        panic!("Cannot explain the literal {literal:?}")
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Expensive
+    }
+
+    fn clone_box(&self) -> Box<dyn Propagator> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtrack::Backtrack;
+    use crate::reasoners::cp::testing::{cause, check_propagator};
+    use crate::reasoners::cp::tests::check_explanations;
+
+    fn table(vars: &[VarRef], tuples: Vec<Vec<IntCst>>, d: &Domains) -> Table {
+        let presences = vars.iter().map(|&v| d.presence(v)).collect();
+        Table::new(vars.to_vec(), presences, Arc::new(tuples), Lit::TRUE)
+    }
+
+    #[test]
+    fn test_tuple_set() {
+        let mut set = TupleSet::empty(100);
+        set.insert(3);
+        set.insert(70);
+        assert!(set.contains(3) && set.contains(70) && !set.contains(4));
+        let full = TupleSet::full(100);
+        assert!(full.contains(99));
+        assert!(set.intersects_range(&full, None));
+        assert!(!set.intersects_range(&full, Some(&set)));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let c = table(&[x, y], vec![vec![1, 5], vec![2, 3], vec![4, 8], vec![7, 7]], &d);
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (1, 7));
+        assert_eq!(d.bounds(y), (3, 8));
+
+        d.save_state();
+        d.set_ub(y, 6, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(x), (1, 2));
+        assert_eq!(d.bounds(y), (3, 5));
+        check_explanations(&c, &d);
+
+        d.save_state();
+        d.set_lb(x, 2, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(y), (3, 3));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_conflict() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 10);
+        let y = d.new_var(0, 10);
+        let c = table(&[x, y], vec![vec![1, 5], vec![2, 3]], &d);
+        d.save_state();
+        d.set_lb(x, 2, Cause::Decision).unwrap();
+        d.set_lb(y, 4, Cause::Decision).unwrap();
+        match c.propagate(&mut d, cause()) {
+            Err(Contradiction::InvalidUpdate(fail)) => {
+                // the failed update can be explained from the current state
+                let mut expl = Explanation::new();
+                c.explain(fail.0, &d, &mut expl);
+                assert!(expl.lits.iter().all(|&l| d.entails(l)));
+            }
+            _ => panic!("Expected a conflict"),
+        }
+    }
+
+    #[test]
+    fn test_optional_variables() {
+        let mut d = Domains::new();
+        let px = d.new_var(0, 1).geq(1);
+        let x = d.new_optional_var(0, 10, px);
+        let y = d.new_var(0, 10);
+        let c = table(&[x, y], vec![vec![1, 5], vec![2, 3]], &d);
+
+        // `x` may be absent, `y` is only restricted by its own column
+        d.save_state();
+        d.set_lb(x, 3, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(y), (3, 5));
+        // `x` has no supported value and must be absent
+        assert!(d.entails(!px));
+        check_explanations(&c, &d);
+
+        d.restore_last();
+        d.save_state();
+        d.set(px, Cause::Decision).unwrap();
+        d.set_ub(x, 1, Cause::Decision).unwrap();
+        assert!(c.propagate(&mut d, cause()).is_ok());
+        assert_eq!(d.bounds(y), (5, 5));
+        check_explanations(&c, &d);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // [active] => (x, y, z) in tuples, with an optional variable `x` that is ignored when absent
+        let mut d = Domains::new();
+        let px = d.new_var(0, 1);
+        let active = d.new_var(0, 1);
+        let x = d.new_optional_var(0, 3, px.geq(1));
+        let y = d.new_var(0, 3);
+        let z = d.new_var(-1, 2);
+        let tuples = vec![
+            vec![0, 1, 2],
+            vec![1, 1, 0],
+            vec![2, 3, -1],
+            vec![3, 0, 0],
+            vec![1, 2, 2],
+        ];
+        let mut c = table(&[x, y, z], tuples.clone(), &d);
+        c.active = active.geq(1);
+        let is_solution = |s: &[IntCst]| {
+            s[4] == 0
+                || tuples
+                    .iter()
+                    .any(|t| (s[3] == 0 || t[0] == s[0]) && t[1] == s[1] && t[2] == s[2])
+        };
+        check_propagator(&c, &d, &[x, y, z, px, active], is_solution, 300);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::core::state::{Cause, Domains, InvalidUpdate};
+use crate::core::{IntCst, Lit, SignedVar};
+use crate::model::lang::scheduling::Task;
+use crate::reasoners::cp::{geq, lb, leq, ub};
+
+/// View of a task in either the forward or backward time direction.
+///
+/// In the backward direction, time is mirrored: a task executing over `[s, s+d)` is seen as executing over `[-s-d, -s)`.
+/// This allows reasoning on the upper bounds of the start times as if they were lower bounds.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct TaskView {
+    /// Index of the task in the constraint.
+    pub id: usize,
+    /// Start time of the task is `start + offset`
+    pub start: SignedVar,
+    pub offset: IntCst,
+    pub duration: IntCst,
+    pub demand: IntCst,
+    pub presence: Lit,
+}
+
+impl TaskView {
+    pub fn new(task: &Task, id: usize, forward: bool) -> Self {
+        let (start, offset) = if forward {
+            (SignedVar::plus(task.start_var()), task.start.shift)
+        } else {
+            (SignedVar::minus(task.start_var()), -task.start.shift - task.duration)
+        };
+        TaskView {
+            id,
+            start,
+            offset,
+            duration: task.duration,
+            demand: task.demand,
+            presence: task.presence,
+        }
+    }
+
+    /// Earliest start time
+    pub fn est(&self, domains: &Domains) -> IntCst {
+        lb(self.start, domains) + self.offset
+    }
+
+    /// Latest start time
+    pub fn lst(&self, domains: &Domains) -> IntCst {
+        ub(self.start, domains) + self.offset
+    }
+
+    /// Latest completion time
+    pub fn lct(&self, domains: &Domains) -> IntCst {
+        self.lst(domains) + self.duration
+    }
+
+    /// Earliest completion time
+    pub fn ect(&self, domains: &Domains) -> IntCst {
+        self.est(domains) + self.duration
+    }
+
+    /// Literal stating that the task starts at or after `t`.
+    pub fn starts_at_or_after(&self, t: IntCst) -> Lit {
+        geq(self.start, t - self.offset)
+    }
+
+    /// Literal stating that the task starts at or before `t`.
+    pub fn starts_at_or_before(&self, t: IntCst) -> Lit {
+        leq(self.start, t - self.offset)
+    }
+
+    /// Returns the compulsory part of the task, i.e., an interval `[lst, ect)` in which the task is necessarily
+    /// executing if it is present.
+    pub fn compulsory_part(&self, domains: &Domains) -> Option<(IntCst, IntCst)> {
+        let lst = self.lst(domains);
+        let ect = self.ect(domains);
+        if lst < ect {
+            Some((lst, ect))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the bounds of the start time can be updated based on the assumption that the task is present.
+    ///
+    /// This is the case if the task is known to be present or if its presence is the one of its start variable
+    /// (whose bounds are irrelevant when absent).
+    pub fn bounds_updatable(&self, domains: &Domains) -> bool {
+        domains.entails(self.presence) || domains.presence(self.start.variable()) == self.presence
+    }
+
+    /// Enforces that the task starts at or after `t` if it is present.
+    /// If the task cannot start at or after `t`, it is made absent.
+    pub fn set_est(&self, t: IntCst, domains: &mut Domains, cause: Cause) -> Result<bool, InvalidUpdate> {
+        self.set_start_bound(self.starts_at_or_after(t), t > self.lst(domains), domains, cause)
+    }
+
+    /// Enforces that the task starts at or before `t` if it is present.
+    /// If the task cannot start at or before `t`, it is made absent.
+    pub fn set_lst(&self, t: IntCst, domains: &mut Domains, cause: Cause) -> Result<bool, InvalidUpdate> {
+        self.set_start_bound(self.starts_at_or_before(t), t < self.est(domains), domains, cause)
+    }
+
+    fn set_start_bound(
+        &self,
+        bound: Lit,
+        empties_domain: bool,
+        domains: &mut Domains,
+        cause: Cause,
+    ) -> Result<bool, InvalidUpdate> {
+        if self.bounds_updatable(domains) {
+            match domains.set(bound, cause) {
+                Err(_) if !domains.entails(self.presence) => domains.set(!self.presence, cause),
+                res => res,
+            }
+        } else if empties_domain {
+            domains.set(!self.presence, cause)
+        } else {
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,468 @@
+//! Utilities to test propagators against a brute-force enumeration of the solutions of their constraint.
+
+use crate::backtrack::Backtrack;
+use crate::core::state::{Cause, DirectOrigin, Domains, Explanation, InvalidUpdate, Origin};
+use crate::core::{IntCst, Lit, Relation, VarRef};
+use crate::reasoners::cp::{Propagator, PropagatorId, Watches};
+use crate::reasoners::{Contradiction, ReasonerId};
+use itertools::Itertools;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+/// Set of all solutions of a constraint, enumerated in the initial domains of its variables.
+///
+/// The value of an absent variable in a solution is meaningless: its domain is empty and all literals on it hold.
+struct Solutions<'a> {
+    vars: &'a [VarRef],
+    /// Presence literal of each variable in `vars`.
+    presences: Vec<Lit>,
+    solutions: Vec<Vec<IntCst>>,
+}
+
+impl<'a> Solutions<'a> {
+    fn new(vars: &'a [VarRef], domains: &Domains, is_solution: impl Fn(&[IntCst]) -> bool) -> Self {
+        let solutions = vars
+            .iter()
+            .map(|&v| domains.lb(v)..=domains.ub(v))
+            .multi_cartesian_product()
+            .filter(|values| is_solution(values))
+            .collect();
+        let presences = vars.iter().map(|&v| domains.presence(v)).collect();
+        Solutions {
+            vars,
+            presences,
+            solutions,
+        }
+    }
+
+    /// Value of a variable in the given solution.
+    fn value(&self, var: VarRef, solution: &[IntCst]) -> IntCst {
+        match self.vars.iter().position(|&v| v == var) {
+            Some(i) => solution[i],
+            None if var == VarRef::ZERO => 0,
+            None if var == VarRef::ONE => 1,
+            None => panic!("The literal on {var:?} is not on a variable of the constraint"),
+        }
+    }
+
+    /// Returns true if the variable is present in the given solution.
+    fn is_present(&self, var: VarRef, solution: &[IntCst]) -> bool {
+        match self.vars.iter().position(|&v| v == var) {
+            Some(i) => self.satisfies(self.presences[i], solution),
+            None => true,
+        }
+    }
+
+    /// Returns true if the literal is satisfied by the value of its variable in the solution.
+    fn satisfies(&self, lit: Lit, solution: &[IntCst]) -> bool {
+        let (var, rel, value) = lit.unpack();
+        match rel {
+            Relation::Leq => self.value(var, solution) <= value,
+            Relation::Gt => self.value(var, solution) > value,
+        }
+    }
+
+    fn holds(&self, lit: Lit, solution: &[IntCst]) -> bool {
+        !self.is_present(lit.variable(), solution) || self.satisfies(lit, solution)
+    }
+
+    /// Returns true if the solution is in the domains, which do not restrict the value of absent variables.
+    fn is_in_domains(&self, solution: &[IntCst], domains: &Domains) -> bool {
+        self.vars
+            .iter()
+            .zip(solution.iter())
+            .all(|(&v, &x)| !self.is_present(v, solution) || (domains.lb(v) <= x && x <= domains.ub(v)))
+    }
+
+    /// Returns all solutions that are in the domains.
+    fn in_domains<'b>(&'b self, domains: &'b Domains) -> impl Iterator<Item = &'b [IntCst]> + 'b {
+        self.solutions
+            .iter()
+            .filter(|sol| self.is_in_domains(sol, domains))
+            .map(|sol| sol.as_slice())
+    }
+
+    /// Checks that the explanation only contains literals entailed by the domains,
+    /// and that all solutions satisfying the explanation satisfy the literal.
+    fn check_explanation(&self, literal: Lit, explanation: &Explanation, domains: &Domains) {
+        for &l in &explanation.lits {
+            assert!(
+                domains.entails(l),
+                "Explanation of {literal:?} contains {l:?} that is not entailed"
+            );
+        }
+        for sol in &self.solutions {
+            if explanation.lits.iter().all(|&l| self.holds(l, sol)) {
+                assert!(
+                    self.holds(literal, sol),
+                    "Explanation {:?} of {literal:?} is satisfied by the solution {sol:?} that violates it",
+                    explanation.lits
+                );
+            }
+        }
+    }
+}
+
+/// Checks a propagator against a brute-force enumeration of the solutions of its constraint.
+///
+/// `vars` are the variables of the constraint, whose domains are given in `domains`, and
+/// `is_solution` tells whether a complete assignment of these variables (in the same order) satisfies the constraint.
+/// An optional variable must come with its presence variable in `vars`, and `is_solution` should ignore its value
+/// when it is absent.
+/// The propagator is run on `num_trials` sequences of random decisions and backtracks. As in the `Cp` reasoner,
+/// it is run with [`Propagator::propagate`] at the root and incrementally afterwards, by replaying each watched
+/// event through [`Propagator::propagate_event`]. The following properties are checked:
+///
+///  - propagation never removes a solution and only fails when there are no solutions left,
+///  - each inference is explained by literals entailed when it was made, and that imply it in all solutions,
+///  - the explanation of a conflict is entailed and is not satisfied by any solution,
+///  - once the incremental propagation is over, propagating from scratch infers nothing more.
+///
+/// Panics with a description of the problem if one of these properties does not hold.
+/// As all assignments are enumerated, the domains of the variables should be small.
+pub fn check_propagator(
+    propagator: &dyn Propagator,
+    domains: &Domains,
+    vars: &[VarRef],
+    is_solution: impl Fn(&[IntCst]) -> bool,
+    num_trials: u32,
+) {
+    let solutions = Solutions::new(vars, domains, is_solution);
+    let mut watches = Watches::default();
+    propagator.setup(PropagatorId::from(0u32), &mut watches);
+    let mut rng = SmallRng::seed_from_u64(0);
+
+    for _ in 0..num_trials {
+        // a fresh copy for each trial, as it may keep track of the decisions and backtracks it has seen
+        let incremental = propagator.clone_box();
+        let mut d = domains.clone();
+        let mut consistent = propagate_and_check(propagator, incremental.as_ref(), &watches, &solutions, &mut d, None);
+        let mut num_backtracks = 0;
+        loop {
+            // the domains of absent variables are ignored
+            let unbound: Vec<VarRef> = vars
+                .iter()
+                .copied()
+                .filter(|&v| d.lb(v) < d.ub(v) && d.present(v) != Some(false))
+                .collect();
+            let backtrack = if !consistent || unbound.is_empty() {
+                if d.num_saved() == 0 || num_backtracks == MAX_BACKTRACKS {
+                    break;
+                }
+                true
+            } else {
+                d.num_saved() > 0 && num_backtracks < MAX_BACKTRACKS && rng.gen_bool(0.2)
+            };
+            if backtrack {
+                // the domains are back to a state where the incremental propagation was over
+                for _ in 0..rng.gen_range(1..=d.num_saved()) {
+                    d.restore_last();
+                }
+                num_backtracks += 1;
+                consistent = true;
+                continue;
+            }
+
+            // make a random decision on an unbound variable
+            let var = unbound[rng.gen_range(0..unbound.len())];
+            let value = rng.gen_range(d.lb(var)..d.ub(var));
+            d.save_state();
+            let decision_index = d.trail().len();
+            let decision = if rng.gen_bool(0.5) {
+                d.set_ub(var, value, Cause::Decision)
+            } else {
+                d.set_lb(var, value + 1, Cause::Decision)
+            };
+            assert_eq!(decision, Ok(true));
+            consistent = propagate_and_check(
+                propagator,
+                incremental.as_ref(),
+                &watches,
+                &solutions,
+                &mut d,
+                Some(decision_index),
+            );
+        }
+    }
+}
+
+/// Maximum number of backtracks in a trial of [`check_propagator`].
+const MAX_BACKTRACKS: u32 = 2;
+
+/// Propagates the domains after the decision at `decision_index` in their trail (or at the root if `None`),
+/// and checks the result. Returns false if the propagation failed.
+///
+/// The domains are propagated by the `incremental` propagator, that sees all their decisions and backtracks.
+/// `propagator` is then run from scratch, in a copy of the domains, to check that it infers nothing more.
+fn propagate_and_check(
+    propagator: &dyn Propagator,
+    incremental: &dyn Propagator,
+    watches: &Watches,
+    solutions: &Solutions,
+    domains: &mut Domains,
+    decision_index: Option<usize>,
+) -> bool {
+    let consistent = check_propagation(incremental, solutions, domains, |d| {
+        // as in the `Cp` reasoner, all propagators are fully propagated at the root
+        let first_event = match decision_index {
+            Some(index) => index,
+            None => {
+                let start = d.trail().len();
+                incremental.propagate(d, cause())?;
+                start
+            }
+        };
+        replay_events(incremental, watches, d, first_event)
+    });
+    if consistent {
+        let mut d = domains.clone();
+        let fixpoint = d.trail().len();
+        assert!(
+            propagator.propagate(&mut d, cause()).is_ok(),
+            "Propagation fails after the incremental propagation succeeded"
+        );
+        if let Some(event) = d.trail().events().get(fixpoint) {
+            panic!(
+                "Propagation infers {:?} missed by the incremental propagation",
+                event.new_literal()
+            );
+        }
+    }
+    consistent
+}
+
+/// Replays each event of the trail from the `first_event` index through [`Propagator::propagate_event`],
+/// if it is watched by the propagator, including the events of its own inferences.
+fn replay_events(
+    propagator: &dyn Propagator,
+    watches: &Watches,
+    domains: &mut Domains,
+    first_event: usize,
+) -> Result<(), Contradiction> {
+    let mut next = first_event;
+    while let Some(&event) = domains.trail().events().get(next) {
+        next += 1;
+        if watches.triggered_by(&event).next().is_some() {
+            propagator.propagate_event(&event, domains, cause())?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs `propagate` on the domains and checks its inferences and conflicts against the solutions.
+/// Returns false if the propagation failed.
+fn check_propagation(
+    propagator: &dyn Propagator,
+    solutions: &Solutions,
+    d: &mut Domains,
+    propagate: impl FnOnce(&mut Domains) -> Result<(), Contradiction>,
+) -> bool {
+    let start = d.trail().len();
+    let before: Vec<Vec<IntCst>> = solutions.in_domains(d).map(|sol| sol.to_vec()).collect();
+    let result = propagate(d);
+    check_inferences(propagator, solutions, d, start);
+
+    match result {
+        Ok(()) => {
+            for sol in &before {
+                assert!(
+                    solutions.is_in_domains(sol, d),
+                    "Propagation removed the solution {sol:?}"
+                );
+            }
+            true
+        }
+        Err(contradiction) => {
+            assert!(
+                before.is_empty(),
+                "Contradiction while the solution {:?} exists",
+                before[0]
+            );
+            let (literal, explanation) = match contradiction {
+                Contradiction::Explanation(explanation) => (Lit::FALSE, explanation),
+                Contradiction::InvalidUpdate(InvalidUpdate(lit, origin)) => {
+                    // the propagator attempted to set a literal incompatible with the domains,
+                    // possibly on a present variable whose domain it would have emptied
+                    let lit = match origin {
+                        Origin::PresenceOfEmptyDomain(invalid_lit, _) => invalid_lit,
+                        Origin::Direct(_) => lit,
+                    };
+                    let mut explanation = Explanation::new();
+                    propagator.explain(lit, d, &mut explanation);
+                    (lit, explanation)
+                }
+            };
+            solutions.check_explanation(literal, &explanation, d);
+            false
+        }
+    }
+}
+
+/// Cause of the inferences of the propagators under test.
+pub fn cause() -> Cause {
+    Cause::inference(ReasonerId::Cp, 0u32)
+}
+
+/// Checks the explanations of all inferences made in the domains since the trail had the `start` length.
+/// Each explanation is requested in the exact state in which the inference was made.
+fn check_inferences(propagator: &dyn Propagator, solutions: &Solutions, domains: &Domains, start: usize) {
+    let mut d = domains.clone();
+    while d.trail().len() > start {
+        let event = *d.last_event().unwrap();
+        let inferred = match d.undo_last_event() {
+            Origin::Direct(DirectOrigin::ExternalInference(_)) => event.new_literal(),
+            // the variable was made absent by emptying its domain, which is what must be explained
+            Origin::PresenceOfEmptyDomain(lit, DirectOrigin::ExternalInference(_)) => lit,
+            _ => continue,
+        };
+        let mut explanation = Explanation::new();
+        propagator.explain(inferred, &d, &mut explanation);
+        solutions.check_explanation(inferred, &explanation, &d);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::Event;
+
+    type Solver = crate::solver::Solver<&'static str>;
+
+    /// Constraint `x + 1 <= y`, written with the public propagator API only.
+    #[derive(Clone)]
+    struct Before {
+        x: VarRef,
+        y: VarRef,
+    }
+
+    impl Propagator for Before {
+        fn setup(&self, id: PropagatorId, context: &mut Watches) {
+            context.add_watch(self.x, id);
+            context.add_watch(self.y, id);
+        }
+
+        fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+            domains.set_lb(self.y, domains.lb(self.x) + 1, cause)?;
+            domains.set_ub(self.x, domains.ub(self.y) - 1, cause)?;
+            Ok(())
+        }
+
+        fn propagate_event(&self, event: &Event, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+            // only the lower bound of `x` and the upper bound of `y` can trigger an inference
+            if event.affected_bound.is_minus() && event.affected_bound.variable() == self.x {
+                domains.set_lb(self.y, domains.lb(self.x) + 1, cause)?;
+            } else if event.affected_bound.is_plus() && event.affected_bound.variable() == self.y {
+                domains.set_ub(self.x, domains.ub(self.y) - 1, cause)?;
+            }
+            Ok(())
+        }
+
+        fn explain(&self, literal: Lit, state: &Domains, out_explanation: &mut Explanation) {
+            if literal.variable() == self.y {
+                // literal is `y > v`
+                out_explanation.push(self.x.geq(literal.value()));
+            } else {
+                // literal is `x <= v`
+                out_explanation.push(self.y.leq(literal.value() + 1));
+            }
+        }
+
+        fn clone_box(&self) -> Box<dyn Propagator> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_check_propagator() {
+        let mut d = Domains::new();
+        let x = d.new_var(0, 5);
+        let y = d.new_var(-2, 4);
+        let before = Before { x, y };
+        check_propagator(&before, &d, &[x, y], |sol| sol[0] < sol[1], 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "violates it")]
+    fn test_check_propagator_wrong_explanation() {
+        /// Same constraint as `Before`, with explanations that are too weak.
+        #[derive(Clone)]
+        struct WrongBefore(Before);
+
+        impl Propagator for WrongBefore {
+            fn setup(&self, id: PropagatorId, context: &mut Watches) {
+                self.0.setup(id, context)
+            }
+            fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+                self.0.propagate(domains, cause)
+            }
+            fn explain(&self, literal: Lit, state: &Domains, out_explanation: &mut Explanation) {
+                let x = self.0.x;
+                out_explanation.push(x.geq(state.lb(x).min(0)));
+            }
+            fn clone_box(&self) -> Box<dyn Propagator> {
+                Box::new(self.clone())
+            }
+        }
+
+        let mut d = Domains::new();
+        let x = d.new_var(0, 5);
+        let y = d.new_var(-2, 4);
+        let wrong = WrongBefore(Before { x, y });
+        check_propagator(&wrong, &d, &[x, y], |sol| sol[0] < sol[1], 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "missed by the incremental propagation")]
+    fn test_check_propagator_missed_event() {
+        /// Same constraint as `Before`, that ignores the events on the upper bound of `y`.
+        #[derive(Clone)]
+        struct IncompleteBefore(Before);
+
+        impl Propagator for IncompleteBefore {
+            fn setup(&self, id: PropagatorId, context: &mut Watches) {
+                self.0.setup(id, context)
+            }
+            fn propagate(&self, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+                self.0.propagate(domains, cause)
+            }
+            fn propagate_event(&self, event: &Event, domains: &mut Domains, cause: Cause) -> Result<(), Contradiction> {
+                if event.affected_bound.variable() == self.0.x {
+                    self.0.propagate_event(event, domains, cause)?;
+                }
+                Ok(())
+            }
+            fn explain(&self, literal: Lit, state: &Domains, out_explanation: &mut Explanation) {
+                self.0.explain(literal, state, out_explanation)
+            }
+            fn clone_box(&self) -> Box<dyn Propagator> {
+                Box::new(self.clone())
+            }
+        }
+
+        let mut d = Domains::new();
+        let x = d.new_var(0, 5);
+        let y = d.new_var(-2, 4);
+        let incomplete = IncompleteBefore(Before { x, y });
+        check_propagator(&incomplete, &d, &[x, y], |sol| sol[0] < sol[1], 100);
+    }
+
+    #[test]
+    fn test_custom_propagator_in_solver() {
+        let mut solver = Solver::new(Default::default());
+        let x = solver.model.new_ivar(0, 3, "x");
+        let y = solver.model.new_ivar(0, 3, "y");
+        let z = solver.model.new_ivar(0, 3, "z");
+        solver.reasoners.cp.add_propagator(Before {
+            x: x.into(),
+            y: y.into(),
+        });
+        solver.reasoners.cp.add_propagator(Before {
+            x: y.into(),
+            y: z.into(),
+        });
+        let solutions = solver.enumerate(&[x.into(), y.into(), z.into()]).unwrap();
+        // choose 3 distinct values among 4
+        assert_eq!(solutions.len(), 4);
+        assert!(solutions.iter().all(|sol| sol[0] < sol[1] && sol[1] < sol[2]));
+    }
+}
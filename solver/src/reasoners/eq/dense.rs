@@ -909,11 +909,20 @@ impl<L: Label> ReifyEq for Model<L> {
         match (a, b) {
             (Var(a), Var(b)) => {
                 let e = if a < b { ReifExpr::Eq(a, b) } else { ReifExpr::Eq(b, a) };
-                self.reify_core(e, false)
+                self.reify_core(e)
             }
             (Var(a), Val(b)) | (Val(b), Var(a)) => {
-                let e = ReifExpr::EqVal(a, b);
-                self.reify_core(e, false)
+                let (lb, ub) = self.state.bounds(a);
+                // when the domain decides the equality, use a literal on the variable itself rather than a constant:
+                // the theory may infer it to be false (resp. true) when the variable is absent
+                if b < lb || b > ub {
+                    a.lt(lb)
+                } else if b == lb && b == ub {
+                    a.geq(lb)
+                } else {
+                    let e = ReifExpr::EqVal(a, b);
+                    self.reify_core(e)
+                }
             }
             (Val(a), Val(b)) => {
                 if a == b {
@@ -25,8 +25,9 @@ impl SplitEqTheory {
 
         let domains = model.domains();
 
-        if domains.value(l) != Some(false) && domains.present(a) != Some(false) && domains.present(b) != Some(false) {
-            // the edge may be true and active, the two variables should be in the same part
+        if domains.present(a) != Some(false) && domains.present(b) != Some(false) {
+            // the edge may be active, the two variables should be in the same part
+            // (even if the edge is already false, as its part is responsible for enforcing it)
 
             match (self.part_of.get(&a).copied(), self.part_of.get(&b).copied()) {
                 (Some(pa), None) => {
@@ -88,8 +89,9 @@ impl SplitEqTheory {
         let l = model.reify_eq(Node::Var(var), Node::Val(val));
 
         let domains = model.domains();
-        if domains.value(l) != Some(false) && domains.present(var) != Some(false) {
-            // the edge may be true and the variable may be present
+        if domains.present(var) != Some(false) {
+            // the variable may be present (even if the edge is already false, as its part is responsible for
+            // removing the value from the domain of the variable)
             // make sure the variable is in a group (necessary for domain propagation
 
             match self.part_of.get(&var) {
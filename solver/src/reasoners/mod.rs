@@ -24,6 +24,8 @@ pub enum ReasonerId {
     Cp,
     Eq(u16),
     Tautologies,
+    /// A theory provided by the user through `Solver::add_theory`.
+    Custom(u16),
 }
 
 impl ReasonerId {
@@ -35,17 +37,14 @@ impl ReasonerId {
 impl Display for ReasonerId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         use ReasonerId::*;
-        write!(
-            f,
-            "{}",
-            match self {
-                Sat => "SAT",
-                Diff => "DiffLog",
-                Eq(_) => "Equality",
-                Cp => "CP",
-                Tautologies => "Optim",
-            }
-        )
+        match self {
+            Sat => write!(f, "SAT"),
+            Diff => write!(f, "DiffLog"),
+            Eq(_) => write!(f, "Equality"),
+            Cp => write!(f, "CP"),
+            Tautologies => write!(f, "Optim"),
+            Custom(i) => write!(f, "Custom({i})"),
+        }
     }
 }
 
@@ -61,6 +60,12 @@ pub trait Theory: Backtrack + Send + 'static {
     fn clone_box(&self) -> Box<dyn Theory>;
 }
 
+impl Clone for Box<dyn Theory> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
 #[derive(Debug)]
 pub enum Contradiction {
     InvalidUpdate(InvalidUpdate),
@@ -93,6 +98,10 @@ pub struct Reasoners {
     pub eq: SplitEqTheory,
     pub cp: Cp,
     pub tautologies: Tautologies,
+    /// Theories provided by the user, each identified by a `ReasonerId::Custom` identity.
+    custom: Vec<Box<dyn Theory>>,
+    /// Identities of all reasoners, in the order in which they are propagated.
+    writers: Vec<ReasonerId>,
 }
 impl Reasoners {
     pub fn new() -> Self {
@@ -102,9 +111,29 @@ impl Reasoners {
             eq: Default::default(),
             cp: Cp::new(ReasonerId::Cp),
             tautologies: Tautologies::default(),
+            custom: Vec::new(),
+            writers: REASONERS.to_vec(),
         }
     }
 
+    /// Adds a theory provided by the user, that will be propagated after all built-in reasoners.
+    /// Its identity must be a `ReasonerId::Custom` that is not used by any other theory.
+    pub(crate) fn add_theory(&mut self, theory: Box<dyn Theory>) -> ReasonerId {
+        let id = theory.identity();
+        assert!(matches!(id, ReasonerId::Custom(_)), "Not a custom theory: {id}");
+        assert!(!self.writers.contains(&id), "Theory {id} is already registered");
+        self.custom.push(theory);
+        self.writers.push(id);
+        id
+    }
+
+    fn custom_index(&self, id: ReasonerId) -> usize {
+        self.custom
+            .iter()
+            .position(|th| th.identity() == id)
+            .unwrap_or_else(|| panic!("No theory registered as {id}"))
+    }
+
     pub fn reasoner(&self, id: ReasonerId) -> &dyn Theory {
         match id {
             ReasonerId::Sat => &self.sat,
@@ -112,6 +141,7 @@ impl Reasoners {
             ReasonerId::Eq(_) => &self.eq,
             ReasonerId::Cp => &self.cp,
             ReasonerId::Tautologies => &self.tautologies,
+            ReasonerId::Custom(_) => self.custom[self.custom_index(id)].as_ref(),
         }
     }
 
@@ -122,11 +152,43 @@ impl Reasoners {
             ReasonerId::Eq(_) => &mut self.eq,
             ReasonerId::Cp => &mut self.cp,
             ReasonerId::Tautologies => &mut self.tautologies,
+            ReasonerId::Custom(_) => {
+                let i = self.custom_index(id);
+                self.custom[i].as_mut()
+            }
         }
     }
 
-    pub fn writers(&self) -> &'static [ReasonerId] {
-        &REASONERS
+    /// Returns a copy of the reasoners in which all theories, except the one identified by `id`, are empty.
+    pub(crate) fn only(&self, id: ReasonerId) -> Reasoners {
+        let mut reasoners = Reasoners::new();
+        match id {
+            ReasonerId::Sat => reasoners.sat = self.sat.clone(),
+            ReasonerId::Diff => reasoners.diff = self.diff.clone(),
+            ReasonerId::Eq(_) => reasoners.eq = self.eq.clone(),
+            ReasonerId::Cp => reasoners.cp = self.cp.clone(),
+            ReasonerId::Tautologies => reasoners.tautologies = self.tautologies.clone(),
+            ReasonerId::Custom(_) => {
+                reasoners.add_theory(self.reasoner(id).clone_box());
+            }
+        }
+        reasoners
+    }
+
+    pub fn writers(&self) -> &[ReasonerId] {
+        &self.writers
+    }
+
+    /// All reasoners, in no particular order.
+    pub(crate) fn theories_mut(&mut self) -> impl Iterator<Item = &mut dyn Theory> + '_ {
+        let builtin: [&mut dyn Theory; 5] = [
+            &mut self.sat,
+            &mut self.diff,
+            &mut self.eq,
+            &mut self.cp,
+            &mut self.tautologies,
+        ];
+        builtin.into_iter().chain(self.custom.iter_mut().map(|th| th.as_mut()))
     }
 
     pub fn theories(&self) -> impl Iterator<Item = (ReasonerId, &dyn Theory)> + '_ {
@@ -142,7 +204,20 @@ impl Default for Reasoners {
 
 impl Explainer for Reasoners {
     fn explain(&mut self, cause: InferenceCause, literal: Lit, model: &Domains, explanation: &mut Explanation) {
+        let start = explanation.lits.len();
         self.reasoner_mut(cause.writer)
-            .explain(literal, cause, model, explanation)
+            .explain(literal, cause, model, explanation);
+        if cause.writer != ReasonerId::Sat {
+            if let Some(proof) = self.sat.proof_log() {
+                // inferences of other theories are recorded, justifying the learnt clauses that rely on them
+                let implying = explanation.lits[start..].iter().map(|&l| !l);
+                proof.add_theory_explanation(cause.writer, std::iter::once(literal).chain(implying));
+            }
+        }
+    }
+
+    fn supports_late_explanations(&self, cause: InferenceCause) -> bool {
+        // clauses and tautologies do not depend on the state in which they are explained
+        matches!(cause.writer, ReasonerId::Sat | ReasonerId::Tautologies)
     }
 }
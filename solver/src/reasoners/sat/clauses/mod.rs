@@ -3,6 +3,7 @@ use crate::collections::ref_store::{RefMap, RefVec};
 use crate::core::literals::Disjunction;
 use crate::core::Lit;
 use crate::create_ref_type;
+use crate::reasoners::sat::proof::ProofLog;
 use std::cmp::Ordering::Equal;
 use std::fmt::{Debug, Display, Error, Formatter};
 use std::ops::{Index, IndexMut};
@@ -21,11 +22,39 @@ impl Default for ClausesParams {
     }
 }
 
+/// Tier of a learnt clause in the database, that determines how long it will be retained.
+///
+/// Tiers are determined by the LBD (Literal Block Distance) of the clause, i.e., the number of distinct decision levels
+/// of its literals. Clauses with a low LBD are known to be the most useful in practice.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum ClauseTier {
+    /// Clauses that are never removed from the database.
+    Core,
+    /// Clauses that are kept as long as they are regularly used in propagation and conflict analysis.
+    /// A clause of this tier that was not used since the last reduction of the database is moved to the local tier.
+    Tier2,
+    /// Clauses that are subject to removal on each reduction of the database.
+    Local,
+}
+
+/// Criterion used to select the clauses of the local tier that should be removed when reducing the database.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ClauseRanking {
+    /// Least active clauses are removed first.
+    Activity,
+    /// Clauses with the highest LBD are removed first, ties being broken on the activity.
+    Lbd,
+}
+
 #[derive(Copy, Clone)]
 struct ClauseMetadata {
     pub activity: f64,
     pub lbd: u32,
     pub learnt: bool,
+    /// Tier of the clause, only meaningful for learnt clauses.
+    pub tier: ClauseTier,
+    /// True if the clause was used since the last reduction of the database.
+    pub used: bool,
 }
 
 /// A clause represents a disjunction of literals `a || b || c`. It may also contain a `scope` literal defining
@@ -255,6 +284,8 @@ pub struct ClauseDb {
     num_fixed: usize,
     /// Total number of clauses.
     num_clauses: usize,
+    /// Number of learnt clauses in each tier, indexed by `ClauseTier as usize`.
+    num_per_tier: [usize; 3],
     first_possibly_free: usize,
     /// Associates each clause id to to a clause.
     /// Unassigned clause ids point to a tautological clause in order to always point to valid one.
@@ -277,6 +308,7 @@ impl ClauseDb {
             params,
             num_fixed: 0,
             num_clauses: 0,
+            num_per_tier: [0; 3],
             first_possibly_free: 0,
             clauses: RefVec::new(),
             metadata: RefMap::default(),
@@ -291,16 +323,22 @@ impl ClauseDb {
             && clause.unwatched.is_empty()
     }
 
+    /// Adds a clause to the database. Learnt clauses are initially placed in the local tier
+    /// and may be later promoted with [`ClauseDb::set_lbd`].
     pub fn add_clause(&mut self, cl: Clause, learnt: bool) -> ClauseId {
         self.num_clauses += 1;
-        if !learnt {
+        if learnt {
+            self.num_per_tier[ClauseTier::Local as usize] += 1;
+        } else {
             self.num_fixed += 1;
         }
 
         let meta = ClauseMetadata {
             activity: 0f64,
-            lbd: 0,
+            lbd: cl.len() as u32,
             learnt,
+            tier: ClauseTier::Local,
+            used: false,
         };
 
         // too costly to check when the number of clause grows
@@ -350,11 +388,38 @@ impl ClauseDb {
         self.metadata.keys()
     }
 
-    pub fn set_lbd(&mut self, clause: ClauseId, lbd: u32) {
-        self.metadata[clause].lbd = lbd;
+    /// Number of learnt clauses currently in the given tier.
+    pub fn num_in_tier(&self, tier: ClauseTier) -> usize {
+        self.num_per_tier[tier as usize]
+    }
+
+    pub fn lbd(&self, clause: ClauseId) -> u32 {
+        self.metadata[clause].lbd
+    }
+
+    pub fn tier(&self, clause: ClauseId) -> ClauseTier {
+        self.metadata[clause].tier
+    }
+
+    /// Records a new LBD for the clause, placing it in the given tier if it is learnt.
+    ///
+    /// The LBD and tier of a clause are only updated if they improve on the current ones:
+    /// a clause is never demoted by this method. Returns true if the clause was promoted to a better tier.
+    pub fn set_lbd(&mut self, clause: ClauseId, lbd: u32, tier: ClauseTier) -> bool {
+        let meta = &mut self.metadata[clause];
+        meta.lbd = meta.lbd.min(lbd);
+        if meta.learnt && tier < meta.tier {
+            self.num_per_tier[meta.tier as usize] -= 1;
+            self.num_per_tier[tier as usize] += 1;
+            meta.tier = tier;
+            true
+        } else {
+            false
+        }
     }
 
     pub fn bump_activity(&mut self, cl: ClauseId) {
+        self.metadata[cl].used = true;
         self.metadata[cl].activity += self.params.cla_inc;
         if self.metadata[cl].activity > 1e100_f64 {
             self.rescale_activities()
@@ -372,22 +437,44 @@ impl ClauseDb {
         self.params.cla_inc *= 1e-100_f64;
     }
 
-    pub fn reduce_db<F: Fn(ClauseId) -> bool>(&mut self, locked: F, remove_watch: &mut impl FnMut(ClauseId, Lit)) {
+    /// Reduces the number of learnt clauses in the database:
+    ///  - clauses of the core tier are always kept,
+    ///  - clauses of the second tier are kept if they were used since the last reduction and otherwise moved to the local tier,
+    ///  - about half of the clauses of the local tier that are not locked are removed, selected according to the `ranking`.
+    ///
+    /// If a proof is being logged, the deletion of the removed clauses is recorded in it.
+    pub fn reduce_db<F: Fn(ClauseId) -> bool>(
+        &mut self,
+        ranking: ClauseRanking,
+        locked: F,
+        remove_watch: &mut impl FnMut(ClauseId, Lit),
+        proof: Option<&ProofLog>,
+    ) {
+        for meta in self.metadata.values_mut() {
+            if meta.learnt && meta.tier == ClauseTier::Tier2 && !meta.used {
+                meta.tier = ClauseTier::Local;
+                self.num_per_tier[ClauseTier::Tier2 as usize] -= 1;
+                self.num_per_tier[ClauseTier::Local as usize] += 1;
+            }
+        }
         let mut clauses: Vec<_> = self
             .metadata
             .entries()
-            .filter_map(|(id, meta)| {
-                if meta.learnt && !locked(id) {
-                    // let score = meta.activity / ((meta.lbd) as f64);
-                    let score = meta.activity;
-                    Some((id, score))
-                } else {
-                    None
-                }
-            })
+            .filter(|(id, meta)| meta.learnt && meta.tier == ClauseTier::Local && !locked(*id))
+            .map(|(id, meta)| (id, *meta))
             .collect();
 
-        clauses.sort_by(|&a, &b| a.1.partial_cmp(&b.1).unwrap_or(Equal));
+        // sort clauses so that the ones to remove first are at the beginning
+        match ranking {
+            ClauseRanking::Activity => {
+                clauses.sort_by(|a, b| a.1.activity.partial_cmp(&b.1.activity).unwrap_or(Equal));
+            }
+            ClauseRanking::Lbd => clauses.sort_by(|a, b| {
+                b.1.lbd
+                    .cmp(&a.1.lbd)
+                    .then(a.1.activity.partial_cmp(&b.1.activity).unwrap_or(Equal))
+            }),
+        }
         // remove half removable
         clauses.iter().take(clauses.len() / 2).for_each(|&(id, _)| {
             let cl = &self.clauses[id];
@@ -397,10 +484,17 @@ impl ClauseDb {
             if cl.len() >= 2 {
                 remove_watch(id, !cl.watch2);
             }
+            if let Some(proof) = proof {
+                proof.delete_lemma(cl.literals());
+            }
             self.clauses[id] = self.tautological_clause.clone();
             self.metadata.remove(id);
             self.num_clauses -= 1;
+            self.num_per_tier[ClauseTier::Local as usize] -= 1;
         });
+        for meta in self.metadata.values_mut() {
+            meta.used = false;
+        }
 
         // make sure we search for free spots from the beginning
         self.first_possibly_free = 0;
@@ -426,3 +520,38 @@ impl IndexMut<ClauseId> for ClauseDb {
         &mut self.clauses[k]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::VarRef;
+
+    #[test]
+    fn test_reduce_db() {
+        let mut db = ClauseDb::new(ClausesParams::default());
+        let mut add = |lbd: u32, tier: ClauseTier| {
+            // a clause with `lbd` literals, whose LBD cannot exceed its size
+            let lits = (1..=lbd).map(|i| VarRef::from_u32(i).geq(1)).collect();
+            let id = db.add_clause(Clause::new(Disjunction::new(lits)), true);
+            db.set_lbd(id, lbd, tier);
+            id
+        };
+        let core = add(2, ClauseTier::Core);
+        let tier2_used = add(4, ClauseTier::Tier2);
+        let tier2_unused = add(5, ClauseTier::Tier2);
+        let locals: Vec<_> = (7..=10).map(|lbd| add(lbd, ClauseTier::Local)).collect();
+        db.bump_activity(tier2_used);
+        assert_eq!(db.num_in_tier(ClauseTier::Local), 4);
+
+        db.reduce_db(ClauseRanking::Lbd, |_| false, &mut |_, _| {}, None);
+        // the unused clause of the second tier is demoted, and the two locals with the highest LBD are removed
+        assert!(db.is_in_db(core) && db.is_in_db(tier2_used) && db.is_in_db(tier2_unused));
+        assert_eq!(db.tier(tier2_unused), ClauseTier::Local);
+        assert!(db.is_in_db(locals[0]) && db.is_in_db(locals[1]));
+        assert!(!db.is_in_db(locals[2]) && !db.is_in_db(locals[3]));
+        assert_eq!(db.num_in_tier(ClauseTier::Core), 1);
+        assert_eq!(db.num_in_tier(ClauseTier::Tier2), 1);
+        assert_eq!(db.num_in_tier(ClauseTier::Local), 3);
+        assert_eq!(db.num_learnt(), 5);
+    }
+}
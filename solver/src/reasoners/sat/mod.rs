@@ -1,3 +1,4 @@
 pub mod clauses;
+pub mod proof;
 mod sat_solver;
 pub use sat_solver::*;
@@ -0,0 +1,736 @@
+//! Logging of proofs certifying the unsatisfiability results of the solver.
+//!
+//! Two formats are supported:
+//!
+//!  - [`ProofFormat::Drat`]: the standard DRAT format for pure boolean problems, where each learnt clause is
+//!    a RUP lemma with respect to the input formula (which is not part of the proof) and the previous lemmas.
+//!    Only boolean literals (`x > 0` and `x <= 0`) can be represented in this format.
+//!  - [`ProofFormat::VeriPB`]: a pseudo-Boolean proof in the style of VeriPB, where each bound literal `x <= k`
+//!    is represented by a boolean variable `v<x>_le_<k>`. Since the input formula is not expressed in a
+//!    pseudo-Boolean form, the proof is self-contained: the definition of the problem (its clauses and
+//!    pseudo-Boolean constraints, the domains of its variables and the relations between the bound literals of a
+//!    variable) is introduced as axioms, while learnt clauses are introduced as RUP lemmas that can be checked
+//!    against them. The explanations of the theories are RUP lemmas as long as all constraints handled by a theory
+//!    have a definition in the proof (i.e. they are pseudo-Boolean constraints), and axioms otherwise.
+//!
+//! When optimizing, the bounds imposed on the objective by each new solution are not implied by the problem.
+//! They are recorded as *hypotheses* in VeriPB proofs: each hypothesis is a clause that is only required by a fresh
+//! selector variable `h<i>`, and all subsequent lemmas are weakened with the negation of the selector of the latest
+//! hypothesis (which requires all previous ones). The final lemma `~h<i>` then certifies that no solution satisfies
+//! the hypotheses, i.e. that the best solution found is optimal.
+//!
+//! Proof logging is only meaningful for a single solver: clauses shared between the workers of a parallel solver
+//! are not justified in the proof.
+
+use crate::core::state::{DirectOrigin, Domains, Origin};
+use crate::core::{IntCst, Lit, LongCst, Relation, UpperBound, VarRef, INT_CST_MAX};
+use crate::reasoners::ReasonerId;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write};
+use std::sync::{Arc, Mutex};
+
+/// Format of the proofs emitted by a [`ProofLog`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ProofFormat {
+    /// DRAT proof for pure boolean problems.
+    Drat,
+    /// VeriPB-style pseudo-Boolean proof, supporting the bound literals on integer variables.
+    VeriPB,
+}
+
+/// A handle to a proof being written.
+///
+/// The handle can be cheaply cloned, all clones writing to the same output.
+#[derive(Clone)]
+pub struct ProofLog {
+    writer: Arc<Mutex<ProofWriter>>,
+}
+
+impl ProofLog {
+    /// Creates a new proof log, writing the proof in the given format to `output`.
+    pub fn new(format: ProofFormat, output: impl Write + Send + 'static) -> Self {
+        let mut writer = ProofWriter {
+            format,
+            output: BufWriter::new(Box::new(output)),
+            error: None,
+            var_ids: Box::new(|v| v.to_u32() as usize),
+            thresholds: Default::default(),
+            next_id: 1,
+            lemma_ids: Default::default(),
+            num_root_events: 0,
+            num_hypotheses: 0,
+            theories_defined: true,
+            line: String::new(),
+        };
+        if format == ProofFormat::VeriPB {
+            writer.write_line("pseudo-Boolean proof version 2.0");
+        }
+        ProofLog {
+            writer: Arc::new(Mutex::new(writer)),
+        }
+    }
+
+    /// Sets the function associating each variable to its identifier in the proof.
+    ///
+    /// By default, the identifier is the index of the variable. A custom numbering is typically needed for
+    /// DRAT proofs, whose variables must be the ones of the original DIMACS file.
+    pub fn with_variable_ids(self, var_ids: impl Fn(VarRef) -> usize + Send + 'static) -> Self {
+        self.writer.lock().unwrap().var_ids = Box::new(var_ids);
+        self
+    }
+
+    pub fn format(&self) -> ProofFormat {
+        self.writer.lock().unwrap().format
+    }
+
+    /// Records a clause that holds by definition of the problem.
+    /// Axioms are ignored in DRAT proofs, where they are assumed to be part of the input formula.
+    pub fn add_axiom(&self, clause: impl IntoIterator<Item = Lit>) {
+        self.writer.lock().unwrap().add_axiom(clause)
+    }
+
+    /// Records the definition of the pseudo-Boolean constraint `active => sum_i w_i * l_i <= upper_bound`
+    /// (with strictly positive weights), from which the explanations of its propagator can be derived.
+    /// Ignored in DRAT proofs.
+    pub fn add_pseudo_boolean(&self, terms: &[(IntCst, Lit)], upper_bound: IntCst, active: Lit) {
+        self.writer
+            .lock()
+            .unwrap()
+            .add_pseudo_boolean(terms, upper_bound, active)
+    }
+
+    /// Notes that a constraint handled by a theory has no definition in the proof.
+    /// The explanations of the theories are then recorded as axioms.
+    pub fn add_undefined_constraint(&self) {
+        self.writer.lock().unwrap().theories_defined = false;
+    }
+
+    /// Records the clause `literal | !l1 | ... | !ln` explaining an inference of a theory
+    /// (with `literal` being false for a conflict).
+    /// It is a lemma if the constraints of the theories are all defined in the proof, and an axiom otherwise.
+    pub fn add_theory_explanation(&self, writer: ReasonerId, clause: impl IntoIterator<Item = Lit>) {
+        let mut proof = self.writer.lock().unwrap();
+        // tautologies are derived from lemmas and hypotheses
+        if proof.theories_defined || writer == ReasonerId::Tautologies {
+            proof.add_lemma(clause)
+        } else {
+            proof.add_axiom(clause)
+        }
+    }
+
+    /// Records a clause that is assumed to hold from now on, without being implied by the problem
+    /// (typically a bound on the objective imposed by a new solution). Ignored in DRAT proofs.
+    pub fn add_hypothesis(&self, clause: impl IntoIterator<Item = Lit>) {
+        self.writer.lock().unwrap().add_hypothesis(clause)
+    }
+
+    /// Records a clause that was learnt and is implied by unit propagation on the previous clauses.
+    pub fn add_lemma(&self, clause: impl IntoIterator<Item = Lit>) {
+        self.writer.lock().unwrap().add_lemma(clause)
+    }
+
+    /// Records the deletion of a previously learnt clause.
+    pub fn delete_lemma(&self, clause: impl IntoIterator<Item = Lit>) {
+        self.writer.lock().unwrap().delete_lemma(clause)
+    }
+
+    /// Adds a comment to the proof.
+    pub fn comment(&self, comment: &str) {
+        let mut writer = self.writer.lock().unwrap();
+        let prefix = match writer.format {
+            ProofFormat::Drat => "c",
+            ProofFormat::VeriPB => "*",
+        };
+        writer.write_line(&format!("{prefix} {comment}"));
+    }
+
+    /// Records the facts that hold at the root decision level (only for VeriPB proofs).
+    ///
+    /// This includes the initial domains of the variables (as axioms) and the inferences made at the root, which are
+    /// never part of the explanations of learnt clauses. Inferences of the clauses and of the theories whose
+    /// constraints are defined are lemmas, and other inferences (e.g. made when encoding the problem) are axioms.
+    /// Only the facts that appeared since the last call are recorded.
+    pub(crate) fn add_root_facts(&self, domains: &Domains) {
+        let mut writer = self.writer.lock().unwrap();
+        if writer.format != ProofFormat::VeriPB {
+            return;
+        }
+        let events = &domains.trail().events()[writer.num_root_events..];
+        writer.num_root_events += events.len();
+        // upper bound of the last created variable, whose lower bound is set by the next event
+        let mut created_ub = None;
+        for event in events {
+            let lit = event.new_literal();
+            let fact = [lit, !domains.presence(lit.variable())];
+            if event.previous.cause.is_none() && event.previous.value == UpperBound::ub(INT_CST_MAX) {
+                // creation of the variable with its initial domain
+                if event.affected_bound.is_plus() {
+                    created_ub = Some(lit);
+                    continue;
+                }
+                let var = lit.variable();
+                let ub = created_ub.take().expect("lower bound set before upper bound");
+                if (lit, ub) != (var.geq(0), var.leq(1)) {
+                    // boolean variables only ever appear through the `x <= 0` literal, no need to relate it to its domain
+                    writer.add_axiom(fact);
+                    writer.add_axiom([ub, fact[1]]);
+                }
+                continue;
+            }
+            let derivable = match event.cause {
+                Origin::Direct(DirectOrigin::ExternalInference(cause)) => {
+                    writer.theories_defined || matches!(cause.writer, ReasonerId::Sat | ReasonerId::Tautologies)
+                }
+                _ => false,
+            };
+            if derivable {
+                writer.add_lemma(fact);
+            } else {
+                // the fact may depend on the hypotheses
+                writer.add_weakened_axiom(fact);
+            }
+        }
+    }
+
+    /// Records the derivation of the empty clause, concluding the proof of unsatisfiability, and flushes the output.
+    ///
+    /// If hypotheses were made, the derived clause is the negation of the selector of the latest hypothesis,
+    /// which only proves that the hypotheses cannot be satisfied (and that the problem is not unsatisfiable).
+    pub fn conclude_unsat(&self) {
+        let mut writer = self.writer.lock().unwrap();
+        writer.add_lemma([]);
+        if writer.format == ProofFormat::VeriPB {
+            let id = writer.next_id - 1;
+            if writer.num_hypotheses == 0 {
+                writer.write_line("output NONE");
+                writer.write_line(&format!("conclusion UNSAT : {id}"));
+            } else {
+                writer.write_line(&format!("* the hypotheses are refuted by {id}"));
+                writer.write_line("output NONE");
+                writer.write_line("conclusion NONE");
+            }
+            writer.write_line("end pseudo-Boolean proof");
+        }
+        if let Err(e) = writer.output.flush() {
+            writer.error.get_or_insert(e);
+        }
+    }
+
+    /// Flushes the proof to its output, returning the first error encountered while writing the proof (if any).
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.writer.lock().unwrap().flush()
+    }
+}
+
+struct ProofWriter {
+    format: ProofFormat,
+    output: BufWriter<Box<dyn Write + Send>>,
+    /// First error that occurred while writing the proof, if any.
+    error: Option<std::io::Error>,
+    /// Associates each variable to its identifier in the proof
+    var_ids: Box<dyn Fn(VarRef) -> usize + Send>,
+    /// (VeriPB) For each variable, the values `k` such that the literal `x <= k` was introduced in the proof.
+    thresholds: BTreeMap<VarRef, BTreeSet<IntCst>>,
+    /// (VeriPB) Identifier of the next constraint in the proof.
+    next_id: u64,
+    /// (VeriPB) Identifiers of the learnt clauses, indexed by their sorted literals.
+    lemma_ids: HashMap<Vec<Lit>, Vec<u64>>,
+    /// (VeriPB) Number of events of the root decision level that were recorded in the proof.
+    num_root_events: usize,
+    /// (VeriPB) Number of hypotheses made, the selector of the latest one weakening all lemmas.
+    num_hypotheses: u64,
+    /// (VeriPB) Whether all constraints handled by the theories have a definition in the proof.
+    theories_defined: bool,
+    /// Buffer used to build the lines of the proof
+    line: String,
+}
+
+/// Value of a literal on one of the reserved constant variables, or `None` if the literal is on another variable.
+fn constant_value(lit: Lit) -> Option<bool> {
+    let (var, rel, value) = lit.unpack();
+    let cst = if var == VarRef::ZERO {
+        0
+    } else if var == VarRef::ONE {
+        1
+    } else {
+        return None;
+    };
+    match rel {
+        Relation::Leq => Some(cst <= value),
+        Relation::Gt => Some(cst > value),
+    }
+}
+
+/// Removes the literals that are always false from the clause.
+/// Returns `None` if the clause contains a literal that is always true.
+fn simplify(clause: impl IntoIterator<Item = Lit>) -> Option<Vec<Lit>> {
+    let mut lits = Vec::new();
+    for l in clause {
+        match constant_value(l) {
+            Some(true) => return None,
+            Some(false) => {}
+            None => lits.push(l),
+        }
+    }
+    lits.sort();
+    lits.dedup();
+    Some(lits)
+}
+
+impl ProofWriter {
+    fn write_line(&mut self, line: &str) {
+        if self.error.is_none() {
+            if let Err(e) = writeln!(self.output, "{line}") {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.output.flush()
+    }
+
+    fn add_axiom(&mut self, clause: impl IntoIterator<Item = Lit>) {
+        if self.format == ProofFormat::VeriPB {
+            if let Some(clause) = simplify(clause) {
+                self.write_pb_clause("a", &clause, false);
+            }
+        }
+    }
+
+    /// Adds an axiom that only holds under the current hypotheses.
+    fn add_weakened_axiom(&mut self, clause: impl IntoIterator<Item = Lit>) {
+        if self.format == ProofFormat::VeriPB {
+            if let Some(clause) = simplify(clause) {
+                self.write_pb_clause("a", &clause, true);
+            }
+        }
+    }
+
+    fn add_lemma(&mut self, clause: impl IntoIterator<Item = Lit>) {
+        let Some(clause) = simplify(clause) else {
+            return;
+        };
+        match self.format {
+            ProofFormat::Drat => self.write_drat_clause("", &clause),
+            ProofFormat::VeriPB => {
+                let id = self.write_pb_clause("rup", &clause, true);
+                self.lemma_ids.entry(clause).or_default().push(id);
+            }
+        }
+    }
+
+    fn add_pseudo_boolean(&mut self, terms: &[(IntCst, Lit)], upper_bound: IntCst, active: Lit) {
+        if self.format != ProofFormat::VeriPB {
+            return;
+        }
+        // `sum_i w_i * !l_i + (W - ub) * !active >= W - ub` where `W` is the sum of the weights
+        let total: LongCst = terms.iter().map(|&(w, _)| w as LongCst).sum();
+        let degree = total - upper_bound as LongCst;
+        if degree <= 0 {
+            return; // trivially satisfied
+        }
+        let mut pb: Vec<(LongCst, Lit)> = terms.iter().map(|&(w, l)| (w as LongCst, !l)).collect();
+        pb.push((degree, !active));
+        pb.retain(|&(_, l)| constant_value(l) != Some(false));
+        if pb.iter().any(|&(_, l)| constant_value(l) == Some(true)) {
+            return;
+        }
+        self.write_pb_constraint("a", &pb, degree, false);
+    }
+
+    fn add_hypothesis(&mut self, clause: impl IntoIterator<Item = Lit>) {
+        if self.format != ProofFormat::VeriPB {
+            return;
+        }
+        let Some(clause) = simplify(clause) else {
+            return;
+        };
+        // the new selector requires the hypothesis and the previous selector
+        let previous = self.num_hypotheses;
+        self.num_hypotheses += 1;
+        let terms: Vec<(LongCst, Lit)> = clause.iter().map(|&l| (1, l)).collect();
+        self.write_pb_constraint("a", &terms, 1, true);
+        if previous > 0 {
+            self.write_line(&format!("a 1 ~h{} 1 h{previous} >= 1 ;", self.num_hypotheses));
+            self.next_id += 1;
+        }
+    }
+
+    fn delete_lemma(&mut self, clause: impl IntoIterator<Item = Lit>) {
+        let Some(clause) = simplify(clause) else {
+            return;
+        };
+        match self.format {
+            ProofFormat::Drat => self.write_drat_clause("d ", &clause),
+            ProofFormat::VeriPB => {
+                if let Some(id) = self.lemma_ids.get_mut(&clause).and_then(|ids| ids.pop()) {
+                    self.write_line(&format!("del id {id}"));
+                }
+            }
+        }
+    }
+
+    /// Writes a clause in the DRAT format, preceded by the given prefix.
+    /// A clause with a non-boolean literal cannot be represented and is recorded as an error of the proof.
+    fn write_drat_clause(&mut self, prefix: &str, clause: &[Lit]) {
+        let mut line = std::mem::take(&mut self.line);
+        line.clear();
+        line.push_str(prefix);
+        for &l in clause {
+            let id = (self.var_ids)(l.variable());
+            match l.unpack() {
+                (_, Relation::Gt, 0) => write!(line, "{id} ").unwrap(),
+                (_, Relation::Leq, 0) => write!(line, "-{id} ").unwrap(),
+                _ => {
+                    let message = format!("DRAT proofs only support boolean literals, got {l:?}");
+                    self.error
+                        .get_or_insert(std::io::Error::new(std::io::ErrorKind::InvalidInput, message));
+                    self.line = line;
+                    return;
+                }
+            }
+        }
+        line.push('0');
+        self.write_line(&line);
+        self.line = line;
+    }
+
+    /// Writes a clause as a pseudo-Boolean constraint of the given rule, and returns its identifier.
+    /// If `weakened`, the clause is weakened with the negation of the selector of the latest hypothesis.
+    fn write_pb_clause(&mut self, rule: &str, clause: &[Lit], weakened: bool) -> u64 {
+        let terms: Vec<(LongCst, Lit)> = clause.iter().map(|&l| (1, l)).collect();
+        self.write_pb_constraint(rule, &terms, 1, weakened)
+    }
+
+    /// Writes the pseudo-Boolean constraint `sum_i w_i * l_i >= degree` with the given rule, and returns its identifier.
+    /// Any bound literal that appears for the first time is introduced beforehand.
+    /// If `weakened`, the term `degree * ~h<i>` is added for the selector of the latest hypothesis (if any).
+    fn write_pb_constraint(&mut self, rule: &str, terms: &[(LongCst, Lit)], degree: LongCst, weakened: bool) -> u64 {
+        for &(_, l) in terms {
+            let (var, _, value) = l.unpack();
+            self.introduce_threshold(var, value);
+        }
+        let mut line = std::mem::take(&mut self.line);
+        line.clear();
+        line.push_str(rule);
+        for &(w, l) in terms {
+            write!(line, " {w} ").unwrap();
+            self.write_pb_literal(&mut line, l);
+        }
+        if weakened && self.num_hypotheses > 0 {
+            write!(line, " {degree} ~h{}", self.num_hypotheses).unwrap();
+        }
+        write!(line, " >= {degree} ;").unwrap();
+        self.write_line(&line);
+        self.line = line;
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Writes the boolean variable representing the literal (or its negation).
+    fn write_pb_literal(&self, out: &mut String, l: Lit) {
+        let (var, rel, value) = l.unpack();
+        if rel == Relation::Gt {
+            out.push('~');
+        }
+        let id = (self.var_ids)(var);
+        if value >= 0 {
+            write!(out, "v{id}_le_{value}").unwrap();
+        } else {
+            write!(out, "v{id}_le_m{}", -value).unwrap();
+        }
+    }
+
+    /// Introduces the boolean variable representing `var <= value`, with the axioms linking it
+    /// to the closest thresholds already introduced on the same variable.
+    fn introduce_threshold(&mut self, var: VarRef, value: IntCst) {
+        let thresholds = self.thresholds.entry(var).or_default();
+        if !thresholds.insert(value) {
+            return;
+        }
+        let below = thresholds.range(..value).next_back().copied();
+        let above = thresholds.range(value + 1..).next().copied();
+        // (var <= below)  =>  (var <= value)
+        if let Some(below) = below {
+            self.write_pb_clause("a", &[Lit::gt(var, below), Lit::leq(var, value)], false);
+        }
+        // (var <= value)  =>  (var <= above)
+        if let Some(above) = above {
+            self.write_pb_clause("a", &[Lit::gt(var, value), Lit::leq(var, above)], false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A writer that keeps the written bytes in a shared buffer.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<String> {
+            let content = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            content.lines().map(|l| l.to_string()).collect()
+        }
+    }
+
+    /// A pseudo-Boolean constraint `sum_i w_i * l_i >= degree` of a VeriPB proof, where each literal is
+    /// a variable name and its polarity.
+    type PbConstraint = (Vec<(i64, (String, bool))>, i64);
+
+    fn parse_pb_constraint(tokens: &[&str]) -> PbConstraint {
+        let (terms, rest) = tokens.split_at(tokens.iter().position(|&t| t == ">=").unwrap());
+        assert_eq!(rest.len(), 3, "invalid constraint {tokens:?}");
+        let terms = terms
+            .chunks(2)
+            .map(|term| {
+                let w = term[0].parse().unwrap();
+                let lit = match term[1].strip_prefix('~') {
+                    Some(var) => (var.to_string(), false),
+                    None => (term[1].to_string(), true),
+                };
+                (w, lit)
+            })
+            .collect();
+        (terms, rest[1].parse().unwrap())
+    }
+
+    /// Returns true if unit propagation on the constraints leads to a conflict, starting from the given assignment.
+    fn propagates_to_conflict(
+        constraints: &BTreeMap<u64, PbConstraint>,
+        mut assignment: HashMap<String, bool>,
+    ) -> bool {
+        loop {
+            let mut changed = false;
+            for (terms, degree) in constraints.values() {
+                let value = |assignment: &HashMap<String, bool>, (var, positive): &(String, bool)| {
+                    assignment.get(var).map(|&v| v == *positive)
+                };
+                let max_sum: i64 = terms
+                    .iter()
+                    .filter(|(_, l)| value(&assignment, l) != Some(false))
+                    .map(|(w, _)| w)
+                    .sum();
+                let slack = max_sum - degree;
+                if slack < 0 {
+                    return true;
+                }
+                for (w, lit) in terms {
+                    if *w > slack && value(&assignment, lit).is_none() {
+                        assignment.insert(lit.0.clone(), lit.1);
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                return false;
+            }
+        }
+    }
+
+    /// Checks a VeriPB proof made of axioms, RUP lemmas (that must be clauses) and deletions.
+    /// Returns the conclusion and the last derived constraint.
+    fn check_veripb(lines: &[String]) -> (String, PbConstraint) {
+        let mut constraints = BTreeMap::new();
+        let mut next_id = 1;
+        let mut last = None;
+        let mut conclusion = None;
+        assert_eq!(lines[0], "pseudo-Boolean proof version 2.0");
+        for line in &lines[1..] {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens[0] {
+                "*" | "output" | "end" => {}
+                "a" => {
+                    constraints.insert(next_id, parse_pb_constraint(&tokens[1..]));
+                    next_id += 1;
+                }
+                "rup" => {
+                    let lemma = parse_pb_constraint(&tokens[1..]);
+                    assert!(
+                        lemma.1 == 1 && lemma.0.iter().all(|&(w, _)| w >= 1),
+                        "not a clause: {line}"
+                    );
+                    let negation = lemma
+                        .0
+                        .iter()
+                        .map(|(_, (var, positive))| (var.clone(), !positive))
+                        .collect();
+                    assert!(propagates_to_conflict(&constraints, negation), "not RUP: {line}");
+                    constraints.insert(next_id, lemma.clone());
+                    last = Some(lemma);
+                    next_id += 1;
+                }
+                "del" => {
+                    assert!(constraints.remove(&tokens[2].parse().unwrap()).is_some());
+                }
+                "conclusion" => conclusion = Some(tokens[1..].join(" ")),
+                _ => panic!("unexpected line: {line}"),
+            }
+        }
+        (conclusion.expect("no conclusion"), last.expect("no lemma"))
+    }
+
+    #[test]
+    fn test_drat() {
+        let a = VarRef::from_u32(2);
+        let b = VarRef::from_u32(3);
+        let buffer = SharedBuffer::default();
+        let proof = ProofLog::new(ProofFormat::Drat, buffer.clone()).with_variable_ids(|v| v.to_u32() as usize - 1);
+        proof.add_axiom([a.geq(1), b.geq(1)]);
+        proof.add_lemma([a.geq(1), b.leq(0), Lit::FALSE]);
+        proof.add_lemma([a.geq(1), Lit::TRUE]);
+        proof.delete_lemma([b.leq(0), a.geq(1)]);
+        proof.conclude_unsat();
+        assert_eq!(buffer.lines(), vec!["1 -2 0", "d 1 -2 0", "0"]);
+    }
+
+    #[test]
+    fn test_veripb() {
+        let x = VarRef::from_u32(2);
+        let buffer = SharedBuffer::default();
+        let proof = ProofLog::new(ProofFormat::VeriPB, buffer.clone());
+        proof.add_axiom([x.leq(5)]);
+        proof.add_lemma([x.geq(3), x.leq(-1)]);
+        proof.add_axiom([x.leq(3)]);
+        proof.delete_lemma([x.leq(-1), x.geq(3)]);
+        proof.conclude_unsat();
+        assert_eq!(
+            buffer.lines(),
+            vec![
+                "pseudo-Boolean proof version 2.0",
+                "a 1 v2_le_5 >= 1 ;",
+                "a 1 ~v2_le_2 1 v2_le_5 >= 1 ;",
+                "a 1 ~v2_le_m1 1 v2_le_2 >= 1 ;",
+                "rup 1 ~v2_le_2 1 v2_le_m1 >= 1 ;",
+                "a 1 ~v2_le_2 1 v2_le_3 >= 1 ;",
+                "a 1 ~v2_le_3 1 v2_le_5 >= 1 ;",
+                "a 1 v2_le_3 >= 1 ;",
+                "del id 4",
+                "rup >= 1 ;",
+                "output NONE",
+                "conclusion UNSAT : 8",
+                "end pseudo-Boolean proof",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_solver_proof() {
+        use crate::model::lang::expr::all_different;
+        type Model = crate::model::Model<&'static str>;
+        type Solver = crate::solver::Solver<&'static str>;
+
+        // pigeonhole problem: four pairwise different variables with only three values
+        let mut model = Model::new();
+        let vars: Vec<_> = (0..4).map(|_| model.new_ivar(0, 2, "x")).collect();
+        model.enforce(all_different(vars), []);
+
+        // the literals of the integer variables cannot be represented in a DRAT proof
+        let mut solver = Solver::new(model.clone());
+        assert!(solver
+            .set_proof_log(ProofLog::new(ProofFormat::Drat, std::io::sink()))
+            .is_err());
+
+        let buffer = SharedBuffer::default();
+        let mut solver = Solver::new(model);
+        solver
+            .set_proof_log(ProofLog::new(ProofFormat::VeriPB, buffer.clone()))
+            .unwrap();
+        assert!(solver.solve().unwrap().is_none());
+        // the inferences of the all-different constraint are axioms
+        let (conclusion, last) = check_veripb(&buffer.lines());
+        assert!(conclusion.starts_with("UNSAT"));
+        assert_eq!(last, (vec![], 1));
+    }
+
+    #[test]
+    fn test_drat_errors() {
+        let x = VarRef::from_u32(2);
+        let proof = ProofLog::new(ProofFormat::Drat, std::io::sink());
+        proof.add_lemma([x.geq(1), x.leq(3)]);
+        assert!(proof.flush().is_err());
+    }
+
+    #[test]
+    fn test_pseudo_boolean_proof() {
+        use crate::model::lang::expr::{or, pb_leq};
+        type Model = crate::model::Model<&'static str>;
+        type Solver = crate::solver::Solver<&'static str>;
+
+        // pigeonhole problem: four pigeons in three holes, each hole holding at most one pigeon
+        let mut model = Model::new();
+        let pigeons: Vec<Vec<Lit>> = (0..4)
+            .map(|_| (0..3).map(|_| model.new_bvar("p").true_lit()).collect())
+            .collect();
+        for holes in &pigeons {
+            model.enforce(or(holes.clone()), []);
+        }
+        for hole in 0..3 {
+            let terms = pigeons.iter().map(|holes| (1, holes[hole]));
+            model.enforce(pb_leq(terms, 1).unwrap(), []);
+        }
+
+        let buffer = SharedBuffer::default();
+        let mut solver = Solver::new(model);
+        solver
+            .set_proof_log(ProofLog::new(ProofFormat::VeriPB, buffer.clone()))
+            .unwrap();
+        assert!(solver.solve().unwrap().is_none());
+        let lines = buffer.lines();
+        // the only axioms are the definitions of the constraints, all inferences are derived from them
+        assert_eq!(lines.iter().filter(|l| l.starts_with("a ")).count(), 4 + 3);
+        let (conclusion, last) = check_veripb(&lines);
+        assert!(conclusion.starts_with("UNSAT"));
+        assert_eq!(last, (vec![], 1));
+    }
+
+    #[test]
+    fn test_optimization_proof() {
+        use crate::model::lang::expr::{or, pb_leq};
+        type Model = crate::model::Model<&'static str>;
+        type Solver = crate::solver::Solver<&'static str>;
+
+        // minimize the cost of covering the pairs of three items, while picking at most two items
+        let mut model = Model::new();
+        let items: Vec<Lit> = (0..3).map(|_| model.new_bvar("item").true_lit()).collect();
+        let cost = model.new_ivar(0, 10, "cost");
+        for i in 0..3 {
+            model.enforce(or([items[i], items[(i + 1) % 3]]), []);
+            // each picked item costs at least `i + 1`
+            model.enforce(or([!items[i], cost.geq(i as IntCst + 1)]), []);
+        }
+        model.enforce(pb_leq(items.iter().map(|&l| (1, l)), 2).unwrap(), []);
+
+        let buffer = SharedBuffer::default();
+        let mut solver = Solver::new(model);
+        solver
+            .set_proof_log(ProofLog::new(ProofFormat::VeriPB, buffer.clone()))
+            .unwrap();
+        let (optimum, _) = solver.minimize(cost).unwrap().unwrap();
+        assert_eq!(optimum, 2);
+        let lines = buffer.lines();
+        // the objective bounds are hypotheses, refuted by the last lemma
+        let hypotheses = lines.iter().filter(|l| l.starts_with("* new solution")).count();
+        assert!(hypotheses >= 1);
+        let (conclusion, last) = check_veripb(&lines);
+        assert_eq!(conclusion, "NONE");
+        assert_eq!(last, (vec![(1, (format!("h{hypotheses}"), false))], 1));
+    }
+}
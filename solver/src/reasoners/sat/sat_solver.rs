@@ -5,6 +5,7 @@ use crate::core::state::{Domains, Event, Explanation, InferenceCause};
 use crate::core::*;
 use crate::model::extensions::{AssignmentExt, DisjunctionExt};
 use crate::reasoners::sat::clauses::*;
+use crate::reasoners::sat::proof::ProofLog;
 use crate::reasoners::{Contradiction, ReasonerId, Theory};
 use itertools::Itertools;
 use smallvec::alloc::collections::VecDeque;
@@ -51,16 +52,36 @@ enum SatEvent {
     Lock(ClauseId),
 }
 
+/// Parameters controlling the management of the learnt clauses database.
 #[derive(Clone)]
 pub struct SearchParams {
     /// Given a problem with N clauses, the number of learnt clause will initially be
     ///     init_learnt_base + N * int_learnt_ratio
-    init_learnt_ratio: f64,
-    init_learnt_base: f64,
+    pub init_learnt_ratio: f64,
+    pub init_learnt_base: f64,
     /// Ratio by which we will expand the DB size on an increase
-    db_expansion_ratio: f64,
+    pub db_expansion_ratio: f64,
     /// ratio by which we will increase the number of allowed conflict before doing a new DB increase
-    increase_ratio_of_conflicts_before_db_expansion: f64,
+    pub increase_ratio_of_conflicts_before_db_expansion: f64,
+    /// Learnt clauses with an LBD lower or equal to this value are placed in the core tier and never removed.
+    pub core_max_lbd: u32,
+    /// Learnt clauses with an LBD lower or equal to this value (and not in the core tier) are placed in the second tier,
+    /// where they are kept as long as they are regularly used.
+    pub tier2_max_lbd: u32,
+    /// Criterion for selecting the clauses to remove from the local tier on a reduction of the database.
+    pub ranking: ClauseRanking,
+}
+impl SearchParams {
+    /// Returns the tier in which a learnt clause with the given LBD should be placed.
+    pub fn tier(&self, lbd: u32) -> ClauseTier {
+        if lbd <= self.core_max_lbd {
+            ClauseTier::Core
+        } else if lbd <= self.tier2_max_lbd {
+            ClauseTier::Tier2
+        } else {
+            ClauseTier::Local
+        }
+    }
 }
 impl Default for SearchParams {
     fn default() -> Self {
@@ -69,6 +90,9 @@ impl Default for SearchParams {
             init_learnt_base: 1000_f64,
             db_expansion_ratio: 1.05_f64,
             increase_ratio_of_conflicts_before_db_expansion: 1.5_f64,
+            core_max_lbd: 2,
+            tier2_max_lbd: 6,
+            ranking: ClauseRanking::Lbd,
         }
     }
 }
@@ -95,6 +119,12 @@ impl Default for SearchState {
 pub struct Stats {
     pub conflicts: u64,
     pub propagations: u64,
+    /// Number of reductions of the learnt clauses database.
+    pub db_reductions: u64,
+    /// Number of learnt clauses that were moved to a better tier after their LBD was updated.
+    pub tier_promotions: u64,
+    /// Number of learnt clauses currently in the core, second and local tiers.
+    pub tier_sizes: [usize; 3],
 }
 
 #[allow(clippy::derivable_impls)]
@@ -103,6 +133,9 @@ impl Default for Stats {
         Stats {
             conflicts: 0,
             propagations: 0,
+            db_reductions: 0,
+            tier_promotions: 0,
+            tier_sizes: [0; 3],
         }
     }
 }
@@ -136,6 +169,10 @@ pub struct SatSolver {
     stats: Stats,
     /// A working data structure to avoid allocations during propagation
     working_watches: WatchSet<ClauseId>,
+    /// A working data structure to avoid allocations when computing the LBD of a clause
+    working_levels: Vec<DecLvl>,
+    /// If set, all clauses added to or removed from the database are recorded in this proof.
+    proof: Option<ProofLog>,
 }
 impl SatSolver {
     pub fn new(identity: ReasonerId) -> SatSolver {
@@ -151,9 +188,39 @@ impl SatSolver {
             state: Default::default(),
             stats: Default::default(),
             working_watches: Default::default(),
+            working_levels: Default::default(),
+            proof: None,
         }
     }
 
+    /// Sets the parameters controlling the management of the learnt clauses database.
+    pub fn set_search_params(&mut self, params: SearchParams) {
+        self.params = params;
+    }
+
+    pub fn search_params(&self) -> &SearchParams {
+        &self.params
+    }
+
+    /// Returns the statistics of the solver, with up-to-date sizes of the clause tiers.
+    pub fn stats(&self) -> Stats {
+        let mut stats = self.stats.clone();
+        stats.tier_sizes =
+            [ClauseTier::Core, ClauseTier::Tier2, ClauseTier::Local].map(|t| self.clauses.num_in_tier(t));
+        stats
+    }
+
+    /// Sets the proof in which clauses will be recorded.
+    /// Clauses that were previously added to the database are not part of the proof.
+    pub fn set_proof_log(&mut self, proof: Option<ProofLog>) {
+        self.proof = proof;
+    }
+
+    /// Returns the proof in which clauses are recorded, if any.
+    pub fn proof_log(&self) -> Option<&ProofLog> {
+        self.proof.as_ref()
+    }
+
     /// Adds a new clause that will be part of the problem definition.
     /// Returns a unique and stable identifier for the clause.
     pub fn add_clause(&mut self, clause: impl Into<Disjunction>) -> ClauseId {
@@ -186,6 +253,9 @@ impl SatSolver {
         self.stats.conflicts += 1;
         let clause = clause.into();
         debug_assert!(clause.contains(asserted));
+        if let Some(proof) = &self.proof {
+            proof.add_lemma(clause.literals().iter().copied());
+        }
         let cl_id = self.clauses.add_clause(Clause::new(clause), true);
 
         // There should be at most one learnt clause (with asserted literal) in the queue
@@ -199,6 +269,13 @@ impl SatSolver {
     }
 
     fn add_clause_impl(&mut self, clause: Clause, learnt: bool) -> ClauseId {
+        if let Some(proof) = &self.proof {
+            if learnt {
+                proof.add_lemma(clause.literals());
+            } else {
+                proof.add_axiom(clause.clause_with_scope());
+            }
+        }
         let cl_id = self.clauses.add_clause(clause, learnt);
         self.pending_clauses.push_back(PendingClause {
             clause: cl_id,
@@ -355,6 +432,9 @@ impl SatSolver {
                 }
                 // bump the activity of the clause
                 self.clauses.bump_activity(violated);
+                if self.clauses.is_learnt(violated) {
+                    self.update_lbd(violated, None, model);
+                }
                 Err(explanation)
             }
         }
@@ -367,6 +447,10 @@ impl SatSolver {
             asserted_literal,
         }) = self.pending_clauses.pop_front()
         {
+            if self.clauses.is_learnt(clause) {
+                // LBD at learning time, the asserted literal (if any) not being set yet
+                self.update_lbd(clause, None, model);
+            }
             if let Some(conflict) = self.process_arbitrary_clause(clause, model) {
                 return Err(conflict);
             }
@@ -512,28 +596,41 @@ impl SatSolver {
             // lock clause to ensure it will not be removed. This is necessary as we might need it to provide an explanation
             self.lock(propagating_clause);
             self.stats.propagations += 1;
-            // if self.clauses.is_learnt(propagating_clause) {
-            //     let lbd = self.lbd(literal, propagating_clause, model);
-            //     self.clauses.set_lbd(propagating_clause, lbd);
-            // }
-        }
-    }
-
-    // fn lbd(&mut self, asserted_literal: Lit, clause: ClauseId, model: &Domains) -> u32 {
-    //     let clause = &self.clauses[clause];
-    //
-    //     let mut lvls = HashSet::with_capacity(clause.len());
-    //     lvls.insert(self.current_decision_level());
-    //     for l in clause.literals() {
-    //         if l != asserted_literal {
-    //             let lvl = model.entailing_level(!l);
-    //             if lvl != DecLvl::ROOT {
-    //                 lvls.insert(lvl);
-    //             }
-    //         }
-    //     }
-    //     lvls.len() as u32
-    // }
+        }
+    }
+
+    /// Recomputes the LBD (Literal Block Distance) of a learnt clause, i.e., the number of distinct decision levels
+    /// of its literals, and promotes it to a better tier if its LBD decreased.
+    ///
+    /// Literals that are not yet false (except `ignored` that is assumed to be explained by the clause) are counted
+    /// as a single additional level, the one at which they will eventually be set. The root level is not counted.
+    fn update_lbd(&mut self, clause: ClauseId, ignored: Option<Lit>, model: &Domains) {
+        if self.clauses.tier(clause) == ClauseTier::Core {
+            return; // core clauses are never removed, their LBD is irrelevant
+        }
+        let levels = &mut self.working_levels;
+        levels.clear();
+        let mut has_unset = false;
+        for l in self.clauses[clause].literals() {
+            if Some(l) == ignored {
+                continue;
+            }
+            if model.entails(!l) {
+                let lvl = model.entailing_level(!l);
+                if lvl != DecLvl::ROOT {
+                    levels.push(lvl);
+                }
+            } else {
+                has_unset = true;
+            }
+        }
+        levels.sort_unstable();
+        levels.dedup();
+        let lbd = levels.len() as u32 + has_unset as u32;
+        if self.clauses.set_lbd(clause, lbd, self.params.tier(lbd)) {
+            self.stats.tier_promotions += 1;
+        }
+    }
 
     fn lock(&mut self, clause: ClauseId) {
         self.locks.lock(clause);
@@ -571,11 +668,15 @@ impl SatSolver {
         true
     }
 
-    pub fn explain(&mut self, literal: Lit, cause: u32, _model: &Domains, explanation: &mut Explanation) {
+    pub fn explain(&mut self, literal: Lit, cause: u32, model: &Domains, explanation: &mut Explanation) {
         //debug_assert_eq!(model.value(literal), None); TODO
         let clause = ClauseId::from(cause);
         // bump the activity of any clause use in an explanation
         self.clauses.bump_activity(clause);
+        if self.clauses.is_learnt(clause) {
+            let explained = self.clauses[clause].literals().find(|l| l.entails(literal));
+            self.update_lbd(clause, explained, model);
+        }
         let clause = &self.clauses[clause];
         // in a normal sat solver, we would expect the clause to be unit,
         // however it is not necessarily the case with eager propagation of optionals
@@ -595,16 +696,19 @@ impl SatSolver {
     /// If all slots are taken, this function can:
     ///  - expand the database with more slots. This occurs if a certain number of conflicts occurred
     ///    since the last expansion.
-    ///  - Remove learnt clauses from the DB. This typically removes about half the clauses, making
+    ///  - Remove learnt clauses from the DB. This typically removes about half the clauses of the local tier, making
     ///    sure that clauses that are used to explain the current value of the literal at kept.
-    ///    Clauses to be removed are the least active ones.
+    ///    Clauses to be removed are selected according to the ranking of the search parameters.
+    ///
+    /// Only the clauses of the local tier count towards the size of the database.
     fn scale_database(&mut self) {
         if self.state.allowed_learnt.is_nan() {
             let initial_clauses = self.clauses.num_clauses() - self.clauses.num_learnt();
             self.state.allowed_learnt =
                 self.params.init_learnt_base + initial_clauses as f64 * self.params.init_learnt_ratio;
         }
-        if self.clauses.num_learnt() as i64 - self.locks.num_locks() as i64 >= self.state.allowed_learnt as i64 {
+        let num_local = self.clauses.num_in_tier(ClauseTier::Local);
+        if num_local as i64 - self.locks.num_locks() as i64 >= self.state.allowed_learnt as i64 {
             // we exceed the number of learnt clause in the DB.
             // Check if it is time to increase the DB maximum size, otherwise shrink it.
             if self.stats.conflicts - self.state.conflicts_at_last_db_expansion
@@ -626,14 +730,27 @@ impl SatSolver {
                 let mut remove_watch = |clause: ClauseId, watched: Lit| {
                     watches.remove_watch(clause, watched);
                 };
-                self.clauses.reduce_db(|cl| locks.contains(cl), &mut remove_watch);
+                self.clauses.reduce_db(
+                    self.params.ranking,
+                    |cl| locks.contains(cl),
+                    &mut remove_watch,
+                    self.proof.as_ref(),
+                );
+                self.stats.db_reductions += 1;
             }
         }
     }
 
     pub fn print_stats(&self) {
+        let stats = self.stats();
         println!("DB size              : {}", self.clauses.num_clauses());
-        println!("Num unit propagations: {}", self.stats.propagations);
+        println!(
+            "Learnt (core/t2/loc) : {} / {} / {}",
+            stats.tier_sizes[0], stats.tier_sizes[1], stats.tier_sizes[2]
+        );
+        println!("DB reductions        : {}", stats.db_reductions);
+        println!("Tier promotions      : {}", stats.tier_promotions);
+        println!("Num unit propagations: {}", stats.propagations);
     }
 }
 
@@ -714,6 +831,39 @@ mod tests {
         assert_eq!(model.boolean_value_of(b), Some(true));
     }
 
+    #[test]
+    fn test_lbd_tiers() {
+        let model = &mut Model::new();
+        let xs: Vec<_> = (0..4).map(|_| model.new_bvar("x").true_lit()).collect();
+        let y = model.new_bvar("y").true_lit();
+
+        let mut sat = SatSolver::new(ReasonerId::Sat);
+        // make each x false at its own decision level
+        for &x in &xs {
+            model.save_state();
+            sat.save_state();
+            model.state.decide(!x).unwrap();
+        }
+        sat.propagate(&mut model.state).unwrap();
+
+        // LBD of 2: the level of x0 and the one at which y will be set
+        sat.add_forgettable_clause([xs[0], y]);
+        // LBD of 4
+        sat.add_forgettable_clause([xs[0], xs[1], xs[2], y]);
+        sat.propagate(&mut model.state).unwrap();
+        assert_eq!(sat.stats().tier_sizes, [1, 1, 0]);
+
+        sat.set_search_params(SearchParams {
+            core_max_lbd: 1,
+            tier2_max_lbd: 3,
+            ..Default::default()
+        });
+        sat.add_forgettable_clause([xs[1], y]);
+        sat.add_forgettable_clause([xs[0], xs[1], xs[2], xs[3], y]);
+        sat.propagate(&mut model.state).unwrap();
+        assert_eq!(sat.stats().tier_sizes, [1, 2, 1]);
+    }
+
     #[test]
     fn test_propagation_complex() {
         let writer = ReasonerId::Sat;
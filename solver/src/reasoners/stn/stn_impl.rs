@@ -60,6 +60,14 @@ impl Stn {
         self.add_edge(b.into(), a.into(), -delay);
     }
 
+    /// Adds a contingent link: the delay `b - a` is in `[lb, ub]` but is chosen by the environment.
+    pub fn add_contingent_link(&mut self, a: Timepoint, b: Timepoint, lb: W, ub: W) {
+        let valid_link = self.get_conjunctive_scope(a, b);
+        let active_link = self.model.get_tautology_of_scope(valid_link);
+        self.stn
+            .add_contingent_link(active_link, a, b, lb, ub, &self.model.state)
+    }
+
     /// Returns a literal that is true iff both timepoints are present.
     fn get_conjunctive_scope(&mut self, a: Timepoint, b: Timepoint) -> Lit {
         let pa = self.model.state.presence(a);
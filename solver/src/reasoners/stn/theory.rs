@@ -1,4 +1,6 @@
 mod contraint_db;
+mod dc;
+mod dispatch;
 mod distances;
 mod edges;
 
@@ -11,10 +13,12 @@ use crate::core::*;
 use crate::reasoners::stn::theory::Event::EdgeActivated;
 use crate::reasoners::{Contradiction, ReasonerId, Theory};
 use contraint_db::*;
+use dc::DcNetwork;
+pub use dispatch::{DispatchEdge, DispatchableNetwork};
 use distances::DijkstraState;
 use edges::*;
 use env_param::EnvParam;
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::*;
 use std::marker::PhantomData;
 use std::str::FromStr;
@@ -116,6 +120,7 @@ enum Event {
 struct Stats {
     num_propagations: u64,
     distance_updates: u64,
+    num_dc_checks: u64,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -156,7 +161,7 @@ where
 /// is to undo the latest change go back to a consistent network. All other
 /// operations have an undefined behavior.
 ///
-/// Requirement for weight : a i32 is used internally to represent both delays
+/// Requirement for weight : an `IntCst` is used internally to represent both delays
 /// (weight on edges) and absolute times (bound on nodes). It is the responsibility
 /// of the caller to ensure that no overflow occurs when adding an absolute and relative time,
 /// either by the choice of an appropriate type (e.g. saturating add) or by the choice of
@@ -184,6 +189,50 @@ pub struct StnTheory {
     internal_propagate_queue: VecDeque<SignedVar>,
     /// Internal data structures used for distance computation.
     internal_dijkstra_states: [DijkstraState; 2],
+    /// Contingent links of the network, whose duration is not controllable.
+    contingent_links: Vec<ContingentLink>,
+    /// Network of the last dynamic controllability check, extended and backtracked with the STN.
+    dc_network: DcNetwork<DcCulprit>,
+    /// Edges activated since the last dynamic controllability check, not yet considered for its network.
+    dc_activated: Vec<PropagatorId>,
+    /// Contingent links added since the last dynamic controllability check, not yet considered for its network.
+    dc_new_links: Vec<usize>,
+    /// Model events not yet considered by the dynamic controllability check.
+    dc_model_events: ObsTrailCursor<ModelEvent>,
+    /// Elements of the network to reconsider for the dynamic controllability check when a literal becomes true,
+    /// indexed by the signed variable of the literal.
+    dc_watches: HashMap<SignedVar, Vec<(Lit, DcWatcher)>>,
+    /// Timepoints whose presence is watched in `dc_watches`.
+    dc_watched_timepoints: HashSet<Timepoint>,
+}
+
+/// A contingent link `activation => contingent`, whose duration is chosen by the environment in `[lb, ub]`.
+#[derive(Copy, Clone, Debug)]
+struct ContingentLink {
+    /// Literal that is true iff the link is active.
+    literal: Lit,
+    activation: Timepoint,
+    contingent: Timepoint,
+    lb: W,
+    ub: W,
+}
+
+/// Element of the STN that may be involved in a failed dynamic controllability check.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum DcCulprit {
+    Edge(PropagatorId),
+    ContingentLink(usize),
+    /// A bound of a timepoint, given by the literal that holds.
+    Bound(Lit),
+}
+
+/// Element of the STN that may be added to the network of the dynamic controllability check once a literal holds.
+#[derive(Copy, Clone, Debug)]
+enum DcWatcher {
+    /// The active edges of the timepoint, waiting for its presence.
+    Timepoint(Timepoint),
+    /// The contingent link with the given index, waiting for its literal and the presence of its literal.
+    ContingentLink(usize),
 }
 
 /// Indicates the source and target of an active shortest path that caused a propagation
@@ -262,6 +311,13 @@ impl StnTheory {
             theory_propagation_causes: Default::default(),
             internal_propagate_queue: Default::default(),
             internal_dijkstra_states: Default::default(),
+            contingent_links: Vec::new(),
+            dc_network: DcNetwork::new(),
+            dc_activated: Vec::new(),
+            dc_new_links: Vec::new(),
+            dc_model_events: ObsTrailCursor::new(),
+            dc_watches: HashMap::new(),
+            dc_watched_timepoints: HashSet::new(),
         }
     }
     pub fn num_nodes(&self) -> u32 {
@@ -349,6 +405,56 @@ impl StnTheory {
         }
     }
 
+    /// Adds a contingent link stating that, when `literal` is true, the duration `contingent - activation`
+    /// is in `[lb, ub]` but chosen by the environment: it is only observed when `contingent` occurs.
+    ///
+    /// The bounds of the duration are added as regular edges, active when `literal` is true.
+    /// Additionally, the network of active edges and contingent links is required to be dynamically
+    /// controllable, i.e., that there exists a strategy to schedule all other timepoints that satisfies all
+    /// active edges whatever the durations chosen by the environment, provided it is only aware
+    /// of the durations that have been observed.
+    ///
+    /// The contingent timepoint should not be the contingent timepoint of another link.
+    pub fn add_contingent_link(
+        &mut self,
+        literal: Lit,
+        activation: impl Into<Timepoint>,
+        contingent: impl Into<Timepoint>,
+        lb: W,
+        ub: W,
+        domains: &Domains,
+    ) {
+        assert!(0 <= lb && lb <= ub, "Invalid duration of contingent link: [{lb}, {ub}]");
+        let activation = activation.into();
+        let contingent = contingent.into();
+        if self.contingent_links.is_empty() {
+            // activated edges are only recorded for the dynamic controllability check once there is a link
+            for tp in (0..self.num_nodes()).map(VarRef::from) {
+                for svar in [SignedVar::plus(tp), SignedVar::minus(tp)] {
+                    self.dc_activated
+                        .extend(self.active_propagators[svar].iter().map(|p| p.id));
+                }
+            }
+        }
+        self.add_reified_edge(literal, activation, contingent, ub, domains);
+        self.add_reified_edge(literal, contingent, activation, -lb, domains);
+        let link = self.contingent_links.len();
+        self.contingent_links.push(ContingentLink {
+            literal,
+            activation,
+            contingent,
+            lb,
+            ub,
+        });
+        self.dc_new_links.push(link);
+        for lit in [literal, domains.presence(literal.variable())] {
+            self.dc_watches
+                .entry(lit.svar())
+                .or_default()
+                .push((lit, DcWatcher::ContingentLink(link)));
+        }
+    }
+
     /// Creates and record a new propagator associated with the given [DirEdge], making sure
     /// to set up the watches to enable it when it becomes active and valid.
     fn record_propagator(&mut self, prop: Propagator, domains: &Domains) {
@@ -576,6 +682,9 @@ impl StnTheory {
                             id: edge,
                         });
                         self.trail.push(EdgeActivated(edge));
+                        if !self.contingent_links.is_empty() {
+                            self.dc_activated.push(edge);
+                        }
                         self.propagate_new_edge(edge, model)?;
 
                         if self.config.theory_propagation.edges() {
@@ -586,9 +695,173 @@ impl StnTheory {
             }
         }
 
+        if !self.contingent_links.is_empty() {
+            self.check_dynamic_controllability(model)?;
+        }
+
         Ok(())
     }
 
+    /// Checks that the network of all active edges and contingent links, restricted to present timepoints,
+    /// is dynamically controllable. The bounds of the timepoints at the root decision level are considered as
+    /// edges from/to the origin.
+    ///
+    /// Only the root bounds and the activation of edges, links and timepoints are requirements of the network,
+    /// including the activations made by the search. The bounds set by the search are not: a decision on a timepoint
+    /// only picks the scenario of the solution (i.e., one particular execution of the network) and the bounds it
+    /// implies may depend on the durations assumed for the contingent links. Hence, only the network at the root
+    /// bounds is guaranteed to be dynamically controllable, not the schedule of a solution.
+    ///
+    /// The check is incremental: the network is only extended with the edges activated, the links added and the
+    /// literals that became true since the previous check, and is backtracked with the STN.
+    /// On failure, the contradiction is explained by the enablers of the edges and contingent links, and by the
+    /// bounds that are involved in a semi-reducible negative cycle.
+    fn check_dynamic_controllability(&mut self, model: &Domains) -> Result<(), Contradiction> {
+        for link in std::mem::take(&mut self.dc_new_links) {
+            self.dc_add_link(link, model);
+        }
+        for edge in std::mem::take(&mut self.dc_activated) {
+            self.dc_add_edge(edge, model);
+        }
+        let mut triggered = Vec::new();
+        while let Some(event) = self.dc_model_events.pop(model.trail()).copied() {
+            let tp = event.affected_bound.variable();
+            if model.current_decision_level() == DecLvl::ROOT
+                && tp != VarRef::ZERO
+                && self.dc_network.contains(tp.into())
+            {
+                // tighter root bound of a timepoint of the network
+                self.dc_add_bound(event.new_literal());
+            }
+            if let Some(watches) = self.dc_watches.get(&event.affected_bound) {
+                triggered.extend(
+                    watches
+                        .iter()
+                        .filter(|(lit, _)| event.makes_true(*lit))
+                        .map(|&(_, watcher)| watcher),
+                );
+            }
+        }
+        for watcher in triggered {
+            match watcher {
+                DcWatcher::Timepoint(tp) => {
+                    let edges: Vec<PropagatorId> = [SignedVar::plus(tp), SignedVar::minus(tp)]
+                        .into_iter()
+                        .flat_map(|svar| self.active_propagators[svar].iter().map(|p| p.id))
+                        .collect();
+                    for edge in edges {
+                        self.dc_add_edge(edge, model);
+                    }
+                }
+                DcWatcher::ContingentLink(link) => self.dc_add_link(link, model),
+            }
+        }
+
+        if self.dc_network.num_links() == 0 {
+            // all contingent links are absent or inactive, the network is controllable iff it is consistent
+            return Ok(());
+        }
+        self.stats.num_dc_checks += 1;
+        match self.dc_network.check() {
+            Ok(()) => Ok(()),
+            Err(mut culprits) => {
+                culprits.sort();
+                culprits.dedup();
+                let mut expl = Explanation::with_capacity(2 * culprits.len());
+                for culprit in culprits {
+                    let lit = match culprit {
+                        DcCulprit::Edge(edge) => self.constraints[edge].enabler.expect("inactive edge").active,
+                        DcCulprit::ContingentLink(i) => self.contingent_links[i].literal,
+                        DcCulprit::Bound(lit) => lit,
+                    };
+                    debug_assert!(model.entails(lit) && model.entails(model.presence(lit.variable())));
+                    expl.push(lit);
+                    expl.push(model.presence(lit.variable()));
+                }
+                Err(Contradiction::Explanation(expl))
+            }
+        }
+    }
+
+    /// Adds an active edge to the network of the dynamic controllability check if both its timepoints are present.
+    /// If the presence of one of them is unknown, the edge is reconsidered when it becomes present.
+    fn dc_add_edge(&mut self, edge: PropagatorId, model: &Domains) {
+        let c = &self.constraints[edge];
+        // both propagators of an edge represent the constraint `target - source <= weight`
+        let (source, target) = if c.source.is_plus() {
+            (c.source.variable(), c.target.variable())
+        } else {
+            (c.target.variable(), c.source.variable())
+        };
+        let weight = c.weight.as_ub_add();
+        match (model.present(source), model.present(target)) {
+            (Some(true), Some(true)) => {
+                self.dc_add_root_bounds(source, model);
+                self.dc_add_root_bounds(target, model);
+                let (source, target) = (source.into(), target.into());
+                self.dc_network
+                    .add_requirement(source, target, weight, DcCulprit::Edge(edge));
+            }
+            (Some(false), _) | (_, Some(false)) => {
+                // the edge can never be part of the network
+            }
+            _ => {
+                for tp in [source, target] {
+                    if model.present(tp).is_none() && self.dc_watched_timepoints.insert(tp) {
+                        let presence = model.presence(tp);
+                        self.dc_watches
+                            .entry(presence.svar())
+                            .or_default()
+                            .push((presence, DcWatcher::Timepoint(tp)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds a contingent link to the network of the dynamic controllability check if it is active and present.
+    fn dc_add_link(&mut self, link: usize, model: &Domains) {
+        let ContingentLink {
+            literal,
+            activation,
+            contingent,
+            lb,
+            ub,
+        } = self.contingent_links[link];
+        if model.entails(literal) && model.entails(model.presence(literal.variable())) {
+            self.dc_add_root_bounds(activation, model);
+            self.dc_add_root_bounds(contingent, model);
+            let (a, c) = (activation.into(), contingent.into());
+            self.dc_network
+                .add_contingent_link(a, c, lb, ub, DcCulprit::ContingentLink(link));
+        }
+    }
+
+    /// Adds the root bounds of a timepoint to the network of the dynamic controllability check,
+    /// unless it is the origin or is already part of the network (in which case its bounds are already there).
+    fn dc_add_root_bounds(&mut self, tp: Timepoint, model: &Domains) {
+        if tp == VarRef::ZERO || self.dc_network.contains(tp.into()) {
+            return;
+        }
+        for svar in [SignedVar::plus(tp), SignedVar::minus(tp)] {
+            self.dc_add_bound(Lit::from_parts(svar, root_bound(svar, model)));
+        }
+    }
+
+    /// Adds the bound `tp <= ub` or `-tp <= -lb` of a timepoint as an edge from/to the origin.
+    fn dc_add_bound(&mut self, bound: Lit) {
+        let value = bound.bound_value().as_int();
+        if value >= INT_CST_MAX {
+            return;
+        }
+        let tp = bound.variable().into();
+        if bound.svar().is_plus() {
+            self.dc_network.add_requirement(0, tp, value, DcCulprit::Bound(bound));
+        } else {
+            self.dc_network.add_requirement(tp, 0, value, DcCulprit::Bound(bound));
+        }
+    }
+
     /// Creates a new backtrack point that represents the STN at the point of the method call,
     /// just before the insertion of the backtrack point.
     pub fn set_backtrack_point(&mut self) -> BacktrackLevel {
@@ -598,6 +871,7 @@ impl StnTheory {
             The code introduced in this commit should enable this but has not been thoroughly tested yet."
         );
         self.trail.save_state();
+        self.dc_network.save_state();
         self.constraints.save_state()
     }
 
@@ -618,6 +892,8 @@ impl StnTheory {
             }
         });
         self.constraints.restore_last();
+        self.dc_network.restore_last();
+        self.dc_activated.clear();
 
         None
     }
@@ -744,6 +1020,10 @@ impl StnTheory {
         println!("# propagators: {}", self.constraints.num_propagator_groups());
         println!("# propagations: {}", self.stats.num_propagations);
         println!("# domain updates: {}", self.stats.distance_updates);
+        if !self.contingent_links.is_empty() {
+            println!("# contingent links: {}", self.contingent_links.len());
+            println!("# controllability checks: {}", self.stats.num_dc_checks);
+        }
     }
 
     /******** Distances ********/
@@ -977,6 +1257,60 @@ impl StnTheory {
         dists.distances().map(|(v, d)| (v.variable(), d.as_lb_add())).collect()
     }
 
+    /// Returns the minimal dispatchable network equivalent to the active edges of the STN, projected on the
+    /// given timepoints. The current bounds of the variables in `model` are represented as edges from and to
+    /// the origin, so the bounds of the timepoints should not be fixed to a particular solution.
+    /// Absent timepoints are ignored, as well as any constraint that is not handled by this theory.
+    ///
+    /// Returns `None` if the active edges are inconsistent with the bounds.
+    pub fn dispatchable_network(&self, timepoints: &[Timepoint], model: &Domains) -> Option<DispatchableNetwork> {
+        let mut edges = Vec::new();
+        let mut vars: Vec<VarRef> = Vec::new();
+        for source in (0..self.num_nodes()).map(VarRef::from) {
+            if model.present(source) != Some(true) {
+                continue;
+            }
+            for prop in &self.active_propagators[SignedVar::plus(source)] {
+                let target = prop.target.variable();
+                if prop.target.is_plus() && model.present(target) == Some(true) {
+                    vars.push(source);
+                    vars.push(target);
+                    edges.push(DispatchEdge {
+                        source,
+                        target,
+                        weight: prop.weight.as_ub_add(),
+                    });
+                }
+            }
+        }
+        let timepoints: Vec<Timepoint> = timepoints
+            .iter()
+            .copied()
+            .filter(|&tp| model.present(tp) == Some(true))
+            .collect();
+        vars.extend_from_slice(&timepoints);
+        vars.sort();
+        vars.dedup();
+        for var in vars.into_iter().filter(|&v| v != VarRef::ZERO) {
+            let (lb, ub) = model.bounds(var);
+            if ub < INT_CST_MAX {
+                edges.push(DispatchEdge {
+                    source: VarRef::ZERO,
+                    target: var,
+                    weight: ub,
+                });
+            }
+            if lb > INT_CST_MIN {
+                edges.push(DispatchEdge {
+                    source: var,
+                    target: VarRef::ZERO,
+                    weight: -lb,
+                });
+            }
+        }
+        DispatchableNetwork::new(&edges, &timepoints)
+    }
+
     /// Computes the one-to-all shortest paths in an STN.
     /// The shortest paths are:
     ///  - in the forward graph if the origin is the upper bound of a variable
@@ -1129,6 +1463,18 @@ impl StnTheory {
     }
 }
 
+/// Returns the value of the given bound at the root decision level.
+fn root_bound(bound: SignedVar, model: &Domains) -> UpperBound {
+    let mut lit = Lit::from_parts(bound, model.get_bound(bound));
+    while let Some(event) = model.implying_event(lit) {
+        if model.trail().decision_level(event) == DecLvl::ROOT {
+            break;
+        }
+        lit = Lit::from_parts(bound, model.get_event(event).previous.value);
+    }
+    lit.bound_value()
+}
+
 impl Theory for StnTheory {
     fn identity(&self) -> ReasonerId {
         self.identity.writer_id
@@ -1258,13 +1604,13 @@ mod tests {
         assert_bounds(s, 0, 1, 0, 10);
         s.set_backtrack_point();
 
-        let ab = s.add_edge(a, b, 5i32);
+        let ab = s.add_edge(a, b, 5);
         s.assert_consistent();
         assert_bounds(s, 0, 1, 0, 6);
 
         s.set_backtrack_point();
 
-        let ba = s.add_edge(b, a, -6i32);
+        let ba = s.add_edge(b, a, -6);
         s.assert_inconsistent(vec![ab, ba]);
 
         s.undo_to_last_backtrack_point();
@@ -1273,12 +1619,26 @@ mod tests {
         s.undo_to_last_backtrack_point();
         assert_bounds(s, 0, 1, 0, 10);
 
-        let x = s.add_inactive_edge(a, b, 5i32);
+        let x = s.add_inactive_edge(a, b, 5);
         s.mark_active(x);
         s.assert_consistent();
         assert_bounds(s, 0, 1, 0, 6);
     }
 
+    #[test]
+    #[cfg(feature = "i64")]
+    fn test_wide_horizon() {
+        // thirty days with a millisecond resolution, which does not fit on 32 bits
+        const MONTH: IntCst = 30 * 24 * 3600 * 1000;
+        let s = &mut Stn::new();
+        let a = s.add_timepoint(0, 2 * MONTH);
+        let b = s.add_timepoint(0, 2 * MONTH);
+        s.add_edge(b, a, -MONTH);
+        s.assert_consistent();
+        assert_eq!(s.model.int_bounds(IVar::new(a)), (0, MONTH));
+        assert_eq!(s.model.int_bounds(IVar::new(b)), (MONTH, 2 * MONTH));
+    }
+
     #[test]
     fn test_explanation() -> Result<(), Contradiction> {
         let stn = &mut Stn::new();
@@ -1371,13 +1731,13 @@ mod tests {
 
         stn.propagate_all()?;
         for (i, (_prez, var)) in vars.iter().enumerate() {
-            let i = i as i32;
+            let i = i as IntCst;
             assert_eq!(stn.model.int_bounds(*var), (i, 20));
         }
         stn.model.state.set_ub(vars[5].1, 4, Cause::Decision)?;
         stn.propagate_all()?;
         for (i, (_prez, var)) in vars.iter().enumerate() {
-            let i = i as i32;
+            let i = i as IntCst;
             if i <= 4 {
                 assert_eq!(stn.model.int_bounds(*var), (i, 20));
             } else {
@@ -1661,4 +2021,209 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_dynamic_controllability() -> Result<(), Contradiction> {
+        let stn = &mut Stn::new();
+        let a = stn.add_timepoint(0, 100);
+        let b = stn.add_timepoint(0, 100);
+        let c = stn.add_timepoint(0, 100);
+        // c occurs between 1 and 3 after a, as chosen by the environment
+        stn.add_contingent_link(a, c, 1, 3);
+        stn.add_delay(a, b, 0);
+        stn.propagate_all()?;
+
+        // b occurs at most 2 before c: it can wait for c
+        let max_before = stn.add_inactive_edge(b, c, 2);
+        // b occurs at least 1 before c: it cannot wait for c and can be executed at a
+        let min_before = stn.add_inactive_edge(c, b, -1);
+        stn.propagate_all()?;
+
+        stn.set_backtrack_point();
+        stn.mark_active(max_before);
+        stn.propagate_all()?;
+        stn.set_backtrack_point();
+        stn.mark_active(min_before);
+        // the network is consistent (c = a + 3) but b cannot be scheduled before observing c
+        match stn.propagate_all() {
+            Err(Contradiction::Explanation(explanation)) => {
+                assert!(explanation.lits.contains(&max_before));
+                assert!(explanation.lits.contains(&min_before));
+            }
+            _ => panic!("The network is not dynamically controllable"),
+        }
+
+        // without the first constraint, b can be executed at a
+        stn.undo_to_last_backtrack_point();
+        stn.undo_to_last_backtrack_point();
+        stn.mark_active(min_before);
+        stn.propagate_all()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_dynamic_controllability_with_bounds() -> Result<(), Contradiction> {
+        // the contingent duration is too long for the deadline
+        let stn = &mut Stn::new();
+        let a = stn.add_timepoint(6, 10);
+        let c = stn.add_timepoint(0, 10);
+        stn.add_contingent_link(a, c, 2, 5);
+        assert!(stn.propagate_all().is_err());
+
+        // the root bounds are requirements: executing a after 5 does not leave enough time for the link
+        let stn = &mut Stn::new();
+        let a = stn.add_timepoint(0, 10);
+        let c = stn.add_timepoint(0, 10);
+        stn.add_contingent_link(a, c, 2, 5);
+        stn.propagate_all()?;
+        assert_eq!(stn.model.domain_of(a), (0, 8));
+        stn.set_lb(a, 6);
+        match stn.propagate_all() {
+            Err(Contradiction::Explanation(explanation)) => {
+                assert!(explanation.lits.contains(&Lit::geq(a, 6)));
+            }
+            _ => panic!("The network is not dynamically controllable"),
+        }
+
+        // a decision only picks a scenario, in which the link is shorter than 5: the network at the root bounds is
+        // dynamically controllable, but the schedule with a = 6 is not executable if the environment picks 5
+        let stn = &mut Stn::new();
+        let a = stn.add_timepoint(0, 10);
+        let c = stn.add_timepoint(0, 10);
+        stn.add_contingent_link(a, c, 2, 5);
+        stn.propagate_all()?;
+        stn.set_backtrack_point();
+        stn.set_lb(a, 6);
+        stn.propagate_all()?;
+        assert_eq!(stn.model.domain_of(c), (8, 10));
+
+        // a bound on a contingent timepoint is not a schedule choice (only the root bounds are requirements)
+        let stn = &mut Stn::new();
+        let a = stn.add_timepoint(0, 0);
+        let c = stn.add_timepoint(0, 10);
+        stn.add_contingent_link(a, c, 1, 3);
+        stn.propagate_all()?;
+        stn.set_backtrack_point();
+        stn.set_ub(c, 2);
+        stn.propagate_all()?;
+
+        // b waits for c and is executed at most 1 after it
+        let stn = &mut Stn::new();
+        let a = stn.add_timepoint(0, 0);
+        let b = stn.add_timepoint(0, 10);
+        let c = stn.add_timepoint(0, 10);
+        stn.add_contingent_link(a, c, 1, 3);
+        stn.add_delay(c, b, 0);
+        stn.add_delay(b, c, -1);
+        stn.propagate_all()?;
+        // a decision that forces the duration of the link picks the scenario where c occurs at 1
+        stn.set_backtrack_point();
+        stn.set_ub(b, 1);
+        stn.propagate_all()?;
+        assert_eq!(stn.model.domain_of(c), (1, 1));
+        // the same bound at the root is a requirement that does not hold if the environment picks a duration of 3
+        stn.undo_to_last_backtrack_point();
+        stn.set_ub(b, 1);
+        assert!(stn.propagate_all().is_err());
+
+        // a link on an absent timepoint is ignored
+        let stn = &mut Stn::new();
+        let prez = stn.model.new_bvar("prez").true_lit();
+        let a = stn.add_timepoint(6, 10);
+        let c = stn.model.new_optional_ivar(0, 10, prez, "c").into();
+        stn.add_contingent_link(a, c, 2, 5);
+        stn.propagate_all()?;
+        stn.model.state.set(!prez, Cause::Decision)?;
+        stn.propagate_all()?;
+
+        // the link and its edges are only added to the network once the timepoint is known to be present
+        let stn = &mut Stn::new();
+        let prez = stn.model.new_bvar("prez").true_lit();
+        let a = stn.add_timepoint(6, 10);
+        let c = stn.model.new_optional_ivar(0, 10, prez, "c").into();
+        stn.add_contingent_link(a, c, 2, 5);
+        stn.propagate_all()?;
+        for _ in 0..2 {
+            // the network is restored on backtrack, and extended again when the timepoint becomes present again
+            stn.set_backtrack_point();
+            stn.model.state.set(prez, Cause::Decision)?;
+            assert!(stn.propagate_all().is_err());
+            stn.undo_to_last_backtrack_point();
+        }
+        stn.set_backtrack_point();
+        stn.model.state.set(!prez, Cause::Decision)?;
+        stn.propagate_all()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_dispatchable_network() {
+        use rand::rngs::SmallRng;
+        use rand::{Rng, SeedableRng};
+        use std::collections::HashMap;
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut num_consistent = 0;
+        for _ in 0..200 {
+            let stn = &mut Stn::new();
+            let tps: Vec<Timepoint> = (0..6).map(|_| stn.add_timepoint(0, 50)).collect();
+            for _ in 0..8 {
+                let a = tps[rng.gen_range(0..tps.len())];
+                let b = tps[rng.gen_range(0..tps.len())];
+                if a != b {
+                    stn.add_edge(a, b, rng.gen_range(-10..20));
+                }
+            }
+            if stn.propagate_all().is_err() {
+                continue;
+            }
+            num_consistent += 1;
+            // the last two timepoints are not part of the plan
+            let plan = &tps[0..4];
+            let network = stn.stn.dispatchable_network(plan, &stn.model.state).unwrap();
+
+            for _ in 0..10 {
+                // dispatch the timepoints at a random time in their window, only looking at the direct neighbours
+                let mut executed: HashMap<VarRef, W> = HashMap::new();
+                executed.insert(VarRef::ZERO, 0);
+                let mut now = 0;
+                while executed.len() < network.timepoints.len() {
+                    let exec = |v: VarRef| executed.get(&v).copied();
+                    let enabled: Vec<(VarRef, LongCst, LongCst)> = network
+                        .timepoints
+                        .iter()
+                        .copied()
+                        .filter(|&tp| exec(tp).is_none() && network.is_enabled(tp, exec))
+                        .map(|tp| {
+                            let (lb, ub) = network.window(tp, exec);
+                            (tp, lb.max(now), ub)
+                        })
+                        .collect();
+                    let earliest = enabled.iter().map(|e| e.1).min().unwrap();
+                    let deadline = enabled.iter().map(|e| e.2).min().unwrap().min(100);
+                    assert!(earliest <= deadline, "dead-end while dispatching");
+                    let time = rng.gen_range(earliest..=deadline);
+                    let candidates: Vec<VarRef> = enabled
+                        .iter()
+                        .filter(|e| e.1 <= time && time <= e.2)
+                        .map(|e| e.0)
+                        .collect();
+                    let tp = candidates[rng.gen_range(0..candidates.len())];
+                    executed.insert(tp, time as W);
+                    now = time;
+                }
+                assert!(network.is_satisfied_by(|v| executed[&v]));
+
+                // the dispatch can be extended to the timepoints that are not part of the plan
+                stn.set_backtrack_point();
+                for tp in plan {
+                    stn.set_lb(*tp, executed[tp]);
+                    stn.set_ub(*tp, executed[tp]);
+                }
+                assert!(stn.propagate_all().is_ok());
+                stn.undo_to_last_backtrack_point();
+            }
+        }
+        assert!(num_consistent > 50);
+    }
 }
@@ -0,0 +1,521 @@
+//! Dynamic controllability checking of Simple Temporal Networks with Uncertainty (STNU).
+//!
+//! The check is an implementation of the O(n³) algorithm of [Morris 2014]: each negative node is
+//! the source of a backward Dijkstra propagation that derives non-negative edges, and the network is
+//! dynamically controllable iff no propagation needs to recursively go through one of its ancestors
+//! (which would correspond to a semi-reducible negative cycle).
+//! Each derived edge keeps track of the path it summarizes so that a non-controllable network can be
+//! explained by the set of original constraints involved in the cycle.
+//!
+//! The check is incremental: the network only grows between checks and the derived edges remain valid, so only
+//! the propagations that went through a node with new incoming edges are redone. All changes are recorded on
+//! a trail, to restore the network (with its derived edges) when backtracking.
+//!
+//! [Morris 2014]: Paul Morris. Dynamic Controllability and Dispatchability Relationships. CPAIOR 2014.
+
+use crate::backtrack::{Backtrack, DecLvl, Trail};
+use crate::reasoners::stn::theory::W;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Index of a timepoint in a [DcNetwork].
+pub(crate) type Node = usize;
+
+type EdgeId = usize;
+
+/// Label of an edge in the labelled distance graph.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Label {
+    Ordinary,
+    /// Lower-case edge `A --c:lb--> C` of the contingent link with the given index.
+    LowerCase(usize),
+    /// Upper-case edge `C --C:-ub--> A` of the contingent link with the given index.
+    UpperCase(usize),
+}
+
+/// Justification of an edge of the network.
+#[derive(Clone, Debug)]
+enum Provenance<T> {
+    /// The edge was given as input and is justified by the associated tag.
+    Original(T),
+    /// The edge was derived and summarizes the given path of edges.
+    Derived(Vec<EdgeId>),
+}
+
+#[derive(Clone, Debug)]
+struct LabelledEdge<T> {
+    source: Node,
+    target: Node,
+    weight: W,
+    label: Label,
+    provenance: Provenance<T>,
+}
+
+/// A change to the network, recorded with the information needed to undo it.
+#[derive(Clone)]
+enum Change {
+    EdgeAdded,
+    /// The node became the source or target of an edge.
+    NodeAdded(Node),
+    ContingentLinkAdded,
+    /// The tightest requirement between two nodes changed, from the given previous weight.
+    Requirement((Node, Node), Option<W>),
+    /// The propagation from a node was completed or invalidated, the previous nodes it reached are given.
+    Reached(Node, Option<Vec<Node>>),
+}
+
+/// A backward propagation from a negative node.
+struct Propagation {
+    source: Node,
+    /// Distance from each node to the source.
+    distances: Vec<Option<W>>,
+    /// Edge leaving each node on its shortest path to the source.
+    predecessors: Vec<Option<EdgeId>>,
+    /// For each node, the contingent link whose upper-case edge starts its shortest path to the source, if any.
+    first_upper_case: Vec<Option<usize>>,
+    /// Node from which a nested propagation was started, if any.
+    current: Node,
+}
+
+impl Propagation {
+    /// Returns the edges on the shortest path from `node` to the source.
+    /// If `node` is the source, this is the negative cycle that reached it, if any.
+    fn path(&self, mut node: Node, edges: &[LabelledEdge<impl Clone>]) -> Vec<EdgeId> {
+        let mut path = Vec::new();
+        while node != self.source || (path.is_empty() && self.predecessors[node].is_some()) {
+            let e = self.predecessors[node].expect("No path to source");
+            path.push(e);
+            node = edges[e].target;
+        }
+        path
+    }
+}
+
+/// Labelled distance graph of an STNU, whose edges are tagged with the constraint that justifies them.
+///
+/// A requirement edge `source --w--> target` represents the constraint `target - source <= w`, while a
+/// contingent link `(activation, contingent, lb, ub)` states that the duration `contingent - activation`
+/// is chosen by the environment in `[lb, ub]` and only observed once it occurred.
+#[derive(Clone)]
+pub(crate) struct DcNetwork<T> {
+    edges: Vec<LabelledEdge<T>>,
+    incoming: Vec<Vec<EdgeId>>,
+    /// Whether each node is the source or target of an edge.
+    nodes: Vec<bool>,
+    /// Activation and contingent nodes of each contingent link.
+    links: Vec<(Node, Node)>,
+    /// Weight of the tightest requirement between two nodes.
+    requirements: HashMap<(Node, Node), W>,
+    /// For each node whose propagation is completed, the nodes whose incoming edges were used by the propagation.
+    reached: Vec<Option<Vec<Node>>>,
+    /// Number of edges that were part of the network at the end of the last successful check.
+    num_checked: usize,
+    trail: Trail<Change>,
+}
+
+impl<T: Clone> DcNetwork<T> {
+    pub fn new() -> Self {
+        DcNetwork {
+            edges: Vec::new(),
+            incoming: Vec::new(),
+            nodes: Vec::new(),
+            links: Vec::new(),
+            requirements: HashMap::new(),
+            reached: Vec::new(),
+            num_checked: 0,
+            trail: Trail::new(),
+        }
+    }
+
+    pub fn num_links(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Returns true if the node is the source or target of an edge of the network.
+    pub fn contains(&self, node: Node) -> bool {
+        self.nodes.get(node).copied().unwrap_or(false)
+    }
+
+    fn add(&mut self, source: Node, target: Node, weight: W, label: Label, provenance: Provenance<T>) -> EdgeId {
+        let id = self.edges.len();
+        while self.incoming.len() <= source.max(target) {
+            self.incoming.push(Vec::new());
+            self.nodes.push(false);
+            self.reached.push(None);
+        }
+        for node in [source, target] {
+            if !self.nodes[node] {
+                self.nodes[node] = true;
+                self.trail.push(Change::NodeAdded(node));
+            }
+        }
+        self.trail.push(Change::EdgeAdded);
+        self.edges.push(LabelledEdge {
+            source,
+            target,
+            weight,
+            label,
+            provenance,
+        });
+        self.incoming[target].push(id);
+        id
+    }
+
+    /// Adds the requirement `target - source <= weight`, justified by `tag`.
+    /// The requirement is ignored if it is subsumed by a previous requirement between the same nodes.
+    pub fn add_requirement(&mut self, source: Node, target: Node, weight: W, tag: T) {
+        let previous = self.requirements.get(&(source, target)).copied();
+        if previous.is_some_and(|w| w <= weight) {
+            return;
+        }
+        self.trail.push(Change::Requirement((source, target), previous));
+        self.requirements.insert((source, target), weight);
+        self.add(source, target, weight, Label::Ordinary, Provenance::Original(tag));
+    }
+
+    /// Adds a contingent link whose duration `contingent - activation` is in `[lb, ub]`, justified by `tag`.
+    /// The link is ignored if the network already has a link between the same nodes.
+    pub fn add_contingent_link(&mut self, activation: Node, contingent: Node, lb: W, ub: W, tag: T) {
+        debug_assert!(0 <= lb && lb <= ub, "Invalid contingent duration [{lb}, {ub}]");
+        if self.links.contains(&(activation, contingent)) {
+            return;
+        }
+        let link = self.links.len();
+        self.links.push((activation, contingent));
+        self.trail.push(Change::ContingentLinkAdded);
+        let tag = || Provenance::Original(tag.clone());
+        self.add(activation, contingent, ub, Label::Ordinary, tag());
+        self.add(contingent, activation, -lb, Label::Ordinary, tag());
+        self.add(activation, contingent, lb, Label::LowerCase(link), tag());
+        self.add(contingent, activation, -ub, Label::UpperCase(link), tag());
+    }
+
+    /// A node is negative if it has an incoming negative edge.
+    /// Upper-case edges are always considered negative, even when their weight is null.
+    fn is_negative(&self, node: Node) -> bool {
+        self.incoming[node].iter().any(|&e| {
+            let e = &self.edges[e];
+            e.weight < 0 || matches!(e.label, Label::UpperCase(_))
+        })
+    }
+
+    fn set_reached(&mut self, node: Node, reached: Option<Vec<Node>>) {
+        let previous = std::mem::replace(&mut self.reached[node], reached);
+        self.trail.push(Change::Reached(node, previous));
+    }
+
+    /// Checks whether the network is dynamically controllable.
+    ///
+    /// If it is not, returns the tags of all constraints involved in a semi-reducible negative cycle:
+    /// the network restricted to these constraints is not dynamically controllable either.
+    /// Edges derived by the check are kept in the network.
+    ///
+    /// Only the propagations that went through a node with new incoming edges since the last successful check
+    /// (or through the source of such a propagation) are redone.
+    pub fn check(&mut self) -> Result<(), Vec<T>> {
+        let n = self.incoming.len();
+        let mut modified = vec![false; n];
+        for e in &self.edges[self.num_checked..] {
+            modified[e.target] = true;
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for source in 0..n {
+                let invalid = self.reached[source]
+                    .as_ref()
+                    .is_some_and(|reached| reached.iter().any(|&node| modified[node]));
+                if invalid {
+                    // the propagations that went through this source must be redone as well
+                    modified[source] = true;
+                    self.set_reached(source, None);
+                    changed = true;
+                }
+            }
+        }
+
+        let mut in_progress = vec![None; n];
+        let mut stack = Vec::new();
+        for node in 0..n {
+            if self.reached[node].is_none() && self.is_negative(node) {
+                self.backpropagate(node, &mut in_progress, &mut stack)?;
+            }
+        }
+        self.num_checked = self.edges.len();
+        Ok(())
+    }
+
+    /// Propagates backward from `source`, unless it was already done.
+    /// `in_progress` gives the depth in the stack of the propagation of each node that is in progress.
+    fn backpropagate(
+        &mut self,
+        source: Node,
+        in_progress: &mut [Option<usize>],
+        stack: &mut Vec<Propagation>,
+    ) -> Result<(), Vec<T>> {
+        if let Some(depth) = in_progress[source] {
+            return Err(self.cycle_explanation(&stack[depth..]));
+        }
+        if self.reached[source].is_some() {
+            return Ok(());
+        }
+        in_progress[source] = Some(stack.len());
+        let n = self.incoming.len();
+        stack.push(Propagation {
+            source,
+            distances: vec![None; n],
+            predecessors: vec![None; n],
+            first_upper_case: vec![None; n],
+            current: source,
+        });
+        let mut queue = BinaryHeap::new();
+
+        stack.last_mut().unwrap().distances[source] = Some(0);
+        for &e in &self.incoming[source] {
+            let edge = &self.edges[e];
+            let first_upper_case = match edge.label {
+                Label::UpperCase(link) => Some(link),
+                Label::Ordinary if edge.weight < 0 => None,
+                _ => continue,
+            };
+            let prop = stack.last_mut().unwrap();
+            if prop.distances[edge.source].is_none_or(|d| edge.weight < d) {
+                prop.distances[edge.source] = Some(edge.weight);
+                prop.predecessors[edge.source] = Some(e);
+                prop.first_upper_case[edge.source] = first_upper_case;
+                queue.push(Reverse((edge.weight, edge.source)));
+            }
+        }
+
+        while let Some(Reverse((dist, node))) = queue.pop() {
+            if stack.last().unwrap().distances[node] != Some(dist) {
+                continue; // outdated entry
+            }
+            if dist >= 0 {
+                // summarize the path with a non-negative edge and stop propagating
+                let path = stack.last().unwrap().path(node, &self.edges);
+                let subsumed = self.incoming[source].iter().any(|&e| {
+                    let e = &self.edges[e];
+                    e.source == node && e.label == Label::Ordinary && e.weight <= dist
+                });
+                if !subsumed {
+                    self.add(node, source, dist, Label::Ordinary, Provenance::Derived(path));
+                }
+                continue;
+            }
+            if self.is_negative(node) {
+                // derive all non-negative edges into this node before going through it
+                stack.last_mut().unwrap().current = node;
+                self.backpropagate(node, in_progress, stack)?;
+            }
+            let prop = stack.last_mut().unwrap();
+            let first_upper_case = prop.first_upper_case[node];
+            // note: the previous recursive call might have added new edges
+            for &e in &self.incoming[node] {
+                let edge = &self.edges[e];
+                match edge.label {
+                    _ if edge.weight < 0 => continue,
+                    Label::UpperCase(_) => continue,
+                    Label::LowerCase(link) if first_upper_case == Some(link) => continue, // unusable
+                    _ => {}
+                }
+                let new_dist = dist.saturating_add(edge.weight);
+                if prop.distances[edge.source].is_none_or(|d| new_dist < d) {
+                    prop.distances[edge.source] = Some(new_dist);
+                    prop.predecessors[edge.source] = Some(e);
+                    prop.first_upper_case[edge.source] = first_upper_case;
+                    queue.push(Reverse((new_dist, edge.source)));
+                }
+            }
+        }
+
+        let prop = stack.pop().unwrap();
+        in_progress[source] = None;
+        // nodes whose incoming edges were used: the source and the nodes at a negative distance
+        let reached = (0..n)
+            .filter(|&node| node == source || prop.distances[node].is_some_and(|d| d < 0))
+            .collect();
+        self.set_reached(source, Some(reached));
+        Ok(())
+    }
+
+    /// Builds the explanation of a cycle through all the given (nested) propagations, where
+    /// the node reached by the last one is the source of the first.
+    fn cycle_explanation(&self, propagations: &[Propagation]) -> Vec<T> {
+        let mut visited = vec![false; self.edges.len()];
+        let mut pending: Vec<EdgeId> = propagations
+            .iter()
+            .flat_map(|prop| prop.path(prop.current, &self.edges))
+            .collect();
+        let mut tags = Vec::new();
+        while let Some(e) = pending.pop() {
+            if visited[e] {
+                continue;
+            }
+            visited[e] = true;
+            match &self.edges[e].provenance {
+                Provenance::Original(tag) => tags.push(tag.clone()),
+                Provenance::Derived(path) => pending.extend_from_slice(path),
+            }
+        }
+        tags
+    }
+}
+
+impl<T> Backtrack for DcNetwork<T> {
+    fn save_state(&mut self) -> DecLvl {
+        self.trail.save_state()
+    }
+
+    fn num_saved(&self) -> u32 {
+        self.trail.num_saved()
+    }
+
+    fn restore_last(&mut self) {
+        self.trail.restore_last_with(|change| match change {
+            Change::EdgeAdded => {
+                let edge = self.edges.pop().unwrap();
+                let removed = self.incoming[edge.target].pop();
+                debug_assert_eq!(removed, Some(self.edges.len()));
+            }
+            Change::NodeAdded(node) => self.nodes[node] = false,
+            Change::ContingentLinkAdded => {
+                self.links.pop();
+            }
+            Change::Requirement(nodes, previous) => match previous {
+                Some(weight) => {
+                    self.requirements.insert(nodes, weight);
+                }
+                None => {
+                    self.requirements.remove(&nodes);
+                }
+            },
+            Change::Reached(node, previous) => self.reached[node] = previous,
+        });
+        self.num_checked = self.num_checked.min(self.edges.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: Node = 0;
+    const B: Node = 1;
+    const C: Node = 2;
+
+    /// Network with a contingent link `A => C` in `[lb, ub]` and the requirement `C - B` in `[min, max]`.
+    fn network(lb: W, ub: W, min: W, max: W) -> DcNetwork<&'static str> {
+        let mut network = DcNetwork::new();
+        network.add_contingent_link(A, C, lb, ub, "link");
+        network.add_requirement(B, C, max, "max");
+        network.add_requirement(C, B, -min, "min");
+        // B is after A
+        network.add_requirement(B, A, 0, "after");
+        network
+    }
+
+    #[test]
+    fn test_controllable() {
+        // B can be executed right after the end of the link is observed
+        assert!(network(1, 3, -1, 0).check().is_ok());
+        // B must be executed before C, as A + lb - 1
+        assert!(network(2, 5, 1, 4).check().is_ok());
+        // B can wait for C
+        assert!(network(1, 10, -1, 1).check().is_ok());
+        // no uncertainty
+        assert!(network(3, 3, 0, 0).check().is_ok());
+    }
+
+    #[test]
+    fn test_not_controllable() {
+        // B should be between 1 and 2 time units before C, whose occurrence is uncertain
+        let mut explanation = network(1, 3, 1, 2).check().unwrap_err();
+        explanation.sort();
+        explanation.dedup();
+        assert_eq!(explanation, vec!["link", "max", "min"]);
+
+        // B must be between 1 and 3 before C, but the duration of the link has an uncertainty of 3
+        assert!(network(2, 5, 1, 3).check().is_err());
+    }
+
+    #[test]
+    fn test_chained_links() {
+        // A => B in [1, 2] and B => C in [1, 2], with C - A <= 4 (controllable) or C - A <= 3 (not controllable)
+        for (deadline, controllable) in [(4, true), (3, false)] {
+            let mut network = DcNetwork::new();
+            network.add_contingent_link(A, B, 1, 2, "AB");
+            network.add_contingent_link(B, C, 1, 2, "BC");
+            network.add_requirement(A, C, deadline, "deadline");
+            assert_eq!(network.check().is_ok(), controllable);
+        }
+    }
+
+    #[test]
+    fn test_incremental() {
+        use rand::rngs::SmallRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let (mut num_controllable, mut num_failures) = (0, 0);
+        for _ in 0..300 {
+            // random requirements on top of two contingent links, added one at a time
+            let requirements: Vec<(Node, Node, W)> = (0..8)
+                .map(|_| (rng.gen_range(0..5), rng.gen_range(0..5), rng.gen_range(-5..10)))
+                .filter(|(s, t, _)| s != t)
+                .collect();
+            let mut network = DcNetwork::new();
+            network.add_contingent_link(0, 1, 1, 4, usize::MAX);
+            network.add_contingent_link(2, 3, 0, 3, usize::MAX);
+            assert!(network.check().is_ok());
+            let mut accepted = Vec::new();
+            for (i, &(s, t, w)) in requirements.iter().enumerate() {
+                network.save_state();
+                network.add_requirement(s, t, w, i);
+                // same network, checked from scratch
+                let mut scratch = DcNetwork::new();
+                scratch.add_contingent_link(0, 1, 1, 4, usize::MAX);
+                scratch.add_contingent_link(2, 3, 0, 3, usize::MAX);
+                for &(j, s, t, w) in accepted.iter().chain([&(i, s, t, w)]) {
+                    scratch.add_requirement(s, t, w, j);
+                }
+                let controllable = scratch.check().is_ok();
+                assert_eq!(network.check().is_ok(), controllable);
+                if controllable {
+                    accepted.push((i, s, t, w));
+                    num_controllable += 1;
+                } else {
+                    // undo the requirement and continue with the next ones
+                    num_failures += 1;
+                    network.restore_last();
+                    assert!(network.check().is_ok());
+                }
+            }
+        }
+        assert!(num_controllable > 300 && num_failures > 100);
+    }
+
+    #[test]
+    fn test_wait() {
+        // With a contingent link A => C in [0, 5], D must occur in [A + 4, A + 5] and at most 2 after C.
+        const D: Node = 3;
+        let network = |min_after_c: W| {
+            let mut network = DcNetwork::new();
+            network.add_contingent_link(A, C, 0, 5, "link");
+            network.add_requirement(C, D, 2, "after-c");
+            network.add_requirement(D, C, -min_after_c, "before-d");
+            network.add_requirement(D, A, -4, "after-a");
+            network.add_requirement(A, D, 5, "deadline");
+            network
+        };
+        // D can be executed at max(A + 4, C), waiting for C or for A + 4
+        assert!(network(0).check().is_ok());
+
+        // D must occur strictly after C, which is impossible if C occurs at A + 5
+        let explanation = network(1).check().unwrap_err();
+        assert!(explanation.contains(&"link"));
+        assert!(explanation.contains(&"deadline"));
+        assert!(explanation.contains(&"before-d"));
+        assert!(!explanation.contains(&"after-a"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Extraction of minimal dispatchable networks from Simple Temporal Networks.
+//!
+//! A network is *dispatchable* if an executive can assign times to its timepoints online, while only
+//! propagating the constraints to the immediate neighbours of each executed timepoint, and never end up
+//! in a dead-end. The all-pairs shortest-paths graph of a consistent STN is dispatchable and the minimal
+//! dispatchable network is obtained by removing the edges that are dominated by another one [Muscettola 1998].
+//! Rigid components (timepoints at a fixed distance from one another) are first collapsed into a single
+//! leader so that no two edges can dominate each other.
+//!
+//! [Muscettola 1998]: Nicola Muscettola, Paul Morris and Ioannis Tsamardinos.
+//! Reformulating Temporal Plans for Efficient Execution. KR 1998.
+
+use crate::core::{LongCst, VarRef};
+use crate::reasoners::stn::theory::W;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Display, Formatter};
+
+/// Distances are computed on [LongCst], twice as wide as the weights, so that summing the weights along a path
+/// cannot overflow.
+type Dist = LongCst;
+
+/// A constraint `target - source <= weight` of a [DispatchableNetwork].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DispatchEdge {
+    pub source: VarRef,
+    pub target: VarRef,
+    pub weight: W,
+}
+
+/// A minimal dispatchable network over a set of timepoints.
+///
+/// The origin ([VarRef::ZERO]) is always the first timepoint of the network and is executed at time `0`:
+/// absolute bounds on the other timepoints are represented by edges from and to the origin.
+#[derive(Clone, Debug)]
+pub struct DispatchableNetwork {
+    pub timepoints: Vec<VarRef>,
+    pub edges: Vec<DispatchEdge>,
+}
+
+impl DispatchableNetwork {
+    /// Builds the minimal dispatchable network equivalent to the given `edges`, projected on the `timepoints`
+    /// (to which the origin is implicitly added). Returns `None` if the edges are not consistent.
+    pub(crate) fn new(edges: &[DispatchEdge], timepoints: &[VarRef]) -> Option<DispatchableNetwork> {
+        let mut vars = vec![VarRef::ZERO];
+        let mut nodes: HashMap<VarRef, usize> = HashMap::new();
+        nodes.insert(VarRef::ZERO, 0);
+        fn node(v: VarRef, vars: &mut Vec<VarRef>, nodes: &mut HashMap<VarRef, usize>) -> usize {
+            *nodes.entry(v).or_insert_with(|| {
+                vars.push(v);
+                vars.len() - 1
+            })
+        }
+        // the timepoints to keep are the first nodes
+        for &tp in timepoints {
+            node(tp, &mut vars, &mut nodes);
+        }
+        let kept = vars.len();
+        let mut all_edges = Vec::with_capacity(edges.len());
+        for e in edges {
+            let source = node(e.source, &mut vars, &mut nodes);
+            let target = node(e.target, &mut vars, &mut nodes);
+            all_edges.push((source, target, Dist::from(e.weight)));
+        }
+        let mut out: Vec<Vec<(usize, Dist)>> = Vec::new();
+        out.resize(vars.len(), Vec::new());
+        for &(s, t, w) in &all_edges {
+            out[s].push((t, w));
+        }
+
+        // potential function for Johnson's reweighting, obtained with Bellman-Ford from a virtual source
+        let mut potential: Vec<Dist> = vec![0; vars.len()];
+        let mut num_rounds = 0;
+        loop {
+            let mut updated = false;
+            for &(s, t, w) in &all_edges {
+                if potential[s] + w < potential[t] {
+                    potential[t] = potential[s] + w;
+                    updated = true;
+                }
+            }
+            if !updated {
+                break;
+            }
+            num_rounds += 1;
+            if num_rounds > vars.len() {
+                return None; // negative cycle
+            }
+        }
+
+        // shortest distances between kept timepoints
+        let mut dist: Vec<Vec<Option<Dist>>> = vec![vec![None; kept]; kept];
+        let mut reduced: Vec<Option<Dist>> = vec![None; vars.len()];
+        let mut queue = BinaryHeap::new();
+        for (from, dist_from) in dist.iter_mut().enumerate() {
+            reduced.fill(None);
+            reduced[from] = Some(0);
+            queue.push(Reverse((0, from)));
+            while let Some(Reverse((d, n))) = queue.pop() {
+                if reduced[n] != Some(d) {
+                    continue; // outdated entry
+                }
+                for &(succ, w) in &out[n] {
+                    let succ_dist = d + w + potential[n] - potential[succ];
+                    debug_assert!(succ_dist >= d);
+                    if reduced[succ].is_none_or(|prev| succ_dist < prev) {
+                        reduced[succ] = Some(succ_dist);
+                        queue.push(Reverse((succ_dist, succ)));
+                    }
+                }
+            }
+            for (to, d) in dist_from.iter_mut().enumerate() {
+                *d = reduced[to].map(|rd| rd + potential[to] - potential[from]);
+            }
+        }
+
+        let to_edge = |s: usize, t: usize, d: Dist| DispatchEdge {
+            source: vars[s],
+            target: vars[t],
+            weight: W::try_from(d).expect("Distance overflow"),
+        };
+        let mut result = Vec::new();
+
+        // collapse rigid components into their leader: the origin if it is part of the component or its earliest member
+        let rigid = |a: usize, b: usize| matches!((dist[a][b], dist[b][a]), (Some(ab), Some(ba)) if ab + ba == 0);
+        let mut leader: Vec<Option<usize>> = vec![None; kept];
+        for a in 0..kept {
+            if leader[a].is_some() {
+                continue;
+            }
+            let component: Vec<usize> = (a..kept).filter(|&b| leader[b].is_none() && rigid(a, b)).collect();
+            let first = if a == 0 {
+                0
+            } else {
+                *component.iter().min_by_key(|&&b| dist[a][b]).unwrap()
+            };
+            for &b in &component {
+                leader[b] = Some(first);
+                if b != first {
+                    result.push(to_edge(first, b, dist[first][b].unwrap()));
+                    result.push(to_edge(b, first, dist[b][first].unwrap()));
+                }
+            }
+        }
+        let leaders: Vec<usize> = (0..kept).filter(|&a| leader[a] == Some(a)).collect();
+
+        // keep all edges between leaders that are not dominated by another edge
+        for &a in &leaders {
+            for &c in &leaders {
+                let Some(ac) = dist[a][c] else { continue };
+                if a == c {
+                    continue;
+                }
+                let dominated = leaders.iter().any(|&b| {
+                    if b == a || b == c {
+                        return false;
+                    }
+                    let (Some(ab), Some(bc)) = (dist[a][b], dist[b][c]) else {
+                        return false;
+                    };
+                    // a non-negative edge AC is upper-dominated by the non-negative edge BC
+                    // and a negative edge AC is lower-dominated by the negative edge AB
+                    ab + bc == ac && ((ac >= 0 && bc >= 0) || (ac < 0 && ab < 0))
+                });
+                if !dominated {
+                    result.push(to_edge(a, c, ac));
+                }
+            }
+        }
+
+        Some(DispatchableNetwork {
+            timepoints: vars[..kept].to_vec(),
+            edges: result,
+        })
+    }
+
+    /// Returns true if the timepoint can be executed, i.e., if all timepoints that must occur strictly before it
+    /// have been executed.
+    /// The `executed` function should return the time at which a timepoint was executed, if any.
+    pub fn is_enabled(&self, tp: VarRef, executed: impl Fn(VarRef) -> Option<W>) -> bool {
+        self.edges
+            .iter()
+            .filter(|e| e.source == tp && e.weight < 0)
+            .all(|e| executed(e.target).is_some())
+    }
+
+    /// Returns the time window `[lb, ub]` in which the timepoint can be executed, given the times at which
+    /// its neighbours were executed.
+    pub fn window(&self, tp: VarRef, executed: impl Fn(VarRef) -> Option<W>) -> (LongCst, LongCst) {
+        let mut lb = Dist::MIN;
+        let mut ub = Dist::MAX;
+        for e in &self.edges {
+            if e.source == tp {
+                if let Some(t) = executed(e.target) {
+                    lb = lb.max(Dist::from(t) - Dist::from(e.weight));
+                }
+            } else if e.target == tp {
+                if let Some(t) = executed(e.source) {
+                    ub = ub.min(Dist::from(t) + Dist::from(e.weight));
+                }
+            }
+        }
+        (lb, ub)
+    }
+
+    /// Returns true if the given times (with the origin at `0`) satisfy all constraints of the network.
+    pub fn is_satisfied_by(&self, time: impl Fn(VarRef) -> W) -> bool {
+        let time = |v: VarRef| if v == VarRef::ZERO { 0 } else { Dist::from(time(v)) };
+        self.edges
+            .iter()
+            .all(|e| time(e.target) - time(e.source) <= Dist::from(e.weight))
+    }
+}
+
+/// Serializes the network, with one timepoint or edge per line:
+///
+/// ```text
+/// timepoint <var>
+/// edge <source> <target> <weight>
+/// ```
+impl Display for DispatchableNetwork {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for tp in &self.timepoints {
+            writeln!(f, "timepoint {}", tp.to_u32())?;
+        }
+        for e in &self.edges {
+            writeln!(f, "edge {} {} {}", e.source.to_u32(), e.target.to_u32(), e.weight)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(source: u32, target: u32, weight: W) -> DispatchEdge {
+        DispatchEdge {
+            source: VarRef::from_u32(source),
+            target: VarRef::from_u32(target),
+            weight,
+        }
+    }
+
+    fn tps(vars: &[u32]) -> Vec<VarRef> {
+        vars.iter().map(|&v| VarRef::from_u32(v)).collect()
+    }
+
+    #[test]
+    fn test_dominated_edges() {
+        // 1 and 2 within [0, 10], 2 between 1 and 5 time units after 1
+        let edges = [
+            edge(0, 1, 10),
+            edge(1, 0, 0),
+            edge(0, 2, 10),
+            edge(2, 0, 0),
+            edge(1, 2, 5),
+            edge(2, 1, -1),
+        ];
+        let net = DispatchableNetwork::new(&edges, &tps(&[1, 2])).unwrap();
+        assert_eq!(net.timepoints, tps(&[0, 1, 2]));
+        let mut result = net.edges.clone();
+        result.sort_by_key(|e| (e.source, e.target));
+        // 1 <= 9 is implied but needed if 1 is executed first,
+        // 1 <= 2 is lower-dominated by 0 <= 1 and 1 + 1 <= 2
+        assert_eq!(
+            result,
+            vec![
+                edge(0, 1, 9),
+                edge(0, 2, 10),
+                edge(1, 0, 0),
+                edge(1, 2, 5),
+                edge(2, 1, -1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_projection() {
+        // 3 is an intermediate timepoint: 1 + 2 <= 3 <= 2 + 1
+        let edges = [edge(3, 1, -2), edge(2, 3, 1), edge(0, 2, 10), edge(1, 0, 0)];
+        let net = DispatchableNetwork::new(&edges, &tps(&[1, 2])).unwrap();
+        assert_eq!(net.timepoints, tps(&[0, 1, 2]));
+        assert!(net.edges.contains(&edge(2, 1, -1)));
+        assert!(net.is_satisfied_by(|v| if v == VarRef::from_u32(1) { 3 } else { 4 }));
+        assert!(!net.is_satisfied_by(|_| 3));
+    }
+
+    #[test]
+    fn test_rigid_and_inconsistent() {
+        // 1 and 2 are simultaneous
+        let edges = [edge(1, 2, 0), edge(2, 1, 0), edge(0, 1, 10), edge(1, 0, -2)];
+        let net = DispatchableNetwork::new(&edges, &tps(&[1, 2])).unwrap();
+        let mut result = net.edges.clone();
+        result.sort_by_key(|e| (e.source, e.target));
+        assert_eq!(
+            result,
+            vec![edge(0, 1, 10), edge(1, 0, -2), edge(1, 2, 0), edge(2, 1, 0)]
+        );
+
+        let edges = [edge(1, 2, 1), edge(2, 1, -2)];
+        assert!(DispatchableNetwork::new(&edges, &tps(&[1, 2])).is_none());
+    }
+}
@@ -9,16 +9,24 @@ use crate::reasoners::{Contradiction, ReasonerId, Theory};
 /// The purpose of this reasoner is to avoid backtracking at the root level to impose a universal fact.
 /// This is in particular the case when optimizing, that leads to frequent additions of tautology
 /// facts to force improvement on the optimized variable.
+///
+/// A tautology may be scoped, in which case it is only propagated when its scope is true
+/// and discarded once its scope becomes false at the root.
 #[derive(Clone, Default)]
 pub struct Tautologies {
-    /// list of literals that are always true
-    tautologies: Vec<Lit>,
+    /// list of literals that are always true, each with the scope in which it holds
+    tautologies: Vec<(Lit, Lit)>,
     lvl: crate::backtrack::DecisionLevelTracker,
 }
 
 impl Tautologies {
     pub fn add_tautology(&mut self, lit: Lit) {
-        self.tautologies.push(lit)
+        self.add_scoped_tautology(lit, Lit::TRUE)
+    }
+
+    /// Adds a literal that is true whenever `scope` is true.
+    pub fn add_scoped_tautology(&mut self, lit: Lit, scope: Lit) {
+        self.tautologies.push((lit, scope))
     }
 }
 
@@ -42,18 +50,22 @@ impl Theory for Tautologies {
     }
 
     fn propagate(&mut self, model: &mut Domains) -> Result<(), Contradiction> {
-        // dummy cause, as
-        let cause = Cause::Inference(InferenceCause {
-            writer: self.identity(),
-            payload: 0,
-        });
-        for l in self.tautologies.iter().rev() {
+        for (i, &(l, scope)) in self.tautologies.iter().enumerate().rev() {
             // iterate backwards as latests are likely stronger
-            model.set(*l, cause)?;
+            if model.entails(scope) {
+                // the index of the tautology is used to retrieve its scope when explaining
+                let cause = Cause::Inference(InferenceCause {
+                    writer: self.identity(),
+                    payload: i as u32,
+                });
+                model.set(l, cause)?;
+            }
         }
         if self.current_decision_level() == DecLvl::ROOT {
-            // these have been propagated and can never be undone
-            self.tautologies.clear()
+            // tautologies of a true scope have been propagated and can never be undone,
+            // those of a false scope will never be propagated again
+            self.tautologies
+                .retain(|&(_, scope)| !model.entails(scope) && !model.entails(!scope))
         }
         Ok(())
     }
@@ -63,13 +75,17 @@ impl Theory for Tautologies {
         literal: Lit,
         context: InferenceCause,
         _domains: &Domains,
-        _out_explanation: &mut Explanation,
+        out_explanation: &mut Explanation,
     ) {
         debug_assert_eq!(context.writer, self.identity());
-        debug_assert_eq!(context.payload, 0);
-        debug_assert!(self.tautologies.iter().any(|l| l.entails(literal)));
-        // Nothing to explain as the literal is in principle entailed at the ROOT.
+        // inferences at the root (made before the tautologies are compacted) are never explained
+        let (lit, scope) = self.tautologies[context.payload as usize];
+        debug_assert!(lit.entails(literal));
+        // Nothing else to explain as the literal is in principle entailed at the ROOT (in its scope).
         // This propagator only enforces it systematically to avoid restarts.
+        if scope != Lit::TRUE {
+            out_explanation.push(scope);
+        }
     }
 
     fn print_stats(&self) {}
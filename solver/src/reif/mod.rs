@@ -1,7 +1,11 @@
 use crate::core::literals::Disjunction;
 use crate::core::state::{Domains, OptDomain};
 use crate::core::{IntCst, Lit, VarRef};
+use crate::model::lang::extensional::{Element, Table};
 use crate::model::lang::linear::NFLinearLeq;
+use crate::model::lang::nonlinear::{Abs, Div, MinMax, Mod, Mul};
+use crate::model::lang::pseudo_boolean::PseudoBoolean;
+use crate::model::lang::scheduling::{ContingentLink, Cumulative, NoOverlap};
 use crate::model::lang::ValidityScope;
 use crate::model::{Label, Model};
 use std::fmt::{Debug, Formatter};
@@ -28,6 +32,30 @@ pub enum ReifExpr {
     Or(Vec<Lit>),
     And(Vec<Lit>),
     Linear(NFLinearLeq),
+    /// All present variables take distinct values (absent variables are ignored).
+    AllDifferent(Vec<VarRef>),
+    /// Cumulative resource constraint over a set of tasks.
+    Cumulative(Cumulative),
+    /// No two present tasks overlap in time.
+    NoOverlap(NoOverlap),
+    /// Uncontrollable duration between two timepoints, in a dynamically controllable temporal network.
+    ContingentLink(ContingentLink),
+    /// The present variables take the values of one of the tuples of a table.
+    Table(Table),
+    /// A variable is the element of an array of constants at a given index.
+    Element(Element),
+    /// Product of two variables.
+    Mul(Mul),
+    /// Division of a variable by a constant.
+    Div(Div),
+    /// Remainder of the division of a variable by a constant.
+    Mod(Mod),
+    /// Minimum or maximum of a set of variables.
+    MinMax(MinMax),
+    /// Absolute value of a variable.
+    Abs(Abs),
+    /// Weighted sum of literals bounded by a constant.
+    PseudoBoolean(PseudoBoolean),
 }
 
 impl std::fmt::Display for ReifExpr {
@@ -42,11 +70,50 @@ impl std::fmt::Display for ReifExpr {
             ReifExpr::Or(or) => write!(f, "or{or:?}"),
             ReifExpr::And(and) => write!(f, "and{and:?}"),
             ReifExpr::Linear(l) => write!(f, "{l}"),
+            ReifExpr::AllDifferent(vars) => write!(f, "all-different{vars:?}"),
+            ReifExpr::Cumulative(cumul) => write!(f, "cumulative({:?} <= {})", cumul.tasks, cumul.capacity),
+            ReifExpr::NoOverlap(no_overlap) => write!(f, "no-overlap{:?}", no_overlap.tasks),
+            ReifExpr::ContingentLink(link) => write!(
+                f,
+                "contingent({:?} - {:?} in [{}, {}])",
+                link.contingent, link.activation, link.lb, link.ub
+            ),
+            ReifExpr::Table(table) => write!(f, "table({:?}, {} tuples)", table.vars, table.tuples.len()),
+            ReifExpr::Element(elem) => write!(f, "({:?} = {:?}[{:?}])", elem.value, elem.array, elem.index),
+            ReifExpr::Mul(mul) => write!(f, "({:?} = {:?} * {:?})", mul.product, mul.lhs, mul.rhs),
+            ReifExpr::Div(div) => write!(f, "({:?} = {:?} / {})", div.quotient, div.numerator, div.denominator),
+            ReifExpr::Mod(m) => write!(f, "({:?} = {:?} mod {})", m.remainder, m.numerator, m.denominator),
+            ReifExpr::MinMax(mm) => {
+                let op = if mm.minimum { "min" } else { "max" };
+                write!(f, "({:?} = {op}{:?})", mm.value, mm.vars)
+            }
+            ReifExpr::Abs(abs) => write!(f, "({:?} = |{:?}|)", abs.value, abs.var),
+            ReifExpr::PseudoBoolean(pb) => write!(f, "(pb{:?} <= {})", pb.terms, pb.upper_bound),
         }
     }
 }
 
 impl ReifExpr {
+    /// Returns true if the expression is a global constraint, that can only be enforced and not reified.
+    /// The solver panics when posting a global constraint whose literal is not entailed.
+    pub fn is_global(&self) -> bool {
+        matches!(
+            self,
+            ReifExpr::AllDifferent(_)
+                | ReifExpr::Cumulative(_)
+                | ReifExpr::NoOverlap(_)
+                | ReifExpr::ContingentLink(_)
+                | ReifExpr::Table(_)
+                | ReifExpr::Element(_)
+                | ReifExpr::Mul(_)
+                | ReifExpr::Div(_)
+                | ReifExpr::Mod(_)
+                | ReifExpr::MinMax(_)
+                | ReifExpr::Abs(_)
+                | ReifExpr::PseudoBoolean(_)
+        )
+    }
+
     pub fn scope(&self, presence: impl Fn(VarRef) -> Lit) -> ValidityScope {
         match self {
             ReifExpr::Lit(l) => ValidityScope::new([presence(l.variable())], []),
@@ -67,6 +134,18 @@ impl ReifExpr {
                     .filter(|l| presence(l.variable()) == Lit::TRUE),
             ),
             ReifExpr::Linear(lin) => lin.validity_scope(presence),
+            ReifExpr::AllDifferent(_)
+            | ReifExpr::Cumulative(_)
+            | ReifExpr::NoOverlap(_)
+            | ReifExpr::ContingentLink(_)
+            | ReifExpr::Table(_)
+            | ReifExpr::Element(_)
+            | ReifExpr::Mul(_)
+            | ReifExpr::Div(_)
+            | ReifExpr::Mod(_)
+            | ReifExpr::MinMax(_)
+            | ReifExpr::Abs(_)
+            | ReifExpr::PseudoBoolean(_) => ValidityScope::new([], []),
         }
     }
 
@@ -144,6 +223,24 @@ impl ReifExpr {
                 }
                 Some(sum <= lin.upper_bound)
             }
+            ReifExpr::AllDifferent(vars) => {
+                let mut values: Vec<IntCst> = vars.iter().filter(|&&v| prez(v)).map(|&v| value(v)).collect();
+                let num_present = values.len();
+                values.sort_unstable();
+                values.dedup();
+                Some(values.len() == num_present)
+            }
+            ReifExpr::Cumulative(cumul) => Some(cumul.eval(assignment)),
+            ReifExpr::NoOverlap(no_overlap) => Some(no_overlap.eval(assignment)),
+            ReifExpr::ContingentLink(link) => Some(link.eval(assignment)),
+            ReifExpr::Table(table) => Some(table.eval(assignment)),
+            ReifExpr::Element(elem) => Some(elem.eval(assignment)),
+            ReifExpr::Mul(mul) => Some(mul.eval(assignment)),
+            ReifExpr::Div(div) => Some(div.eval(assignment)),
+            ReifExpr::Mod(m) => Some(m.eval(assignment)),
+            ReifExpr::MinMax(mm) => Some(mm.eval(assignment)),
+            ReifExpr::Abs(abs) => Some(abs.eval(assignment)),
+            ReifExpr::PseudoBoolean(pb) => Some(pb.eval(assignment)),
         }
     }
 }
@@ -194,6 +291,20 @@ impl Not for ReifExpr {
                 ReifExpr::Or(lits)
             }
             ReifExpr::Linear(lin) => ReifExpr::Linear(!lin),
+            ReifExpr::AllDifferent(_)
+            | ReifExpr::Cumulative(_)
+            | ReifExpr::NoOverlap(_)
+            | ReifExpr::ContingentLink(_)
+            | ReifExpr::Table(_)
+            | ReifExpr::Element(_)
+            | ReifExpr::Mul(_)
+            | ReifExpr::Div(_)
+            | ReifExpr::Mod(_)
+            | ReifExpr::MinMax(_)
+            | ReifExpr::Abs(_)
+            | ReifExpr::PseudoBoolean(_) => {
+                panic!("Negation of global constraints is not supported.")
+            }
         }
     }
 }
@@ -0,0 +1,462 @@
+//! Debug mode of the solver in which all explanations produced by the reasoners are verified.
+
+use crate::backtrack::{Backtrack, DecLvl};
+use crate::core::state::{Domains, Explainer, Explanation, InferenceCause};
+use crate::core::Lit;
+use crate::model::{Label, Model, ModelShape};
+use crate::reasoners::{ReasonerId, Reasoners};
+use crate::solver::Solver;
+use itertools::Itertools;
+
+/// An explainer that delegates to the reasoners and, if `enabled`, checks each explanation before returning it.
+pub(crate) struct CheckedExplainer<'a, Lbl> {
+    pub reasoners: &'a mut Reasoners,
+    pub shape: &'a ModelShape<Lbl>,
+    pub enabled: bool,
+}
+
+impl<Lbl: Label> Explainer for CheckedExplainer<'_, Lbl> {
+    fn explain(&mut self, cause: InferenceCause, literal: Lit, model: &Domains, explanation: &mut Explanation) {
+        let start = explanation.lits.len();
+        self.reasoners.explain(cause, literal, model, explanation);
+        if self.enabled {
+            let implying = &explanation.lits[start..];
+            check_explanation(self.reasoners, cause.writer, self.shape, model, literal, implying);
+        }
+    }
+
+    fn supports_late_explanations(&self, cause: InferenceCause) -> bool {
+        self.reasoners.supports_late_explanations(cause)
+    }
+}
+
+/// Panics if `literal` is not implied by the `explanation` under the constraints of the reasoner `writer`.
+///
+/// This is verified by showing that the explanation and the negation of the literal are unsatisfiable
+/// in a sub-solver whose only non-empty reasoner is a copy of `writer` (in the root state of `domains`).
+/// As a literal on an optional variable only needs to hold when the variable is present, its presence is assumed.
+/// Counter-examples in which a variable of the explanation is absent are ignored.
+/// A conflict of the reasoner is checked by giving `Lit::FALSE` as the literal.
+pub(crate) fn check_explanation<Lbl: Label>(
+    reasoners: &Reasoners,
+    writer: ReasonerId,
+    shape: &ModelShape<Lbl>,
+    domains: &Domains,
+    literal: Lit,
+    explanation: &[Lit],
+) {
+    let mut state = domains.clone();
+    state.restore(DecLvl::ROOT);
+    let mut shape = shape.clone();
+    // all constraints have already been posted in the reasoners
+    shape.constraints.clear();
+
+    let mut solver = Solver::new(Model { shape, state });
+    solver.set_explanation_checks(false);
+    solver.reasoners = reasoners.only(writer);
+    for theory in solver.reasoners.theories_mut() {
+        theory.restore(DecLvl::ROOT);
+    }
+    solver.stats.per_module_stat.entry(writer).or_default();
+
+    let presence = solver.model.state.presence(literal.variable());
+    let assumptions: Vec<Lit> = explanation.iter().copied().chain([presence, !literal]).collect();
+    match solver.solve_with_assumptions(&assumptions) {
+        Ok(Err(_)) => {}
+        Ok(Ok(counter_example)) => {
+            // the domain of an absent variable is meaningless: the literals of the explanation on it may not hold
+            // in the counter-example, or may hold together with their negation
+            let absent = |l: &Lit| counter_example.present(l.variable()) == Some(false);
+            if !explanation.iter().any(absent) {
+                panic!(
+                    "Invalid explanation of {literal:?} by {writer}: [{}] does not imply it",
+                    explanation.iter().map(|l| format!("{l:?}")).join(", ")
+                )
+            }
+        }
+        Err(_) => panic!("Interrupted while checking an explanation"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtrack::{Backtrack, DecLvl, DecisionLevelTracker};
+    use crate::core::state::{Cause, Domains, Explanation, InferenceCause};
+    use crate::core::{IntCst, Lit, VarRef};
+    use crate::model::lang::expr::or;
+    use crate::model::lang::extensional::{Element, Table};
+    use crate::model::lang::linear::{NFLinearLeq, NFLinearSumItem};
+    use crate::model::lang::nonlinear::{Abs, Div, MinMax, Mod, Mul};
+    use crate::model::lang::pseudo_boolean::PseudoBoolean;
+    use crate::model::lang::scheduling::{Cumulative, NoOverlap, Task};
+    use crate::model::lang::{IAtom, IVar};
+    use crate::reasoners::{Contradiction, ReasonerId, Theory};
+    use crate::reif::{DifferenceExpression, ReifExpr};
+    use itertools::Itertools;
+    use rand::rngs::SmallRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    type Model = crate::model::Model<&'static str>;
+    type Solver = crate::solver::Solver<&'static str>;
+
+    /// Variables of a random model: `p0` and `p1` are the presence literals of the optional variables
+    /// and `b0` is used to reify constraints.
+    struct Vars {
+        all: Vec<VarRef>,
+        ints: Vec<VarRef>,
+        lits: Vec<Lit>,
+        reif: Lit,
+    }
+
+    impl Vars {
+        fn new(model: &mut Model) -> Vars {
+            let p0 = model.new_bvar("p0").true_lit();
+            let p1 = model.new_bvar("p1").true_lit();
+            let b0 = model.new_bvar("b0");
+            let b1 = model.new_optional_bvar(p1, "b1");
+            let x0 = model.new_ivar(0, 2, "x0");
+            let x1 = model.new_optional_ivar(0, 2, p0, "x1");
+            let x2 = model.new_optional_ivar(-1, 1, p1, "x2");
+            let ints: Vec<VarRef> = vec![x0.into(), x1.into(), x2.into()];
+            let mut lits = vec![p0, p1, b1.true_lit(), b1.false_lit()];
+            for &x in &ints {
+                let (lb, ub) = model.state.bounds(x);
+                lits.extend((lb..ub).flat_map(|v| [x.leq(v), x.gt(v)]));
+            }
+            Vars {
+                all: vec![
+                    p0.variable(),
+                    p1.variable(),
+                    b0.into(),
+                    b1.into(),
+                    x0.into(),
+                    x1.into(),
+                    x2.into(),
+                ],
+                ints,
+                lits,
+                reif: b0.true_lit(),
+            }
+        }
+    }
+
+    fn random_expr(vars: &Vars, model: &Model, rng: &mut SmallRng) -> ReifExpr {
+        let mut ints = vars.ints.clone();
+        ints.shuffle(rng);
+        let [x, y, z]: [VarRef; 3] = ints.try_into().unwrap();
+        let lit = |rng: &mut SmallRng| *vars.lits.choose(rng).unwrap();
+        let value = |rng: &mut SmallRng| rng.gen_range(-1..=2);
+        // a task starting at `var` (possibly shifted), present with it
+        let task = |var: VarRef, rng: &mut SmallRng| {
+            let start = IAtom::new(IVar::new(var), rng.gen_range(-1..=1));
+            Task::optional(
+                start,
+                rng.gen_range(0..=2),
+                rng.gen_range(1..=2),
+                model.state.presence(var),
+            )
+        };
+        match rng.gen_range(0..19) {
+            0 => ReifExpr::Lit(lit(rng)),
+            1 => ReifExpr::MaxDiff(DifferenceExpression::new(x, y, rng.gen_range(-2..=2))),
+            2 => ReifExpr::Eq(x, y),
+            3 => ReifExpr::Neq(x, y),
+            4 => ReifExpr::EqVal(x, value(rng)),
+            5 => ReifExpr::NeqVal(x, value(rng)),
+            6 => ReifExpr::Or(vec![lit(rng), lit(rng)]),
+            7 => ReifExpr::And(vec![lit(rng), lit(rng)]),
+            8 => {
+                let num_terms = rng.gen_range(2..=3);
+                let sum = vars
+                    .ints
+                    .choose_multiple(rng, num_terms)
+                    .map(|&var| NFLinearSumItem {
+                        var,
+                        factor: *[-2, -1, 1, 2].choose(rng).unwrap(),
+                        lit: model.state.presence(var),
+                    })
+                    .collect();
+                ReifExpr::Linear(NFLinearLeq {
+                    sum,
+                    upper_bound: rng.gen_range(-2..=2),
+                })
+            }
+            9 => ReifExpr::AllDifferent(vars.ints.clone()),
+            10 => {
+                let num_tuples = rng.gen_range(1..=4);
+                let tuples = (0..num_tuples).map(|_| vec![value(rng), value(rng)]).collect();
+                ReifExpr::Table(Table::new(vec![x, y], tuples))
+            }
+            11 => {
+                let len = rng.gen_range(1..=3);
+                ReifExpr::Element(Element {
+                    array: (0..len).map(|_| value(rng)).collect(),
+                    index: x,
+                    value: y,
+                })
+            }
+            12 => ReifExpr::Mul(Mul {
+                product: x,
+                lhs: y,
+                rhs: *[y, z].choose(rng).unwrap(),
+            }),
+            13 => ReifExpr::Div(Div {
+                quotient: x,
+                numerator: y,
+                denominator: *[-2, -1, 1, 2].choose(rng).unwrap(),
+            }),
+            14 => ReifExpr::Mod(Mod {
+                remainder: x,
+                numerator: y,
+                denominator: *[-2, -1, 1, 2].choose(rng).unwrap(),
+            }),
+            15 => ReifExpr::MinMax(MinMax {
+                value: x,
+                vars: vec![y, z][..rng.gen_range(1..=2)].to_vec(),
+                minimum: rng.gen(),
+            }),
+            16 => ReifExpr::Abs(Abs { value: x, var: y }),
+            17 => {
+                // literals of the optional variables may take any value when absent and are not considered
+                let lits = vars
+                    .lits
+                    .iter()
+                    .filter(|l| model.state.presence(l.variable()) == Lit::TRUE)
+                    .copied()
+                    .collect_vec();
+                let num_terms = rng.gen_range(2..=3);
+                let terms = lits
+                    .choose_multiple(rng, num_terms)
+                    .map(|&l| (rng.gen_range(-2..=3), l))
+                    .collect_vec();
+                ReifExpr::PseudoBoolean(PseudoBoolean::leq(terms, rng.gen_range(-1..=4)).unwrap())
+            }
+            _ => {
+                let tasks = vars.ints.iter().map(|&var| task(var, rng)).collect();
+                if rng.gen() {
+                    ReifExpr::Cumulative(Cumulative {
+                        tasks,
+                        capacity: rng.gen_range(1..=3),
+                    })
+                } else {
+                    ReifExpr::NoOverlap(NoOverlap { tasks })
+                }
+            }
+        }
+    }
+
+    /// Variables appearing in a (non-global) expression.
+    fn variables(expr: &ReifExpr) -> Vec<VarRef> {
+        match expr {
+            ReifExpr::Lit(l) => vec![l.variable()],
+            ReifExpr::MaxDiff(diff) => vec![diff.a, diff.b],
+            ReifExpr::Eq(a, b) | ReifExpr::Neq(a, b) => vec![*a, *b],
+            ReifExpr::EqVal(a, _) | ReifExpr::NeqVal(a, _) => vec![*a],
+            ReifExpr::Or(lits) | ReifExpr::And(lits) => lits.iter().map(|l| l.variable()).collect(),
+            ReifExpr::Linear(lin) => lin.sum.iter().map(|item| item.var).collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// How a constraint of a random model relates to its expression.
+    #[derive(Copy, Clone, Debug)]
+    enum Kind {
+        /// The expression must hold.
+        Enforced,
+        /// The expression must hold if the literal holds.
+        Conditional(Lit),
+        /// The expression holds if and only if the literal holds.
+        Reified(Lit),
+    }
+
+    /// A constraint of a random model, only active when all variables of its expression are present.
+    struct Constraint {
+        expr: ReifExpr,
+        presences: Vec<Lit>,
+        kind: Kind,
+    }
+
+    impl Constraint {
+        fn random(vars: &Vars, model: &Model, rng: &mut SmallRng) -> Constraint {
+            let expr = random_expr(vars, model, rng);
+            if expr.is_global() {
+                // global constraints handle absent variables themselves
+                return Constraint {
+                    expr,
+                    presences: vec![],
+                    kind: Kind::Enforced,
+                };
+            }
+            let mut presences = variables(&expr)
+                .into_iter()
+                .map(|v| model.state.presence(v))
+                .filter(|&p| p != Lit::TRUE)
+                .collect_vec();
+            presences.sort();
+            presences.dedup();
+            let kind = match rng.gen_range(0..3) {
+                0 => Kind::Enforced,
+                1 => Kind::Conditional(vars.reif),
+                _ => Kind::Reified(vars.reif),
+            };
+            Constraint { expr, presences, kind }
+        }
+
+        fn post(&self, solver: &mut Solver) {
+            let scope = solver.model.get_conjunctive_scope(&self.presences);
+            match self.kind {
+                Kind::Enforced => solver.enforce(self.expr.clone(), self.presences.iter().copied()),
+                Kind::Conditional(l) => solver.enforce(self.expr.clone(), [scope, l]),
+                Kind::Reified(l) => {
+                    solver.enforce(self.expr.clone(), [scope, l]);
+                    solver.enforce(!self.expr.clone(), [scope, !l]);
+                }
+            }
+        }
+
+        fn holds(&self, assignment: &Domains) -> bool {
+            if !self.presences.iter().all(|&p| assignment.entails(p)) {
+                return true;
+            }
+            let value = self.expr.eval(assignment);
+            match self.kind {
+                Kind::Enforced => value == Some(true),
+                Kind::Conditional(l) => !assignment.entails(l) || value == Some(true),
+                Kind::Reified(l) => value == Some(assignment.entails(l)),
+            }
+        }
+    }
+
+    impl std::fmt::Display for Constraint {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?} => {:?}: {}", self.presences, self.kind, self.expr)
+        }
+    }
+
+    /// Projects an assignment on the present variables, absent variables having no value.
+    fn project(vars: &[VarRef], values: &[IntCst], presence: impl Fn(VarRef) -> Lit) -> Vec<Option<IntCst>> {
+        let value = |v: VarRef| values[vars.iter().position(|&x| x == v).unwrap()];
+        let holds = |l: Lit| l == Lit::TRUE || value(l.variable()) >= 1;
+        vars.iter().map(|&v| holds(presence(v)).then(|| value(v))).collect()
+    }
+
+    /// Fuzzer comparing the solutions enumerated by the solver on random small models to the ones
+    /// obtained by brute-force enumeration, with all explanations checked.
+    #[test]
+    fn fuzz_enumerate() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let mut model = Model::new();
+            let vars = Vars::new(&mut model);
+            let num_constraints = rng.gen_range(1..=4);
+            let constraints = (0..num_constraints)
+                .map(|_| Constraint::random(&vars, &model, &mut rng))
+                .collect_vec();
+
+            let initial = model.state.clone();
+            let presence = |v: VarRef| initial.presence(v);
+            let mut expected = vars
+                .all
+                .iter()
+                .map(|&v| initial.lb(v)..=initial.ub(v))
+                .multi_cartesian_product()
+                .filter_map(|values| {
+                    let projection = project(&vars.all, &values, presence);
+                    let mut assignment = initial.clone();
+                    for (&v, &val) in vars.all.iter().zip(&projection) {
+                        if let Some(val) = val {
+                            assignment.set_lb(v, val, Cause::Decision).unwrap();
+                            assignment.set_ub(v, val, Cause::Decision).unwrap();
+                        }
+                    }
+                    constraints.iter().all(|c| c.holds(&assignment)).then_some(projection)
+                })
+                .collect_vec();
+            expected.sort();
+            expected.dedup();
+
+            let mut solver = Solver::new(model);
+            solver.set_explanation_checks(true);
+            for c in &constraints {
+                c.post(&mut solver);
+            }
+            let mut solutions = solver
+                .enumerate(&vars.all)
+                .unwrap()
+                .iter()
+                .map(|values| project(&vars.all, values, presence))
+                .collect_vec();
+            solutions.sort();
+
+            let constraints = constraints.iter().join("\n");
+            assert_eq!(
+                solutions, expected,
+                "Wrong solutions for the constraints:\n{constraints}"
+            );
+        }
+    }
+
+    /// A theory enforcing `a => b`, whose explanations omit `a`.
+    #[derive(Clone)]
+    struct WrongImplication {
+        a: Lit,
+        b: Lit,
+        lvl: DecisionLevelTracker,
+    }
+
+    impl Backtrack for WrongImplication {
+        fn save_state(&mut self) -> DecLvl {
+            self.lvl.save_state()
+        }
+
+        fn num_saved(&self) -> u32 {
+            self.lvl.num_saved()
+        }
+
+        fn restore_last(&mut self) {
+            self.lvl.restore_last()
+        }
+    }
+
+    impl Theory for WrongImplication {
+        fn identity(&self) -> ReasonerId {
+            ReasonerId::Custom(0)
+        }
+
+        fn propagate(&mut self, model: &mut Domains) -> Result<(), Contradiction> {
+            if model.entails(self.a) {
+                model.set(self.b, Cause::inference(self.identity(), 0u32))?;
+            }
+            Ok(())
+        }
+
+        fn explain(&mut self, _: Lit, _: InferenceCause, _: &Domains, _: &mut Explanation) {}
+
+        fn print_stats(&self) {}
+
+        fn clone_box(&self) -> Box<dyn Theory> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid explanation")]
+    fn test_invalid_explanation() {
+        let mut model = Model::new();
+        let a = model.new_bvar("a").true_lit();
+        let b = model.new_bvar("b").true_lit();
+        let c = model.new_bvar("c").true_lit();
+        let mut solver = Solver::new(model);
+        solver.add_theory(Box::new(WrongImplication {
+            a,
+            b,
+            lvl: Default::default(),
+        }));
+        // the theory's explanation is needed to learn a clause from the conflict
+        solver.enforce(or([!b, c]), []);
+        solver.enforce(or([!b, !c]), []);
+        solver.set_explanation_checks(true);
+        let _ = solver.enumerate(&[a.variable(), b.variable(), c.variable()]);
+    }
+}
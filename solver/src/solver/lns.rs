@@ -0,0 +1,539 @@
+//! Large Neighbourhood Search (LNS).
+//!
+//! Starting from an incumbent solution, each LNS iteration selects a *neighbourhood*: a subset of the variables
+//! that are *relaxed* while all other variables are fixed to their value in the incumbent.
+//! The solver then searches for an improving solution in this neighbourhood, under a limited conflict budget.
+//!
+//! LNS is enabled on a solver with [`Solver::set_lns`](crate::solver::Solver::set_lns), after which its `minimize`
+//! and `maximize` methods run the LNS loop.
+//! This allows LNS to be run by a single worker of a `ParSolver`, from the `adapt` closure of `ParSolver::new`,
+//! while other workers run a complete search and share their solutions.
+//!
+//! Variables are fixed through assumptions, which means that clauses learnt in a neighbourhood remain valid
+//! for the rest of the search. If a neighbourhood is shown to be unsatisfiable regardless of the assumptions,
+//! the incumbent is proven optimal.
+//!
+//! As neighbourhoods may never cover the whole search space, an unrestricted search is periodically run in place
+//! of a neighbourhood. Its conflict budget doubles each time, which ensures that the search eventually proves
+//! the optimality of the incumbent.
+
+use crate::collections::set::RefSet;
+use crate::core::{IntCst, Lit, VarRef};
+use crate::model::extensions::SavedAssignment;
+use crate::model::lang::IAtom;
+use crate::model::Label;
+use crate::solver::{Exit, SolveResult, Solver};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+
+/// Outcome of the search in a neighbourhood, used by neighbourhoods to adapt their size.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LnsOutcome {
+    /// An improving solution was found in the neighbourhood.
+    Improved,
+    /// The neighbourhood was proven to contain no improving solution.
+    Exhausted,
+    /// The conflict budget was exceeded before finding an improving solution or proving there is none.
+    BudgetExceeded,
+}
+
+/// Selects the variables to relax in an LNS iteration.
+///
+/// This trait is the extension point for domain-specific neighbourhoods (e.g. freeing all chronicles of a given template
+/// in planning).
+pub trait Neighbourhood: Send {
+    /// Pushes into `relaxed` the variables to be relaxed in the next iteration.
+    /// All other variables of the LNS (`vars`) are fixed to their value in the incumbent solution.
+    fn select(&mut self, vars: &[VarRef], incumbent: &SavedAssignment, rng: &mut SmallRng, relaxed: &mut Vec<VarRef>);
+
+    /// Notifies the neighbourhood of the outcome of the search in the last neighbourhood it selected.
+    fn feedback(&mut self, _outcome: LnsOutcome) {}
+
+    fn clone_box(&self) -> Box<dyn Neighbourhood>;
+}
+
+impl Clone for Box<dyn Neighbourhood> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Configuration and state of an LNS optimizer.
+#[derive(Clone)]
+pub struct Lns {
+    /// Variables that are fixed to their value in the incumbent solution, unless relaxed by the selected neighbourhood.
+    vars: Vec<VarRef>,
+    /// Neighbourhoods among which one is picked at random at each iteration.
+    neighbourhoods: Vec<Box<dyn Neighbourhood>>,
+    /// Maximum number of conflicts allowed when searching a neighbourhood.
+    conflict_budget: u64,
+    /// Maximum number of conflicts allowed in the next unrestricted search.
+    full_search_budget: u64,
+    /// Number of LNS iterations since the last unrestricted search.
+    iterations_since_full_search: u32,
+    rng: SmallRng,
+}
+
+impl Lns {
+    /// Number of neighbourhoods searched between two unrestricted searches.
+    const FULL_SEARCH_PERIOD: u32 = 10;
+
+    /// Creates a new LNS optimizer that, at each iteration, fixes the `vars` that are not relaxed
+    /// by the neighbourhood and searches for an improving solution with at most `conflict_budget` conflicts.
+    ///
+    /// At least one neighbourhood should be added with `with_neighbourhood`. Otherwise, the search is a complete
+    /// one, restarted with a conflict budget that doubles each time.
+    pub fn new(vars: Vec<VarRef>, conflict_budget: u64) -> Self {
+        Lns {
+            vars,
+            neighbourhoods: Vec::new(),
+            conflict_budget,
+            full_search_budget: conflict_budget,
+            iterations_since_full_search: 0,
+            rng: SmallRng::seed_from_u64(0),
+        }
+    }
+
+    /// Adds a neighbourhood that may be selected at each iteration.
+    pub fn with_neighbourhood(mut self, neighbourhood: impl Neighbourhood + 'static) -> Self {
+        self.neighbourhoods.push(Box::new(neighbourhood));
+        self
+    }
+
+    /// Sets the seed of the random number generator used to select the neighbourhoods.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Runs the LNS loop on the given solver, until the `best` solution is proven optimal or the solver is interrupted.
+    ///
+    /// The first solution is found with a complete search. Solutions found by other solvers are adopted as incumbent
+    /// if they improve on the current one.
+    pub(crate) fn optimize_with<Lbl: Label>(
+        &mut self,
+        solver: &mut Solver<Lbl>,
+        objective: IAtom,
+        minimize: bool,
+        on_new_solution: &mut impl FnMut(IntCst, &SavedAssignment),
+        best: &mut Option<(IntCst, Arc<SavedAssignment>)>,
+    ) -> Result<(), Exit> {
+        let mut relaxed = Vec::new();
+        let mut relaxed_set = RefSet::new();
+        let mut assumptions = Vec::new();
+        loop {
+            assumptions.clear();
+            // index of the neighbourhood selected for this iteration, if any
+            let mut selected = None;
+            let mut conflict_limit = None;
+            if let Some((_, incumbent)) = &*best {
+                self.iterations_since_full_search += 1;
+                if self.iterations_since_full_search > Self::FULL_SEARCH_PERIOD || self.neighbourhoods.is_empty() {
+                    // unrestricted search, with a budget that doubles each time so that it is eventually complete
+                    self.iterations_since_full_search = 0;
+                    conflict_limit = Some(solver.stats.num_conflicts() + self.full_search_budget);
+                    self.full_search_budget = self.full_search_budget.saturating_mul(2);
+                } else {
+                    let n = self.rng.gen_range(0..self.neighbourhoods.len());
+                    relaxed.clear();
+                    self.neighbourhoods[n].select(&self.vars, incumbent, &mut self.rng, &mut relaxed);
+                    relaxed_set.clear();
+                    for &v in &relaxed {
+                        relaxed_set.insert(v);
+                    }
+                    for &v in &self.vars {
+                        if !relaxed_set.contains(v) {
+                            fix(v, incumbent, &mut assumptions);
+                        }
+                    }
+                    selected = Some(n);
+                    conflict_limit = Some(solver.stats.num_conflicts() + self.conflict_budget);
+                }
+            }
+
+            // solutions from other solvers are adopted as incumbent if they are better, without interrupting the search
+            let on_external_solution = |solver: &mut Solver<Lbl>, sol: &Arc<SavedAssignment>| {
+                solver.record_solution(objective, minimize, sol.clone(), best);
+                true
+            };
+            let outcome = match solver.solve_under_limit(&assumptions, conflict_limit, on_external_solution)? {
+                Some(SolveResult::AtSolution) => {
+                    // the solver must improve on the incumbent, this is necessarily an improvement
+                    let sol = solver.solution_found(objective, on_new_solution);
+                    solver.record_solution(objective, minimize, sol, best);
+                    LnsOutcome::Improved
+                }
+                Some(SolveResult::ExternalSolution(_)) => unreachable!("External solutions do not stop the search"),
+                Some(SolveResult::Unsat(core)) if core.is_empty() => {
+                    // unsatisfiable regardless of the neighbourhood, the incumbent is optimal
+                    return Ok(());
+                }
+                Some(SolveResult::Unsat(_)) => LnsOutcome::Exhausted,
+                None => LnsOutcome::BudgetExceeded,
+            };
+            if let Some(n) = selected {
+                self.neighbourhoods[n].feedback(outcome);
+            }
+        }
+    }
+}
+
+/// Pushes to `out` the literals fixing `var` to its value in the incumbent.
+/// If the variable is absent in the incumbent, only its absence is fixed.
+pub fn fix(var: VarRef, incumbent: &SavedAssignment, out: &mut Vec<Lit>) {
+    if incumbent.present(var) == Some(false) {
+        out.push(!incumbent.presence(var));
+    } else {
+        let (lb, ub) = incumbent.bounds(var);
+        out.push(Lit::geq(var, lb));
+        out.push(Lit::leq(var, ub));
+    }
+}
+
+/// Size of a neighbourhood, as a ratio in `[min, 1]`, that is adapted from the outcome of previous iterations:
+/// it grows when the neighbourhood contained no improving solution and shrinks when the conflict budget was exceeded.
+#[derive(Copy, Clone, Debug)]
+pub struct AdaptiveRatio {
+    pub value: f64,
+    pub min: f64,
+}
+
+impl AdaptiveRatio {
+    /// Factor by which the ratio is multiplied (or divided) after each iteration.
+    const FACTOR: f64 = 1.1;
+
+    pub fn new(initial: f64) -> Self {
+        AdaptiveRatio {
+            value: initial.clamp(0.01, 1.0),
+            min: 0.01,
+        }
+    }
+
+    pub fn update(&mut self, outcome: LnsOutcome) {
+        match outcome {
+            LnsOutcome::Improved => {}
+            LnsOutcome::Exhausted => self.value = (self.value * Self::FACTOR).min(1.0),
+            LnsOutcome::BudgetExceeded => self.value = (self.value / Self::FACTOR).max(self.min),
+        }
+    }
+
+    /// Number of elements (at least one) to select among `n`.
+    pub fn count(&self, n: usize) -> usize {
+        ((self.value * n as f64).ceil() as usize).clamp(1.min(n), n)
+    }
+}
+
+/// Relaxes a random subset of the LNS variables.
+#[derive(Clone, Debug)]
+pub struct RandomVars {
+    ratio: AdaptiveRatio,
+}
+
+impl RandomVars {
+    /// Creates a neighbourhood that initially relaxes the given ratio of the variables.
+    pub fn new(relaxed_ratio: f64) -> Self {
+        RandomVars {
+            ratio: AdaptiveRatio::new(relaxed_ratio),
+        }
+    }
+}
+
+impl Neighbourhood for RandomVars {
+    fn select(&mut self, vars: &[VarRef], _incumbent: &SavedAssignment, rng: &mut SmallRng, relaxed: &mut Vec<VarRef>) {
+        relaxed.extend(vars.choose_multiple(rng, self.ratio.count(vars.len())));
+    }
+
+    fn feedback(&mut self, outcome: LnsOutcome) {
+        self.ratio.update(outcome)
+    }
+
+    fn clone_box(&self) -> Box<dyn Neighbourhood> {
+        Box::new(self.clone())
+    }
+}
+
+/// Relaxes all activities that, in the incumbent solution, occur in a random time window.
+///
+/// Each activity is given as a timepoint and the variables to relax when the timepoint is in the window.
+/// Activities absent in the incumbent are never relaxed.
+#[derive(Clone, Debug)]
+pub struct TemporalWindow {
+    activities: Vec<(VarRef, Vec<VarRef>)>,
+    /// Width of the window, relatively to the span of all timepoints in the incumbent.
+    ratio: AdaptiveRatio,
+}
+
+impl TemporalWindow {
+    pub fn new(activities: Vec<(VarRef, Vec<VarRef>)>, width_ratio: f64) -> Self {
+        TemporalWindow {
+            activities,
+            ratio: AdaptiveRatio::new(width_ratio),
+        }
+    }
+}
+
+impl Neighbourhood for TemporalWindow {
+    fn select(&mut self, _vars: &[VarRef], incumbent: &SavedAssignment, rng: &mut SmallRng, relaxed: &mut Vec<VarRef>) {
+        let value = |tp: VarRef| match incumbent.present(tp) {
+            Some(false) => None,
+            _ => Some(incumbent.lb(tp)),
+        };
+        let values = self.activities.iter().filter_map(|(tp, _)| value(*tp));
+        let (Some(earliest), Some(latest)) = (values.clone().min(), values.max()) else {
+            return;
+        };
+        let width = (self.ratio.value * (latest - earliest) as f64).ceil() as IntCst;
+        let start = rng.gen_range(earliest..=(latest - width).max(earliest));
+        let end = start + width;
+        for (tp, vars) in &self.activities {
+            if value(*tp).is_some_and(|t| start <= t && t <= end) {
+                relaxed.extend_from_slice(vars);
+            }
+        }
+    }
+
+    fn feedback(&mut self, outcome: LnsOutcome) {
+        self.ratio.update(outcome)
+    }
+
+    fn clone_box(&self) -> Box<dyn Neighbourhood> {
+        Box::new(self.clone())
+    }
+}
+
+/// Relaxes all variables related to randomly selected resources.
+///
+/// Each resource is given as the set of variables to relax when it is selected (e.g. the ordering
+/// of the tasks on a machine).
+#[derive(Clone, Debug)]
+pub struct ResourceBased {
+    resources: Vec<Vec<VarRef>>,
+    /// Ratio of the resources that are relaxed.
+    ratio: AdaptiveRatio,
+}
+
+impl ResourceBased {
+    /// Creates a neighbourhood that initially relaxes a single resource.
+    pub fn new(resources: Vec<Vec<VarRef>>) -> Self {
+        ResourceBased {
+            resources,
+            ratio: AdaptiveRatio::new(0.0),
+        }
+    }
+}
+
+impl Neighbourhood for ResourceBased {
+    fn select(
+        &mut self,
+        _vars: &[VarRef],
+        _incumbent: &SavedAssignment,
+        rng: &mut SmallRng,
+        relaxed: &mut Vec<VarRef>,
+    ) {
+        for resource in self
+            .resources
+            .choose_multiple(rng, self.ratio.count(self.resources.len()))
+        {
+            relaxed.extend_from_slice(resource);
+        }
+    }
+
+    fn feedback(&mut self, outcome: LnsOutcome) {
+        self.ratio.update(outcome)
+    }
+
+    fn clone_box(&self) -> Box<dyn Neighbourhood> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::Domains;
+    use crate::model::extensions::AssignmentExt;
+    use crate::model::lang::expr::leq;
+    use crate::model::lang::IVar;
+    use crate::model::Model;
+    use crate::solver::parallel::{ParSolver, SolverResult};
+
+    /// A jobshop instance: (machine, duration) of each task of each job.
+    const JOBS: [[(usize, IntCst); 3]; 4] = [
+        [(0, 3), (1, 2), (2, 2)],
+        [(0, 2), (2, 1), (1, 4)],
+        [(1, 4), (2, 3), (0, 1)],
+        [(2, 2), (0, 3), (1, 3)],
+    ];
+
+    /// A task, identified by its job and its position in the job.
+    type Task = (usize, usize);
+
+    struct Jobshop {
+        model: Model<String>,
+        makespan: IVar,
+        /// Start time of each task of each job.
+        starts: Vec<Vec<IVar>>,
+        /// All precedence variables, with the tasks they order.
+        precedences: Vec<(VarRef, Task, Task)>,
+    }
+
+    fn jobshop() -> Jobshop {
+        let mut model = Model::new();
+        let makespan = model.new_ivar(0, 100, "makespan");
+        let starts: Vec<Vec<IVar>> = (0..JOBS.len())
+            .map(|j| (0..3).map(|t| model.new_ivar(0, 100, format!("s{j}_{t}"))).collect())
+            .collect();
+        let end = |j: usize, t: usize| starts[j][t] + JOBS[j][t].1;
+        for (j, job) in starts.iter().enumerate() {
+            for (t, &start) in job.iter().enumerate().skip(1) {
+                model.enforce(leq(end(j, t - 1), start), []);
+            }
+            model.enforce(leq(end(j, 2), makespan), []);
+        }
+        let mut precedences = Vec::new();
+        let tasks: Vec<Task> = (0..JOBS.len()).flat_map(|j| (0..3).map(move |t| (j, t))).collect();
+        for (i, &(j1, t1)) in tasks.iter().enumerate() {
+            for &(j2, t2) in &tasks[i + 1..] {
+                if JOBS[j1][t1].0 == JOBS[j2][t2].0 {
+                    let prec = model.new_bvar(format!("prec_{j1}_{t1}_{j2}_{t2}"));
+                    model.bind(leq(end(j1, t1), starts[j2][t2]), prec.true_lit());
+                    model.bind(leq(end(j2, t2), starts[j1][t1]), prec.false_lit());
+                    precedences.push((prec.into(), (j1, t1), (j2, t2)));
+                }
+            }
+        }
+        Jobshop {
+            model,
+            makespan,
+            starts,
+            precedences,
+        }
+    }
+
+    /// LNS over the precedence variables, with all kinds of neighbourhoods.
+    fn lns(pb: &Jobshop) -> Lns {
+        let vars: Vec<VarRef> = pb.precedences.iter().map(|(v, _, _)| *v).collect();
+        let machines: Vec<Vec<VarRef>> = (0..3)
+            .map(|m| {
+                pb.precedences
+                    .iter()
+                    .filter(|(_, (j, t), _)| JOBS[*j][*t].0 == m)
+                    .map(|(v, _, _)| *v)
+                    .collect()
+            })
+            .collect();
+        let activities: Vec<(VarRef, Vec<VarRef>)> = (0..JOBS.len())
+            .flat_map(|j| (0..3).map(move |t| (j, t)))
+            .map(|task| {
+                let precs = pb
+                    .precedences
+                    .iter()
+                    .filter(|(_, a, b)| *a == task || *b == task)
+                    .map(|(v, _, _)| *v)
+                    .collect();
+                (pb.starts[task.0][task.1].into(), precs)
+            })
+            .collect();
+        Lns::new(vars, 5)
+            .with_seed(1)
+            .with_neighbourhood(RandomVars::new(0.2))
+            .with_neighbourhood(TemporalWindow::new(activities, 0.2))
+            .with_neighbourhood(ResourceBased::new(machines))
+    }
+
+    fn optimum(pb: &Jobshop) -> IntCst {
+        let mut solver = Solver::new(pb.model.clone());
+        let (optimum, _) = solver.minimize(pb.makespan).unwrap().unwrap();
+        optimum
+    }
+
+    #[test]
+    fn test_lns_optimum() {
+        let pb = jobshop();
+        let optimum = optimum(&pb);
+
+        let mut solver = Solver::new(pb.model.clone());
+        solver.set_lns(lns(&pb));
+        let mut costs = Vec::new();
+        let (cost, sol) = solver
+            .minimize_with(pb.makespan, |cost, _| costs.push(cost))
+            .unwrap()
+            .unwrap();
+        assert_eq!(cost, optimum);
+        assert_eq!(sol.var_domain(pb.makespan).lb, optimum);
+        // all reported solutions are strict improvements
+        assert!(costs.windows(2).all(|w| w[0] > w[1]));
+        assert_eq!(costs.last(), Some(&optimum));
+    }
+
+    #[test]
+    fn test_lns_worker() {
+        let pb = jobshop();
+        let optimum = optimum(&pb);
+
+        let lns = lns(&pb);
+        let solver = Box::new(Solver::new(pb.model.clone()));
+        let mut par_solver = ParSolver::new(solver, 2, |id, s| {
+            if id == 0 {
+                s.set_lns(lns.clone())
+            }
+        });
+        match par_solver.minimize(pb.makespan, None) {
+            SolverResult::Sol(sol) => assert_eq!(sol.var_domain(pb.makespan).lb, optimum),
+            _ => panic!("Expected an optimal solution"),
+        }
+    }
+
+    #[test]
+    fn test_lns_termination() {
+        let pb = jobshop();
+        let optimum = optimum(&pb);
+
+        // a neighbourhood that never relaxes any variable, and thus never contains an improving solution
+        let vars: Vec<VarRef> = pb.precedences.iter().map(|(v, _, _)| *v).collect();
+        let lns = Lns::new(vars, 5).with_neighbourhood(TemporalWindow::new(Vec::new(), 0.2));
+        let mut solver = Solver::new(pb.model.clone());
+        solver.set_lns(lns);
+        let (cost, _) = solver.minimize(pb.makespan).unwrap().unwrap();
+        assert_eq!(cost, optimum);
+    }
+
+    #[test]
+    fn test_fix() {
+        let mut d = Domains::new();
+        let prez = d.new_var(0, 1).geq(1);
+        let x = d.new_var(0, 10);
+        let y = d.new_optional_var(0, 10, prez);
+        d.set_lb(x, 3, crate::core::state::Cause::Decision).unwrap();
+        d.set_ub(x, 3, crate::core::state::Cause::Decision).unwrap();
+        d.set(!prez, crate::core::state::Cause::Decision).unwrap();
+
+        let mut out = Vec::new();
+        fix(x, &d, &mut out);
+        assert_eq!(out, vec![x.geq(3), x.leq(3)]);
+        out.clear();
+        fix(y, &d, &mut out);
+        assert_eq!(out, vec![!prez]);
+    }
+
+    #[test]
+    fn test_adaptive_ratio() {
+        let mut ratio = AdaptiveRatio::new(0.5);
+        assert_eq!(ratio.count(10), 5);
+        ratio.update(LnsOutcome::Improved);
+        assert_eq!(ratio.count(10), 5);
+        ratio.update(LnsOutcome::Exhausted);
+        assert_eq!(ratio.count(10), 6);
+        for _ in 0..100 {
+            ratio.update(LnsOutcome::BudgetExceeded);
+        }
+        assert_eq!(ratio.count(10), 1);
+        assert_eq!(ratio.count(0), 0);
+        for _ in 0..100 {
+            ratio.update(LnsOutcome::Exhausted);
+        }
+        assert_eq!(ratio.count(10), 10);
+    }
+}
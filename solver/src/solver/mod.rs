@@ -1,6 +1,10 @@
+pub mod lns;
+pub mod multi_objective;
+pub mod optimization;
 pub mod parallel;
 pub mod search;
 pub mod stats;
 
+mod explanation_checker;
 mod solver_impl;
 pub use solver_impl::*;
@@ -0,0 +1,292 @@
+//! Algorithms for optimizing several objectives at once.
+//!
+//!  - [`Solver::minimize_lexicographic`] minimizes the objectives one after the other: each objective is
+//!    minimized while requiring all previous ones to keep their optimal values.
+//!  - [`Solver::pareto_front`] enumerates the Pareto-optimal points of the objectives: the solutions that
+//!    cannot be improved on an objective without degrading another one.
+//!
+//! Both algorithms run on a single solver, relying on assumptions to constrain the successive searches
+//! so that the clauses learnt along the way remain valid (and useful) until completion.
+
+use crate::backtrack::Backtrack;
+use crate::core::{IntCst, Lit};
+use crate::model::extensions::{AssignmentExt, SavedAssignment};
+use crate::model::lang::expr::or;
+use crate::model::lang::IAtom;
+use crate::model::Label;
+use crate::solver::{Exit, SolveResult, Solver};
+use std::sync::Arc;
+
+/// Values of the objectives in a solution, with the solution itself.
+pub type ObjectivesValues = (Vec<IntCst>, Arc<SavedAssignment>);
+
+/// Values taken by the `objectives` in the given solution.
+pub(crate) fn objectives_values(objectives: &[IAtom], sol: &SavedAssignment) -> Vec<IntCst> {
+    objectives.iter().map(|&obj| sol.var_domain(obj).lb).collect()
+}
+
+/// Returns true if `a` is at least as good as `b` on all objectives.
+pub(crate) fn weakly_dominates(a: &[IntCst], b: &[IntCst]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a <= b)
+}
+
+impl<Lbl: Label> Solver<Lbl> {
+    /// Minimizes the objectives in lexicographic order: the first one has the highest priority.
+    ///
+    /// Returns the values of the objectives in the optimal solution, together with the solution,
+    /// or `None` if there is no solution.
+    /// As with `minimize`, the constraints requiring the objectives to take their optimal values remain in the solver.
+    pub fn minimize_lexicographic(&mut self, objectives: &[IAtom]) -> Result<Option<ObjectivesValues>, Exit> {
+        self.minimize_lexicographic_with(objectives, |_, _| ())
+    }
+
+    /// Minimizes the objectives in lexicographic order, invoking `on_new_solution` on each improving solution.
+    ///
+    /// Each objective is minimized in its own stage, where the solver searches for solutions
+    /// that improve on the best one under the assumption that the current objective is strictly better.
+    /// When this assumption is proven unsatisfiable, the objective is fixed to its optimal value and the
+    /// next stage starts. Learnt clauses are kept from one stage to the next.
+    pub fn minimize_lexicographic_with(
+        &mut self,
+        objectives: &[IAtom],
+        mut on_new_solution: impl FnMut(&[IntCst], &SavedAssignment),
+    ) -> Result<Option<ObjectivesValues>, Exit> {
+        let mut best: Option<ObjectivesValues> = None;
+        // number of solutions recorded, used as a decreasing surrogate objective for the brancher
+        let mut num_improvements = 0;
+        let mut stage = 0;
+        while stage < objectives.len() {
+            let objective = objectives[stage];
+            // literal stating that the objective of the stage improves on the best solution
+            let improved = best.as_ref().map(|(values, _)| objective.lt_lit(values[stage]));
+            let assumptions: Vec<Lit> = improved.into_iter().collect();
+            // solutions from other solvers interrupt the search if they are better than the best one
+            let on_external_solution = |_: &mut Self, sol: &Arc<SavedAssignment>| {
+                let values = objectives_values(objectives, sol);
+                best.as_ref().is_some_and(|(best_values, _)| &values >= best_values)
+            };
+            let sol = match self.solve_under(&assumptions, on_external_solution)? {
+                SolveResult::AtSolution => {
+                    let sol = Arc::new(self.model.state.clone());
+                    self.sync.notify_solution_found(sol.clone());
+                    on_new_solution(&objectives_values(objectives, &sol), &sol);
+                    sol
+                }
+                SolveResult::ExternalSolution(sol) => sol,
+                SolveResult::Unsat(core) if core.is_empty() => return Ok(best),
+                SolveResult::Unsat(_) => {
+                    // no better value for this objective, fix it to its optimal value and move to the next one
+                    let values = &best.as_ref().expect("no solution for a non-empty core").0;
+                    let optimal = objective.le_lit(values[stage]);
+                    let clause = self.level_clause(optimal);
+                    if let Some(proof) = self.reasoners.sat.proof_log() {
+                        // only the lower bound is implied, fixing the objective restricts the search
+                        proof.add_hypothesis(clause);
+                    }
+                    self.add_tautology(optimal);
+                    stage += 1;
+                    continue;
+                }
+            };
+            let values = objectives_values(objectives, &sol);
+            num_improvements += 1;
+            self.brancher.new_assignment_found(-num_improvements, sol.clone());
+            self.stats.add_solution(values[0]);
+            best = Some((values, sol));
+        }
+        if best.is_none() && objectives.is_empty() {
+            // no objective, any solution is optimal
+            if let Some(sol) = self.solve()? {
+                best = Some((Vec::new(), sol));
+            }
+        }
+        Ok(best)
+    }
+
+    /// Enumerates the Pareto front of the objectives to minimize, returning one solution for each of its points.
+    ///
+    /// Each point is obtained from a solution that is not dominated by the previous points, which is improved
+    /// on each objective in turn without degrading the other ones, until no improvement is possible.
+    /// Once the point is found, `on_point` is invoked and a clause is added to exclude all solutions that
+    /// it dominates. The points are returned in the order they were found.
+    ///
+    /// When part of a parallel solver, the points are shared with the other solvers, which adopt them as their own.
+    pub fn pareto_front(
+        &mut self,
+        objectives: &[IAtom],
+        mut on_point: impl FnMut(&[IntCst], &SavedAssignment),
+    ) -> Result<Vec<ObjectivesValues>, Exit> {
+        let mut front: Vec<ObjectivesValues> = Vec::new();
+        // only points of the front are shared by other solvers, they interrupt the search of the current point
+        let on_external_solution = |_: &mut Self, _: &Arc<SavedAssignment>| false;
+        'points: loop {
+            // any solution that is not dominated by the points already found
+            let mut sol = match self.solve_under(&[], on_external_solution)? {
+                SolveResult::AtSolution => Arc::new(self.model.state.clone()),
+                SolveResult::ExternalSolution(point) => {
+                    self.add_pareto_point(objectives, point, &mut front);
+                    continue;
+                }
+                SolveResult::Unsat(_) => return Ok(front),
+            };
+            let mut values = objectives_values(objectives, &sol);
+            for i in 0..objectives.len() {
+                loop {
+                    // improve on the i-th objective without degrading any other
+                    let assumptions: Vec<Lit> = objectives
+                        .iter()
+                        .enumerate()
+                        .map(|(j, obj)| {
+                            if i == j {
+                                obj.lt_lit(values[j])
+                            } else {
+                                obj.le_lit(values[j])
+                            }
+                        })
+                        .collect();
+                    match self.solve_under(&assumptions, on_external_solution)? {
+                        SolveResult::AtSolution => {
+                            sol = Arc::new(self.model.state.clone());
+                            values = objectives_values(objectives, &sol);
+                        }
+                        SolveResult::ExternalSolution(point) => {
+                            // our candidate might be dominated by the new point, start over
+                            self.add_pareto_point(objectives, point, &mut front);
+                            continue 'points;
+                        }
+                        SolveResult::Unsat(_) => break,
+                    }
+                }
+            }
+            self.sync.notify_solution_found(sol.clone());
+            self.stats.add_solution(values[0]);
+            on_point(&values, &sol);
+            self.add_pareto_point(objectives, sol, &mut front);
+        }
+    }
+
+    /// Adds a Pareto-optimal solution to the front and requires all future solutions to be better on at least one objective.
+    fn add_pareto_point(
+        &mut self,
+        objectives: &[IAtom],
+        point: Arc<SavedAssignment>,
+        front: &mut Vec<ObjectivesValues>,
+    ) {
+        let values = objectives_values(objectives, &point);
+        if front.iter().any(|(other, _)| weakly_dominates(other, &values)) {
+            return; // already known
+        }
+        let improves_on_one: Vec<Lit> = objectives
+            .iter()
+            .zip(&values)
+            .map(|(obj, &value)| obj.lt_lit(value))
+            .collect();
+        self.reset();
+        if let Some(proof) = self.reasoners.sat.proof_log() {
+            proof.comment(&format!("new Pareto point {values:?}"));
+            proof.add_hypothesis(improves_on_one.iter().copied());
+        }
+        self.enforce(or(improves_on_one), []);
+        front.push((values, point));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::lang::expr::leq;
+    use crate::model::lang::linear::LinearSum;
+    use crate::model::lang::IVar;
+    use crate::model::Model;
+    use crate::solver::parallel::{ParSolver, SolverResult};
+
+    /// Chooses `x` and `y` in [0, 10] with `x + y >= 10`, costs are `x` and `y`.
+    fn trade_off() -> (Model<String>, IVar, IVar) {
+        let mut model = Model::new();
+        let x = model.new_ivar(0, 10, "x");
+        let y = model.new_ivar(0, 10, "y");
+        model.enforce(LinearSum::of(vec![x, y]).geq(10), []);
+        (model, x, y)
+    }
+
+    #[test]
+    fn test_lexicographic() {
+        let (mut model, x, y) = trade_off();
+        model.enforce(leq(y, 7), []);
+        let mut solver = Solver::new(model);
+        let mut previous: Option<Vec<IntCst>> = None;
+        let (values, sol) = solver
+            .minimize_lexicographic_with(&[x.into(), y.into()], |values, _| {
+                // each solution improves lexicographically on the previous one
+                assert!(previous.as_ref().is_none_or(|prev| values < prev.as_slice()));
+                previous = Some(values.to_vec());
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(values, vec![3, 7]);
+        assert_eq!(sol.var_domain(x).lb, 3);
+
+        // objectives in the other order
+        let (model, x, y) = trade_off();
+        let mut solver = Solver::new(model);
+        let (values, _) = solver.minimize_lexicographic(&[y.into(), x.into()]).unwrap().unwrap();
+        assert_eq!(values, vec![0, 10]);
+    }
+
+    #[test]
+    fn test_lexicographic_unsat() {
+        let (mut model, x, y) = trade_off();
+        model.enforce(leq(x, 4), []);
+        model.enforce(leq(y, 4), []);
+        let mut solver = Solver::new(model);
+        assert!(solver.minimize_lexicographic(&[x.into(), y.into()]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_pareto_front() {
+        let (mut model, x, y) = trade_off();
+        // make the front sparser: x is even
+        let half = model.new_ivar(0, 5, "half");
+        model.enforce(LinearSum::of(vec![half, half]).leq(x), []);
+        model.enforce(LinearSum::of(vec![half, half]).geq(x), []);
+        let mut solver = Solver::new(model);
+        let mut num_points = 0;
+        let front = solver
+            .pareto_front(&[x.into(), y.into()], |_, _| num_points += 1)
+            .unwrap();
+        assert_eq!(num_points, front.len());
+        let mut points: Vec<Vec<IntCst>> = front.iter().map(|(values, _)| values.clone()).collect();
+        points.sort();
+        let expected: Vec<Vec<IntCst>> = (0..=5).map(|h| vec![2 * h, 10 - 2 * h]).collect();
+        assert_eq!(points, expected);
+        for (values, sol) in &front {
+            assert_eq!(&objectives_values(&[x.into(), y.into()], sol), values);
+        }
+    }
+
+    #[test]
+    fn test_parallel() {
+        let (model, x, y) = trade_off();
+        let objectives: [IAtom; 2] = [x.into(), y.into()];
+        let solver = Box::new(Solver::new(model));
+        let mut par_solver = ParSolver::new(solver, 3, |_, _| ());
+        match par_solver.minimize_lexicographic(&objectives, None) {
+            SolverResult::Sol(sol) => assert_eq!(objectives_values(&objectives, &sol), vec![0, 10]),
+            _ => panic!("Expected an optimal solution"),
+        }
+
+        let (model, x, y) = trade_off();
+        let objectives: [IAtom; 2] = [x.into(), y.into()];
+        let solver = Box::new(Solver::new(model));
+        let mut par_solver = ParSolver::new(solver, 3, |_, _| ());
+        match par_solver.pareto_front(&objectives, |_, _| (), None) {
+            SolverResult::Sol(front) => {
+                let mut points: Vec<Vec<IntCst>> = front.into_iter().map(|(values, _)| values).collect();
+                points.sort();
+                let expected: Vec<Vec<IntCst>> = (0..=10).map(|v| vec![v, 10 - v]).collect();
+                assert_eq!(points, expected);
+            }
+            _ => panic!("Expected a Pareto front"),
+        }
+    }
+}
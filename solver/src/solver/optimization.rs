@@ -0,0 +1,612 @@
+//! Algorithms for minimizing or maximizing an objective.
+//!
+//! The algorithm used by the `minimize` and `maximize` methods of a solver is selected with
+//! [`Solver::set_optimization_options`](crate::solver::Solver::set_optimization_options):
+//!
+//!  - [`OptimizationMode::Linear`] (default): SAT-UNSAT search, where each solution is followed by the search for
+//!    a strictly better one.
+//!  - [`OptimizationMode::Binary`]: dichotomic search on the objective, where each search assumes that the objective
+//!    is in the better half of its remaining domain.
+//!  - [`OptimizationMode::LowerBoundFirst`]: UNSAT-SAT search, where each search assumes that the objective takes its
+//!    best possible value, which is improved by one on failure.
+//!  - [`OptimizationMode::CoreGuided`]: OLL core-guided search, for objectives that are a weighted sum of Boolean literals.
+//!
+//! All modes but the linear one prove bounds on the objective as they progress. The best bound is reported
+//! in the [`OptimizationResult`] together with the best solution, and in the solver's statistics.
+
+use crate::backtrack::Backtrack;
+use crate::core::{IntCst, Lit, VarRef};
+use crate::model::extensions::{AssignmentExt, SavedAssignment};
+use crate::model::lang::linear::{LinearSum, LinearTerm};
+use crate::model::lang::IAtom;
+use crate::model::{Constraint, Label, Model};
+use crate::reif::ReifExpr;
+use crate::solver::{Exit, SolveResult, Solver};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Algorithm used to optimize an objective.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum OptimizationMode {
+    /// SAT-UNSAT search: each solution is followed by the search for a strictly better one.
+    #[default]
+    Linear,
+    /// Dichotomic search on the objective, assuming that its value is in the better half of its remaining domain.
+    Binary,
+    /// UNSAT-SAT search: assumes that the objective takes its best possible value, which is improved by one on failure.
+    LowerBoundFirst,
+    /// OLL core-guided search for objectives that are a weighted sum of Boolean literals.
+    /// For any other objective, this falls back to the lower-bound-first search.
+    CoreGuided,
+}
+
+impl FromStr for OptimizationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(OptimizationMode::Linear),
+            "binary" => Ok(OptimizationMode::Binary),
+            "lb-first" => Ok(OptimizationMode::LowerBoundFirst),
+            "core" => Ok(OptimizationMode::CoreGuided),
+            _ => Err(format!("Unknown optimization mode: {s}")),
+        }
+    }
+}
+
+impl Display for OptimizationMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptimizationMode::Linear => write!(f, "linear"),
+            OptimizationMode::Binary => write!(f, "binary"),
+            OptimizationMode::LowerBoundFirst => write!(f, "lb-first"),
+            OptimizationMode::CoreGuided => write!(f, "core"),
+        }
+    }
+}
+
+/// Options of the `minimize` and `maximize` methods of a solver.
+#[derive(Clone, Debug, Default)]
+pub struct OptimizationOptions {
+    pub mode: OptimizationMode,
+}
+
+/// Outcome of an optimization.
+#[derive(Clone)]
+pub struct OptimizationResult {
+    /// Best solution found, with its objective value.
+    pub best: Option<(IntCst, Arc<SavedAssignment>)>,
+    /// Best bound proven on the objective: a lower bound when minimizing and an upper bound when maximizing.
+    /// It is `None` if the problem was proven to have no solution.
+    pub bound: Option<IntCst>,
+    /// True if the search completed, i.e., `best` is optimal or there is no solution.
+    /// Otherwise, the solver was interrupted.
+    pub complete: bool,
+}
+
+/// An ongoing optimization, with the best solution and bound found so far.
+pub(crate) struct Optimization {
+    pub objective: IAtom,
+    pub minimize: bool,
+    pub best: Option<(IntCst, Arc<SavedAssignment>)>,
+    pub bound: Option<IntCst>,
+}
+
+impl Optimization {
+    pub fn new(objective: IAtom, minimize: bool) -> Self {
+        Optimization {
+            objective,
+            minimize,
+            best: None,
+            bound: None,
+        }
+    }
+
+    /// Converts a value of the objective to a cost to be minimized.
+    fn cost(&self, value: IntCst) -> IntCst {
+        if self.minimize {
+            value
+        } else {
+            -value
+        }
+    }
+
+    /// Records a new bound on the objective, if it improves on the previous one.
+    fn add_bound<Lbl: Label>(&mut self, solver: &mut Solver<Lbl>, bound: IntCst) {
+        if self.bound.is_none_or(|previous| self.cost(bound) > self.cost(previous)) {
+            self.bound = Some(bound);
+            solver.stats.add_bound(bound);
+        }
+    }
+
+    /// Records a solution, that becomes the best one if it improves on it.
+    fn add_solution<Lbl: Label>(&mut self, solver: &mut Solver<Lbl>, sol: Arc<SavedAssignment>) {
+        solver.record_solution(self.objective, self.minimize, sol, &mut self.best);
+    }
+
+    /// Returns true if the best solution reaches the bound, i.e., is proven optimal.
+    fn is_optimal(&self) -> bool {
+        matches!((&self.best, self.bound), (Some((value, _)), Some(bound)) if *value == bound)
+    }
+
+    /// Backtracks to the root and propagates, to update the bound from the domain of the objective.
+    /// Returns false if the root is inconsistent, i.e., there is no (better) solution.
+    fn propagate_root<Lbl: Label>(&mut self, solver: &mut Solver<Lbl>) -> bool {
+        solver.reset();
+        if !solver.propagate_and_backtrack_to_consistent() {
+            return false;
+        }
+        let dom = solver.model.state.var_domain(self.objective);
+        self.add_bound(solver, if self.minimize { dom.lb } else { dom.ub });
+        true
+    }
+
+    /// Runs the optimization with the given `search` algorithm, until completion or interruption.
+    pub fn run<Lbl: Label>(
+        mut self,
+        solver: &mut Solver<Lbl>,
+        search: impl FnOnce(&mut Self, &mut Solver<Lbl>) -> Result<(), Exit>,
+    ) -> OptimizationResult {
+        if self.propagate_root(solver) && search(&mut self, solver).is_err() {
+            return self.result(false);
+        }
+        // the search space is exhausted, the best solution (if any) is optimal
+        if let Some((value, _)) = self.best {
+            self.add_bound(solver, value);
+        } else {
+            self.bound = None;
+        }
+        self.result(true)
+    }
+
+    /// Searches for an optimal solution with the algorithm of the given mode.
+    pub fn search<Lbl: Label>(
+        &mut self,
+        solver: &mut Solver<Lbl>,
+        mode: OptimizationMode,
+        on_new_solution: &mut impl FnMut(IntCst, &SavedAssignment),
+    ) -> Result<(), Exit> {
+        match mode {
+            OptimizationMode::Linear => self.linear(solver, on_new_solution),
+            OptimizationMode::Binary | OptimizationMode::LowerBoundFirst => self.bounded(solver, mode, on_new_solution),
+            OptimizationMode::CoreGuided => self.core_guided(solver, on_new_solution),
+        }
+    }
+
+    fn result(self, complete: bool) -> OptimizationResult {
+        OptimizationResult {
+            best: self.best,
+            bound: self.bound,
+            complete,
+        }
+    }
+
+    /// SAT-UNSAT search: after each solution, the search continues from the current state,
+    /// with the constraint that the next solution must improve on the previous one.
+    fn linear<Lbl: Label>(
+        &mut self,
+        solver: &mut Solver<Lbl>,
+        on_new_solution: &mut impl FnMut(IntCst, &SavedAssignment),
+    ) -> Result<(), Exit> {
+        while !self.is_optimal() {
+            let sol = match solver._solve()? {
+                SolveResult::AtSolution => {
+                    // solver stopped at a solution, this is necessarily an improvement on the best solution found so far
+                    solver.solution_found(self.objective, on_new_solution)
+                }
+                SolveResult::ExternalSolution(sol) => sol, // a solution was handed out to us by another solver
+                SolveResult::Unsat(_) => return Ok(()),    // exhausted search space
+            };
+            self.add_solution(solver, sol);
+        }
+        Ok(())
+    }
+
+    /// Binary or lower-bound-first search: each search assumes that the objective is better than some pivot value.
+    /// If there is no such solution, the bound is moved past the pivot.
+    fn bounded<Lbl: Label>(
+        &mut self,
+        solver: &mut Solver<Lbl>,
+        mode: OptimizationMode,
+        on_new_solution: &mut impl FnMut(IntCst, &SavedAssignment),
+    ) -> Result<(), Exit> {
+        while !self.is_optimal() && self.propagate_root(solver) {
+            let dom = solver.model.state.var_domain(self.objective);
+            let (lb, ub) = (dom.lb, dom.ub);
+            let objective = self.objective;
+            // literal stating that the objective is at least as good as the pivot
+            let assumption = match (mode, self.minimize) {
+                (OptimizationMode::Binary, true) => objective.le_lit(lb + (ub - lb) / 2),
+                (OptimizationMode::Binary, false) => objective.ge_lit(ub - (ub - lb) / 2),
+                (_, true) => objective.le_lit(lb),
+                (_, false) => objective.ge_lit(ub),
+            };
+            // solutions from other solvers are recorded without interrupting the search
+            let on_external_solution = |solver: &mut Solver<Lbl>, sol: &Arc<SavedAssignment>| {
+                self.add_solution(solver, sol.clone());
+                true
+            };
+            match solver.solve_under(&[assumption], on_external_solution)? {
+                SolveResult::AtSolution => {
+                    let sol = solver.solution_found(objective, on_new_solution);
+                    self.add_solution(solver, sol);
+                }
+                SolveResult::ExternalSolution(_) => unreachable!("External solutions do not stop the search"),
+                SolveResult::Unsat(core) if core.is_empty() => return Ok(()),
+                SolveResult::Unsat(_) => {
+                    // no solution as good as the pivot, all future solutions must be worse
+                    solver.add_tautology(!assumption);
+                    let clause = solver.level_clause(!assumption);
+                    if let Some(proof) = solver.reasoners.sat.proof_log() {
+                        proof.add_lemma(clause);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// OLL core-guided search, where the objective is a weighted sum of literals that should be false.
+    ///
+    /// The solver assumes that all these literals are false. Each unsatisfiable core raises the bound by the minimal
+    /// weight of its literals, and is relaxed by allowing one of them to be true. This is done by introducing
+    /// a variable counting the true literals of the core, whose value should not exceed one.
+    /// Once a solution is found, the search continues with the linear search if it does not reach the bound.
+    fn core_guided<Lbl: Label>(
+        &mut self,
+        solver: &mut Solver<Lbl>,
+        on_new_solution: &mut impl FnMut(IntCst, &SavedAssignment),
+    ) -> Result<(), Exit> {
+        let Some(sum) = BoolSum::of_objective(&solver.model, self.objective, self.minimize) else {
+            return self.bounded(solver, OptimizationMode::LowerBoundFirst, on_new_solution);
+        };
+        // lower bound on the cost
+        let mut lb = sum.offset;
+        // literals that should be false, with their weight
+        let mut soft: Vec<(Lit, IntCst)> = Vec::new();
+        for (lit, weight) in sum.terms {
+            match solver.model.state.value(lit) {
+                Some(true) => lb += weight,
+                Some(false) => {}
+                None => soft.push((lit, weight)),
+            }
+        }
+        // for literals of the form `counter >= k` on the counter of the true literals of a core: the counter, `k`
+        // and the size of the core
+        let mut counters: HashMap<Lit, (VarRef, IntCst, IntCst)> = HashMap::new();
+        // literal activating the definitions of the counters, that is only assumed during this search
+        // so that the counters do not constrain later searches
+        let mut active: Option<Lit> = None;
+        let mut assumptions = Vec::new();
+        loop {
+            self.add_bound(solver, if self.minimize { lb } else { -lb });
+            assumptions.clear();
+            assumptions.extend(active);
+            assumptions.extend(soft.iter().filter(|(_, w)| *w > 0).map(|(l, _)| !*l));
+
+            let on_external_solution = |solver: &mut Solver<Lbl>, sol: &Arc<SavedAssignment>| {
+                self.add_solution(solver, sol.clone());
+                true
+            };
+            match solver.solve_under(&assumptions, on_external_solution)? {
+                SolveResult::AtSolution => {
+                    let sol = solver.solution_found(self.objective, on_new_solution);
+                    self.add_solution(solver, sol);
+                    break;
+                }
+                SolveResult::ExternalSolution(_) => unreachable!("External solutions do not stop the search"),
+                SolveResult::Unsat(core) if core.is_empty() => return Ok(()),
+                SolveResult::Unsat(core) => {
+                    let core: Vec<Lit> = core.iter().filter(|&&l| Some(l) != active).map(|&l| !l).collect();
+                    if core.is_empty() {
+                        // the counters are consistent by construction, there is no solution
+                        return Ok(());
+                    }
+                    let weight = |l: Lit| soft.iter().find(|(s, _)| *s == l).map_or(0, |(_, w)| *w);
+                    let min_weight = core.iter().map(|&l| weight(l)).min().unwrap();
+                    lb += min_weight;
+                    for (l, w) in soft.iter_mut() {
+                        if core.contains(l) {
+                            *w -= min_weight;
+                        }
+                    }
+                    let mut relaxed = Vec::new();
+                    for &l in &core {
+                        // a counter exceeding its allowed value may now take the next one
+                        if let Some(&(counter, k, size)) = counters.get(&l) {
+                            if k < size {
+                                let next = counter.geq(k + 1);
+                                counters.insert(next, (counter, k + 1, size));
+                                relaxed.push((next, min_weight));
+                            }
+                        }
+                    }
+                    if core.len() > 1 {
+                        // at most one literal of the core may be true, at the cost of `min_weight` for each additional one
+                        let active = *active.get_or_insert_with(|| solver.new_local_ivar(0, 1).geq(1));
+                        let counter = count_true(solver, &core, active);
+                        let next = counter.geq(2);
+                        counters.insert(next, (counter, 2, core.len() as IntCst));
+                        relaxed.push((next, min_weight));
+                    }
+                    for (l, w) in relaxed {
+                        // the relaxation of a counter may already be soft if the counter appeared in a previous core
+                        match soft.iter_mut().find(|(s, _)| *s == l) {
+                            Some((_, weight)) => *weight += w,
+                            None => soft.push((l, w)),
+                        }
+                    }
+                }
+            }
+        }
+        if self.is_optimal() {
+            Ok(())
+        } else {
+            // the objective is not tight (e.g. it is also constrained by other constraints), resume with a linear search
+            self.linear(solver, on_new_solution)
+        }
+    }
+}
+
+/// Creates a variable whose value is the number of true literals among `lits`, whenever `active` is true.
+fn count_true<Lbl: Label>(solver: &mut Solver<Lbl>, lits: &[Lit], active: Lit) -> VarRef {
+    let counter = solver.new_local_ivar(0, lits.len() as IntCst);
+    let mut sum = LinearSum::zero();
+    for &l in lits {
+        sum += LinearTerm::constant_int(1, l);
+    }
+    solver.enforce(sum.clone().leq(counter), [active]);
+    solver.enforce(sum.geq(counter), [active]);
+    counter.into()
+}
+
+/// A lower bound on the cost of an objective, in the form of a weighted sum of literals: `offset + sum_i w_i * l_i`,
+/// where `w_i > 0` and the literal `l_i` is true if the `i`-th term is paid.
+/// The cost is the value of the objective when minimizing, and its opposite when maximizing.
+struct BoolSum {
+    offset: IntCst,
+    terms: Vec<(Lit, IntCst)>,
+}
+
+impl BoolSum {
+    /// Looks for a linear constraint bounding the objective by a weighted sum of Boolean literals.
+    fn of_objective<Lbl: Label>(model: &Model<Lbl>, objective: IAtom, minimize: bool) -> Option<BoolSum> {
+        let obj_var = VarRef::from(objective.var);
+        // coefficient of the objective variable in a constraint `sum + coef * obj_var <= ub` bounding the cost from below
+        let obj_coef = if minimize { -1 } else { 1 };
+        model.shape.constraints.iter().find_map(|c| match c {
+            Constraint::Reified(ReifExpr::Linear(lin), value) if model.entails(*value) => {
+                let lin = lin.simplify();
+                let obj_term = lin.sum.iter().find(|t| t.var == obj_var)?;
+                if obj_term.factor != obj_coef || obj_term.lit != Lit::TRUE {
+                    return None;
+                }
+                // with `obj = obj_var + shift`, the constraint states that `cost >= sum - ub + (obj_coef * -shift)`
+                let mut sum = BoolSum {
+                    offset: -lin.upper_bound - obj_coef * objective.shift,
+                    terms: Vec::new(),
+                };
+                for t in lin.sum.iter().filter(|t| t.var != obj_var) {
+                    let lit = if t.var == VarRef::ONE {
+                        t.lit
+                    } else if t.lit == Lit::TRUE && model.state.presence(t.var) == Lit::TRUE {
+                        match model.state.bounds(t.var) {
+                            (0, 0) => continue,
+                            (1, 1) => Lit::TRUE,
+                            (0, 1) => t.var.geq(1),
+                            _ => return None,
+                        }
+                    } else {
+                        return None;
+                    };
+                    sum.add(t.factor, lit);
+                }
+                Some(sum)
+            }
+            _ => None,
+        })
+    }
+
+    /// Adds the term `factor * lit` to the sum.
+    fn add(&mut self, factor: IntCst, lit: Lit) {
+        if factor > 0 {
+            self.terms.push((lit, factor));
+        } else if factor < 0 {
+            // factor * lit = factor + |factor| * !lit
+            self.offset += factor;
+            self.terms.push((!lit, -factor));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::INT_CST_MAX;
+    use crate::model::lang::expr::{leq, or};
+    use crate::model::lang::IVar;
+
+    /// A weighted set cover problem: each clause must be covered by one of the chosen items, whose total weight is minimized.
+    fn set_cover() -> (Model<String>, IVar, IntCst) {
+        let mut model = Model::new();
+        let weights = [3, 2, 4, 2, 5];
+        let items: Vec<Lit> = (0..weights.len())
+            .map(|i| model.new_bvar(format!("x{i}")).true_lit())
+            .collect();
+        for clause in [[0, 1], [1, 2], [2, 3], [3, 4], [0, 4]] {
+            model.enforce(or(clause.map(|i| items[i])), []);
+        }
+        let cost = model.new_ivar(0, 100, "cost");
+        let mut sum = LinearSum::zero();
+        for (&item, &w) in items.iter().zip(&weights) {
+            sum += LinearTerm::constant_int(w, item);
+        }
+        model.enforce(sum.clone().leq(cost), []);
+        model.enforce(sum.geq(cost), []);
+        // optimal cover: x1, x3 and x0 (or x4)
+        (model, cost, 7)
+    }
+
+    fn optimize(model: Model<String>, objective: IVar, minimize: bool, mode: OptimizationMode) -> OptimizationResult {
+        let mut solver = Solver::new(model);
+        solver.set_optimization_options(OptimizationOptions { mode });
+        let mut values = Vec::new();
+        let result = solver.optimize(objective, minimize, |value, _| values.push(value));
+        // each solution found by the solver improves on the previous ones
+        for w in values.windows(2) {
+            assert!(if minimize { w[1] < w[0] } else { w[1] > w[0] });
+        }
+        result
+    }
+
+    #[test]
+    fn test_modes_set_cover() {
+        for mode in [
+            OptimizationMode::Linear,
+            OptimizationMode::Binary,
+            OptimizationMode::LowerBoundFirst,
+            OptimizationMode::CoreGuided,
+        ] {
+            let (model, cost, optimum) = set_cover();
+            let result = optimize(model, cost, true, mode);
+            assert!(result.complete);
+            assert_eq!(result.best.as_ref().map(|(v, _)| *v), Some(optimum), "{mode}");
+            assert_eq!(result.bound, Some(optimum));
+        }
+    }
+
+    #[test]
+    fn test_modes_maximize() {
+        // maximize the number of items (plus 10) of a set cover with a total weight of at most 9
+        for mode in [
+            OptimizationMode::Linear,
+            OptimizationMode::Binary,
+            OptimizationMode::LowerBoundFirst,
+            OptimizationMode::CoreGuided,
+        ] {
+            let (mut model, cost, _) = set_cover();
+            model.enforce(leq(cost, 9), []);
+            let items: Vec<IVar> = (0..5)
+                .map(|i| IVar::new(model.shape.get_variable(&format!("x{i}")).unwrap()))
+                .collect();
+            let count = model.new_ivar(0, 10, "count");
+            model.enforce(LinearSum::of(items).geq(count), []);
+            let mut solver = Solver::new(model);
+            solver.set_optimization_options(OptimizationOptions { mode });
+            let result = solver.optimize(IAtom::new(count, 10), false, |_, _| ());
+            assert!(result.complete);
+            // {x0, x1, x3} or {x1, x3, x4}
+            assert_eq!(result.best.as_ref().map(|(v, _)| *v), Some(13), "{mode}");
+            assert_eq!(result.bound, Some(13));
+        }
+    }
+
+    #[test]
+    fn test_modes_knapsack() {
+        // maximize the value of the selected items under two capacity constraints,
+        // which requires relaxing the same counters in several cores
+        let weights = [[4, 3], [6, 2], [3, 4], [4, 3], [5, 5], [2, 1], [4, 2], [3, 4]];
+        let values = [10, 13, 7, 8, 12, 5, 9, 6];
+        for mode in [OptimizationMode::Linear, OptimizationMode::CoreGuided] {
+            let mut model: Model<String> = Model::new();
+            let items: Vec<Lit> = (0..values.len())
+                .map(|i| model.new_bvar(format!("x{i}")).true_lit())
+                .collect();
+            for (dim, capacity) in [(0, 15), (1, 12)] {
+                let mut sum = LinearSum::zero();
+                for (&item, w) in items.iter().zip(&weights) {
+                    sum += LinearTerm::constant_int(w[dim], item);
+                }
+                model.enforce(sum.leq(capacity), []);
+            }
+            model.enforce(or([!items[0], !items[1]]), []);
+            // cost of the items left out
+            let cost = model.new_ivar(0, 70, "cost");
+            let mut sum = LinearSum::zero();
+            for (&item, &value) in items.iter().zip(&values) {
+                sum += LinearTerm::constant_int(value, !item);
+            }
+            model.enforce(sum.clone().leq(cost), []);
+            model.enforce(sum.geq(cost), []);
+            let result = optimize(model, cost, true, mode);
+            assert!(result.complete);
+            // total value of 70, the best selection {x0, x4, x5, x6} having a value of 36
+            assert_eq!(result.best.as_ref().map(|(v, _)| *v), Some(34), "{mode}");
+        }
+    }
+
+    #[test]
+    fn test_modes_unsat() {
+        for mode in [OptimizationMode::Binary, OptimizationMode::CoreGuided] {
+            let (mut model, cost, _) = set_cover();
+            model.enforce(leq(cost, 6), []);
+            let result = optimize(model, cost, true, mode);
+            assert!(result.complete);
+            assert!(result.best.is_none());
+            assert_eq!(result.bound, None);
+        }
+    }
+
+    #[test]
+    fn test_soft_constraints() {
+        for mode in [
+            OptimizationMode::Linear,
+            OptimizationMode::Binary,
+            OptimizationMode::LowerBoundFirst,
+            OptimizationMode::CoreGuided,
+        ] {
+            // prefer x >= 5 (weight 4) and x <= 2 (weight 2), but also y >= x (weight 4) and y <= 3 (weight 1)
+            let mut model: Model<String> = Model::new();
+            let x = model.new_ivar(0, 10, "x");
+            let y = model.new_ivar(0, 10, "y");
+            model.add_soft(x.geq(5), 4);
+            let small_x = model.add_soft(x.leq(2), 2);
+            model.add_soft(leq(x, y), 4);
+            model.add_soft(y.leq(3), 1);
+            let cost = model.violation_cost().unwrap();
+            // with the cost of all violated soft constraints, the cost is detected as a sum of literals
+            assert!(BoolSum::of_objective(&model, cost.into(), true).is_some());
+            let result = optimize(model, cost, true, mode);
+            assert!(result.complete);
+            let (value, sol) = result.best.unwrap();
+            // x >= 5, y >= x: only y <= 3 and x <= 2 violated
+            assert_eq!(value, 3, "{mode}");
+            assert!(sol.entails(!small_x));
+        }
+
+        // the total weight of the soft constraints cannot be represented
+        let mut model: Model<String> = Model::new();
+        let x = model.new_ivar(0, 10, "x");
+        model.add_soft(x.geq(5), INT_CST_MAX);
+        model.add_soft(x.leq(2), 1);
+        assert!(model.violation_cost().is_err());
+    }
+
+    #[test]
+    fn test_bool_sum_detection() {
+        let (model, cost, _) = set_cover();
+        let sum = BoolSum::of_objective(&model, cost.into(), true).unwrap();
+        assert_eq!(sum.offset, 0);
+        assert_eq!(sum.terms.iter().map(|(_, w)| w).sum::<IntCst>(), 16);
+        // the cost is bounded in both directions by the sum
+        assert!(BoolSum::of_objective(&model, cost.into(), false).is_some());
+        let mut model = model;
+        let other = model.new_ivar(0, 10, "other");
+        assert!(BoolSum::of_objective(&model, other.into(), true).is_none());
+    }
+
+    #[test]
+    fn test_mode_parsing() {
+        for mode in [
+            OptimizationMode::Linear,
+            OptimizationMode::Binary,
+            OptimizationMode::LowerBoundFirst,
+            OptimizationMode::CoreGuided,
+        ] {
+            assert_eq!(mode.to_string().parse::<OptimizationMode>(), Ok(mode));
+        }
+        assert!("unknown".parse::<OptimizationMode>().is_err());
+    }
+}
@@ -1,6 +1,8 @@
+use crate::core::IntCst;
 use crate::model::extensions::{AssignmentExt, SavedAssignment, Shaped};
 use crate::model::lang::IAtom;
 use crate::model::{Label, ModelShape};
+use crate::solver::multi_objective::{objectives_values, weakly_dominates, ObjectivesValues};
 use crate::solver::parallel::signals::{InputSignal, InputStream, OutputSignal, SolverOutput, ThreadID};
 use crate::solver::{Exit, Solver};
 use crossbeam_channel::{select, Receiver, Sender};
@@ -116,7 +118,7 @@ impl<Lbl: Label> ParSolver<Lbl> {
 
     /// Solve the problem that was given on initialization using all available solvers.
     pub fn solve(&mut self, deadline: Option<Instant>) -> SolverResult<Solution> {
-        self.race_solvers(|s| s.solve(), |_| {}, deadline)
+        self.race_solvers(|s| s.solve(), |sol| sol, deadline)
     }
 
     /// Minimize the value of the given expression.
@@ -128,7 +130,7 @@ impl<Lbl: Label> ParSolver<Lbl> {
                 Ok(None) => Ok(None),
                 Err(x) => Err(x),
             },
-            |_| {},
+            |sol| sol,
             deadline,
         )
     }
@@ -155,9 +157,10 @@ impl<Lbl: Label> ParSolver<Lbl> {
                 None => true,
             };
             if is_improvement {
-                on_improved_solution(ass);
+                on_improved_solution(ass.clone());
                 previous_best = Some(obj_value)
             }
+            ass
         };
         self.race_solvers(
             move |s| match s.minimize(objective) {
@@ -170,15 +173,93 @@ impl<Lbl: Label> ParSolver<Lbl> {
         )
     }
 
+    /// Minimize the given objectives in lexicographic order: the first one has the highest priority.
+    pub fn minimize_lexicographic(
+        &mut self,
+        objectives: &[IAtom],
+        deadline: Option<Instant>,
+    ) -> SolverResult<Solution> {
+        self.minimize_lexicographic_with(objectives, |_| {}, deadline)
+    }
+
+    /// Minimize the given objectives in lexicographic order: the first one has the highest priority.
+    /// Each time a new solution is found that improves lexicographically on the previous ones, the corresponding
+    /// assignment will be passed to the given callback.
+    pub fn minimize_lexicographic_with(
+        &mut self,
+        objectives: &[IAtom],
+        on_improved_solution: impl Fn(Solution),
+        deadline: Option<Instant>,
+    ) -> SolverResult<Solution> {
+        let objectives = objectives.to_vec();
+        // best solution found so far, with the values of its objectives
+        let mut best: Option<ObjectivesValues> = None;
+        let on_new_sol = |ass: Solution| {
+            let values = objectives_values(&objectives, &ass);
+            if best.as_ref().is_none_or(|(prev, _)| &values < prev) {
+                on_improved_solution(ass.clone());
+                best = Some((values, ass));
+            }
+            // the intermediate result is the best solution, that may have been found before `ass`
+            best.as_ref().unwrap().1.clone()
+        };
+        let run_objectives = objectives.clone();
+        self.race_solvers(
+            move |s| match s.minimize_lexicographic(&run_objectives) {
+                Ok(Some((_values, sol))) => Ok(Some(sol)),
+                Ok(None) => Ok(None),
+                Err(x) => Err(x),
+            },
+            on_new_sol,
+            deadline,
+        )
+    }
+
+    /// Enumerates the Pareto front of the objectives to minimize, with one solution for each of its points.
+    ///
+    /// All solvers work on the same front, each point found by a solver being shared with the others.
+    /// Each new point is passed to the `on_point` callback. On timeout, the points found so far are returned.
+    pub fn pareto_front(
+        &mut self,
+        objectives: &[IAtom],
+        on_point: impl Fn(&[IntCst], Solution),
+        deadline: Option<Instant>,
+    ) -> SolverResult<Vec<ObjectivesValues>> {
+        let objectives = objectives.to_vec();
+        // points received from the solvers, that are only shared once they are known to be Pareto-optimal
+        let mut front: Vec<ObjectivesValues> = Vec::new();
+        let on_new_point = |ass: Solution| {
+            let values = objectives_values(&objectives, &ass);
+            if !front.iter().any(|(other, _)| weakly_dominates(other, &values)) {
+                on_point(&values, ass.clone());
+                front.push((values, ass));
+            }
+            front.clone()
+        };
+        let run_objectives = objectives.clone();
+        self.race_solvers(
+            move |s| match s.pareto_front(&run_objectives, |_, _| ()) {
+                Ok(front) if front.is_empty() => Ok(None),
+                Ok(front) => Ok(Some(front)),
+                Err(x) => Err(x),
+            },
+            on_new_point,
+            deadline,
+        )
+    }
+
     /// Generic function to run a lambda in parallel on all available solvers and return the result of the
     /// first finishing one.
     ///
     /// This function also setups inter-solver communication to enable clause/solution sharing.
+    /// Each solution shared by a solver is passed to `on_new_sol`, whose output is the intermediate result
+    /// returned on timeout.
     /// Once a first result is found, it sends an interruption message to all other workers and wait for them to yield.
-    fn race_solvers<F, G>(&mut self, run: F, mut on_new_sol: G, deadline: Option<Instant>) -> SolverResult<Solution>
+    fn race_solvers<O, F, G>(&mut self, run: F, mut on_new_sol: G, deadline: Option<Instant>) -> SolverResult<O>
     where
-        F: Fn(&mut Solver<Lbl>) -> Result<Option<Solution>, Exit> + Send + 'static + Copy,
-        G: FnMut(Solution),
+        O: Send + 'static,
+        F: Fn(&mut Solver<Lbl>) -> Result<Option<O>, Exit> + Send + 'static + Clone,
+        G: FnMut(Solution) -> O,
     {
         // a receiver that will collect all intermediates results (incumbent solution and learned clauses)
         // from the solvers
@@ -188,15 +269,15 @@ impl<Lbl: Label> ParSolver<Lbl> {
         let (result_snd, result_rcv) = crossbeam_channel::unbounded();
 
         // lambda used to start a thread and run a solver on it.
-        let spawn =
-            |id: usize, mut solver: Box<Solver<Lbl>>, result_snd: Sender<WorkerResult<Option<Solution>, Lbl>>| {
-                thread::spawn(move || {
-                    let output = run(&mut solver);
-                    let answer = WorkerResult { id, output, solver };
-                    // ignore message delivery failures (on another solver might have found the solution earlier)
-                    let _ = result_snd.send(answer);
-                });
-            };
+        let spawn = |id: usize, mut solver: Box<Solver<Lbl>>, result_snd: Sender<WorkerResult<Option<O>, Lbl>>| {
+            let run = run.clone();
+            thread::spawn(move || {
+                let output = run(&mut solver);
+                let answer = WorkerResult { id, output, solver };
+                // ignore message delivery failures (on another solver might have found the solution earlier)
+                let _ = result_snd.send(answer);
+            });
+        };
 
         let mut solvers_inputs = Vec::with_capacity(self.solvers.len());
 
@@ -245,8 +326,7 @@ impl<Lbl: Label> ParSolver<Lbl> {
                         self.share_among_solvers(&msg);
                         if !matches!(status, SolverStatus::Final(_)) {
                             if let OutputSignal::SolutionFound(assignment) = msg.msg {
-                                on_new_sol(assignment.clone());
-                                status = SolverStatus::Intermediate(assignment);
+                                status = SolverStatus::Intermediate(on_new_sol(assignment));
                             }
                         }
                     }
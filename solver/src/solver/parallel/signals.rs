@@ -1,4 +1,5 @@
 use crate::core::literals::Disjunction;
+use crate::core::VarRef;
 use crate::model::extensions::SavedAssignment;
 use crossbeam_channel::{Receiver, Sender};
 use env_param::EnvParam;
@@ -65,6 +66,9 @@ pub struct Synchro {
     pub signals: Receiver<InputSignal>,
     /// A channel where a solver's output can be sent (typically for learnt clauses or intermediate solutions).
     pub output: Option<Sender<SolverOutput>>,
+    /// If set, variables from this one onward were introduced by this solver alone (e.g. for core-guided optimization).
+    /// They are unknown to other solvers and clauses on them are not shared.
+    pub first_local_var: Option<VarRef>,
 }
 
 impl Synchro {
@@ -75,6 +79,7 @@ impl Synchro {
             sender: snd,
             signals: rcv,
             output: None,
+            first_local_var: None,
         }
     }
 
@@ -96,7 +101,8 @@ impl Synchro {
     pub fn notify_learnt(&self, clause: &Disjunction) {
         if let Some(output) = &self.output {
             let len = clause.len();
-            if len > 0 && len <= MAX_CLAUSE_SHARING_SIZE.get() {
+            let is_shared = |first_local: VarRef| clause.literals().iter().all(|l| l.variable() < first_local);
+            if len > 0 && len <= MAX_CLAUSE_SHARING_SIZE.get() && self.first_local_var.is_none_or(is_shared) {
                 let msg = OutputSignal::LearntClause(Arc::new(Disjunction::from(clause)));
                 // ignore errors as the thread might just be running alone in the ether
                 let _ = output.send(SolverOutput { emitter: self.id, msg });
@@ -120,6 +126,7 @@ impl Clone for Synchro {
         if let Some(out) = &self.output {
             res.output = Some(out.clone())
         }
+        res.first_local_var = self.first_local_var;
         res
     }
 }
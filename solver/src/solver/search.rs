@@ -3,6 +3,7 @@ pub mod combinators;
 pub mod conflicts;
 pub mod lexical;
 pub mod random;
+pub mod restarts;
 
 use crate::backtrack::Backtrack;
 use crate::core::state::{Conflict, Explainer};
@@ -51,5 +52,9 @@ pub trait SearchControl<Lbl>: Backtrack {
     /// Invoked by the search when an asserting clause is added to the database. `lit` is the literal that would be asserted.
     fn asserted_after_conflict(&mut self, lit: Lit, model: &Model<Lbl>) {}
 
+    /// Disables the restarts that the search control would request by itself.
+    /// Invoked when the search control is extended with a restart policy, which then decides of all restarts.
+    fn disable_restarts(&mut self) {}
+
     fn clone_to_box(&self) -> Brancher<Lbl>;
 }
@@ -490,6 +490,11 @@ impl<Lbl: Label> SearchControl<Lbl> for ActivityBrancher<Lbl> {
         }
     }
 
+    fn disable_restarts(&mut self) {
+        // the number of allowed conflicts is never exceeded
+        self.params.allowed_conflicts = u64::MAX;
+    }
+
     fn clone_to_box(&self) -> Box<dyn SearchControl<Lbl> + Send> {
         Box::new(self.clone())
     }
@@ -3,6 +3,7 @@ use crate::core::state::{Conflict, Explainer};
 use crate::core::{IntCst, Lit};
 use crate::model::extensions::SavedAssignment;
 use crate::model::Model;
+use crate::solver::search::restarts::{GeometricRestarts, NoRestarts, RestartPolicy};
 use crate::solver::search::{Brancher, Decision, SearchControl};
 use crate::solver::stats::Stats;
 use std::sync::Arc;
@@ -15,6 +16,10 @@ pub trait CombinatorExt<L> {
 
     /// Creates a brancher that extends `self` to have geometric restarts.
     fn with_restarts(self, allowed_conflicts: u64, increase_ratio: f32) -> Brancher<L>;
+
+    /// Creates a brancher that extends `self` with restarts following the given policy,
+    /// which replaces the restarts of `self`.
+    fn with_restart_policy(self, policy: Box<dyn RestartPolicy>) -> Brancher<L>;
 }
 
 impl<L: 'static> CombinatorExt<L> for Brancher<L> {
@@ -23,7 +28,11 @@ impl<L: 'static> CombinatorExt<L> for Brancher<L> {
     }
 
     fn with_restarts(self, allowed_conflicts: u64, increase_ratio: f32) -> Brancher<L> {
-        Box::new(WithGeomRestart::new(allowed_conflicts, increase_ratio, self))
+        self.with_restart_policy(Box::new(GeometricRestarts::new(allowed_conflicts, increase_ratio)))
+    }
+
+    fn with_restart_policy(self, policy: Box<dyn RestartPolicy>) -> Brancher<L> {
+        Box::new(WithRestarts::new(policy, self))
     }
 }
 
@@ -93,6 +102,11 @@ impl<L: 'static> SearchControl<L> for AndThen<L> {
         self.second.pre_conflict_analysis(model)
     }
 
+    fn disable_restarts(&mut self) {
+        self.first.disable_restarts();
+        self.second.disable_restarts();
+    }
+
     fn clone_to_box(&self) -> Box<dyn SearchControl<L> + Send> {
         Box::new(AndThen {
             first: self.first.clone_to_box(),
@@ -170,6 +184,10 @@ impl<L: 'static> SearchControl<L> for UntilFirstConflict<L> {
         }
     }
 
+    fn disable_restarts(&mut self) {
+        self.brancher.disable_restarts()
+    }
+
     fn clone_to_box(&self) -> Box<dyn SearchControl<L> + Send> {
         Box::new(UntilFirstConflict {
             active: self.active,
@@ -178,26 +196,32 @@ impl<L: 'static> SearchControl<L> for UntilFirstConflict<L> {
     }
 }
 
-/// A brancher that extends a `brancher` with geometric restarts.
-pub struct WithGeomRestart<L> {
-    allowed_conflicts: u64,
-    increase_ratio_for_allowed_conflict: f32,
-    conflicts_at_last_restart: u64,
+/// A brancher that extends a `brancher` with restarts, following a given restart policy.
+///
+/// The policy replaces the restarts of the wrapped `brancher`, which are disabled
+/// (see [`SearchControl::disable_restarts`]).
+pub struct WithRestarts<L> {
+    policy: Box<dyn RestartPolicy>,
     brancher: Brancher<L>,
 }
 
-impl<L> WithGeomRestart<L> {
-    pub fn new(allowed_conflicts: u64, increase_ratio: f32, brancher: Brancher<L>) -> Self {
-        WithGeomRestart {
-            allowed_conflicts,
-            increase_ratio_for_allowed_conflict: increase_ratio,
-            conflicts_at_last_restart: 0,
+impl<L> WithRestarts<L> {
+    pub fn new(policy: Box<dyn RestartPolicy>, mut brancher: Brancher<L>) -> Self {
+        brancher.disable_restarts();
+        WithRestarts { policy, brancher }
+    }
+
+    /// Extends the `brancher` with geometric restarts: a restart occurs after `allowed_conflicts` conflicts,
+    /// this number being multiplied by `increase_ratio` after each restart.
+    pub fn geometric(allowed_conflicts: u64, increase_ratio: f32, brancher: Brancher<L>) -> Self {
+        Self::new(
+            Box::new(GeometricRestarts::new(allowed_conflicts, increase_ratio)),
             brancher,
-        }
+        )
     }
 }
 
-impl<L> Backtrack for WithGeomRestart<L> {
+impl<L> Backtrack for WithRestarts<L> {
     fn save_state(&mut self) -> DecLvl {
         self.brancher.save_state()
     }
@@ -211,13 +235,9 @@ impl<L> Backtrack for WithGeomRestart<L> {
     }
 }
 
-impl<L: 'static> SearchControl<L> for WithGeomRestart<L> {
+impl<L: 'static> SearchControl<L> for WithRestarts<L> {
     fn next_decision(&mut self, stats: &Stats, model: &Model<L>) -> Option<Decision> {
-        if stats.num_conflicts() - self.conflicts_at_last_restart >= self.allowed_conflicts {
-            // we have exceeded the number of allowed conflict, time for a restart
-            self.conflicts_at_last_restart = stats.num_conflicts();
-            // increase the number of allowed conflicts
-            self.allowed_conflicts = (self.allowed_conflicts as f32 * self.increase_ratio_for_allowed_conflict) as u64;
+        if self.policy.should_restart() {
             Some(Decision::Restart)
         } else {
             self.brancher.next_decision(stats, model)
@@ -233,6 +253,17 @@ impl<L: 'static> SearchControl<L> for WithGeomRestart<L> {
     }
 
     fn conflict(&mut self, clause: &Conflict, model: &Model<L>, explainer: &mut dyn Explainer) {
+        // the LBD of the learnt clause is the number of distinct decision levels at which its literals were falsified
+        let mut levels: Vec<DecLvl> = clause
+            .literals()
+            .iter()
+            .filter(|&&l| model.state.entails(!l))
+            .map(|&l| model.state.entailing_level(!l))
+            .filter(|&lvl| lvl != DecLvl::ROOT)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+        self.policy.conflict(levels.len() as u32, model.state.trail().len());
         self.brancher.conflict(clause, model, explainer)
     }
 
@@ -248,12 +279,80 @@ impl<L: 'static> SearchControl<L> for WithGeomRestart<L> {
         self.brancher.pre_conflict_analysis(model);
     }
 
+    fn disable_restarts(&mut self) {
+        self.policy = Box::new(NoRestarts);
+        self.brancher.disable_restarts();
+    }
+
     fn clone_to_box(&self) -> Box<dyn SearchControl<L> + Send> {
-        Box::new(WithGeomRestart {
-            allowed_conflicts: self.allowed_conflicts,
-            increase_ratio_for_allowed_conflict: self.increase_ratio_for_allowed_conflict,
-            conflicts_at_last_restart: self.conflicts_at_last_restart,
+        Box::new(WithRestarts {
+            policy: self.policy.clone_box(),
             brancher: self.brancher.clone_to_box(),
         })
     }
 }
+
+/// A brancher that extends a `brancher` with geometric restarts.
+#[deprecated(note = "Use `WithRestarts::geometric` or `WithRestarts::new` with a restart policy")]
+pub struct WithGeomRestart<L>(WithRestarts<L>);
+
+#[allow(deprecated)]
+impl<L> WithGeomRestart<L> {
+    pub fn new(allowed_conflicts: u64, increase_ratio: f32, brancher: Brancher<L>) -> Self {
+        WithGeomRestart(WithRestarts::geometric(allowed_conflicts, increase_ratio, brancher))
+    }
+}
+
+#[allow(deprecated)]
+impl<L> Backtrack for WithGeomRestart<L> {
+    fn save_state(&mut self) -> DecLvl {
+        self.0.save_state()
+    }
+
+    fn num_saved(&self) -> u32 {
+        self.0.num_saved()
+    }
+
+    fn restore_last(&mut self) {
+        self.0.restore_last()
+    }
+}
+
+#[allow(deprecated)]
+impl<L: 'static> SearchControl<L> for WithGeomRestart<L> {
+    fn next_decision(&mut self, stats: &Stats, model: &Model<L>) -> Option<Decision> {
+        self.0.next_decision(stats, model)
+    }
+
+    fn import_vars(&mut self, model: &Model<L>) {
+        self.0.import_vars(model)
+    }
+
+    fn new_assignment_found(&mut self, objective_value: IntCst, assignment: Arc<SavedAssignment>) {
+        self.0.new_assignment_found(objective_value, assignment)
+    }
+
+    fn conflict(&mut self, clause: &Conflict, model: &Model<L>, explainer: &mut dyn Explainer) {
+        self.0.conflict(clause, model, explainer)
+    }
+
+    fn asserted_after_conflict(&mut self, lit: Lit, model: &Model<L>) {
+        self.0.asserted_after_conflict(lit, model)
+    }
+
+    fn pre_save_state(&mut self, model: &Model<L>) {
+        self.0.pre_save_state(model);
+    }
+
+    fn pre_conflict_analysis(&mut self, model: &Model<L>) {
+        self.0.pre_conflict_analysis(model);
+    }
+
+    fn disable_restarts(&mut self) {
+        self.0.disable_restarts()
+    }
+
+    fn clone_to_box(&self) -> Box<dyn SearchControl<L> + Send> {
+        self.0.clone_to_box()
+    }
+}
@@ -0,0 +1,465 @@
+//! Restart policies, that decide when the search should be restarted from the root.
+//!
+//! A policy is attached to a brancher with [`CombinatorExt::with_restart_policy`](super::combinators::CombinatorExt)
+//! (or [`Solver::set_restart_policy`](crate::solver::Solver::set_restart_policy)), and can be selected from
+//! a textual description with the [`Restarts`] configuration.
+
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A policy that decides when the search should be restarted.
+pub trait RestartPolicy: Send {
+    /// Notifies the policy of a new conflict, whose learnt clause has the given LBD (number of distinct decision levels)
+    /// and that occurred with `trail_size` literals on the trail.
+    fn conflict(&mut self, lbd: u32, trail_size: usize);
+
+    /// Returns true if the search should be restarted immediately.
+    /// It is assumed that a restart is made each time this method returns true.
+    fn should_restart(&mut self) -> bool;
+
+    fn clone_box(&self) -> Box<dyn RestartPolicy>;
+}
+
+/// Geometric restarts: a restart occurs after a given number of conflicts, which is multiplied
+/// by `increase_ratio` after each restart.
+#[derive(Clone)]
+pub struct GeometricRestarts {
+    allowed_conflicts: u64,
+    increase_ratio: f32,
+    conflicts_since_restart: u64,
+}
+
+impl GeometricRestarts {
+    pub fn new(allowed_conflicts: u64, increase_ratio: f32) -> Self {
+        GeometricRestarts {
+            allowed_conflicts,
+            increase_ratio,
+            conflicts_since_restart: 0,
+        }
+    }
+}
+
+impl RestartPolicy for GeometricRestarts {
+    fn conflict(&mut self, _lbd: u32, _trail_size: usize) {
+        self.conflicts_since_restart += 1;
+    }
+
+    fn should_restart(&mut self) -> bool {
+        if self.conflicts_since_restart >= self.allowed_conflicts {
+            self.conflicts_since_restart = 0;
+            self.allowed_conflicts = (self.allowed_conflicts as f32 * self.increase_ratio) as u64;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn RestartPolicy> {
+        Box::new(self.clone())
+    }
+}
+
+/// Restarts following the Luby sequence `(1, 1, 2, 1, 1, 2, 4, 1, 1, 2, ...)`: the i-th restart occurs
+/// after `unit * luby(i)` conflicts.
+#[derive(Clone)]
+pub struct LubyRestarts {
+    unit: u64,
+    num_restarts: u64,
+    conflicts_since_restart: u64,
+}
+
+impl LubyRestarts {
+    pub fn new(unit: u64) -> Self {
+        LubyRestarts {
+            unit,
+            num_restarts: 0,
+            conflicts_since_restart: 0,
+        }
+    }
+}
+
+/// Returns the i-th element (starting from 0) of the Luby sequence `(1, 1, 2, 1, 1, 2, 4, 1, 1, 2, ...)`.
+pub fn luby(i: u64) -> u64 {
+    // find the finite subsequence that contains index `i`, and the size of that subsequence
+    let mut size = 1;
+    let mut seq = 0;
+    while size < i + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    let mut i = i;
+    while size - 1 != i {
+        size = (size - 1) >> 1;
+        seq -= 1;
+        i %= size;
+    }
+    1 << seq
+}
+
+impl RestartPolicy for LubyRestarts {
+    fn conflict(&mut self, _lbd: u32, _trail_size: usize) {
+        self.conflicts_since_restart += 1;
+    }
+
+    fn should_restart(&mut self) -> bool {
+        if self.conflicts_since_restart >= self.unit * luby(self.num_restarts) {
+            self.conflicts_since_restart = 0;
+            self.num_restarts += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn RestartPolicy> {
+        Box::new(self.clone())
+    }
+}
+
+/// A queue that keeps the last `capacity` values pushed to it and maintains their sum.
+#[derive(Clone)]
+struct BoundedQueue {
+    values: VecDeque<u64>,
+    capacity: usize,
+    sum: u64,
+}
+
+impl BoundedQueue {
+    fn new(capacity: usize) -> Self {
+        BoundedQueue {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+            sum: 0,
+        }
+    }
+
+    fn push(&mut self, value: u64) {
+        if self.values.len() == self.capacity {
+            self.sum -= self.values.pop_front().unwrap();
+        }
+        self.values.push_back(value);
+        self.sum += value;
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.len() == self.capacity
+    }
+
+    fn average(&self) -> f64 {
+        self.sum as f64 / self.values.len().max(1) as f64
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.sum = 0;
+    }
+}
+
+/// Parameters of the [`GlucoseRestarts`] policy. The default values are the ones of the Glucose SAT solver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlucoseParams {
+    /// Number of recent conflicts over which the moving average of the LBD is computed.
+    pub lbd_window: usize,
+    /// A restart is triggered when `recent_lbd_average * k > global_lbd_average`.
+    pub k: f64,
+    /// If true, restarts are postponed when the trail is much larger than usual, which indicates
+    /// that the solver might be close to a solution.
+    pub blocking: bool,
+    /// Number of recent conflicts over which the moving average of the trail size is computed.
+    pub trail_window: usize,
+    /// Restarts are postponed if the trail size at a conflict exceeds `r * recent_trail_average`.
+    pub r: f64,
+    /// Restarts are never postponed before this number of conflicts.
+    pub min_conflicts_for_blocking: u64,
+}
+
+impl Default for GlucoseParams {
+    fn default() -> Self {
+        GlucoseParams {
+            lbd_window: 50,
+            k: 0.8,
+            blocking: false,
+            trail_window: 5000,
+            r: 1.4,
+            min_conflicts_for_blocking: 10000,
+        }
+    }
+}
+
+/// Dynamic restarts of the Glucose SAT solver: a restart is triggered when the LBD of the recently learnt clauses
+/// is high compared to the average LBD since the beginning of the search (i.e. the solver is learning poor clauses).
+///
+/// When blocking is enabled, restarts are postponed when the trail is significantly larger than usual
+/// (the solver might be approaching a solution).
+#[derive(Clone)]
+pub struct GlucoseRestarts {
+    params: GlucoseParams,
+    recent_lbds: BoundedQueue,
+    recent_trail_sizes: BoundedQueue,
+    num_conflicts: u64,
+    sum_lbds: u64,
+    /// Number of times a restart was postponed.
+    pub num_blocked: u64,
+}
+
+impl GlucoseRestarts {
+    pub fn new(params: GlucoseParams) -> Self {
+        GlucoseRestarts {
+            recent_lbds: BoundedQueue::new(params.lbd_window),
+            recent_trail_sizes: BoundedQueue::new(params.trail_window),
+            params,
+            num_conflicts: 0,
+            sum_lbds: 0,
+            num_blocked: 0,
+        }
+    }
+}
+
+impl RestartPolicy for GlucoseRestarts {
+    fn conflict(&mut self, lbd: u32, trail_size: usize) {
+        self.num_conflicts += 1;
+        self.sum_lbds += lbd as u64;
+        if self.params.blocking {
+            let trail_size = trail_size as u64;
+            let large_trail = self.num_conflicts > self.params.min_conflicts_for_blocking
+                && self.recent_trail_sizes.is_full()
+                && trail_size as f64 > self.params.r * self.recent_trail_sizes.average();
+            if large_trail && self.recent_lbds.is_full() {
+                // postpone the next restart
+                self.recent_lbds.clear();
+                self.num_blocked += 1;
+            }
+            self.recent_trail_sizes.push(trail_size);
+        }
+        self.recent_lbds.push(lbd as u64);
+    }
+
+    fn should_restart(&mut self) -> bool {
+        let global_average = self.sum_lbds as f64 / self.num_conflicts.max(1) as f64;
+        if self.recent_lbds.is_full() && self.recent_lbds.average() * self.params.k > global_average {
+            self.recent_lbds.clear();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn RestartPolicy> {
+        Box::new(self.clone())
+    }
+}
+
+/// A restart policy that never restarts.
+#[derive(Clone)]
+pub struct NoRestarts;
+
+impl RestartPolicy for NoRestarts {
+    fn conflict(&mut self, _lbd: u32, _trail_size: usize) {}
+
+    fn should_restart(&mut self) -> bool {
+        false
+    }
+
+    fn clone_box(&self) -> Box<dyn RestartPolicy> {
+        Box::new(NoRestarts)
+    }
+}
+
+/// Configuration of a restart policy, that can be parsed from the following textual representations:
+///
+///  - `none`: no restarts
+///  - `geom[:<allowed-conflicts>[:<increase-ratio>]]`: geometric restarts (default: `geom:100:1.5`)
+///  - `luby[:<unit>]`: Luby restarts (default: `luby:100`)
+///  - `glucose`: glucose-style dynamic restarts
+///  - `glucose-blocking`: glucose-style dynamic restarts, postponed when the trail is unusually large
+#[derive(Clone, Debug, PartialEq)]
+pub enum Restarts {
+    None,
+    Geometric {
+        allowed_conflicts: u64,
+        increase_ratio: f32,
+    },
+    Luby {
+        unit: u64,
+    },
+    Glucose(GlucoseParams),
+}
+
+impl Restarts {
+    /// Builds a new instance of the restart policy.
+    pub fn policy(&self) -> Box<dyn RestartPolicy> {
+        match self {
+            Restarts::None => Box::new(NoRestarts),
+            Restarts::Geometric {
+                allowed_conflicts,
+                increase_ratio,
+            } => Box::new(GeometricRestarts::new(*allowed_conflicts, *increase_ratio)),
+            Restarts::Luby { unit } => Box::new(LubyRestarts::new(*unit)),
+            Restarts::Glucose(params) => Box::new(GlucoseRestarts::new(params.clone())),
+        }
+    }
+}
+
+impl FromStr for Restarts {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let kind = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+        fn arg<T: FromStr>(args: &[&str], i: usize, default: T) -> Result<T, String> {
+            match args.get(i) {
+                Some(a) => a.parse().map_err(|_| format!("Invalid restart parameter: {a}")),
+                None => Ok(default),
+            }
+        }
+        let max_args = match kind {
+            "geom" => 2,
+            "luby" => 1,
+            _ => 0,
+        };
+        if args.len() > max_args {
+            return Err(format!("Too many parameters for restart policy: {s}"));
+        }
+        match kind {
+            "none" => Ok(Restarts::None),
+            "geom" => Ok(Restarts::Geometric {
+                allowed_conflicts: arg(&args, 0, 100)?,
+                increase_ratio: arg(&args, 1, 1.5)?,
+            }),
+            "luby" => Ok(Restarts::Luby {
+                unit: arg(&args, 0, 100)?,
+            }),
+            "glucose" => Ok(Restarts::Glucose(GlucoseParams::default())),
+            "glucose-blocking" => Ok(Restarts::Glucose(GlucoseParams {
+                blocking: true,
+                ..Default::default()
+            })),
+            _ => Err(format!("Unknown restart policy: {s}")),
+        }
+    }
+}
+
+impl Display for Restarts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Restarts::None => write!(f, "none"),
+            Restarts::Geometric {
+                allowed_conflicts,
+                increase_ratio,
+            } => write!(f, "geom:{allowed_conflicts}:{increase_ratio}"),
+            Restarts::Luby { unit } => write!(f, "luby:{unit}"),
+            Restarts::Glucose(params) if params.blocking => write!(f, "glucose-blocking"),
+            Restarts::Glucose(_) => write!(f, "glucose"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the number of conflicts between each of the first `n` restarts of the policy.
+    fn restart_intervals(policy: &mut dyn RestartPolicy, n: usize) -> Vec<u64> {
+        let mut intervals = Vec::new();
+        let mut conflicts = 0;
+        while intervals.len() < n {
+            if policy.should_restart() {
+                intervals.push(conflicts);
+                conflicts = 0;
+            }
+            policy.conflict(1, 10);
+            conflicts += 1;
+        }
+        intervals
+    }
+
+    #[test]
+    fn test_luby() {
+        let seq: Vec<u64> = (0..15).map(luby).collect();
+        assert_eq!(seq, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+        assert_eq!(
+            restart_intervals(&mut LubyRestarts::new(10), 7),
+            vec![10, 10, 20, 10, 10, 20, 40]
+        );
+    }
+
+    #[test]
+    fn test_geometric() {
+        assert_eq!(
+            restart_intervals(&mut GeometricRestarts::new(10, 2.0), 4),
+            vec![10, 20, 40, 80]
+        );
+    }
+
+    #[test]
+    fn test_glucose() {
+        let mut policy = GlucoseRestarts::new(GlucoseParams {
+            lbd_window: 3,
+            ..Default::default()
+        });
+        for _ in 0..10 {
+            policy.conflict(10, 100);
+            assert!(!policy.should_restart());
+        }
+        // recent LBDs are much higher than the average
+        policy.conflict(30, 100);
+        assert!(policy.should_restart());
+        // the queue of recent LBDs is reset after a restart
+        policy.conflict(30, 100);
+        assert!(!policy.should_restart());
+    }
+
+    #[test]
+    fn test_glucose_blocking() {
+        let mut policy = GlucoseRestarts::new(GlucoseParams {
+            lbd_window: 3,
+            blocking: true,
+            trail_window: 5,
+            min_conflicts_for_blocking: 0,
+            ..Default::default()
+        });
+        for _ in 0..10 {
+            policy.conflict(10, 100);
+        }
+        // a restart would be triggered without the large trail, that indicates that we might be close to a solution
+        policy.conflict(30, 1000);
+        assert!(!policy.should_restart());
+        assert_eq!(policy.num_blocked, 1);
+        // the trail size is back to normal, the restart occurs once the recent LBDs are known again
+        policy.conflict(30, 100);
+        assert!(!policy.should_restart());
+        policy.conflict(30, 100);
+        assert!(policy.should_restart());
+        assert_eq!(policy.num_blocked, 1);
+    }
+
+    #[test]
+    fn test_parse() {
+        let parse = |s: &str| s.parse::<Restarts>();
+        assert_eq!(
+            parse("geom"),
+            Ok(Restarts::Geometric {
+                allowed_conflicts: 100,
+                increase_ratio: 1.5
+            })
+        );
+        assert_eq!(
+            parse("geom:50:1.3"),
+            Ok(Restarts::Geometric {
+                allowed_conflicts: 50,
+                increase_ratio: 1.3
+            })
+        );
+        assert_eq!(parse("luby:32"), Ok(Restarts::Luby { unit: 32 }));
+        assert_eq!(parse("none"), Ok(Restarts::None));
+        assert!(matches!(parse("glucose-blocking"), Ok(Restarts::Glucose(p)) if p.blocking));
+        assert!(parse("luby:1:2").is_err());
+        assert!(parse("luby:x").is_err());
+        assert!(parse("foo").is_err());
+        for s in ["none", "geom:50:1.3", "luby:32", "glucose", "glucose-blocking"] {
+            assert_eq!(parse(s).unwrap().to_string(), s);
+        }
+    }
+}
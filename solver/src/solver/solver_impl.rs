@@ -3,11 +3,18 @@ use crate::core::literals::Disjunction;
 use crate::core::state::*;
 use crate::core::*;
 use crate::model::extensions::{AssignmentExt, DisjunctionExt, SavedAssignment, Shaped};
-use crate::model::lang::IAtom;
+use crate::model::lang::{IAtom, IVar};
 use crate::model::{Constraint, Label, Model, ModelShape};
-use crate::reasoners::{Contradiction, Reasoners};
+use crate::reasoners::cp::PropagatorId;
+use crate::reasoners::sat::proof::{ProofFormat, ProofLog};
+use crate::reasoners::{Contradiction, ReasonerId, Reasoners, Theory};
 use crate::reif::{DifferenceExpression, ReifExpr, Reifiable};
+use crate::solver::explanation_checker::{check_explanation, CheckedExplainer};
+use crate::solver::lns::Lns;
+use crate::solver::optimization::{Optimization, OptimizationOptions, OptimizationResult};
 use crate::solver::parallel::signals::{InputSignal, InputStream, SolverOutput, Synchro};
+use crate::solver::search::combinators::CombinatorExt;
+use crate::solver::search::restarts::RestartPolicy;
 use crate::solver::search::{default_brancher, Decision, SearchControl};
 use crate::solver::stats::Stats;
 use crate::utils::cpu_time::StartCycleCount;
@@ -24,6 +31,12 @@ static LOG_DECISIONS: EnvParam<bool> = EnvParam::new("ARIES_LOG_DECISIONS", "fal
 /// If true: each time a solution is found, the solver's stats will be printed (in optimization)
 static STATS_AT_SOLUTION: EnvParam<bool> = EnvParam::new("ARIES_STATS_AT_SOLUTION", "false");
 
+/// If true, the clauses learnt from conflicts are minimized by removing the literals that are implied by the other ones.
+static CLAUSE_MINIMIZATION: EnvParam<bool> = EnvParam::new("ARIES_CLAUSE_MINIMIZATION", "true");
+
+/// If true, all explanations produced by the reasoners are verified (very expensive, for debugging purposes only).
+static CHECK_EXPLANATIONS: EnvParam<bool> = EnvParam::new("ARIES_CHECK_EXPLANATIONS", "false");
+
 /// Macro that uses the the same syntax as `println!()` but:
 ///  - only evaluate arguments and print if `LOG_DECISIONS` is true.
 ///  - prepends the thread id to the line.
@@ -38,15 +51,20 @@ macro_rules! log_dec {
 }
 
 /// Result of the `_solve` method.
-enum SolveResult {
+pub(crate) enum SolveResult {
     /// A solution was found through search and the solver's assignment is on this solution
     AtSolution,
     /// The solver was made aware of a solution from its input channel.
     ExternalSolution(Arc<SavedAssignment>),
     /// The solver has exhausted its search space.
-    Unsat,
+    /// The core is the subset of the assumptions that was shown to be unsatisfiable (empty if the problem
+    /// is unsatisfiable regardless of the assumptions).
+    Unsat(UnsatCore),
 }
 
+/// A set of assumptions that cannot be jointly satisfied.
+pub type UnsatCore = Vec<Lit>;
+
 #[derive(Debug)]
 pub enum Exit {
     Interrupted,
@@ -65,10 +83,28 @@ pub struct Solver<Lbl> {
     pub brancher: Box<dyn SearchControl<Lbl> + Send>,
     pub reasoners: Reasoners,
     decision_level: DecLvl,
+    /// Activation literals of the assertion levels opened by `push`, from the outermost to the innermost.
+    /// They are assumed on the first decision levels of any search, before the `assumptions`.
+    scopes: Vec<Lit>,
+    /// For each assertion level, the identifier of its first CP propagator, the propagators of the level being
+    /// removed when it is popped.
+    scope_propagators: Vec<PropagatorId>,
+    /// Literals that must be decided (in order) on the first decision levels, before any other decision is taken.
+    /// Only non-empty during a call to `solve_with_assumptions`.
+    assumptions: Vec<Lit>,
+    /// If set, the search is stopped once the number of conflicts reaches this value.
+    /// Only set during a call to `solve_under_limit`.
+    conflict_limit: Option<u64>,
+    /// If set, optimization is carried out by Large Neighbourhood Search.
+    lns: Option<Lns>,
+    /// Options of the optimization algorithm, when not using LNS.
+    optimization: OptimizationOptions,
     pub stats: Stats,
+    /// If set, every explanation produced by a reasoner is verified by a sub-solver.
+    check_explanations: bool,
     /// A data structure with the various communication channels
     /// needed to receive/send updates and commands.
-    sync: Synchro,
+    pub(crate) sync: Synchro,
 }
 impl<Lbl: Label> Solver<Lbl> {
     pub fn new(model: Model<Lbl>) -> Solver<Lbl> {
@@ -78,7 +114,14 @@ impl<Lbl: Label> Solver<Lbl> {
             brancher: default_brancher(),
             reasoners: Reasoners::new(),
             decision_level: DecLvl::ROOT,
+            scopes: Vec::new(),
+            scope_propagators: Vec::new(),
+            assumptions: Vec::new(),
+            conflict_limit: None,
+            lns: None,
+            optimization: Default::default(),
             stats: Default::default(),
+            check_explanations: CHECK_EXPLANATIONS.get(),
             sync: Synchro::new(),
         }
     }
@@ -91,6 +134,23 @@ impl<Lbl: Label> Solver<Lbl> {
         self.brancher = brancher
     }
 
+    /// Extends the current brancher with restarts following the given policy,
+    /// which replaces the restarts of the brancher.
+    pub fn set_restart_policy(&mut self, policy: Box<dyn RestartPolicy>) {
+        self.brancher = self.brancher.clone_to_box().with_restart_policy(policy);
+    }
+
+    /// Makes the solver optimize through Large Neighbourhood Search (LNS) instead of a complete search,
+    /// starting from the next call to `minimize` or `maximize`.
+    pub fn set_lns(&mut self, lns: Lns) {
+        self.lns = Some(lns);
+    }
+
+    /// Sets the options of the algorithm used by `minimize` and `maximize` (when LNS is not enabled).
+    pub fn set_optimization_options(&mut self, options: OptimizationOptions) {
+        self.optimization = options;
+    }
+
     pub fn input_stream(&self) -> InputStream {
         self.sync.input_stream()
     }
@@ -99,8 +159,33 @@ impl<Lbl: Label> Solver<Lbl> {
         self.sync.set_output(output);
     }
 
+    /// Records a proof of all subsequent unsatisfiability results in the given proof log.
+    ///
+    /// The proof covers the clauses learnt from now on: it should be set before the first call to `solve`
+    /// and is only valid for a solver that is not part of a parallel solver.
+    ///
+    /// Fails if the proof is in the DRAT format while the model has non-boolean variables,
+    /// whose literals cannot be represented in the proof.
+    pub fn set_proof_log(&mut self, proof: ProofLog) -> anyhow::Result<()> {
+        if proof.format() == ProofFormat::Drat {
+            let state = &self.model.state;
+            if let Some(var) = state.variables().find(|&v| state.lb(v) < 0 || state.ub(v) > 1) {
+                anyhow::bail!(
+                    "DRAT proofs only support boolean variables, but {var:?} has the domain [{}, {}]",
+                    state.lb(var),
+                    state.ub(var)
+                );
+            }
+        }
+        self.reasoners.sat.set_proof_log(Some(proof));
+        Ok(())
+    }
+
+    /// Enforces the expression whenever all literals of the scope are true.
+    /// The constraint belongs to the current assertion level and is removed when this level is popped.
     pub fn enforce<Expr: Reifiable<Lbl>>(&mut self, bool_expr: Expr, scope: impl IntoIterator<Item = Lit>) {
         assert_eq!(self.decision_level, DecLvl::ROOT);
+        let scope: Vec<Lit> = scope.into_iter().chain(self.scopes.last().copied()).collect();
         self.model.enforce(bool_expr, scope);
     }
     pub fn enforce_all<Expr: Reifiable<Lbl>>(
@@ -108,8 +193,91 @@ impl<Lbl: Label> Solver<Lbl> {
         bools: impl IntoIterator<Item = Expr>,
         scope: impl IntoIterator<Item = Lit> + Clone,
     ) {
-        assert_eq!(self.decision_level, DecLvl::ROOT);
-        self.model.enforce_all(bools, scope);
+        for b in bools {
+            self.enforce(b, scope.clone());
+        }
+    }
+
+    /// Enables or disables a debug mode in which every explanation produced by a reasoner is verified.
+    /// The verification shows, with a sub-solver that only contains the reasoner, that the explanation
+    /// and the negation of the explained literal are unsatisfiable.
+    /// The solver panics on the first explanation that fails the verification.
+    ///
+    /// This is very expensive and only intended for debugging reasoners.
+    /// It can also be enabled with the `ARIES_CHECK_EXPLANATIONS` environment variable.
+    pub fn set_explanation_checks(&mut self, enabled: bool) {
+        self.check_explanations = enabled;
+    }
+
+    /// Adds a theory provided by the user, which is propagated after all built-in reasoners and explains
+    /// its own inferences. Its identity must be a `ReasonerId::Custom` that is not used by another theory.
+    ///
+    /// The theory is cloned along with the solver (e.g. for each worker of a parallel solver).
+    /// The solver is backtracked to the root.
+    pub fn add_theory(&mut self, theory: Box<dyn Theory>) -> ReasonerId {
+        self.reset();
+        let id = self.reasoners.add_theory(theory);
+        self.stats.per_module_stat.insert(id, Default::default());
+        id
+    }
+
+    /// Opens a new assertion level. All constraints enforced (with `enforce` or `enforce_all`) until the matching
+    /// call to `pop` belong to this level.
+    ///
+    /// The constraints of the level are scoped by an activation literal, which is assumed true in any search.
+    /// The solver is backtracked to the root.
+    pub fn push(&mut self) {
+        self.reset();
+        // post the pending constraints of the enclosing levels, so that all propagators posted from now on belong
+        // to the new level. On failure, the constraints remain pending and the next search reports the inconsistency.
+        let _ = self.post_constraints();
+        let activation = self.model.new_auxiliary_bvar().true_lit();
+        self.scopes.push(activation);
+        self.scope_propagators.push(self.reasoners.cp.next_propagator_id());
+    }
+
+    /// Closes the innermost assertion level, removing all constraints enforced since the matching call to `push`.
+    ///
+    /// The activation literal of the level is made false at the root, which makes its constraints absent and
+    /// satisfies all clauses learnt from them. The CP propagators of the level are removed, as are the facts
+    /// learnt during the searches of the level (e.g. bounds on an optimized objective).
+    /// Everything learnt independently of the level is kept.
+    /// Variables created in the level remain in the model (as do the definitions of reified expressions,
+    /// which are valid regardless of the level), but are no longer constrained by the constraints of the level.
+    ///
+    /// Panics if there is no open assertion level.
+    pub fn pop(&mut self) {
+        let activation = self.scopes.pop().expect("No assertion level to pop");
+        let first_propagator = self.scope_propagators.pop().unwrap();
+        self.reset();
+        self.reasoners.cp.remove_propagators_from(first_propagator);
+        // the literal is never true at the root, but may already be false if the level was shown unsatisfiable
+        let res = self.model.state.set(!activation, Cause::Encoding);
+        debug_assert!(res.is_ok(), "The activation literal of a level is entailed");
+    }
+
+    /// Records a literal that holds in all solutions of the current assertion level, and is thus propagated
+    /// at every decision level until the level is popped.
+    pub(crate) fn add_tautology(&mut self, lit: Lit) {
+        match self.scopes.last() {
+            Some(&activation) => self.reasoners.tautologies.add_scoped_tautology(lit, activation),
+            None => self.reasoners.tautologies.add_tautology(lit),
+        }
+    }
+
+    /// Clause stating that `lit` holds in the current assertion level, as recorded in a proof.
+    pub(crate) fn level_clause(&self, lit: Lit) -> Vec<Lit> {
+        self.scopes
+            .last()
+            .map(|&activation| !activation)
+            .into_iter()
+            .chain([lit])
+            .collect()
+    }
+
+    /// Number of assertion levels that are currently open.
+    pub fn num_assertion_levels(&self) -> usize {
+        self.scopes.len()
     }
 
     /// Interns the given expression and returns an equivalent literal.
@@ -123,6 +291,14 @@ impl<Lbl: Label> Solver<Lbl> {
         self.model.reify(expr)
     }
 
+    /// Creates an auxiliary integer variable that is only known to this solver.
+    /// Clauses involving it are never shared with other solvers.
+    pub(crate) fn new_local_ivar(&mut self, lb: IntCst, ub: IntCst) -> IVar {
+        let var = self.model.new_auxiliary_ivar(lb, ub);
+        self.sync.first_local_var.get_or_insert(var.into());
+        var
+    }
+
     /// Immediately adds the given constraint to the appropriate reasoner.
     /// Returns an error if the model become invalid as a result.
     fn post_constraint(&mut self, constraint: &Constraint) -> Result<(), InvalidUpdate> {
@@ -133,6 +309,16 @@ impl<Lbl: Label> Solver<Lbl> {
         if self.model.entails(!scope) {
             return Ok(()); // constraint is absent, ignore
         }
+        let defined = matches!(
+            expr,
+            ReifExpr::Lit(_) | ReifExpr::Or(_) | ReifExpr::And(_) | ReifExpr::Linear(_) | ReifExpr::PseudoBoolean(_)
+        );
+        if !defined {
+            // the constraint is handled by a theory whose inferences cannot be derived in the proof
+            if let Some(proof) = self.reasoners.sat.proof_log() {
+                proof.add_undefined_constraint();
+            }
+        }
         match expr {
             &ReifExpr::Lit(lit) => {
                 let expr_scope = self.model.presence_literal(lit.variable());
@@ -180,7 +366,12 @@ impl<Lbl: Label> Solver<Lbl> {
                 Ok(())
             }
             ReifExpr::NeqVal(a, b) => {
-                let lit = !self.reasoners.eq.add_val_edge(*a, *b, &mut self.model);
+                let (lb, ub) = self.model.state.bounds(*a);
+                let lit = if (lb..=ub).contains(b) {
+                    !self.reasoners.eq.add_val_edge(*a, *b, &mut self.model)
+                } else {
+                    Lit::TRUE
+                };
                 if lit != value {
                     self.add_clause([!value, lit], scope)?; // value => lit
                     self.add_clause([!lit, value], scope)?; // lit => value
@@ -267,7 +458,17 @@ impl<Lbl: Label> Solver<Lbl> {
                             let b = if fst.factor > 0 { fst } else { snd };
                             let a = if fst.factor < 0 { fst } else { snd };
                             let diff = DifferenceExpression::new(b.var, a.var, lin.upper_bound / b.factor);
-                            self.post_constraint(&Constraint::Reified(ReifExpr::MaxDiff(diff), value))?;
+                            let prez = |v: VarRef| self.model.state.presence(v);
+                            if self.model.state.implies(prez(diff.a), scope)
+                                && self.model.state.implies(prez(diff.b), scope)
+                            {
+                                self.post_constraint(&Constraint::Reified(ReifExpr::MaxDiff(diff), value))?;
+                            } else {
+                                // constraint enforced in a scope smaller than the one of its variables, which the STN
+                                // does not support: enforce the reification of the difference in this scope instead
+                                let lit = self.model.reify(ReifExpr::MaxDiff(diff));
+                                self.add_clause([!value, lit], scope)?;
+                            }
                             true
                         }
                     }
@@ -277,10 +478,164 @@ impl<Lbl: Label> Solver<Lbl> {
                 if !handled {
                     assert!(self.model.entails(value), "Unsupported reified linear constraints.");
                     let scope = self.model.state.presence(value);
+                    if let Some(proof) = self.reasoners.sat.proof_log() {
+                        proof.add_undefined_constraint();
+                    }
                     self.reasoners.cp.add_opt_linear_constraint(&lin, scope);
                 }
                 Ok(())
             }
+            ReifExpr::AllDifferent(vars) => {
+                assert!(
+                    self.model.entails(value),
+                    "Unsupported reified all-different constraints."
+                );
+                let scope = self.model.state.presence(value);
+                let vars: Vec<(VarRef, Lit)> = vars.iter().map(|&v| (v, self.model.state.presence(v))).collect();
+                self.reasoners.cp.add_all_different(&vars, scope);
+                Ok(())
+            }
+            ReifExpr::Cumulative(cumul) => {
+                assert!(self.model.entails(value), "Unsupported reified cumulative constraints.");
+                let scope = self.model.state.presence(value);
+                // a task without explicit presence is present with its start variable
+                let mut cumul = cumul.clone();
+                for task in &mut cumul.tasks {
+                    if task.presence == Lit::TRUE {
+                        task.presence = self.model.state.presence(task.start_var());
+                    }
+                }
+                self.reasoners.cp.add_cumulative(&cumul, scope);
+                Ok(())
+            }
+            ReifExpr::NoOverlap(no_overlap) => {
+                assert!(self.model.entails(value), "Unsupported reified no-overlap constraints.");
+                let scope = self.model.state.presence(value);
+                // a task without explicit presence is present with its start variable
+                let mut no_overlap = no_overlap.clone();
+                for task in &mut no_overlap.tasks {
+                    if task.presence == Lit::TRUE {
+                        task.presence = self.model.state.presence(task.start_var());
+                    }
+                }
+                self.reasoners.cp.add_no_overlap(&no_overlap, scope);
+                Ok(())
+            }
+            ReifExpr::ContingentLink(link) => {
+                assert!(self.model.entails(value), "Unsupported reified contingent links.");
+                let prez_a = self.model.state.presence(link.activation);
+                let prez_c = self.model.state.presence(link.contingent);
+                let implies = |a, b| self.model.state.implies(a, b);
+                let active = if implies(scope, prez_a) && implies(scope, prez_c) {
+                    // both timepoints are present in the scope of the link
+                    value
+                } else {
+                    assert!(
+                        implies(prez_a, scope) && implies(prez_c, scope),
+                        "Unsupported contingent link in a scope that is not comparable with the one of its timepoints."
+                    );
+                    // the link is active whenever both timepoints are present
+                    let link_scope = self.model.get_conjunctive_scope(&[prez_a, prez_c]);
+                    self.model.get_tautology_of_scope(link_scope)
+                };
+                self.reasoners.diff.add_contingent_link(
+                    active,
+                    link.activation,
+                    link.contingent,
+                    link.lb,
+                    link.ub,
+                    &self.model.state,
+                );
+                Ok(())
+            }
+            ReifExpr::Table(table) => {
+                assert!(self.model.entails(value), "Unsupported reified table constraints.");
+                if table.tuples.is_empty() {
+                    // no allowed tuple, the constraint cannot hold
+                    return self.post_constraint(&Constraint::Reified(ReifExpr::Lit(Lit::FALSE), value));
+                }
+                let scope = self.model.state.presence(value);
+                let presences = table.vars.iter().map(|&v| self.model.state.presence(v)).collect();
+                self.reasoners.cp.add_table(table, presences, scope);
+                Ok(())
+            }
+            ReifExpr::Element(elem) => {
+                assert!(self.model.entails(value), "Unsupported reified element constraints.");
+                let scope = self.model.state.presence(value);
+                let index_presence = self.model.state.presence(elem.index);
+                let value_presence = self.model.state.presence(elem.value);
+                self.reasoners
+                    .cp
+                    .add_element(elem, index_presence, value_presence, scope);
+                Ok(())
+            }
+            ReifExpr::Mul(mul) => {
+                assert!(self.model.entails(value), "Unsupported reified product constraints.");
+                let scope = self.model.state.presence(value);
+                let presences = [mul.product, mul.lhs, mul.rhs]
+                    .iter()
+                    .map(|&v| self.model.state.presence(v))
+                    .collect();
+                self.reasoners.cp.add_mul(mul, presences, scope);
+                Ok(())
+            }
+            ReifExpr::Div(div) => {
+                assert!(self.model.entails(value), "Unsupported reified division constraints.");
+                let scope = self.model.state.presence(value);
+                let presences = [div.quotient, div.numerator]
+                    .iter()
+                    .map(|&v| self.model.state.presence(v))
+                    .collect();
+                self.reasoners.cp.add_div(div, presences, scope);
+                Ok(())
+            }
+            ReifExpr::Mod(m) => {
+                assert!(self.model.entails(value), "Unsupported reified modulo constraints.");
+                let scope = self.model.state.presence(value);
+                let presences = [m.remainder, m.numerator]
+                    .iter()
+                    .map(|&v| self.model.state.presence(v))
+                    .collect();
+                self.reasoners.cp.add_mod(m, presences, scope);
+                Ok(())
+            }
+            ReifExpr::MinMax(min_max) => {
+                assert!(self.model.entails(value), "Unsupported reified min/max constraints.");
+                let scope = self.model.state.presence(value);
+                let presences = min_max
+                    .vars
+                    .iter()
+                    .chain([&min_max.value])
+                    .map(|&v| self.model.state.presence(v))
+                    .collect();
+                self.reasoners.cp.add_min_max(min_max, presences, scope);
+                Ok(())
+            }
+            ReifExpr::Abs(abs) => {
+                assert!(
+                    self.model.entails(value),
+                    "Unsupported reified absolute value constraints."
+                );
+                let scope = self.model.state.presence(value);
+                let presences = [abs.value, abs.var]
+                    .iter()
+                    .map(|&v| self.model.state.presence(v))
+                    .collect();
+                self.reasoners.cp.add_abs(abs, presences, scope);
+                Ok(())
+            }
+            ReifExpr::PseudoBoolean(pb) => {
+                assert!(
+                    self.model.entails(value),
+                    "Unsupported reified pseudo-Boolean constraints."
+                );
+                let scope = self.model.state.presence(value);
+                if let Some(proof) = self.reasoners.sat.proof_log() {
+                    proof.add_pseudo_boolean(&pb.terms, pb.upper_bound, scope);
+                }
+                self.reasoners.cp.add_pseudo_boolean(pb, scope);
+                Ok(())
+            }
         }
     }
 
@@ -351,12 +706,78 @@ impl<Lbl: Label> Solver<Lbl> {
         match self._solve()? {
             SolveResult::AtSolution => Ok(Some(Arc::new(self.model.state.clone()))),
             SolveResult::ExternalSolution(s) => Ok(Some(s)),
-            SolveResult::Unsat => Ok(None),
+            SolveResult::Unsat(_) => Ok(None),
+        }
+    }
+
+    /// Searches for a satisfying assignment in which all `assumptions` hold.
+    ///
+    /// The assumptions are treated as decisions taken on the first decision levels: they are only
+    /// valid for the duration of this call, and all clauses learnt in the process remain valid afterwards.
+    ///
+    /// Returns:
+    ///  - `Ok(Ok(solution))` if a solution was found. As with `solve`, the solver's state is left on this solution.
+    ///  - `Ok(Err(core))` if there is no solution under the assumptions. `core` is the subset of the assumptions
+    ///    that was shown to be jointly unsatisfiable (empty if the problem is unsatisfiable regardless of assumptions).
+    ///    The solver is left at the root decision level.
+    pub fn solve_with_assumptions(
+        &mut self,
+        assumptions: &[Lit],
+    ) -> Result<Result<Arc<SavedAssignment>, UnsatCore>, Exit> {
+        // solutions from other solvers are only relevant if they agree with our assumptions
+        let disagrees = |_: &mut Self, s: &Arc<SavedAssignment>| !assumptions.iter().all(|&l| s.entails(l));
+        match self.solve_under(assumptions, disagrees)? {
+            SolveResult::AtSolution => Ok(Ok(Arc::new(self.model.state.clone()))),
+            SolveResult::ExternalSolution(s) => Ok(Ok(s)),
+            SolveResult::Unsat(core) => Ok(Err(core)),
         }
     }
 
+    /// Searches for a satisfying assignment in which all `assumptions` hold.
+    ///
+    /// Each solution received from another solver is passed to `on_external_solution`: the search
+    /// continues if it returns true, otherwise the solution is returned.
+    pub(crate) fn solve_under(
+        &mut self,
+        assumptions: &[Lit],
+        on_external_solution: impl FnMut(&mut Self, &Arc<SavedAssignment>) -> bool,
+    ) -> Result<SolveResult, Exit> {
+        let result = self.solve_under_limit(assumptions, None, on_external_solution)?;
+        Ok(result.expect("No conflict limit"))
+    }
+
+    /// Same as `solve_under`, but stops the search once the total number of conflicts reaches `conflict_limit`.
+    /// Returns `None` if the limit was reached before finding a solution or exhausting the search space.
+    pub(crate) fn solve_under_limit(
+        &mut self,
+        assumptions: &[Lit],
+        conflict_limit: Option<u64>,
+        mut on_external_solution: impl FnMut(&mut Self, &Arc<SavedAssignment>) -> bool,
+    ) -> Result<Option<SolveResult>, Exit> {
+        // previous assumptions may still be on the trail, start from a clean state
+        self.reset();
+        // assumptions that hold at the root would only open empty decision levels
+        self.assumptions = assumptions
+            .iter()
+            .copied()
+            .filter(|&l| !self.model.entails(l))
+            .collect();
+        self.conflict_limit = conflict_limit;
+        let result = loop {
+            match self._solve_limited() {
+                Ok(Some(SolveResult::ExternalSolution(s))) if on_external_solution(self, &s) => continue,
+                result => break result,
+            }
+        };
+        self.assumptions.clear();
+        self.conflict_limit = None;
+        result
+    }
+
     /// Enumerates all possible values for the given variables.
-    /// Returns a list of assignments, where each assigment is a vector of values for the variables given as input
+    /// Returns a list of assignments, where each assigment is a vector of values for the variables given as input.
+    /// The value of an absent variable is arbitrary and two assignments never differ only by the values of absent variables.
+    /// The enumerated assignments are only excluded from the current assertion level (see [`Solver::push`]).
     pub fn enumerate(&mut self, variables: &[VarRef]) -> Result<Vec<Vec<IntCst>>, Exit> {
         debug_assert!(
             {
@@ -372,7 +793,7 @@ impl<Lbl: Label> Solver<Lbl> {
         let mut valid_assignments = Vec::with_capacity(64);
         loop {
             match self._solve()? {
-                SolveResult::Unsat => return Ok(valid_assignments),
+                SolveResult::Unsat(_) => return Ok(valid_assignments),
                 SolveResult::AtSolution => {
                     // found a solution. record the corresponding assignment and add a clause forbidding it in future solutions
                     let mut assignment = Vec::with_capacity(variables.len());
@@ -380,10 +801,17 @@ impl<Lbl: Label> Solver<Lbl> {
                     for v in variables {
                         let (val, _) = self.model.state.bounds(*v);
                         assignment.push(val);
+                        if self.model.state.present(*v) == Some(false) {
+                            // the value of an absent variable is meaningless, its absence is blocked through
+                            // the value of its presence variable
+                            continue;
+                        }
                         clause.push(Lit::lt(*v, val));
                         clause.push(Lit::gt(*v, val));
                     }
                     valid_assignments.push(assignment);
+                    // the solution is only blocked in the current assertion level
+                    clause.extend(self.scopes.last().map(|&activation| !activation));
 
                     if let Some((dl, _asserted)) = self.backtrack_level_for_clause(&clause) {
                         self.restore(dl);
@@ -400,7 +828,23 @@ impl<Lbl: Label> Solver<Lbl> {
     /// Implementation of the public facing `solve()` method that provides more control.
     /// In particular, the output distinguishes between whether the solution was found by this
     /// solver or another one (i.e. was read from the input channel).
-    fn _solve(&mut self) -> Result<SolveResult, Exit> {
+    pub(crate) fn _solve(&mut self) -> Result<SolveResult, Exit> {
+        debug_assert!(self.conflict_limit.is_none());
+        Ok(self._solve_limited()?.expect("No conflict limit"))
+    }
+
+    /// Same as `_solve`, but stops the search once the total number of conflicts reaches the conflict limit,
+    /// in which case `None` is returned.
+    fn _solve_limited(&mut self) -> Result<Option<SolveResult>, Exit> {
+        // post pending constraints first, as they may introduce new variables
+        if self.post_constraints().is_err() {
+            // the constraints are inconsistent at the root
+            self.log_root_facts();
+            if let Some(proof) = self.reasoners.sat.proof_log() {
+                proof.conclude_unsat();
+            }
+            return Ok(Some(SolveResult::Unsat(Vec::new())));
+        }
         // make sure brancher has knowledge of all variables.
         self.brancher.import_vars(&self.model);
 
@@ -420,16 +864,47 @@ impl<Lbl: Label> Solver<Lbl> {
                     InputSignal::SolutionFound(assignment) => {
                         self.stats.solve_time += start_time.elapsed();
                         self.stats.solve_cycles += start_cycles.elapsed();
-                        return Ok(SolveResult::ExternalSolution(assignment));
+                        return Ok(Some(SolveResult::ExternalSolution(assignment)));
                     }
                 }
             }
 
             if !self.propagate_and_backtrack_to_consistent() {
                 // UNSAT
+                if let Some(proof) = self.reasoners.sat.proof_log() {
+                    proof.conclude_unsat();
+                }
                 self.stats.solve_time += start_time.elapsed();
                 self.stats.solve_cycles += start_cycles.elapsed();
-                return Ok(SolveResult::Unsat);
+                return Ok(Some(SolveResult::Unsat(Vec::new())));
+            }
+            if self
+                .conflict_limit
+                .is_some_and(|limit| self.stats.num_conflicts() >= limit)
+            {
+                self.stats.solve_time += start_time.elapsed();
+                self.stats.solve_cycles += start_cycles.elapsed();
+                return Ok(None);
+            }
+            let level = self.decision_level.to_int() as usize;
+            if let Some(assumption) = self.scopes.iter().chain(&self.assumptions).nth(level).copied() {
+                // not all assumptions (including the activation literals of the assertion levels) are on the trail yet,
+                // each is placed on its own decision level
+                match self.model.state.value(assumption) {
+                    Some(true) => {
+                        // already entailed, open an empty decision level to keep levels and assumptions aligned
+                        self.save_state();
+                    }
+                    Some(false) => {
+                        log_dec!("=> UNSAT under assumptions");
+                        let core = self.extract_unsat_core(assumption);
+                        self.stats.solve_time += start_time.elapsed();
+                        self.stats.solve_cycles += start_cycles.elapsed();
+                        return Ok(Some(SolveResult::Unsat(core)));
+                    }
+                    None => self.assume(assumption),
+                }
+                continue;
             }
             match self.brancher.next_decision(&self.stats, &self.model) {
                 Some(Decision::SetLiteral(lit)) => {
@@ -449,7 +924,7 @@ impl<Lbl: Label> Solver<Lbl> {
                         self.model.shape.validate(&self.model.state).unwrap();
                         true
                     });
-                    return Ok(SolveResult::AtSolution);
+                    return Ok(Some(SolveResult::AtSolution));
                 }
             }
         }
@@ -483,61 +958,130 @@ impl<Lbl: Label> Solver<Lbl> {
         &mut self,
         objective: IAtom,
         minimize: bool,
-        mut on_new_solution: impl FnMut(IntCst, &SavedAssignment),
+        on_new_solution: impl FnMut(IntCst, &SavedAssignment),
     ) -> Result<Option<(IntCst, Arc<SavedAssignment>)>, Exit> {
-        // best solution found so far
-        let mut best = None;
-        loop {
-            let sol = match self._solve()? {
-                SolveResult::AtSolution => {
-                    // solver stopped at a solution, this is necessarily an improvement on the best solution found so far
-                    let sol = Arc::new(self.model.state.clone());
-                    // notify other solvers that we have found a new solution
-                    self.sync.notify_solution_found(sol.clone());
-                    let objective_value = sol.var_domain(objective).lb;
-                    on_new_solution(objective_value, &sol);
-                    if STATS_AT_SOLUTION.get() {
-                        println!("*********  New sol: {objective_value} *********");
-                        self.print_stats();
-                    }
-                    sol
-                }
-                SolveResult::ExternalSolution(sol) => sol, // a solution was handed out to us by another solver
-                SolveResult::Unsat => return Ok(best), // exhausted search space, return the best result found so far
-            };
+        let result = self.optimize(objective, minimize, on_new_solution);
+        if result.complete {
+            Ok(result.best)
+        } else {
+            Err(Exit::Interrupted)
+        }
+    }
 
-            // determine whether the solution found is an improvement on the previous one (might not be the case if sent by another solver)
-            let objective_value = sol.var_domain(objective).lb;
-            let is_improvement = match best {
-                None => true,
-                Some((previous_best, _)) => {
-                    if minimize {
-                        objective_value < previous_best
-                    } else {
-                        objective_value > previous_best
-                    }
+    /// Minimizes (or maximizes) the objective with the configured optimization algorithm.
+    ///
+    /// Unlike `minimize` and `maximize`, the result is returned even if the solver is interrupted.
+    /// It contains the best solution found and the best bound proven on the objective.
+    pub fn optimize(
+        &mut self,
+        objective: impl Into<IAtom>,
+        minimize: bool,
+        mut on_new_solution: impl FnMut(IntCst, &SavedAssignment),
+    ) -> OptimizationResult {
+        let mode = self.optimization.mode;
+        let mut lns = self.lns.take();
+        let result = Optimization::new(objective.into(), minimize).run(self, |opt, solver| match &mut lns {
+            Some(lns) => lns.optimize_with(solver, opt.objective, minimize, &mut on_new_solution, &mut opt.best),
+            None => opt.search(solver, mode, &mut on_new_solution),
+        });
+        self.lns = lns;
+        result
+    }
+
+    /// Saves the solution the solver is currently at and notifies the listeners (other solvers and `on_new_solution`).
+    pub(crate) fn solution_found(
+        &mut self,
+        objective: IAtom,
+        on_new_solution: &mut impl FnMut(IntCst, &SavedAssignment),
+    ) -> Arc<SavedAssignment> {
+        let sol = Arc::new(self.model.state.clone());
+        // notify other solvers that we have found a new solution
+        self.sync.notify_solution_found(sol.clone());
+        let objective_value = sol.var_domain(objective).lb;
+        on_new_solution(objective_value, &sol);
+        if STATS_AT_SOLUTION.get() {
+            println!("*********  New sol: {objective_value} *********");
+            self.print_stats();
+        }
+        sol
+    }
+
+    /// If the solution is an improvement over `best`, replaces `best` with it and requires all future
+    /// solutions to improve on it.
+    /// Returns true if the solution was an improvement.
+    pub(crate) fn record_solution(
+        &mut self,
+        objective: IAtom,
+        minimize: bool,
+        sol: Arc<SavedAssignment>,
+        best: &mut Option<(IntCst, Arc<SavedAssignment>)>,
+    ) -> bool {
+        // determine whether the solution found is an improvement on the previous one (might not be the case if sent by another solver)
+        let objective_value = sol.var_domain(objective).lb;
+        let is_improvement = match best {
+            None => true,
+            Some((previous_best, _)) => {
+                if minimize {
+                    objective_value < *previous_best
+                } else {
+                    objective_value > *previous_best
                 }
-            };
+            }
+        };
 
-            if is_improvement {
-                // Notify the brancher that a new solution has been found.
-                // This enables the use of LNS-like solution and letting the brancher use the values in the best solution
-                // as the preferred ones.
-                self.brancher.new_assignment_found(objective_value, sol.clone());
-                self.stats.add_solution(objective_value); // TODO: might consider external solutions
+        if is_improvement {
+            // Notify the brancher that a new solution has been found.
+            // This enables the use of LNS-like solution and letting the brancher use the values in the best solution
+            // as the preferred ones.
+            self.brancher.new_assignment_found(objective_value, sol.clone());
+            self.stats.add_solution(objective_value); // TODO: might consider external solutions
 
-                // save the best solution
-                best = Some((objective_value, sol));
+            // save the best solution
+            *best = Some((objective_value, sol));
 
-                // force future solutions to improve on this one
-                let must_improve_lit = if minimize {
-                    objective.lt_lit(objective_value)
-                } else {
-                    objective.gt_lit(objective_value)
-                };
-                self.reasoners.tautologies.add_tautology(must_improve_lit);
+            // force future solutions to improve on this one
+            let must_improve_lit = if minimize {
+                objective.lt_lit(objective_value)
+            } else {
+                objective.gt_lit(objective_value)
+            };
+            let clause = self.level_clause(must_improve_lit);
+            if let Some(proof) = self.reasoners.sat.proof_log() {
+                proof.comment(&format!("new solution with objective value {objective_value}"));
+                proof.add_hypothesis(clause);
             }
+            self.add_tautology(must_improve_lit);
         }
+        is_improvement
+    }
+
+    /// Places the given assumption on a new decision level.
+    fn assume(&mut self, assumption: Lit) {
+        self.save_state();
+        log_dec!(
+            "assumption: {:?} -- {}",
+            self.decision_level,
+            self.model.fmt(assumption)
+        );
+        let res = self.model.state.decide(assumption);
+        debug_assert!(res.is_ok(), "Assumption is not consistent with the current state");
+    }
+
+    /// Computes the subset of the assumptions on the trail that entail the negation of `failed`, the
+    /// next assumption to be made.
+    /// The result (that includes `failed`) is an unsat core. The solver is backtracked to the root in the process.
+    fn extract_unsat_core(&mut self, failed: Lit) -> UnsatCore {
+        debug_assert!(self.model.entails(!failed));
+        debug_assert!(self.decision_level.to_int() as usize <= self.scopes.len() + self.assumptions.len());
+        let mut core = self.model.state.decisions_implying(!failed, &mut self.reasoners);
+        core.push(failed);
+        // activation literals of the assertion levels are not assumptions of the caller
+        core.retain(|l| !self.scopes.contains(l));
+        core.sort();
+        core.dedup();
+        // domains may have been partially backtracked, bring everything back in sync
+        self.restore(DecLvl::ROOT);
+        core
     }
 
     pub fn decide(&mut self, decision: Lit) {
@@ -709,7 +1253,8 @@ impl<Lbl: Label> Solver<Lbl> {
             let num_events_at_start = self.model.state.num_events();
 
             // propagate all theories
-            for &i in self.reasoners.writers() {
+            for k in 0..self.reasoners.writers().len() {
+                let i = self.reasoners.writers()[k];
                 let theory_propagation_start = StartCycleCount::now();
                 self.stats[i].propagation_loops += 1;
                 let th = self.reasoners.reasoner_mut(i);
@@ -717,16 +1262,37 @@ impl<Lbl: Label> Solver<Lbl> {
                 match th.propagate(&mut self.model.state) {
                     Ok(()) => (),
                     Err(contradiction) => {
+                        self.log_root_facts();
                         self.brancher.pre_conflict_analysis(&self.model);
                         // contradiction, learn clause and exit
-                        let clause = match contradiction {
+                        let explainer = &mut CheckedExplainer {
+                            reasoners: &mut self.reasoners,
+                            shape: &self.model.shape,
+                            enabled: self.check_explanations,
+                        };
+                        let mut clause = match contradiction {
                             Contradiction::InvalidUpdate(fail) => {
-                                self.model.state.clause_for_invalid_update(fail, &mut self.reasoners)
+                                self.model.state.clause_for_invalid_update(fail, explainer)
                             }
                             Contradiction::Explanation(expl) => {
-                                self.model.state.refine_explanation(expl, &mut self.reasoners)
+                                if let Some(proof) =
+                                    explainer.reasoners.sat.proof_log().filter(|_| i != ReasonerId::Sat)
+                                {
+                                    // a conflict detected by a theory is not derivable from the clauses alone
+                                    proof.add_theory_explanation(i, expl.lits.iter().map(|&l| !l));
+                                }
+                                if explainer.enabled {
+                                    let (reasoners, shape, state) =
+                                        (&*explainer.reasoners, explainer.shape, &self.model.state);
+                                    check_explanation(reasoners, i, shape, state, Lit::FALSE, &expl.lits);
+                                }
+                                self.model.state.refine_explanation(expl, explainer)
                             }
                         };
+                        if CLAUSE_MINIMIZATION.get() {
+                            let removed = self.model.state.minimize_clause(&mut clause, explainer);
+                            self.stats.add_minimized_literals(removed);
+                        }
                         self.stats.add_conflict(self.current_decision_level(), clause.len());
                         self.stats[i].conflicts += 1;
                         self.stats.propagation_time += global_start.elapsed();
@@ -742,10 +1308,20 @@ impl<Lbl: Label> Solver<Lbl> {
                 break;
             }
         }
+        self.log_root_facts();
         self.stats.propagation_time += global_start.elapsed();
         Ok(())
     }
 
+    /// If a proof is being recorded and we are at the root decision level, records the facts inferred at the root.
+    fn log_root_facts(&self) {
+        if self.decision_level == DecLvl::ROOT {
+            if let Some(proof) = self.reasoners.sat.proof_log() {
+                proof.add_root_facts(&self.model.state);
+            }
+        }
+    }
+
     pub fn print_stats(&self) {
         println!("{}", self.stats);
         for (i, th) in self.reasoners.theories() {
@@ -763,8 +1339,7 @@ impl<Lbl> Backtrack for Solver<Lbl> {
         assert_eq!(self.model.save_state(), n);
         assert_eq!(self.brancher.save_state(), n);
 
-        for w in self.reasoners.writers() {
-            let th = self.reasoners.reasoner_mut(*w);
+        for th in self.reasoners.theories_mut() {
             assert_eq!(th.save_state(), n);
         }
         n
@@ -793,8 +1368,7 @@ impl<Lbl> Backtrack for Solver<Lbl> {
         self.decision_level = saved_id;
         self.model.restore(saved_id);
         self.brancher.restore(saved_id);
-        for w in self.reasoners.writers() {
-            let th = self.reasoners.reasoner_mut(*w);
+        for th in self.reasoners.theories_mut() {
             th.restore(saved_id);
         }
         debug_assert_eq!(self.current_decision_level(), saved_id);
@@ -809,7 +1383,14 @@ impl<Lbl: Label> Clone for Solver<Lbl> {
             brancher: self.brancher.clone_to_box(),
             reasoners: self.reasoners.clone(),
             decision_level: self.decision_level,
+            scopes: self.scopes.clone(),
+            scope_propagators: self.scope_propagators.clone(),
+            assumptions: self.assumptions.clone(),
+            conflict_limit: self.conflict_limit,
+            lns: self.lns.clone(),
+            optimization: self.optimization.clone(),
             stats: self.stats.clone(),
+            check_explanations: self.check_explanations,
             sync: self.sync.clone(),
         }
     }
@@ -823,8 +1404,13 @@ impl<Lbl: Label> Shaped<Lbl> for Solver<Lbl> {
 
 #[cfg(test)]
 mod test {
+    use crate::backtrack::{Backtrack, DecLvl, DecisionLevelTracker};
     use crate::core::literals::Disjunction;
-    use crate::core::Lit;
+    use crate::core::state::{Cause, Domains, Explanation, InferenceCause};
+    use crate::core::{Lit, VarRef};
+    use crate::model::lang::expr::{contingent_link, leq, lt, or};
+    use crate::reasoners::{Contradiction, ReasonerId, Theory};
+    use crate::reif::ReifExpr;
 
     type Model = crate::model::Model<&'static str>;
     type Solver = crate::solver::Solver<&'static str>;
@@ -872,4 +1458,214 @@ mod test {
         // check(s, T, [!px, !py, xy1], [xy1]);
         // check(s, T, [!px, !py], [!px, !py]); // !pxy, would be correct as well
     }
+
+    /// A custom theory enforcing that at most one of its literals is true.
+    #[derive(Clone)]
+    struct AtMostOne {
+        id: ReasonerId,
+        lits: Vec<Lit>,
+        lvl: DecisionLevelTracker,
+    }
+
+    impl Backtrack for AtMostOne {
+        fn save_state(&mut self) -> DecLvl {
+            self.lvl.save_state()
+        }
+
+        fn num_saved(&self) -> u32 {
+            self.lvl.num_saved()
+        }
+
+        fn restore_last(&mut self) {
+            self.lvl.restore_last()
+        }
+    }
+
+    impl Theory for AtMostOne {
+        fn identity(&self) -> ReasonerId {
+            self.id
+        }
+
+        fn propagate(&mut self, model: &mut Domains) -> Result<(), Contradiction> {
+            for (i, &l) in self.lits.iter().enumerate() {
+                if model.entails(l) {
+                    let cause = Cause::inference(self.id, i as u32);
+                    for &other in self.lits.iter().filter(|&&other| other != l) {
+                        model.set(!other, cause)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn explain(&mut self, literal: Lit, context: InferenceCause, _: &Domains, out: &mut Explanation) {
+            let cause = self.lits[context.payload as usize];
+            debug_assert!(self.lits.iter().any(|&l| l != cause && (!l).entails(literal)));
+            out.push(cause);
+        }
+
+        fn print_stats(&self) {}
+
+        fn clone_box(&self) -> Box<dyn Theory> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_custom_theory() {
+        let mut m = Model::new();
+        let a = m.new_bvar("a");
+        let b = m.new_bvar("b");
+        let c = m.new_bvar("c");
+        let lits = vec![a.true_lit(), b.true_lit(), c.true_lit()];
+        let mut s = Solver::new(m);
+        s.enforce(or(lits.clone()), []);
+
+        let id = s.add_theory(Box::new(AtMostOne {
+            id: ReasonerId::Custom(0),
+            lits: lits.clone(),
+            lvl: Default::default(),
+        }));
+        assert_eq!(id, ReasonerId::Custom(0));
+        assert!(s.stats.per_module_stat.contains_key(&id));
+
+        // the theory is carried over to clones of the solver
+        let mut clone = s.clone();
+        let vars = [a.into(), b.into(), c.into()];
+        assert_eq!(s.enumerate(&vars).unwrap().len(), 3);
+        assert_eq!(clone.enumerate(&vars).unwrap().len(), 3);
+
+        // refuting pairwise disjunctions requires explanations from the theory
+        s.reset();
+        s.enforce(or([lits[0], lits[1]]), []);
+        s.enforce(or([lits[0], lits[2]]), []);
+        s.enforce(or([lits[1], lits[2]]), []);
+        assert!(s.solve().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_enumerate_with_equalities() {
+        let mut m = Model::new();
+        let p = m.new_presence_variable(Lit::TRUE, "p").true_lit();
+        let q = m.new_presence_variable(Lit::TRUE, "q").true_lit();
+        let x: VarRef = m.new_ivar(0, 2, "x").into();
+        let y: VarRef = m.new_optional_ivar(0, 2, p, "y").into();
+        let z: VarRef = m.new_optional_ivar(-1, 1, q, "z").into();
+        let mut s = Solver::new(m);
+        s.enforce(ReifExpr::NeqVal(x, 0), []);
+        s.enforce(ReifExpr::NeqVal(y, 1), [p]);
+        // equality between variables with independent presences
+        s.enforce(ReifExpr::Eq(y, z), [p, q]);
+
+        let solutions = s.enumerate(&[p.variable(), q.variable(), x, y, z]).unwrap();
+        // for each of the 2 values of x: both absent (1), only y (2), only z (3) or y = z = 0 (1)
+        assert_eq!(solutions.len(), 14);
+        assert!(solutions.iter().all(|sol| sol[2] != 0 && (sol[0] == 0 || sol[3] != 1)));
+    }
+
+    #[test]
+    fn test_enumerate_in_assertion_level() {
+        let mut m = Model::new();
+        let x = m.new_ivar(0, 2, "x");
+        let mut s = Solver::new(m);
+        s.push();
+        assert_eq!(s.enumerate(&[x.into()]).unwrap().len(), 3);
+        s.pop();
+        s.push();
+        s.enforce(leq(x, 1), []);
+        assert_eq!(s.enumerate(&[x.into()]).unwrap().len(), 2);
+        s.pop();
+        // the solutions blocked in the popped levels are available again
+        assert_eq!(s.enumerate(&[x.into()]).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_inconsistent_constraints() {
+        let mut m = Model::new();
+        let x = m.new_ivar(0, 5, "x");
+        let mut s = Solver::new(m);
+        // the constraint cannot be posted, as its only literal is false at the root
+        s.enforce(or([x.geq(8)]), []);
+        assert!(s.solve().unwrap().is_none());
+        assert!(s.solve().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_contingent_link() {
+        let mut m = Model::new();
+        let a = m.new_ivar(0, 10, "a");
+        let b = m.new_ivar(0, 10, "b");
+        let c = m.new_ivar(0, 10, "c");
+        let mut s = Solver::new(m);
+        // the duration between a and c is chosen by the environment in [1, 3]
+        s.enforce(contingent_link(a, c, 1, 3), []);
+        s.enforce(leq(a, b), []);
+        s.enforce(leq(c, b + 2), []);
+        // b cannot be executed before c, as it would need to be less than 2 before c whatever its occurrence
+        let b_before_c = s.reify(lt(b, c));
+
+        let solutions = s
+            .enumerate(&[b_before_c.variable(), a.into(), b.into(), c.into()])
+            .unwrap();
+        assert!(!solutions.is_empty());
+        for sol in solutions {
+            assert_eq!(sol[0], 0);
+            assert!((1..=3).contains(&(sol[3] - sol[1])));
+        }
+    }
+
+    #[test]
+    fn test_contingent_link_decisions() {
+        let mut m = Model::new();
+        let a = m.new_ivar(0, 0, "a");
+        let b = m.new_ivar(0, 10, "b");
+        let c = m.new_ivar(0, 10, "c");
+        let mut s = Solver::new(m);
+        s.enforce(contingent_link(a, c, 1, 3), []);
+        // b can wait for c and be executed right after it: the decisions on b or c only pick a scenario
+        s.enforce(leq(c, b), []);
+        s.enforce(leq(b, c + 1), []);
+
+        let sol = s.solve().unwrap().expect("The network is dynamically controllable");
+        let (b_value, c_value) = (sol.lb(b.into()), sol.lb(c.into()));
+        assert!((1..=3).contains(&c_value));
+        assert!((c_value..=c_value + 1).contains(&b_value));
+
+        // as a constraint of the model, executing b before 1 is a requirement that fails if the link lasts 3
+        s.reset();
+        s.enforce(leq(b, 1), []);
+        assert!(s.solve().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_no_restarts() {
+        use crate::solver::search::restarts::Restarts;
+        // pigeonhole problem: eight pigeons in seven holes, each hole holding at most one pigeon
+        let solve = |restarts: Option<Restarts>| {
+            let mut m = Model::new();
+            let pigeons: Vec<Vec<Lit>> = (0..8)
+                .map(|_| (0..7).map(|_| m.new_bvar("x").true_lit()).collect())
+                .collect();
+            let mut s = Solver::new(m);
+            for holes in &pigeons {
+                s.enforce(or(holes.clone()), []);
+            }
+            for (i, first) in pigeons.iter().enumerate() {
+                for second in &pigeons[i + 1..] {
+                    // the two pigeons are not in the same hole
+                    for (&x, &y) in first.iter().zip(second) {
+                        s.enforce(or([!x, !y]), []);
+                    }
+                }
+            }
+            if let Some(restarts) = restarts {
+                s.set_restart_policy(restarts.policy());
+            }
+            assert!(s.solve().unwrap().is_none());
+            s.stats.num_restarts()
+        };
+        // the default brancher restarts, unless its restarts are replaced by a policy
+        assert!(solve(None) > 0);
+        assert_eq!(solve(Some(Restarts::None)), 0);
+    }
 }
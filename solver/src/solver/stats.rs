@@ -23,10 +23,16 @@ pub struct Stats {
     num_conflicts: u64,
     num_restarts: u64,
     num_solutions: u64,
+    /// Total number of literals in the learnt clauses (after minimization).
+    num_learnt_literals: u64,
+    /// Number of literals removed from the learnt clauses by minimization.
+    num_minimized_literals: u64,
     pub propagation_time: CycleCount,
     pub per_module_stat: BTreeMap<ReasonerId, ModuleStat>,
     running: RunningStats,
     best_cost: Option<IntCst>,
+    /// Best bound proven on the objective (a lower bound when minimizing, an upper bound when maximizing).
+    best_bound: Option<IntCst>,
 }
 
 #[derive(Clone, Default)]
@@ -52,10 +58,13 @@ impl Stats {
             num_conflicts: 0,
             num_restarts: 0,
             num_solutions: 0,
+            num_learnt_literals: 0,
+            num_minimized_literals: 0,
             propagation_time: CycleCount::zero(),
             per_module_stat: per_mod,
             running: Default::default(),
             best_cost: None,
+            best_bound: None,
         }
     }
 
@@ -66,18 +75,29 @@ impl Stats {
 
     pub fn add_conflict(&mut self, depth: DecLvl, size: usize) {
         self.num_conflicts += 1;
+        self.num_learnt_literals += size as u64;
         self.running.add_conflict(size, depth);
         if self.running.count == 1000 {
             self.print_running(" ");
         }
     }
 
+    /// Records that some literals were removed from a learnt clause by minimization.
+    pub fn add_minimized_literals(&mut self, num_removed: usize) {
+        self.num_minimized_literals += num_removed as u64;
+    }
+
     pub fn add_solution(&mut self, cost: IntCst) {
         self.num_solutions += 1;
         self.best_cost = Some(cost);
         self.print_running("*");
     }
 
+    /// Records a new bound proven on the objective.
+    pub fn add_bound(&mut self, bound: IntCst) {
+        self.best_bound = Some(bound);
+    }
+
     pub fn add_restart(&mut self) {
         self.num_restarts += 1;
         self.print_running("<");
@@ -103,6 +123,20 @@ impl Stats {
     pub fn num_conflicts(&self) -> u64 {
         self.num_conflicts
     }
+
+    pub fn num_restarts(&self) -> u64 {
+        self.num_restarts
+    }
+
+    /// Objective value of the last solution found.
+    pub fn best_cost(&self) -> Option<IntCst> {
+        self.best_cost
+    }
+
+    /// Best bound proven on the objective, if any.
+    pub fn best_bound(&self) -> Option<IntCst> {
+        self.best_bound
+    }
 }
 
 impl Default for Stats {
@@ -126,6 +160,15 @@ impl Display for Stats {
         label(f, "solutions")?;
         writeln!(f, "{:<12}", self.num_solutions)?;
 
+        if let Some(cost) = self.best_cost {
+            label(f, "best cost")?;
+            writeln!(f, "{cost:<12}")?;
+        }
+        if let Some(bound) = self.best_bound {
+            label(f, "objective bound")?;
+            writeln!(f, "{bound:<12}")?;
+        }
+
         label(f, "restarts")?;
         writeln!(f, "{:<12}", self.num_restarts)?;
 
@@ -137,6 +180,15 @@ impl Display for Stats {
         val_throughput(f, self.num_conflicts, &self.solve_time)?;
         new_line(f)?;
 
+        label(f, "minimized literals")?;
+        let num_literals_before = self.num_learnt_literals + self.num_minimized_literals;
+        writeln!(
+            f,
+            "{:<12} ({:.1} %)",
+            self.num_minimized_literals,
+            100.0 * self.num_minimized_literals as f64 / num_literals_before.max(1) as f64
+        )?;
+
         writeln!(f, "================= ")?;
         label(f, "Solvers")?;
         for i in self.per_module_stat.keys() {